@@ -0,0 +1,137 @@
+//! Chain Profiles
+//!
+//! Chain-specific constants (chain id, WETH, Multicall3, Balancer vault,
+//! block time, pools to watch) for each supported network, selected by
+//! `chain` in the config. Arbitrum One is the default; any L2 running the same venue
+//! stack can be added as another profile.
+
+use ethers::types::Address;
+
+use crate::detector::{ARBITRUM_BALANCER_VAULT, ARBITRUM_WETH};
+
+/// Multicall3 is deployed at the same address on every supported chain
+const MULTICALL3: &str = "0xcA11bde05977b3631167028862bE2a173976CA11";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainProfile {
+    pub name: &'static str,
+    pub chain_id: u64,
+    pub weth: Address,
+    pub multicall3: Address,
+    /// Zero where Balancer isn't deployed - set `venues.balancer_vault` explicitly
+    pub balancer_vault: Address,
+    pub block_time_ms: u64,
+    /// Chain name in the DexScreener API
+    pub dexscreener_id: &'static str,
+    /// Pools whose swap events trigger detection
+    pub watched_uniswap_v3_pools: Vec<Address>,
+    pub watched_uniswap_v2_pools: Vec<Address>,
+    pub watched_curve_pools: Vec<Address>,
+}
+
+impl ChainProfile {
+    pub fn arbitrum_one() -> Self {
+        Self {
+            name: "arbitrum-one",
+            chain_id: 42161,
+            weth: ARBITRUM_WETH.parse().unwrap(),
+            multicall3: MULTICALL3.parse().unwrap(),
+            balancer_vault: ARBITRUM_BALANCER_VAULT.parse().unwrap(),
+            block_time_ms: 250,
+            dexscreener_id: "arbitrum",
+            watched_uniswap_v3_pools: addresses(&[
+                // wstETH/ETH 0.05%
+                "0x35218a1cbaC5Bbc3E57fd9Bd38219D37571b3537",
+                // wstETH/ETH 0.01%
+                "0x7A20B2F07d5B2A9aE5F1F24b8C3c0c9F7b9e4C3A",
+                // rETH/ETH 0.05%
+                "0x09BA4E5F0D0f0C3A0a7AC7D7A05c1C0A0B0C0D0E",
+            ]),
+            watched_uniswap_v2_pools: addresses(&[
+                // Camelot wstETH/ETH (Uniswap V2 fork)
+                "0x0E3eF0c8D2D4A1d4F8c9c0F9c8E9c0D2f0A1b2C3",
+            ]),
+            watched_curve_pools: addresses(&[
+                // Curve wstETH/ETH NG Pool on Arbitrum
+                "0x6eB2dc694eB516B16Dc9d7671f465248B71E9091",
+            ]),
+        }
+    }
+
+    pub fn arbitrum_nova() -> Self {
+        Self {
+            name: "arbitrum-nova",
+            chain_id: 42170,
+            weth: "0x722E8BdD2ce80A4422E880164f2079488e115365".parse().unwrap(),
+            multicall3: MULTICALL3.parse().unwrap(),
+            balancer_vault: Address::zero(),
+            block_time_ms: 250,
+            dexscreener_id: "arbitrumnova",
+            // No LST pools worth watching yet; detection runs off the backup poll
+            watched_uniswap_v3_pools: Vec::new(),
+            watched_uniswap_v2_pools: Vec::new(),
+            watched_curve_pools: Vec::new(),
+        }
+    }
+
+    pub fn by_name(name: &str) -> eyre::Result<Self> {
+        match name {
+            "arbitrum-one" => Ok(Self::arbitrum_one()),
+            "arbitrum-nova" => Ok(Self::arbitrum_nova()),
+            other => Err(eyre::eyre!("Unknown chain profile: {}", other)),
+        }
+    }
+
+    /// Backup poll every two blocks
+    pub fn backup_poll_interval_ms(&self) -> u64 {
+        self.block_time_ms * 2
+    }
+
+    /// Refuse to run against an RPC serving a different chain
+    pub fn check_chain_id(&self, rpc_chain_id: u64) -> eyre::Result<()> {
+        if rpc_chain_id != self.chain_id {
+            eyre::bail!(
+                "RPC is on chain {} but the {} profile expects chain {}",
+                rpc_chain_id, self.name, self.chain_id
+            );
+        }
+        Ok(())
+    }
+}
+
+fn addresses(list: &[&str]) -> Vec<Address> {
+    list.iter().map(|address| address.parse().unwrap()).collect()
+}
+
+impl Default for ChainProfile {
+    fn default() -> Self {
+        Self::arbitrum_one()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Config, ParsedConfig};
+
+    #[test]
+    fn test_profile_swaps_addresses_and_chain_check() {
+        let config = Config { chain: "arbitrum-nova".into(), ..Config::default() };
+        let parsed = ParsedConfig::from_config(&config).unwrap();
+
+        let nova = ChainProfile::arbitrum_nova();
+        assert_eq!(parsed.chain, nova);
+        assert_eq!(parsed.weth, nova.weth);
+        assert_ne!(parsed.weth, ChainProfile::arbitrum_one().weth);
+        assert_eq!(parsed.venues.multicall3, nova.multicall3);
+
+        assert!(parsed.chain.check_chain_id(42170).is_ok());
+        assert!(parsed.chain.check_chain_id(42161).is_err());
+        assert!(ChainProfile::by_name("optimism").is_err());
+
+        // No Balancer on Nova isn't a config error
+        let parsed = ParsedConfig { arb_contract: Address::from_low_u64_be(1), ..parsed };
+        assert!(parsed.venues.balancer_vault.is_zero());
+        assert!(parsed.validate().is_ok());
+    }
+}
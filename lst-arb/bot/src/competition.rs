@@ -0,0 +1,236 @@
+//! Competitor Activity Monitor
+//!
+//! Arbitrum's sequencer is FIFO, so there's no gas auction to win - but
+//! other bots hitting the same pools still means a contested opportunity is
+//! less likely to land. This watches each block for transactions sent to
+//! known competitor arb contracts and remembers which pools they touched.
+//! Opportunities on recently contested pools are then either executed more
+//! aggressively (resubmitted sooner with steeper fee bumps, and given up on
+//! sooner when stuck) or pushed to the back of the queue.
+
+use ethers::prelude::*;
+use ethers::types::{Address, Transaction, TransactionReceipt};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+use crate::detector::Opportunity;
+use crate::rpc::RpcLoadBalancer;
+
+/// How long a pending tx may sit unconfirmed before it's treated as stuck
+pub const DEFAULT_STUCK_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Stuck timeout for opportunities a competitor is working
+pub const CONTESTED_STUCK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Wait for inclusion before re-bumping a contested opportunity's tx
+pub const CONTESTED_RESUBMIT_AFTER: Duration = Duration::from_millis(500);
+
+/// Contested opportunities bump their fee this many times the configured step
+pub const CONTESTED_BUMP_MULTIPLIER: f64 = 2.0;
+
+/// What to do with an opportunity on a contested pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompetitionResponse {
+    /// Execute anyway, resubmitting sooner with bigger fee bumps and giving
+    /// up on stuck transactions sooner
+    Aggressive,
+    /// Execute uncontested opportunities first
+    Deprioritize,
+}
+
+impl std::str::FromStr for CompetitionResponse {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> eyre::Result<Self> {
+        match s {
+            "aggressive" => Ok(Self::Aggressive),
+            "deprioritize" => Ok(Self::Deprioritize),
+            other => Err(eyre::eyre!("Unknown competition response: {}", other)),
+        }
+    }
+}
+
+/// Tracks which pools competitor contracts have touched recently
+pub struct CompetitorMonitor {
+    competitors: HashSet<Address>,
+    window: Duration,
+    response: CompetitionResponse,
+    /// pool -> last time a competitor tx touched it
+    contested: parking_lot::Mutex<HashMap<Address, Instant>>,
+}
+
+impl CompetitorMonitor {
+    pub fn new(competitors: Vec<Address>, window: Duration, response: CompetitionResponse) -> Self {
+        Self {
+            competitors: competitors.into_iter().collect(),
+            window,
+            response,
+            contested: parking_lot::Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn response(&self) -> CompetitionResponse {
+        self.response
+    }
+
+    /// Whether a transaction was sent to a known competitor contract
+    pub fn is_competitor_tx(&self, tx: &Transaction) -> bool {
+        tx.to.is_some_and(|to| self.competitors.contains(&to))
+    }
+
+    /// Record the pools a competitor transaction touched
+    pub fn observe(&self, pools: impl IntoIterator<Item = Address>, now: Instant) {
+        let mut contested = self.contested.lock();
+        for pool in pools {
+            contested.insert(pool, now);
+        }
+        contested.retain(|_, seen| now.saturating_duration_since(*seen) <= self.window);
+    }
+
+    /// Record a competitor transaction from its receipt logs
+    pub fn observe_receipt(&self, receipt: &TransactionReceipt, now: Instant) {
+        self.observe(receipt.logs.iter().map(|log| log.address), now);
+    }
+
+    /// Whether a competitor touched either of the opportunity's pools recently
+    pub fn is_contested(&self, opportunity: &Opportunity, now: Instant) -> bool {
+        let contested = self.contested.lock();
+        [opportunity.buy_pool, opportunity.sell_pool].iter().any(|pool| {
+            contested
+                .get(pool)
+                .is_some_and(|seen| now.saturating_duration_since(*seen) <= self.window)
+        })
+    }
+
+    /// Whether this opportunity is submitted aggressively
+    pub fn is_escalated(&self, opportunity: &Opportunity, now: Instant) -> bool {
+        self.response == CompetitionResponse::Aggressive && self.is_contested(opportunity, now)
+    }
+
+    /// How long to wait on an unconfirmed tx for this opportunity
+    pub fn stuck_timeout(&self, opportunity: &Opportunity, now: Instant) -> Duration {
+        if self.is_escalated(opportunity, now) {
+            CONTESTED_STUCK_TIMEOUT
+        } else {
+            DEFAULT_STUCK_TIMEOUT
+        }
+    }
+
+    /// Order opportunities so contested ones go last (stable otherwise)
+    pub fn prioritize(&self, opportunities: &mut [Opportunity], now: Instant) {
+        if self.response == CompetitionResponse::Deprioritize {
+            opportunities.sort_by_key(|opp| self.is_contested(opp, now));
+        }
+    }
+
+    /// Scan each new block for competitor transactions
+    pub async fn run(self: Arc<Self>, rpc: Arc<RpcLoadBalancer>) {
+        info!("Competitor monitor watching {} contracts", self.competitors.len());
+
+        loop {
+            let Some(client) = rpc.get_client().await else {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                continue;
+            };
+
+            let mut blocks = match client.subscribe_blocks().await {
+                Ok(stream) => stream,
+                Err(e) => {
+                    warn!("Competitor monitor failed to subscribe to blocks: {:?}", e);
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    continue;
+                }
+            };
+
+            while let Some(block) = blocks.next().await {
+                let Some(hash) = block.hash else { continue };
+
+                let block = match client.get_block_with_txs(hash).await {
+                    Ok(Some(block)) => block,
+                    Ok(None) => continue,
+                    Err(e) => {
+                        debug!("Competitor monitor failed to fetch block: {:?}", e);
+                        continue;
+                    }
+                };
+
+                for tx in block.transactions.iter().filter(|tx| self.is_competitor_tx(tx)) {
+                    if let Ok(Some(receipt)) = client.get_transaction_receipt(tx.hash).await {
+                        debug!("👀 Competitor tx {:?} from {:?}", tx.hash, tx.from);
+                        self.observe_receipt(&receipt, Instant::now());
+                    }
+                }
+            }
+
+            warn!("Competitor monitor block stream ended, resubscribing");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::Log;
+
+    fn opportunity(buy_pool: u64, sell_pool: u64) -> Opportunity {
+        Opportunity {
+            buy_pool: Address::from_low_u64_be(buy_pool),
+            sell_pool: Address::from_low_u64_be(sell_pool),
+            ..Opportunity::fixture()
+        }
+    }
+
+    #[test]
+    fn test_competitor_tx_makes_pool_aggressive() {
+        let competitor = Address::from_low_u64_be(0xbad);
+        let monitor = CompetitorMonitor::new(
+            vec![competitor],
+            Duration::from_secs(30),
+            CompetitionResponse::Aggressive,
+        );
+        let now = Instant::now();
+        let opp = opportunity(10, 11);
+
+        assert_eq!(monitor.stuck_timeout(&opp, now), DEFAULT_STUCK_TIMEOUT);
+
+        let tx = Transaction { to: Some(competitor), ..Default::default() };
+        assert!(monitor.is_competitor_tx(&tx));
+        assert!(!monitor.is_competitor_tx(&Transaction::default()));
+
+        // The competitor tx swapped through our sell pool
+        let receipt = TransactionReceipt {
+            logs: vec![Log { address: Address::from_low_u64_be(11), ..Default::default() }],
+            ..Default::default()
+        };
+        monitor.observe_receipt(&receipt, now);
+
+        assert!(monitor.is_contested(&opp, now));
+        assert!(monitor.is_escalated(&opp, now));
+        assert_eq!(monitor.stuck_timeout(&opp, now), CONTESTED_STUCK_TIMEOUT);
+        assert_eq!(monitor.stuck_timeout(&opportunity(12, 13), now), DEFAULT_STUCK_TIMEOUT);
+
+        // Contention expires after the window
+        let later = now + Duration::from_secs(31);
+        assert_eq!(monitor.stuck_timeout(&opp, later), DEFAULT_STUCK_TIMEOUT);
+    }
+
+    #[test]
+    fn test_contested_opportunities_deprioritized() {
+        let monitor = CompetitorMonitor::new(
+            Vec::new(),
+            Duration::from_secs(30),
+            CompetitionResponse::Deprioritize,
+        );
+        let now = Instant::now();
+        monitor.observe([Address::from_low_u64_be(10)], now);
+
+        let mut opps = vec![opportunity(10, 11), opportunity(12, 13)];
+        monitor.prioritize(&mut opps, now);
+
+        assert_eq!(opps[0].buy_pool, Address::from_low_u64_be(12));
+        // Deprioritize mode doesn't change timeouts
+        assert_eq!(monitor.stuck_timeout(&opps[1], now), DEFAULT_STUCK_TIMEOUT);
+    }
+}
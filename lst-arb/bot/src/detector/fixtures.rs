@@ -0,0 +1,182 @@
+//! Solver Verification Fixtures
+//!
+//! Scenarios with known optimal trade sizes, used by `--verify-solver` to
+//! catch a broken solver before it trades. Expected values were computed
+//! offline by dense grid search plus golden-section refinement (200 rounds)
+//! over each pool model's exact profit function.
+
+use ethers::types::{Address, U256};
+use tracing::{info, warn};
+
+use crate::price::Venue;
+use super::solver::{OptimalTrade, PoolParams, Solver};
+
+/// A scenario with a known optimal input and profit
+#[derive(Debug, Clone)]
+pub struct SolverFixture {
+    pub name: &'static str,
+    pub buy: PoolParams,
+    pub sell: PoolParams,
+    pub expected_input_eth: f64,
+    pub expected_profit_eth: f64,
+    /// Allowed relative error on the optimal input
+    pub input_tolerance: f64,
+    /// Allowed relative error on the profit
+    pub profit_tolerance: f64,
+}
+
+fn pool(
+    id: u64,
+    venue: Venue,
+    reserve_x_eth: u64,
+    reserve_y_eth: u64,
+    fee_bps: u64,
+    amp: Option<u64>,
+) -> PoolParams {
+    PoolParams {
+        venue,
+        fee_tier: None,
+        pool: Address::from_low_u64_be(id),
+        reserve_x: U256::from(reserve_x_eth) * U256::exp10(18),
+        reserve_y: U256::from(reserve_y_eth) * U256::exp10(18),
+        fee_bps,
+        amp,
+        stable: false,
+    }
+}
+
+/// Bundled fixture set covering constant-product, stableswap and mixed pairs
+pub fn fixtures() -> Vec<SolverFixture> {
+    vec![
+        SolverFixture {
+            name: "constant-product 30bps",
+            buy: pool(1, Venue::UniswapV3, 1000, 1030, 30, None),
+            sell: pool(2, Venue::Balancer, 1000, 960, 30, None),
+            expected_input_eth: 15.826917163045765,
+            expected_profit_eth: 0.5176898758921913,
+            input_tolerance: 0.02,
+            profit_tolerance: 0.001,
+        },
+        SolverFixture {
+            name: "constant-product 5bps",
+            buy: pool(3, Venue::UniswapV3, 2000, 2020, 5, None),
+            sell: pool(4, Venue::Balancer, 1500, 1490, 5, None),
+            expected_input_eth: 6.6681653534554295,
+            expected_profit_eth: 0.05234634203321549,
+            input_tolerance: 0.02,
+            profit_tolerance: 0.001,
+        },
+        SolverFixture {
+            name: "stableswap",
+            buy: pool(5, Venue::Curve, 6000, 14000, 4, Some(100)),
+            sell: pool(6, Venue::Curve, 7000, 3000, 4, Some(100)),
+            expected_input_eth: 2415.0991277208004,
+            expected_profit_eth: 10.802596181716762,
+            input_tolerance: 0.05,
+            profit_tolerance: 0.01,
+        },
+        SolverFixture {
+            name: "mixed curve -> constant-product",
+            buy: pool(7, Venue::Curve, 6000, 10000, 4, Some(100)),
+            sell: pool(8, Venue::UniswapV3, 1000, 980, 5, None),
+            expected_input_eth: 10.873191344035464,
+            expected_profit_eth: 0.12097666928308838,
+            input_tolerance: 0.10,
+            profit_tolerance: 0.01,
+        },
+    ]
+}
+
+/// Check a solver result against a fixture's expected optimum
+pub fn check_fixture(fixture: &SolverFixture, trade: Option<&OptimalTrade>) -> Result<(), String> {
+    let trade = trade.ok_or_else(|| "solver found no trade".to_string())?;
+
+    let input = wei_to_eth(trade.optimal_input);
+    let profit = wei_to_eth(trade.expected_profit);
+
+    let input_error = relative_error(input, fixture.expected_input_eth);
+    if input_error > fixture.input_tolerance {
+        return Err(format!(
+            "optimal input {:.6} ETH, expected {:.6} ETH ({:.2}% off)",
+            input,
+            fixture.expected_input_eth,
+            input_error * 100.0
+        ));
+    }
+
+    let profit_error = relative_error(profit, fixture.expected_profit_eth);
+    if profit_error > fixture.profit_tolerance {
+        return Err(format!(
+            "profit {:.6} ETH, expected {:.6} ETH ({:.2}% off)",
+            profit,
+            fixture.expected_profit_eth,
+            profit_error * 100.0
+        ));
+    }
+
+    Ok(())
+}
+
+/// Run the solver over every fixture, returning the failures
+pub fn verify_solver(solver: &Solver) -> Vec<(&'static str, String)> {
+    let mut failures = Vec::new();
+
+    for fixture in fixtures() {
+        let trade = solver.find_optimal_trade(&[fixture.buy.clone(), fixture.sell.clone()]);
+
+        match check_fixture(&fixture, trade.as_ref()) {
+            Ok(()) => info!("✅ Solver fixture passed: {}", fixture.name),
+            Err(reason) => {
+                warn!("❌ Solver fixture failed: {} - {}", fixture.name, reason);
+                failures.push((fixture.name, reason));
+            }
+        }
+    }
+
+    failures
+}
+
+fn wei_to_eth(wei: U256) -> f64 {
+    ethers::utils::format_ether(wei).parse().unwrap_or(0.0)
+}
+
+fn relative_error(actual: f64, expected: f64) -> f64 {
+    ((actual - expected) / expected).abs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solver_passes_constant_product_fixtures() {
+        let solver = Solver::new();
+
+        for fixture in fixtures().iter().filter(|f| f.buy.amp.is_none() && f.sell.amp.is_none()) {
+            let trade = solver.find_optimal_trade(&[fixture.buy.clone(), fixture.sell.clone()]);
+            assert_eq!(check_fixture(fixture, trade.as_ref()), Ok(()), "{}", fixture.name);
+        }
+    }
+
+    #[test]
+    fn test_wrong_solver_output_fails_verification() {
+        let fixture = &fixtures()[0];
+        let mut trade = Solver::new()
+            .find_optimal_trade(&[fixture.buy.clone(), fixture.sell.clone()])
+            .unwrap();
+        assert!(check_fixture(fixture, Some(&trade)).is_ok());
+
+        // Double the input - a classic sign-error style regression
+        trade.optimal_input *= 2;
+        assert!(check_fixture(fixture, Some(&trade)).is_err());
+
+        // Right size but inflated profit
+        let mut trade = Solver::new()
+            .find_optimal_trade(&[fixture.buy.clone(), fixture.sell.clone()])
+            .unwrap();
+        trade.expected_profit = trade.expected_profit * 11 / 10;
+        assert!(check_fixture(fixture, Some(&trade)).is_err());
+
+        assert!(check_fixture(fixture, None).is_err());
+    }
+}
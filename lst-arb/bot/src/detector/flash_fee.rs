@@ -0,0 +1,47 @@
+//! Flash-Loan Fees
+//!
+//! The arb contract borrows its trade capital for the length of the
+//! transaction. Balancer lends for free, but other providers charge a
+//! premium on the amount borrowed (Aave's is 0.05%), and an opportunity is
+//! only worth taking if it clears that on top of the pools' own fees.
+
+use ethers::types::U256;
+
+/// Where the trade capital is flash-loaned from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlashProvider {
+    Balancer,
+    Aave,
+}
+
+impl FlashProvider {
+    /// The provider's standard fee on the borrowed amount
+    pub fn fee_bps(&self) -> u64 {
+        match self {
+            Self::Balancer => 0,
+            Self::Aave => 5,
+        }
+    }
+}
+
+impl std::str::FromStr for FlashProvider {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> eyre::Result<Self> {
+        match s {
+            "balancer" => Ok(Self::Balancer),
+            "aave" => Ok(Self::Aave),
+            other => Err(eyre::eyre!("Unknown flash provider: {}", other)),
+        }
+    }
+}
+
+/// Fee on borrowing `amount` at `fee_bps`, rounded up as providers do
+pub fn flash_fee(amount: U256, fee_bps: u64) -> U256 {
+    if fee_bps == 0 {
+        return U256::zero();
+    }
+    let scaled = amount.saturating_mul(U256::from(fee_bps));
+    let fee = scaled / 10_000;
+    if (scaled % 10_000).is_zero() { fee } else { fee + 1 }
+}
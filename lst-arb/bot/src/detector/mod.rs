@@ -1,6 +1,17 @@
 pub mod spread;
 pub mod solver;
+pub mod spread_tuner;
+pub mod fixtures;
+pub mod triangular;
+pub mod route;
+pub mod rate_staleness;
+pub mod quote_age;
+pub mod flash_fee;
 
 pub use spread::*;
 // Export solver constants for external reference
-pub use solver::{ARBITRUM_BALANCER_VAULT, ARBITRUM_WETH, MAX_LIQUIDITY_PERCENT};
+pub use solver::{ARBITRUM_BALANCER_VAULT, ARBITRUM_WETH};
+// Export the solver so backtests can size trades from historical reserve
+// snapshots without a live client
+#[allow(unused_imports)]
+pub use solver::{OptimalTrade, PoolParams, Solver};
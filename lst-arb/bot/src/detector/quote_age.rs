@@ -0,0 +1,199 @@
+//! Quote Age Budget
+//!
+//! Detection latency is measured from the trigger, but what execution acts
+//! on is the quotes - and those keep ageing through a slow detect step, the
+//! gas window and the conflict checks. On Arbitrum a quote a few blocks old
+//! is worthless, so right before execution every opportunity whose
+//! underlying quotes are older than the budget is rejected.
+//!
+//! Wall-clock age is a proxy for what matters: the block a quote reflects.
+//! Quotes carry the block their multicall ran against, so with a block
+//! budget an opportunity is also rejected once the chain has moved more
+//! than that many blocks past its quotes.
+
+use ethers::types::Address;
+use std::collections::HashMap;
+
+use super::Opportunity;
+use crate::price::TokenQuotes;
+
+/// About three Arbitrum blocks
+pub const DEFAULT_MAX_QUOTE_AGE_MS: u64 = 750;
+
+/// When each market's quotes in one scan were fetched. Markets are keyed by
+/// (base token, token), so an LST's WETH quotes and its quotes against
+/// another LST are aged separately.
+pub struct QuoteAges {
+    fetched_at_ms: HashMap<(Address, Address), u64>,
+    /// 0 = no limit
+    max_age_ms: u64,
+    /// Block each market's quotes reflect, where it was read
+    quoted_at_block: HashMap<(Address, Address), u64>,
+    /// (chain head, blocks quotes may trail it by)
+    block_limit: Option<(u64, u64)>,
+}
+
+impl QuoteAges {
+    /// Ages of `token_quotes`, whose base is `weth` unless they're an LST/LST pair
+    pub fn new(token_quotes: &[TokenQuotes], weth: Address, max_age_ms: u64) -> Self {
+        let market = |tq: &TokenQuotes| (tq.base_token.unwrap_or(weth), tq.token);
+        Self {
+            fetched_at_ms: token_quotes.iter().map(|tq| (market(tq), tq.fetched_at_ms)).collect(),
+            max_age_ms,
+            quoted_at_block: token_quotes.iter()
+                .filter_map(|tq| Some((market(tq), tq.block_number?)))
+                .collect(),
+            block_limit: None,
+        }
+    }
+
+    /// Also reject opportunities whose quotes are more than `max_age_blocks`
+    /// behind `head_block` (0 = quotes must be from the head block)
+    pub fn with_block_limit(mut self, head_block: u64, max_age_blocks: u64) -> Self {
+        self.block_limit = Some((head_block, max_age_blocks));
+        self
+    }
+
+    /// Blocks the chain has advanced past the oldest quotes an opportunity
+    /// was priced from, or None without a head block or quote block numbers
+    pub fn blocks_behind(&self, opportunity: &Opportunity) -> Option<u64> {
+        let (head_block, _) = self.block_limit?;
+        quoted(&self.quoted_at_block, opportunity)
+            .min()
+            .map(|quoted_at| head_block.saturating_sub(quoted_at))
+    }
+
+    /// Age of the oldest quotes an opportunity was priced from, or None if
+    /// none of its markets were quoted this scan
+    pub fn age_ms(&self, opportunity: &Opportunity, now_ms: u64) -> Option<u64> {
+        quoted(&self.fetched_at_ms, opportunity)
+            .min()
+            .map(|fetched_at| now_ms.saturating_sub(fetched_at))
+    }
+
+    /// Whether an opportunity's quotes are still within the budget. Quotes of
+    /// unknown age don't pass; quotes of unknown block are judged by age alone.
+    pub fn is_fresh(&self, opportunity: &Opportunity, now_ms: u64) -> bool {
+        let within_ms = self.max_age_ms == 0
+            || self.age_ms(opportunity, now_ms).is_some_and(|age| age <= self.max_age_ms);
+        let within_blocks = match (self.block_limit, self.blocks_behind(opportunity)) {
+            (Some((_, max_age_blocks)), Some(behind)) => behind <= max_age_blocks,
+            _ => true,
+        };
+        within_ms && within_blocks
+    }
+
+    /// Split opportunities into those still fresh enough to execute and the stale rest
+    pub fn split(&self, opportunities: Vec<Opportunity>, now_ms: u64) -> (Vec<Opportunity>, Vec<Opportunity>) {
+        opportunities.into_iter().partition(|opportunity| self.is_fresh(opportunity, now_ms))
+    }
+}
+
+/// Markets an opportunity was priced from: one per leg of its route, or its
+/// base and token when no route is recorded
+fn quoted_markets(opportunity: &Opportunity) -> Vec<(Address, Address)> {
+    if opportunity.route.is_empty() {
+        return vec![(opportunity.base_token, opportunity.token)];
+    }
+    opportunity.route.iter().map(|leg| (leg.token_in, leg.token_out)).collect()
+}
+
+/// `by_market`'s entries for the markets an opportunity was priced from,
+/// whichever way round each leg trades
+fn quoted<'a>(by_market: &'a HashMap<(Address, Address), u64>, opportunity: &Opportunity) -> impl Iterator<Item = u64> + 'a {
+    quoted_markets(opportunity).into_iter().filter_map(|(from, to)| {
+        by_market.get(&(from, to)).or_else(|| by_market.get(&(to, from))).copied()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::price::Venue;
+    use ethers::types::U256;
+
+    fn quotes(token: u64, fetched_at_ms: u64) -> TokenQuotes {
+        TokenQuotes {
+            token: Address::from_low_u64_be(token),
+            token_name: format!("lst{}", token),
+            quotes: Vec::new(),
+            curve_amp: None,
+            sized_quotes: Vec::new(),
+            fetched_at_ms,
+            block_number: None,
+            base_token: None,
+        }
+    }
+
+    fn opportunity(token: u64) -> Opportunity {
+        Opportunity {
+            token: Address::from_low_u64_be(token),
+            token_name: format!("lst{}", token),
+            ..Opportunity::fixture()
+        }
+    }
+
+    #[test]
+    fn test_stale_quotes_rejected_before_execution() {
+        let now = 10_000;
+        let ages = QuoteAges::new(&[quotes(1, now - 200), quotes(2, now - 900)], Address::zero(), 750);
+
+        assert_eq!(ages.age_ms(&opportunity(1), now), Some(200));
+        let (fresh, stale) = ages.split(vec![opportunity(1), opportunity(2), opportunity(3)], now);
+        assert_eq!(fresh.iter().map(|opp| opp.token).collect::<Vec<_>>(), vec![Address::from_low_u64_be(1)]);
+        // Too old, and never quoted at all
+        assert_eq!(stale.len(), 2);
+
+        // A route is as old as its oldest leg's quotes
+        let leg = |token_in, token_out| crate::detector::route::Leg {
+            pool: Address::zero(),
+            venue: Venue::Curve,
+            token_in: Address::from_low_u64_be(token_in),
+            token_out: Address::from_low_u64_be(token_out),
+            amount_in: U256::zero(),
+        };
+        let cycle = Opportunity { route: vec![leg(0, 1), leg(2, 0)], ..opportunity(1) };
+        assert_eq!(ages.age_ms(&cycle, now), Some(900));
+        assert!(!ages.is_fresh(&cycle, now));
+
+        // An LST/LST pair is aged by its own quotes, not its token's WETH quotes
+        let pair_quotes = TokenQuotes { base_token: Some(Address::from_low_u64_be(1)), ..quotes(2, now - 100) };
+        let ages = QuoteAges::new(&[quotes(2, now - 900), pair_quotes], Address::zero(), 750);
+        let pair = Opportunity { base_token: Address::from_low_u64_be(1), ..opportunity(2) };
+        assert_eq!(ages.age_ms(&pair, now), Some(100));
+        assert_eq!(ages.age_ms(&opportunity(2), now), Some(900));
+
+        // 0 disables the budget
+        assert!(QuoteAges::new(&[], Address::zero(), 0).is_fresh(&opportunity(3), now));
+    }
+
+    #[test]
+    fn test_quotes_behind_the_chain_head_rejected() {
+        let now = 10_000;
+        // Real-scale Arbitrum One L2 blocks, as arbBlockNumber() and eth_blockNumber report them
+        let head = 265_000_001;
+        let at_block = |token, block| TokenQuotes { block_number: Some(block), ..quotes(token, now) };
+        let unknown_block = quotes(3, now);
+        let ages = QuoteAges::new(&[at_block(1, head - 1), at_block(2, head - 3), unknown_block], Address::zero(), 0)
+            .with_block_limit(head, 1);
+
+        assert_eq!(ages.blocks_behind(&opportunity(1)), Some(1));
+        assert_eq!(ages.blocks_behind(&opportunity(2)), Some(3));
+        // Just fetched by the clock, but three blocks behind the head
+        let (fresh, stale) = ages.split(vec![opportunity(1), opportunity(2), opportunity(3)], now);
+        assert_eq!(stale.iter().map(|opp| opp.token).collect::<Vec<_>>(), vec![Address::from_low_u64_be(2)]);
+        // A failed block read leaves the wall-clock check in charge
+        assert_eq!(fresh.len(), 2);
+        assert_eq!(ages.blocks_behind(&opportunity(3)), None);
+
+        // 0 blocks: the chain must not have moved past the quotes at all
+        let strict = QuoteAges::new(&[at_block(1, head - 1)], Address::zero(), 0).with_block_limit(head, 0);
+        assert!(!strict.is_fresh(&opportunity(1), now));
+
+        // Stamped with the L1 block (Multicall3's getBlockNumber()), every
+        // quote would look hundreds of millions of blocks old
+        let l1_stamped = QuoteAges::new(&[at_block(1, 21_000_000)], Address::zero(), 0).with_block_limit(head, 1_000);
+        assert_eq!(l1_stamped.blocks_behind(&opportunity(1)), Some(244_000_001));
+        assert!(!l1_stamped.is_fresh(&opportunity(1), now));
+    }
+}
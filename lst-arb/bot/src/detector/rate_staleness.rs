@@ -0,0 +1,139 @@
+//! Rate Provider Staleness
+//!
+//! An LRT's fair rate comes from an oracle feed that can stop updating. A
+//! stale rate makes the spread look real when it isn't (or hides one that
+//! is), so while a token's feed is older than the max age its opportunities
+//! need a wider spread, or are skipped entirely.
+
+use ethers::prelude::*;
+use ethers::types::Address;
+use futures::future::join_all;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::rpc::WsClient;
+
+/// Max feed age: LST/LRT rate feeds update on a 24h heartbeat, plus an hour of slack
+pub const DEFAULT_RATE_MAX_AGE_SECS: u64 = 25 * 3600;
+
+/// Extra spread required of a token while its rate is stale
+pub const DEFAULT_STALE_RATE_EXTRA_SPREAD_BPS: u64 = 20;
+
+abigen!(
+    RateFeed,
+    r#"[
+        function latestRoundData() external view returns (uint80 roundId, int256 answer, uint256 startedAt, uint256 updatedAt, uint80 answeredInRound)
+    ]"#
+);
+
+/// How to treat a token whose rate is stale
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StaleRateResponse {
+    /// Require this many extra bps of spread
+    Widen(u64),
+    /// Skip the token until its rate updates
+    Pause,
+}
+
+pub struct RateStaleness {
+    /// Token -> its rate feed
+    feeds: HashMap<Address, Address>,
+    /// Token -> feed's last update (unix secs, 0 until first read)
+    updated_at: parking_lot::RwLock<HashMap<Address, u64>>,
+    max_age_secs: u64,
+    response: StaleRateResponse,
+}
+
+impl RateStaleness {
+    pub fn new(feeds: HashMap<Address, Address>, max_age_secs: u64, response: StaleRateResponse) -> Self {
+        // Unread feeds count as stale until the first refresh
+        let updated_at = feeds.keys().map(|token| (*token, 0)).collect();
+        Self {
+            feeds,
+            updated_at: parking_lot::RwLock::new(updated_at),
+            max_age_secs,
+            response,
+        }
+    }
+
+    pub fn record(&self, token: Address, updated_at: u64) {
+        self.updated_at.write().insert(token, updated_at);
+    }
+
+    /// Whether `token` has a feed that hasn't updated within the max age.
+    /// Tokens without a feed are never stale.
+    pub fn is_stale(&self, token: Address, now_secs: u64) -> bool {
+        self.updated_at.read()
+            .get(&token)
+            .is_some_and(|updated_at| now_secs.saturating_sub(*updated_at) > self.max_age_secs)
+    }
+
+    /// Spread `token` needs given the normal `min_spread_bps`, or None while paused
+    pub fn required_spread_bps(&self, token: Address, min_spread_bps: u64, now_secs: u64) -> Option<u64> {
+        if !self.is_stale(token, now_secs) {
+            return Some(min_spread_bps);
+        }
+        match self.response {
+            StaleRateResponse::Widen(extra_bps) => Some(min_spread_bps.saturating_add(extra_bps)),
+            StaleRateResponse::Pause => None,
+        }
+    }
+
+    /// Read every feed's last update. A failed read keeps the previous time,
+    /// so a feed we can't reach goes stale on its own.
+    pub async fn refresh(&self, client: Arc<WsClient>) {
+        let reads = self.feeds.iter().map(|(token, feed)| {
+            let feed = RateFeed::new(*feed, client.clone());
+            async move { (*token, feed.latest_round_data().call().await) }
+        });
+
+        for (token, result) in join_all(reads).await {
+            match result {
+                Ok((_, _, _, updated_at, _)) => self.record(token, updated_at.low_u64()),
+                Err(e) => warn!("Failed to read rate feed for {:?}: {:?}", token, e),
+            }
+        }
+    }
+}
+
+impl std::str::FromStr for StaleRateResponse {
+    type Err = eyre::Report;
+
+    /// "widen" (with the default extra spread) or "pause"
+    fn from_str(s: &str) -> eyre::Result<Self> {
+        match s {
+            "widen" => Ok(Self::Widen(DEFAULT_STALE_RATE_EXTRA_SPREAD_BPS)),
+            "pause" => Ok(Self::Pause),
+            other => Err(eyre::eyre!("Unknown stale rate response: {}", other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stale_rate_widens_required_spread() {
+        let (weeth, ezeth, wsteth) = (Address::from_low_u64_be(1), Address::from_low_u64_be(2), Address::from_low_u64_be(3));
+        let feeds = HashMap::from([(weeth, Address::from_low_u64_be(11)), (ezeth, Address::from_low_u64_be(12))]);
+        let staleness = RateStaleness::new(feeds, 3600, StaleRateResponse::Widen(25));
+        let now = 1_000_000;
+
+        // Never read yet: treated conservatively
+        assert_eq!(staleness.required_spread_bps(weeth, 10, now), Some(35));
+
+        staleness.record(weeth, now - 600);
+        staleness.record(ezeth, now - 2 * 3600);
+        assert_eq!(staleness.required_spread_bps(weeth, 10, now), Some(10));
+        assert!(staleness.is_stale(ezeth, now));
+        assert_eq!(staleness.required_spread_bps(ezeth, 10, now), Some(35));
+
+        // Tokens without a feed are unaffected
+        assert_eq!(staleness.required_spread_bps(wsteth, 10, now), Some(10));
+
+        let paused = RateStaleness::new(HashMap::from([(ezeth, Address::zero())]), 3600, StaleRateResponse::Pause);
+        assert_eq!(paused.required_spread_bps(ezeth, 10, now), None);
+    }
+}
@@ -0,0 +1,45 @@
+//! Multi-Leg Routes
+//!
+//! Every opportunity carries its trade as an ordered list of legs, so plain
+//! buy/sell arbs, triangular cycles and split routes share one execution
+//! path. A plain arb is the two-leg route WETH -> LST -> WETH.
+
+use ethers::types::{Address, U256};
+
+use crate::price::Venue;
+
+/// One swap of a route
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Leg {
+    pub pool: Address,
+    pub venue: Venue,
+    pub token_in: Address,
+    pub token_out: Address,
+    /// Input of the first leg (the flash-loaned amount). Zero on later legs:
+    /// they swap the whole output of the leg before.
+    pub amount_in: U256,
+}
+
+/// The two legs of a plain arb: buy `token` with `amount` WETH on the buy
+/// pool, sell it all back to WETH on the sell pool
+pub fn pair_route(
+    weth: Address,
+    token: Address,
+    (buy_venue, buy_pool): (Venue, Address),
+    (sell_venue, sell_pool): (Venue, Address),
+    amount: U256,
+) -> Vec<Leg> {
+    vec![
+        Leg { pool: buy_pool, venue: buy_venue, token_in: weth, token_out: token, amount_in: amount },
+        Leg { pool: sell_pool, venue: sell_venue, token_in: token, token_out: weth, amount_in: U256::zero() },
+    ]
+}
+
+/// `route` with its input resized to `amount`
+pub fn with_amount(route: &[Leg], amount: U256) -> Vec<Leg> {
+    let mut route = route.to_vec();
+    if let Some(first) = route.first_mut() {
+        first.amount_in = amount;
+    }
+    route
+}
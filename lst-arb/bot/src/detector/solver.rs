@@ -3,16 +3,18 @@
 //! Calculates optimal input 'x' where P'(x) = 0 for:
 //! - Constant Product AMMs (Uniswap V2/V3)
 //! - StableSwap AMMs (Curve)
+//! - Solidly stable (x³y + y³x = k) and volatile (x * y = k) pools
 //!
 //! Includes liquidity clamping for Arbitrum Balancer Vault
 
 use ethers::prelude::*;
 use ethers::types::{Address, U256};
 use std::sync::Arc;
-use tracing::debug;
+use tracing::{debug, warn};
 
-use crate::rpc::WsClient;
-use crate::price::Venue;
+use crate::chain::ChainProfile;
+use crate::price::{same_pool, Venue};
+use super::flash_fee::flash_fee;
 
 // Arbitrum hardcoded addresses
 pub const ARBITRUM_BALANCER_VAULT: &str = "0xBA12222222228d8Ba445958a75a0704d566BF2C8";
@@ -24,12 +26,25 @@ pub const MAX_LIQUIDITY_PERCENT: u64 = 90;
 // Minimum trade size (0.01 ETH)
 pub const MIN_TRADE_SIZE_WEI: u64 = 10_000_000_000_000_000;
 
+// Solver math runs in whole tokens (wei / 10^18) rather than raw wei
+const SCALE_DECIMALS: usize = 18;
+const SCALE: f64 = 1e18;
+
+// MIN_TRADE_SIZE_WEI in solver units
+const MIN_TRADE_SIZE: f64 = MIN_TRADE_SIZE_WEI as f64 / SCALE;
+
 // Maximum iterations for Newton-Raphson (hard limit for speed on L2)
 pub const MAX_ITERATIONS: u32 = 5;
 
 // Convergence threshold (0.1% relative change)
 pub const CONVERGENCE_THRESHOLD: f64 = 0.001;
 
+// Iteration budget for the StableSwap D and y solves; real pools converge in a handful
+const STABLESWAP_MAX_ITERATIONS: usize = 256;
+
+// Largest sane input as a multiple of the buy pool's WETH reserve
+pub const DEFAULT_MAX_RESERVE_MULTIPLE: f64 = 1.0;
+
 abigen!(
     IERC20,
     r#"[
@@ -41,10 +56,22 @@ abigen!(
 #[derive(Debug, Clone)]
 pub struct PoolParams {
     pub venue: Venue,
+    pub fee_tier: Option<u32>, // Distinguishes pools of the same venue (Uniswap V3 tiers)
+    pub pool: Address,         // Pool address (zero if unknown)
     pub reserve_x: U256,  // ETH/WETH reserve
     pub reserve_y: U256,  // LST reserve
     pub fee_bps: u64,     // Fee in basis points (e.g., 30 = 0.3%)
     pub amp: Option<u64>, // Amplification factor for StableSwap
+    pub stable: bool,     // Solidly stable-curve pool (ignored for other venues)
+}
+
+impl PoolParams {
+    pub fn is_same_pool(&self, other: &PoolParams) -> bool {
+        same_pool(
+            (self.venue, self.fee_tier, self.pool),
+            (other.venue, other.fee_tier, other.pool),
+        )
+    }
 }
 
 /// Optimization result
@@ -54,6 +81,10 @@ pub struct OptimalTrade {
     pub expected_profit: U256,
     pub buy_venue: Venue,
     pub sell_venue: Venue,
+    pub buy_fee_tier: Option<u32>,
+    pub sell_fee_tier: Option<u32>,
+    pub buy_pool: Address,
+    pub sell_pool: Address,
     pub iterations: u32,
 }
 
@@ -61,6 +92,12 @@ pub struct OptimalTrade {
 pub struct Solver {
     balancer_vault: Address,
     weth: Address,
+    /// Inputs beyond this multiple of the buy pool's reserve are rejected (None = no bound)
+    max_reserve_multiple: Option<f64>,
+    /// Re-pick the venue pair at the clamped size when clamping binds
+    reoptimize_after_clamp: bool,
+    /// Flash-loan fee charged on the trade input
+    flash_fee_bps: u64,
 }
 
 impl Solver {
@@ -68,15 +105,77 @@ impl Solver {
         Self {
             balancer_vault: ARBITRUM_BALANCER_VAULT.parse().unwrap(),
             weth: ARBITRUM_WETH.parse().unwrap(),
+            max_reserve_multiple: Some(DEFAULT_MAX_RESERVE_MULTIPLE),
+            reoptimize_after_clamp: false,
+            flash_fee_bps: 0,
+        }
+    }
+
+    /// Use another chain's WETH and Balancer vault for liquidity clamping
+    pub fn with_chain(mut self, chain: &ChainProfile) -> Self {
+        self.weth = chain.weth;
+        self.balancer_vault = chain.balancer_vault;
+        self
+    }
+
+    /// Reject optimal inputs above `multiple` x the buy pool's WETH reserve (0 = no bound)
+    pub fn with_max_reserve_multiple(mut self, multiple: f64) -> Self {
+        self.max_reserve_multiple = (multiple > 0.0).then_some(multiple);
+        self
+    }
+
+    /// After the liquidity clamp cuts a trade, choose the venue pair again
+    /// for the clamped size instead of keeping the unclamped choice
+    pub fn with_reoptimize_after_clamp(mut self, enabled: bool) -> Self {
+        self.reoptimize_after_clamp = enabled;
+        self
+    }
+
+    /// Net the flash-loan fee on the input out of every trade's profit
+    pub fn with_flash_fee_bps(mut self, fee_bps: u64) -> Self {
+        self.flash_fee_bps = fee_bps;
+        self
+    }
+
+    /// Charge the flash-loan fee on a trade's input, or None if nothing is
+    /// left of its profit once the borrowing cost is paid
+    fn net_of_flash_fee(&self, mut trade: OptimalTrade) -> Option<OptimalTrade> {
+        let fee = flash_fee(trade.optimal_input, self.flash_fee_bps);
+        trade.expected_profit = trade.expected_profit.checked_sub(fee).filter(|profit| !profit.is_zero())?;
+        Some(trade)
+    }
+
+    /// Whether a solved input is plausible for the pool it buys from.
+    /// Bad reserves or f64 blow-ups can produce inputs no pool could absorb,
+    /// and clamping would only scale those down to something still harmful.
+    pub fn is_sane(&self, trade: &OptimalTrade, buy_pool: &PoolParams) -> bool {
+        let Some(multiple) = self.max_reserve_multiple else {
+            return true;
+        };
+        let (Some(input), Some(reserve)) = (u256_to_f64(trade.optimal_input), u256_to_f64(buy_pool.reserve_x)) else {
+            return false;
+        };
+
+        if input > reserve * multiple {
+            warn!(
+                "🚫 Rejecting absurd solver input {} ETH for {:?} pool with {} ETH reserve",
+                ethers::utils::format_ether(trade.optimal_input),
+                buy_pool.venue,
+                ethers::utils::format_ether(buy_pool.reserve_x)
+            );
+            return false;
         }
+        true
     }
 
-    /// Fetch WETH balance from Arbitrum Balancer Vault for liquidity clamping
-    pub async fn fetch_vault_weth_balance(&self, client: Arc<WsClient>) -> eyre::Result<U256> {
-        let weth_contract = IERC20::new(self.weth, client);
-        let balance = weth_contract.balance_of(self.balancer_vault).call().await?;
+    /// Fetch the Balancer Vault's balance of `token` (the flash-loaned
+    /// asset) for liquidity clamping
+    pub async fn fetch_vault_balance<M: Middleware + 'static>(&self, client: Arc<M>, token: Address) -> eyre::Result<U256> {
+        let token_contract = IERC20::new(token, client);
+        let balance = token_contract.balance_of(self.balancer_vault).call().await?;
         debug!(
-            "Balancer Vault WETH balance: {} ETH",
+            "Balancer Vault balance of {:?}: {}",
+            token,
             ethers::utils::format_ether(balance)
         );
         Ok(balance)
@@ -117,10 +216,10 @@ impl Solver {
         sell_pool: &PoolParams,
     ) -> Option<OptimalTrade> {
         // Convert to f64 for numerical optimization
-        let buy_x = u256_to_f64(buy_pool.reserve_x)?;
-        let buy_y = u256_to_f64(buy_pool.reserve_y)?;
-        let sell_x = u256_to_f64(sell_pool.reserve_y)?; // Note: LST is "x" in sell pool
-        let sell_y = u256_to_f64(sell_pool.reserve_x)?; // ETH is "y" in sell pool
+        let buy_x = to_scaled(buy_pool.reserve_x)?;
+        let buy_y = to_scaled(buy_pool.reserve_y)?;
+        let sell_x = to_scaled(sell_pool.reserve_y)?; // Note: LST is "x" in sell pool
+        let sell_y = to_scaled(sell_pool.reserve_x)?; // ETH is "y" in sell pool
 
         // Fee multipliers (1 - fee)
         let buy_fee = 1.0 - (buy_pool.fee_bps as f64 / 10000.0);
@@ -145,7 +244,7 @@ impl Solver {
 
         let optimal_x = numerator / denominator;
 
-        if optimal_x < MIN_TRADE_SIZE_WEI as f64 {
+        if optimal_x < MIN_TRADE_SIZE {
             debug!("Optimal trade size below minimum threshold");
             return None;
         }
@@ -160,10 +259,14 @@ impl Solver {
         }
 
         Some(OptimalTrade {
-            optimal_input: f64_to_u256(optimal_x)?,
-            expected_profit: f64_to_u256(profit)?,
+            optimal_input: from_scaled(optimal_x)?,
+            expected_profit: from_scaled(profit)?,
             buy_venue: buy_pool.venue,
             sell_venue: sell_pool.venue,
+            buy_fee_tier: buy_pool.fee_tier,
+            sell_fee_tier: sell_pool.fee_tier,
+            buy_pool: buy_pool.pool,
+            sell_pool: sell_pool.pool,
             iterations: 1, // Closed-form solution
         })
     }
@@ -184,10 +287,10 @@ impl Solver {
         let amp_buy = buy_pool.amp.unwrap_or(100) as f64;
         let amp_sell = sell_pool.amp.unwrap_or(100) as f64;
 
-        let buy_x = u256_to_f64(buy_pool.reserve_x)?;
-        let buy_y = u256_to_f64(buy_pool.reserve_y)?;
-        let sell_x = u256_to_f64(sell_pool.reserve_y)?;
-        let sell_y = u256_to_f64(sell_pool.reserve_x)?;
+        let buy_x = to_scaled(buy_pool.reserve_x)?;
+        let buy_y = to_scaled(buy_pool.reserve_y)?;
+        let sell_x = to_scaled(sell_pool.reserve_y)?;
+        let sell_y = to_scaled(sell_pool.reserve_x)?;
 
         let buy_fee = 1.0 - (buy_pool.fee_bps as f64 / 10000.0);
         let sell_fee = 1.0 - (sell_pool.fee_bps as f64 / 10000.0);
@@ -195,7 +298,7 @@ impl Solver {
         // Use Newton-Raphson to find optimal x
         // Start with geometric mean of reserves as initial guess
         let mut x = ((buy_x * sell_y) / 1000.0).sqrt();
-        x = x.max(MIN_TRADE_SIZE_WEI as f64);
+        x = x.max(MIN_TRADE_SIZE);
 
         for i in 0..MAX_ITERATIONS {
             // Calculate output from buy pool (ETH -> LST)
@@ -236,7 +339,7 @@ impl Solver {
             };
 
             // Ensure x stays positive
-            let x_new = x_new.max(MIN_TRADE_SIZE_WEI as f64);
+            let x_new = x_new.max(MIN_TRADE_SIZE);
 
             // Check for convergence
             if ((x_new - x) / x).abs() < CONVERGENCE_THRESHOLD {
@@ -247,10 +350,14 @@ impl Solver {
 
                 if final_profit > 0.0 {
                     return Some(OptimalTrade {
-                        optimal_input: f64_to_u256(x_new)?,
-                        expected_profit: f64_to_u256(final_profit)?,
+                        optimal_input: from_scaled(x_new)?,
+                        expected_profit: from_scaled(final_profit)?,
                         buy_venue: buy_pool.venue,
                         sell_venue: sell_pool.venue,
+                        buy_fee_tier: buy_pool.fee_tier,
+                        sell_fee_tier: sell_pool.fee_tier,
+                        buy_pool: buy_pool.pool,
+                        sell_pool: sell_pool.pool,
                         iterations: i + 1,
                     });
                 }
@@ -265,12 +372,16 @@ impl Solver {
         let final_eth = stableswap_get_dy(sell_x, sell_y, final_lst * sell_fee, amp_sell)?;
         let final_profit = final_eth - x;
 
-        if final_profit > 0.0 && x >= MIN_TRADE_SIZE_WEI as f64 {
+        if final_profit > 0.0 && x >= MIN_TRADE_SIZE {
             Some(OptimalTrade {
-                optimal_input: f64_to_u256(x)?,
-                expected_profit: f64_to_u256(final_profit)?,
+                optimal_input: from_scaled(x)?,
+                expected_profit: from_scaled(final_profit)?,
                 buy_venue: buy_pool.venue,
                 sell_venue: sell_pool.venue,
+                buy_fee_tier: buy_pool.fee_tier,
+                sell_fee_tier: sell_pool.fee_tier,
+                buy_pool: buy_pool.pool,
+                sell_pool: sell_pool.pool,
                 iterations: MAX_ITERATIONS,
             })
         } else {
@@ -288,26 +399,13 @@ impl Solver {
         // Try all combinations of buy/sell venues
         for buy_pool in pools {
             for sell_pool in pools {
-                if buy_pool.venue == sell_pool.venue {
+                // Buying and selling on one pool is a guaranteed loss, but
+                // different pools of the same venue type can still arb
+                if buy_pool.is_same_pool(sell_pool) {
                     continue;
                 }
 
-                let trade = match (buy_pool.venue, sell_pool.venue) {
-                    // Both are StableSwap (Curve)
-                    (Venue::Curve, Venue::Curve) => {
-                        self.optimal_stableswap(buy_pool, sell_pool)
-                    }
-                    // Both are Constant Product
-                    (Venue::UniswapV3 | Venue::Balancer, Venue::UniswapV3 | Venue::Balancer) => {
-                        self.optimal_constant_product(buy_pool, sell_pool)
-                    }
-                    // Mixed: Use numerical optimization
-                    _ => {
-                        self.optimal_mixed(buy_pool, sell_pool)
-                    }
-                };
-
-                if let Some(t) = trade {
+                if let Some(t) = self.optimal_pair(buy_pool, sell_pool) {
                     match &best_trade {
                         None => best_trade = Some(t),
                         Some(best) if t.expected_profit > best.expected_profit => {
@@ -322,16 +420,76 @@ impl Solver {
         best_trade
     }
 
+    /// Optimal trade buying from `buy_pool` and selling into `sell_pool`
+    fn optimal_pair(&self, buy_pool: &PoolParams, sell_pool: &PoolParams) -> Option<OptimalTrade> {
+        let trade = match (buy_pool.venue, sell_pool.venue) {
+            // Both are StableSwap (Curve)
+            (Venue::Curve, Venue::Curve) => {
+                self.optimal_stableswap(buy_pool, sell_pool)
+            }
+            // Both are Constant Product
+            (Venue::UniswapV3 | Venue::Balancer, Venue::UniswapV3 | Venue::Balancer) => {
+                self.optimal_constant_product(buy_pool, sell_pool)
+            }
+            // Mixed: Use numerical optimization
+            _ => {
+                self.optimal_mixed(buy_pool, sell_pool)
+            }
+        };
+        trade.filter(|t| self.is_sane(t, buy_pool))
+            .and_then(|t| self.net_of_flash_fee(t))
+    }
+
+    /// Best trade across all venue combinations with the input capped at
+    /// `max_input`. Profit is concave in the input, so a pair whose optimum
+    /// is above the cap does best at the cap itself.
+    pub fn find_optimal_trade_capped(&self, pools: &[PoolParams], max_input: U256) -> Option<OptimalTrade> {
+        let cap = to_scaled(max_input)?;
+        let mut best_trade: Option<OptimalTrade> = None;
+
+        for buy_pool in pools {
+            for sell_pool in pools {
+                if buy_pool.is_same_pool(sell_pool) {
+                    continue;
+                }
+                let Some(mut t) = self.optimal_pair(buy_pool, sell_pool) else {
+                    continue;
+                };
+
+                if t.optimal_input > max_input {
+                    let Some(profit) = pair_profit(buy_pool, sell_pool, cap).filter(|p| *p > 0.0) else {
+                        continue;
+                    };
+                    let Some(profit) = from_scaled(profit) else {
+                        continue;
+                    };
+                    t.optimal_input = max_input;
+                    t.expected_profit = profit;
+                    let Some(net) = self.net_of_flash_fee(t) else {
+                        continue;
+                    };
+                    t = net;
+                }
+
+                if best_trade.as_ref().is_none_or(|best| t.expected_profit > best.expected_profit) {
+                    best_trade = Some(t);
+                }
+            }
+        }
+
+        best_trade
+    }
+
     /// Optimal trade for mixed AMM types using numerical gradient descent
     fn optimal_mixed(
         &self,
         buy_pool: &PoolParams,
         sell_pool: &PoolParams,
     ) -> Option<OptimalTrade> {
-        let buy_x = u256_to_f64(buy_pool.reserve_x)?;
-        let buy_y = u256_to_f64(buy_pool.reserve_y)?;
-        let sell_x = u256_to_f64(sell_pool.reserve_y)?;
-        let sell_y = u256_to_f64(sell_pool.reserve_x)?;
+        let buy_x = to_scaled(buy_pool.reserve_x)?;
+        let buy_y = to_scaled(buy_pool.reserve_y)?;
+        let sell_x = to_scaled(sell_pool.reserve_y)?;
+        let sell_y = to_scaled(sell_pool.reserve_x)?;
 
         let buy_fee = 1.0 - (buy_pool.fee_bps as f64 / 10000.0);
         let sell_fee = 1.0 - (sell_pool.fee_bps as f64 / 10000.0);
@@ -346,15 +504,11 @@ impl Solver {
                 (sell_x, sell_y, amp_sell)
             };
             let fee = if is_buy { buy_fee } else { sell_fee };
-
-            match pool.venue {
-                Venue::Curve => stableswap_get_dy(x, y, input * fee, amp),
-                _ => Some(fee * y * input / (x + fee * input)), // Constant product
-            }
+            venue_output(pool, x, y, amp, input * fee)
         };
 
         // Golden section search for optimal x
-        let mut a = MIN_TRADE_SIZE_WEI as f64;
+        let mut a = MIN_TRADE_SIZE;
         let mut b = buy_x.min(sell_y) * 0.5; // Cap at 50% of smaller reserve
         let phi = (1.0 + 5.0_f64.sqrt()) / 2.0;
 
@@ -380,7 +534,7 @@ impl Solver {
                 a = c;
             }
 
-            if (b - a).abs() < MIN_TRADE_SIZE_WEI as f64 {
+            if (b - a).abs() < MIN_TRADE_SIZE {
                 break;
             }
         }
@@ -390,12 +544,16 @@ impl Solver {
         let eth_received = calc_output(lst_bought, sell_pool, false)?;
         let profit = eth_received - optimal_x;
 
-        if profit > 0.0 && optimal_x >= MIN_TRADE_SIZE_WEI as f64 {
+        if profit > 0.0 && optimal_x >= MIN_TRADE_SIZE {
             Some(OptimalTrade {
-                optimal_input: f64_to_u256(optimal_x)?,
-                expected_profit: f64_to_u256(profit)?,
+                optimal_input: from_scaled(optimal_x)?,
+                expected_profit: from_scaled(profit)?,
                 buy_venue: buy_pool.venue,
                 sell_venue: sell_pool.venue,
+                buy_fee_tier: buy_pool.fee_tier,
+                sell_fee_tier: sell_pool.fee_tier,
+                buy_pool: buy_pool.pool,
+                sell_pool: sell_pool.pool,
                 iterations: MAX_ITERATIONS,
             })
         } else {
@@ -403,41 +561,70 @@ impl Solver {
         }
     }
 
-    /// Find optimal trade with liquidity clamping
-    pub async fn find_optimal_trade_clamped(
+    /// Clamp a solved trade to 90% of `vault_balance`, recomputing its profit
+    ///
+    /// Profit is concave in the input and zero at zero, so scaling it with
+    /// the input gives a lower bound for the clamped trade.
+    pub fn clamp_trade(&self, optimal: OptimalTrade, vault_balance: U256) -> OptimalTrade {
+        let clamped_input = self.clamp_to_liquidity(optimal.optimal_input, vault_balance);
+        if clamped_input >= optimal.optimal_input {
+            return optimal;
+        }
+
+        debug!(
+            "Trade clamped: {} -> {} ETH",
+            ethers::utils::format_ether(optimal.optimal_input),
+            ethers::utils::format_ether(clamped_input)
+        );
+
+        // Profit will be lower but the flash loan won't revert
+        OptimalTrade {
+            optimal_input: clamped_input,
+            expected_profit: optimal.expected_profit * clamped_input / optimal.optimal_input,
+            ..optimal
+        }
+    }
+
+    /// Clamp a solved trade and, if enabled and the clamp binds, switch to
+    /// the venue pair that does best at the clamped size
+    pub fn clamp_and_reoptimize(&self, pools: &[PoolParams], optimal: OptimalTrade, vault_balance: U256) -> OptimalTrade {
+        let unclamped_input = optimal.optimal_input;
+        let clamped = self.clamp_trade(optimal, vault_balance);
+        if !self.reoptimize_after_clamp || clamped.optimal_input >= unclamped_input {
+            return clamped;
+        }
+
+        match self.find_optimal_trade_capped(pools, clamped.optimal_input) {
+            Some(better) if better.expected_profit > clamped.expected_profit => {
+                debug!(
+                    "Re-optimized clamped trade: {:?} -> {:?} to {:?} -> {:?}",
+                    clamped.buy_venue, clamped.sell_venue, better.buy_venue, better.sell_venue
+                );
+                better
+            }
+            _ => clamped,
+        }
+    }
+
+    /// Find optimal trade with liquidity clamping. `base` is what's
+    /// flash-loaned, for an LST/LST pair (None = WETH).
+    pub async fn find_optimal_trade_clamped<M: Middleware + 'static>(
         &self,
-        client: Arc<WsClient>,
+        client: Arc<M>,
         pools: &[PoolParams],
+        base: Option<Address>,
     ) -> eyre::Result<Option<OptimalTrade>> {
-        // Find mathematically optimal trade
-        let optimal = match self.find_optimal_trade(pools) {
-            Some(t) => t,
-            None => return Ok(None),
+        let Some(optimal) = self.find_optimal_trade(pools) else {
+            return Ok(None);
         };
 
-        // Fetch vault balance for clamping
-        let vault_balance = self.fetch_vault_weth_balance(client).await?;
-
-        // Clamp to 90% of vault liquidity
-        let clamped_input = self.clamp_to_liquidity(optimal.optimal_input, vault_balance);
-
-        // If significantly clamped, recalculate expected profit
-        if clamped_input < optimal.optimal_input {
-            debug!(
-                "Trade clamped: {} -> {} ETH",
-                ethers::utils::format_ether(optimal.optimal_input),
-                ethers::utils::format_ether(clamped_input)
-            );
-
-            // Return clamped trade (profit will be lower but trade won't revert)
-            Ok(Some(OptimalTrade {
-                optimal_input: clamped_input,
-                expected_profit: optimal.expected_profit * clamped_input / optimal.optimal_input,
-                ..optimal
-            }))
-        } else {
-            Ok(Some(optimal))
+        // No Balancer vault on this chain, so nothing to clamp against
+        if self.balancer_vault.is_zero() {
+            return Ok(Some(optimal));
         }
+
+        let vault_balance = self.fetch_vault_balance(client, base.unwrap_or(self.weth)).await?;
+        Ok(Some(self.clamp_and_reoptimize(pools, optimal, vault_balance)))
     }
 }
 
@@ -447,8 +634,34 @@ impl Default for Solver {
     }
 }
 
+/// Output of a pool with (in, out) reserves `x`, `y` for `dx` in after fees
+fn venue_output(pool: &PoolParams, x: f64, y: f64, amp: f64, dx: f64) -> Option<f64> {
+    match pool.venue {
+        Venue::Curve => stableswap_get_dy(x, y, dx, amp),
+        Venue::Solidly => solidly_get_dy(x, y, dx, pool.stable),
+        // Maverick's liquidity bins behave like constant product within the active bin
+        Venue::UniswapV3 | Venue::Balancer | Venue::Maverick => Some(y * dx / (x + dx)),
+    }
+}
+
+/// Profit (solver units) of buying from `buy_pool` with `input` WETH and
+/// selling the LST into `sell_pool`
+fn pair_profit(buy_pool: &PoolParams, sell_pool: &PoolParams, input: f64) -> Option<f64> {
+    let leg = |pool: &PoolParams, x: U256, y: U256, input: f64| {
+        let fee = 1.0 - pool.fee_bps as f64 / 10000.0;
+        venue_output(pool, to_scaled(x)?, to_scaled(y)?, pool.amp.unwrap_or(100) as f64, input * fee)
+    };
+    let lst = leg(buy_pool, buy_pool.reserve_x, buy_pool.reserve_y, input)?;
+    let eth = leg(sell_pool, sell_pool.reserve_y, sell_pool.reserve_x, lst)?;
+    Some(eth - input)
+}
+
 /// StableSwap output calculation
 /// D = A * n^n * sum(x_i) + D / (n^n * prod(x_i) / D^n)
+///
+/// None if D or the new y doesn't converge within the iteration budget -
+/// pathological reserves (one side near zero, extreme imbalance) overflow
+/// or oscillate, and whatever the loop stopped on would be garbage.
 fn stableswap_get_dy(x: f64, y: f64, dx: f64, amp: f64) -> Option<f64> {
     // Simplified 2-coin StableSwap
     // D = 2 * A * (x + y) + D - A * D + D^3 / (4 * x * y * D)
@@ -462,16 +675,23 @@ fn stableswap_get_dy(x: f64, y: f64, dx: f64, amp: f64) -> Option<f64> {
     }
 
     let mut d = s;
-    for _ in 0..256 {
+    let mut converged = false;
+    for _ in 0..STABLESWAP_MAX_ITERATIONS {
         let d_p = d * d * d / (4.0 * x * y);
         let d_new = (ann * s + d_p * n) * d / ((ann - 1.0) * d + (n + 1.0) * d_p);
 
-        if (d_new - d).abs() < 1.0 {
+        // Relative tolerance so the result doesn't depend on the units reserves are in.
+        // NaN never compares as converged.
+        if (d_new - d).abs() <= d * 1e-15 {
             d = d_new;
+            converged = true;
             break;
         }
         d = d_new;
     }
+    if !converged {
+        return None;
+    }
 
     // Calculate y after swap
     let x_new = x + dx;
@@ -479,14 +699,20 @@ fn stableswap_get_dy(x: f64, y: f64, dx: f64, amp: f64) -> Option<f64> {
     let c = d * d * d / (4.0 * ann * x_new);
     let b = x_new + d / ann;
 
-    for _ in 0..256 {
+    converged = false;
+    for _ in 0..STABLESWAP_MAX_ITERATIONS {
         let y_prev = y_new;
         y_new = (y_new * y_new + c) / (2.0 * y_new + b - d);
 
-        if (y_new - y_prev).abs() < 1.0 {
+        // The update subtracts D, so its rounding error scales with D rather than y
+        if (y_new - y_prev).abs() <= y_new.max(d) * 1e-15 {
+            converged = true;
             break;
         }
     }
+    if !converged || !y_new.is_finite() {
+        return None;
+    }
 
     if y > y_new {
         Some(y - y_new)
@@ -495,8 +721,70 @@ fn stableswap_get_dy(x: f64, y: f64, dx: f64, amp: f64) -> Option<f64> {
     }
 }
 
+/// (WETH, LST) reserves of the constant-product pool whose output matches
+/// the smallest and largest of these (WETH in, LST out) quotes
+///
+/// For x * y = k with fee multiplier f, 1/out = x / (f * y) * (1/in) + 1/y,
+/// so two sizes pin down both reserves. This captures the impact a large
+/// trade actually sees, where a 1 ETH quote alone says nothing about depth.
+pub fn implied_reserves(buy: &[(U256, U256)], fee_bps: u64) -> Option<(U256, U256)> {
+    let (&(small_in, small_out), &(large_in, large_out)) = (buy.first()?, buy.last()?);
+    if small_in >= large_in || small_out.is_zero() || large_out.is_zero() {
+        return None;
+    }
+
+    let inv = |v: U256| to_scaled(v).filter(|v| *v > 0.0).map(|v| 1.0 / v);
+    let (inv_small_in, inv_small_out) = (inv(small_in)?, inv(small_out)?);
+    let (inv_large_in, inv_large_out) = (inv(large_in)?, inv(large_out)?);
+
+    let slope = (inv_small_out - inv_large_out) / (inv_small_in - inv_large_in);
+    let intercept = inv_small_out - slope * inv_small_in;
+    // No impact between the sizes (or noise): nothing to fit
+    if slope <= 0.0 || intercept <= 0.0 {
+        return None;
+    }
+
+    let fee = 1.0 - fee_bps as f64 / 10_000.0;
+    let reserve_y = 1.0 / intercept;
+    let reserve_x = slope * fee * reserve_y;
+    Some((from_scaled(reserve_x)?, from_scaled(reserve_y)?))
+}
+
+/// Solidly (Velodrome/Aerodrome) output for `dx` in after fees
+///
+/// Volatile pools are constant product. Stable pools hold x³y + y³x = k,
+/// solved for the new y by Newton's method as the pool contract does.
+pub fn solidly_get_dy(x: f64, y: f64, dx: f64, stable: bool) -> Option<f64> {
+    if x <= 0.0 || y <= 0.0 || dx < 0.0 {
+        return None;
+    }
+    if !stable {
+        return Some(y * dx / (x + dx));
+    }
+
+    let k = x * y * (x * x + y * y);
+    let x_new = x + dx;
+    let mut y_new = y;
+    for _ in 0..255 {
+        let f = x_new * y_new * y_new * y_new + x_new * x_new * x_new * y_new;
+        let f_prime = 3.0 * x_new * y_new * y_new + x_new * x_new * x_new;
+        let y_next = y_new - (f - k) / f_prime;
+
+        if (y_next - y_new).abs() <= y_next * 1e-15 {
+            y_new = y_next;
+            break;
+        }
+        y_new = y_next;
+    }
+
+    if !y_new.is_finite() {
+        return None;
+    }
+    Some((y - y_new).max(0.0))
+}
+
 /// Convert U256 to f64 (with precision loss for large numbers)
-fn u256_to_f64(val: U256) -> Option<f64> {
+pub(crate) fn u256_to_f64(val: U256) -> Option<f64> {
     // Handle the conversion carefully to avoid overflow
     let mut result = 0.0f64;
     let mut val = val;
@@ -515,8 +803,29 @@ fn u256_to_f64(val: U256) -> Option<f64> {
     }
 }
 
+/// Rescale a wei amount to whole tokens for the solver's f64 math
+///
+/// The integer and fractional parts are split exactly in U256 first, so the
+/// only rounding is the final f64 conversion of each part.
+pub(crate) fn to_scaled(val: U256) -> Option<f64> {
+    let (whole, frac) = val.div_mod(U256::exp10(SCALE_DECIMALS));
+    Some(u256_to_f64(whole)? + frac.as_u64() as f64 / SCALE)
+}
+
+/// Inverse of `to_scaled`: whole tokens back to wei
+pub(crate) fn from_scaled(val: f64) -> Option<U256> {
+    if val < 0.0 || !val.is_finite() {
+        return None;
+    }
+    let whole = val.trunc();
+    let frac_wei = ((val - whole) * SCALE).round() as u64;
+    f64_to_u256(whole)?
+        .checked_mul(U256::exp10(SCALE_DECIMALS))?
+        .checked_add(U256::from(frac_wei))
+}
+
 /// Convert f64 to U256
-fn f64_to_u256(val: f64) -> Option<U256> {
+pub(crate) fn f64_to_u256(val: f64) -> Option<U256> {
     if val < 0.0 || !val.is_finite() {
         return None;
     }
@@ -541,18 +850,24 @@ mod tests {
 
         let buy_pool = PoolParams {
             venue: Venue::UniswapV3,
+            fee_tier: None,
+            pool: Address::zero(),
             reserve_x: ethers::utils::parse_ether("1000.0").unwrap(), // 1000 ETH
             reserve_y: ethers::utils::parse_ether("950.0").unwrap(),  // 950 LST (cheaper to buy)
             fee_bps: 30, // 0.3%
             amp: None,
+            stable: false,
         };
 
         let sell_pool = PoolParams {
             venue: Venue::Balancer,
+            fee_tier: None,
+            pool: Address::zero(),
             reserve_x: ethers::utils::parse_ether("500.0").unwrap(), // 500 ETH
             reserve_y: ethers::utils::parse_ether("480.0").unwrap(), // 480 LST (more expensive)
             fee_bps: 30,
             amp: None,
+            stable: false,
         };
 
         let result = solver.optimal_constant_product(&buy_pool, &sell_pool);
@@ -564,6 +879,68 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_sanity_bound_rejects_absurd_input() {
+        let pool = PoolParams {
+            venue: Venue::UniswapV3,
+            fee_tier: Some(500),
+            pool: Address::zero(),
+            reserve_x: ethers::utils::parse_ether("100.0").unwrap(),
+            reserve_y: ethers::utils::parse_ether("95.0").unwrap(),
+            fee_bps: 5,
+            amp: None,
+            stable: false,
+        };
+        let trade = |input: &str| OptimalTrade {
+            optimal_input: ethers::utils::parse_ether(input).unwrap(),
+            expected_profit: ethers::utils::parse_ether("1.0").unwrap(),
+            buy_venue: Venue::UniswapV3,
+            sell_venue: Venue::Balancer,
+            buy_fee_tier: Some(500),
+            sell_fee_tier: None,
+            buy_pool: Address::zero(),
+            sell_pool: Address::zero(),
+            iterations: 1,
+        };
+
+        let solver = Solver::new();
+        assert!(solver.is_sane(&trade("40.0"), &pool));
+        // Degenerate output worth a million times the pool
+        assert!(!solver.is_sane(&trade("100000000.0"), &pool));
+
+        let loose = Solver::new().with_max_reserve_multiple(0.0);
+        assert!(loose.is_sane(&trade("100000000.0"), &pool));
+    }
+
+    #[test]
+    fn test_scaled_round_trip_relative_error() {
+        use rand::{Rng, SeedableRng};
+
+        let relative_error = |value: U256| {
+            let back = from_scaled(to_scaled(value).unwrap()).unwrap();
+            let diff = if back > value { back - value } else { value - back };
+            u256_to_f64(diff).unwrap() / u256_to_f64(value).unwrap()
+        };
+
+        // Realistic reserves, e.g. 1234.567890123456789 ETH
+        let reserve = U256::from_dec_str("1234567890123456789012").unwrap();
+        assert!((to_scaled(reserve).unwrap() - 1_234.567_890_123_457).abs() < 1e-9);
+
+        // Every magnitude from 1 wei up to 10^27 (a billion tokens)
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        for exponent in 0..=27 {
+            for _ in 0..100 {
+                let mantissa = rng.gen_range(1_000_000_000u64..10_000_000_000u64);
+                let value = U256::from(mantissa) * U256::exp10(exponent) / U256::exp10(9);
+                if value.is_zero() {
+                    continue;
+                }
+                assert!(relative_error(value) < 1e-9, "{} round-tripped badly", value);
+            }
+        }
+        assert!(relative_error(U256::exp10(27)) < 1e-9);
+    }
+
     #[test]
     fn test_liquidity_clamping() {
         let solver = Solver::new();
@@ -578,6 +955,139 @@ mod tests {
         assert_eq!(clamped, expected);
     }
 
+    #[test]
+    fn test_solidly_get_dy_matches_pool_math() {
+        // Reference outputs from the Velodrome pair's integer _getAmountOut
+        // (18-decimal tokens) for reserves of 1000 in / 1050 out
+        let cases = [
+            (1.0, 1.0000273239246686, 1.048951048951049),
+            (10.0, 10.00015786241283, 10.396039603960396),
+            (100.0, 99.98549646909744, 95.45454545454545),
+        ];
+        for (dx, stable_out, volatile_out) in cases {
+            let stable = solidly_get_dy(1000.0, 1050.0, dx, true).unwrap();
+            let volatile = solidly_get_dy(1000.0, 1050.0, dx, false).unwrap();
+            assert!(((stable - stable_out) / stable_out).abs() < 1e-9, "stable {} vs {}", stable, stable_out);
+            assert!(((volatile - volatile_out) / volatile_out).abs() < 1e-9, "volatile {} vs {}", volatile, volatile_out);
+        }
+
+        // Units don't change the result (the solver works in whole tokens)
+        let wei = solidly_get_dy(1000e18, 1050e18, 10e18, true).unwrap() / 1e18;
+        assert!((wei - 10.00015786241283).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_stableswap_get_dy_rejects_degenerate_reserves() {
+        // Balanced pools converge, including a trade as large as the reserve
+        let small = stableswap_get_dy(1000.0, 1000.0, 1.0, 100.0).unwrap();
+        assert!((small - 0.999995).abs() < 1e-6);
+        let large = stableswap_get_dy(1.0, 1.0, 1.0, 100.0).unwrap();
+        assert!(large > 0.9 && large < 1.0);
+
+        // One reserve (near) zero: D overflows to NaN and never converges
+        assert_eq!(stableswap_get_dy(1e-300, 1000.0, 1.0, 100.0), None);
+        assert_eq!(stableswap_get_dy(0.0, 1000.0, 1.0, 100.0), None);
+        assert_eq!(stableswap_get_dy(1000.0, 1e-300, 1.0, 100.0), None);
+
+        // Extreme imbalance either way
+        assert_eq!(stableswap_get_dy(1e-200, 1e200, 1.0, 100.0), None);
+        assert_eq!(stableswap_get_dy(1e300, 1.0, 1.0, 100.0), None);
+
+        // The solver sees no opportunity rather than a bogus profit
+        let solver = Solver::new();
+        let pool = |reserve_x: f64, reserve_y: f64| PoolParams {
+            venue: Venue::Curve,
+            fee_tier: None,
+            pool: Address::zero(),
+            reserve_x: from_scaled(reserve_x).unwrap(),
+            reserve_y: from_scaled(reserve_y).unwrap(),
+            fee_bps: 4,
+            amp: Some(100),
+            stable: false,
+        };
+        assert!(solver.optimal_stableswap(&pool(1e-30, 1e40), &pool(1000.0, 1000.0)).is_none());
+    }
+
+    #[test]
+    fn test_implied_reserves_from_sized_quotes() {
+        // Constant-product pool of 2000 WETH / 1800 LST at 5 bps
+        let (x, y, fee) = (2000.0, 1800.0, 0.9995);
+        let quote = |eth: f64| {
+            let out = y * eth * fee / (x + eth * fee);
+            (f64_to_u256(eth * 1e18).unwrap(), f64_to_u256(out * 1e18).unwrap())
+        };
+
+        let (reserve_x, reserve_y) = implied_reserves(&[quote(1.0), quote(10.0), quote(50.0)], 5).unwrap();
+        let (reserve_x, reserve_y) = (to_scaled(reserve_x).unwrap(), to_scaled(reserve_y).unwrap());
+        assert!((reserve_x - x).abs() / x < 1e-3, "reserve_x {}", reserve_x);
+        assert!((reserve_y - y).abs() / y < 1e-3, "reserve_y {}", reserve_y);
+
+        // A single size (or no impact between sizes) can't be fitted
+        assert!(implied_reserves(&[quote(1.0)], 5).is_none());
+        let flat = |eth: f64| (f64_to_u256(eth * 1e18).unwrap(), f64_to_u256(eth * 0.9 * 1e18).unwrap());
+        assert!(implied_reserves(&[flat(1.0), flat(50.0)], 5).is_none());
+    }
+
+    #[test]
+    fn test_reoptimize_picks_better_pair_at_clamped_size() {
+        let eth = |v: &str| ethers::utils::parse_ether(v).unwrap();
+        let pool = |id: u64, x: &str, y: &str| PoolParams {
+            venue: Venue::UniswapV3,
+            fee_tier: Some(500),
+            pool: Address::from_low_u64_be(id),
+            reserve_x: eth(x),
+            reserve_y: eth(y),
+            fee_bps: 5,
+            amp: None,
+            stable: false,
+        };
+        // Two deep pools 1% apart, and a shallow pool 3% cheap
+        let pools = [pool(1, "10000", "10000"), pool(2, "10000", "9900"), pool(3, "100", "103")];
+
+        let solver = Solver::new().with_reoptimize_after_clamp(true);
+        let optimal = solver.find_optimal_trade(&pools).unwrap();
+        assert_eq!(optimal.buy_pool, Address::from_low_u64_be(1));
+        assert!(optimal.optimal_input > eth("10"));
+
+        // A 1 ETH vault caps the trade at 0.9 ETH, where the shallow pool wins
+        let scaled = Solver::new().clamp_and_reoptimize(&pools, optimal.clone(), eth("1"));
+        let reoptimized = solver.clamp_and_reoptimize(&pools, optimal, eth("1"));
+        assert_eq!(scaled.buy_pool, Address::from_low_u64_be(1));
+        assert_eq!(reoptimized.buy_pool, Address::from_low_u64_be(3));
+        assert!(reoptimized.optimal_input <= eth("0.9"));
+        assert!(reoptimized.expected_profit > scaled.expected_profit * 2);
+    }
+
+    #[test]
+    fn test_clamp_trade_without_client() {
+        let solver = Solver::new();
+        let eth = |v: &str| ethers::utils::parse_ether(v).unwrap();
+        let pool = |fee_tier: u32, x: &str, y: &str| PoolParams {
+            venue: Venue::UniswapV3,
+            fee_tier: Some(fee_tier),
+            pool: Address::zero(),
+            reserve_x: eth(x),
+            reserve_y: eth(y),
+            fee_bps: 5,
+            amp: None,
+            stable: false,
+        };
+        let pools = [pool(100, "1000", "1030"), pool(500, "1000", "960")];
+        let optimal = solver.find_optimal_trade(&pools).expect("arb between pools");
+        assert!(optimal.optimal_input > eth("1"));
+
+        // A deep vault leaves the trade alone
+        let unclamped = solver.clamp_trade(optimal.clone(), eth("100000"));
+        assert_eq!(unclamped.optimal_input, optimal.optimal_input);
+        assert_eq!(unclamped.expected_profit, optimal.expected_profit);
+
+        // A shallow vault caps the input at 90% and scales profit down with it
+        let clamped = solver.clamp_trade(optimal.clone(), eth("1"));
+        assert_eq!(clamped.optimal_input, eth("0.9"));
+        assert_eq!(clamped.expected_profit, optimal.expected_profit * eth("0.9") / optimal.optimal_input);
+        assert_eq!(clamped.buy_venue, optimal.buy_venue);
+    }
+
     #[test]
     fn test_solver_speed() {
         use std::time::Instant;
@@ -587,18 +1097,24 @@ mod tests {
         // Set up test pools for constant product (Uniswap V3)
         let buy_pool = PoolParams {
             venue: Venue::UniswapV3,
+            fee_tier: None,
+            pool: Address::zero(),
             reserve_x: ethers::utils::parse_ether("1000.0").unwrap(),
             reserve_y: ethers::utils::parse_ether("950.0").unwrap(),
             fee_bps: 30,
             amp: None,
+            stable: false,
         };
 
         let sell_pool = PoolParams {
             venue: Venue::Balancer,
+            fee_tier: None,
+            pool: Address::zero(),
             reserve_x: ethers::utils::parse_ether("500.0").unwrap(),
             reserve_y: ethers::utils::parse_ether("480.0").unwrap(),
             fee_bps: 30,
             amp: None,
+            stable: false,
         };
 
         // Benchmark constant product solver (should be O(1) closed-form)
@@ -615,18 +1131,24 @@ mod tests {
         // Set up test pools for stableswap (Curve)
         let curve_buy = PoolParams {
             venue: Venue::Curve,
+            fee_tier: None,
+            pool: Address::zero(),
             reserve_x: ethers::utils::parse_ether("10000.0").unwrap(),
             reserve_y: ethers::utils::parse_ether("9800.0").unwrap(),
             fee_bps: 4, // 0.04%
             amp: Some(100),
+            stable: false,
         };
 
         let curve_sell = PoolParams {
             venue: Venue::Curve,
+            fee_tier: None,
+            pool: Address::zero(),
             reserve_x: ethers::utils::parse_ether("5000.0").unwrap(),
             reserve_y: ethers::utils::parse_ether("5100.0").unwrap(),
             fee_bps: 4,
             amp: Some(100),
+            stable: false,
         };
 
         // Benchmark stableswap solver (Newton-Raphson with max 5 iterations)
@@ -640,4 +1162,67 @@ mod tests {
         println!("Stableswap solver: {} microseconds average", avg_micros);
         assert!(avg_micros < 10, "Stableswap solver too slow: {} us (max 10 us)", avg_micros);
     }
+
+    /// Compare the StableSwap model on fetched reserves to the pool's own get_dy
+    ///
+    /// Needs a mainnet websocket RPC (the stETH Curve pool lives on mainnet):
+    /// `MAINNET_WS_URL=wss://... cargo test curve_reserves -- --ignored`
+    #[tokio::test]
+    #[ignore]
+    async fn test_curve_reserves_match_onchain_get_dy() {
+        use crate::price::{MulticallQuoter, VenueAddresses};
+
+        let url = std::env::var("MAINNET_WS_URL").expect("MAINNET_WS_URL required");
+        let client = Arc::new(Provider::<Ws>::connect(url).await.unwrap());
+
+        let curve_steth: Address = "0xDC24316b9AE028F1497c275EB9192a3Ea0f67022".parse().unwrap();
+        let steth: Address = "0xae7ab96520DE3A18E5e111B5EaAb095312D7fE84".parse().unwrap();
+
+        let quoter = MulticallQuoter::new(VenueAddresses {
+            multicall3: "0xcA11bde05977b3631167028862bE2a173976CA11".parse().unwrap(),
+            curve_pools: std::collections::HashMap::from([(steth, curve_steth)]),
+            balancer_vault: Address::zero(),
+            balancer_pools: Default::default(),
+            uniswap_quoter: Address::zero(),
+            maverick_quoter: Address::zero(),
+            maverick_pools: Default::default(),
+            solidly_pools: Default::default(),
+            weth: "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2".parse().unwrap(),
+        })
+        .with_uniswap_fee_tiers(Vec::new());
+
+        let amount = ethers::utils::parse_ether("1").unwrap();
+        let quotes = quoter
+            .fetch_all_quotes(client.clone(), &[(steth, "steth".into())], amount)
+            .await
+            .unwrap();
+        let (_, quote) = quotes[0].quotes.iter()
+            .find(|(venue, _)| *venue == Venue::Curve)
+            .expect("Curve quote");
+        let (reserve_x, reserve_y) = quote.reserves.expect("Curve reserves");
+
+        // Read the pool's current A and fee so the model uses the same parameters
+        let read_u256 = |signature: &str| {
+            let client = client.clone();
+            let tx: ethers::types::transaction::eip2718::TypedTransaction = TransactionRequest::new()
+                .to(curve_steth)
+                .data(ethers::utils::id(signature).to_vec())
+                .into();
+            async move { U256::from_big_endian(&client.call(&tx, None).await.unwrap()) }
+        };
+        let amp = read_u256("A()").await.as_u64() as f64;
+        let fee = read_u256("fee()").await.as_u64() as f64 / 1e10;
+
+        // quote.buy_amount is the pool's own get_dy(0, 1, 1 ETH)
+        let modeled = stableswap_get_dy(
+            u256_to_f64(reserve_x).unwrap(),
+            u256_to_f64(reserve_y).unwrap(),
+            u256_to_f64(amount).unwrap(),
+            amp,
+        ).unwrap() * (1.0 - fee);
+        let onchain = u256_to_f64(quote.buy_amount).unwrap();
+
+        let error = ((modeled - onchain) / onchain).abs();
+        assert!(error < 0.001, "model {} vs get_dy {} ({:.4}% off)", modeled, onchain, error * 100.0);
+    }
 }
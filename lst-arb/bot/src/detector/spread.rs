@@ -1,38 +1,251 @@
-use ethers::types::{Address, U256};
+use ethers::providers::Middleware;
+use ethers::types::{Address, H256, U256};
+use std::collections::HashSet;
 use std::sync::Arc;
 use tracing::{info, debug};
 
-use crate::price::{Quote, Venue, TokenQuotes};
-use crate::rpc::WsClient;
-use super::solver::{Solver, PoolParams};
+use crate::chain::ChainProfile;
+use crate::price::{same_pool, EnabledVenues, MulticallQuoter, Quote, QuoteLeg, Venue, TokenQuotes};
+use crate::price::reserves::ReserveSource;
+use crate::price::stableswap;
+use super::solver::{f64_to_u256, implied_reserves, solidly_get_dy, u256_to_f64, Solver, PoolParams, ARBITRUM_WETH};
+use super::rate_staleness::RateStaleness;
+use super::spread_tuner::SpreadTuner;
+use super::route::{pair_route, Leg};
+use super::triangular;
+use super::flash_fee::flash_fee;
 
 #[derive(Debug, Clone)]
 pub struct Opportunity {
+    /// Stable ID, set at detection, that logs, the opportunity recorder,
+    /// metrics, alerts and the execution result all reference
+    pub id: H256,
     pub token: Address,
     pub token_name: String,
+    /// What the trade starts and ends in, i.e. the flash-loaned asset: WETH,
+    /// or the other LST of a direct LST/LST pair. `trade_amount` and
+    /// `base_profit` are in this token; `expected_profit` is always ETH.
+    pub base_token: Address,
     pub buy_venue: Venue,
     pub sell_venue: Venue,
-    pub buy_price: U256,      // LST received per ETH
-    pub sell_price: U256,     // ETH received per LST
+    pub buy_fee_tier: Option<u32>,
+    pub sell_fee_tier: Option<u32>,
+    pub buy_pool: Address,
+    pub sell_pool: Address,
+    /// Whether the buy/sell pool is a Solidly pool on the stable curve
+    pub buy_stable: bool,
+    pub sell_stable: bool,
+    /// The token's Curve pool A as read on-chain, when fetched
+    pub curve_amp: Option<u64>,
+    pub buy_price: U256,      // LST received per base token
+    pub sell_price: U256,     // base token received per LST
     pub spread_bps: u64,
     pub expected_profit: U256,
+    /// The profit in `base_token`, the unit the contract's minProfit and
+    /// profit event use (equal to `expected_profit` when the base is WETH)
+    pub base_profit: U256,
     pub trade_amount: U256,
     pub timestamp_ms: u64,
+    /// Every leg of the trade in order; a plain buy/sell arb is two legs
+    pub route: Vec<Leg>,
+    /// (base, LST) reserves of the buy and sell pools, when actually read
+    pub buy_reserves: Option<(U256, U256)>,
+    pub sell_reserves: Option<(U256, U256)>,
 }
 
 pub struct OpportunityDetector {
     min_spread_bps: u64,
     min_profit: U256,
+    /// Gas units an arb is expected to use, for pre-filtering before simulation
+    gas_estimate: U256,
     solver: Solver,
+    weth: Address,
+    reserve_source: Option<Arc<dyn ReserveSource>>,
+    quote_quorum: usize,
+    max_detection_latency: Option<std::time::Duration>,
+    spread_tuner: Option<Arc<SpreadTuner>>,
+    /// Venues whose pools the solver may trade
+    enabled_venues: EnabledVenues,
+    /// Smallest trade worth its operational overhead, however profitable
+    min_trade_size: U256,
+    /// Widens the required spread (or pauses) tokens whose rate feed is stale
+    rate_staleness: Option<Arc<RateStaleness>>,
+    /// Solidly pools on the stable curve; the rest are volatile
+    solidly_stable_pools: HashSet<Address>,
+    /// Flash-loan fee on the trade input, netted out of every profit
+    flash_fee_bps: u64,
 }
 
+/// Successful venue quotes a token needs before its data is trusted
+pub const DEFAULT_QUOTE_QUORUM: usize = 2;
+
+/// Gas a flash-loan arb typically uses, for pre-filtering opportunities
+pub const DEFAULT_GAS_ESTIMATE: u64 = 400_000;
+
+/// Trigger-to-detection latency beyond which quotes are too stale to act on (one Arbitrum block)
+pub const DEFAULT_MAX_DETECTION_LATENCY_MS: u64 = 250;
+
 impl OpportunityDetector {
-    pub fn new(min_spread_bps: u64, min_profit: U256) -> Self {
+    /// `gas_estimate` is the gas an arb is expected to use (0 = don't net
+    /// gas out when filtering)
+    pub fn new(min_spread_bps: u64, min_profit: U256, gas_estimate: U256) -> Self {
         Self {
             min_spread_bps,
             min_profit,
+            gas_estimate,
             solver: Solver::new(),
+            weth: ARBITRUM_WETH.parse().unwrap(),
+            reserve_source: None,
+            quote_quorum: DEFAULT_QUOTE_QUORUM,
+            max_detection_latency: None,
+            spread_tuner: None,
+            enabled_venues: EnabledVenues::default(),
+            min_trade_size: U256::zero(),
+            rate_staleness: None,
+            solidly_stable_pools: HashSet::new(),
+            flash_fee_bps: 0,
+        }
+    }
+
+    /// Only size trades through these venues; the set can be changed while running
+    pub fn with_enabled_venues(mut self, venues: EnabledVenues) -> Self {
+        self.enabled_venues = venues;
+        self
+    }
+
+    /// Quotes from enabled venues that returned something in either direction
+    fn tradable_quotes<'a>(&self, tq: &'a TokenQuotes) -> Vec<&'a (Venue, Quote)> {
+        tq.quotes.iter()
+            .filter(|(venue, _)| self.enabled_venues.contains(*venue))
+            .filter(|(_, q)| q.buy_amount > U256::zero() || q.sell_amount > U256::zero())
+            .collect()
+    }
+
+    /// Take the minimum spread from an auto-tuner instead of the fixed value
+    pub fn with_spread_tuner(mut self, tuner: Arc<SpreadTuner>) -> Self {
+        self.spread_tuner = Some(tuner);
+        self
+    }
+
+    /// Minimum spread currently in effect
+    pub fn min_spread_bps(&self) -> u64 {
+        self.spread_tuner.as_ref().map(|t| t.current()).unwrap_or(self.min_spread_bps)
+    }
+
+    /// Solidly pools to model with the stable curve
+    pub fn with_solidly_stable_pools(mut self, pools: HashSet<Address>) -> Self {
+        self.solidly_stable_pools = pools;
+        self
+    }
+
+    /// Treat tokens with a stale rate feed conservatively
+    pub fn with_rate_staleness(mut self, staleness: Arc<RateStaleness>) -> Self {
+        self.rate_staleness = Some(staleness);
+        self
+    }
+
+    /// Spread `token` needs right now, or None while it's paused for a stale rate
+    pub fn required_spread_bps(&self, token: Address) -> Option<u64> {
+        let min_spread_bps = self.min_spread_bps();
+        match &self.rate_staleness {
+            Some(staleness) => {
+                let now_secs = chrono::Utc::now().timestamp() as u64;
+                staleness.required_spread_bps(token, min_spread_bps, now_secs)
+            }
+            None => Some(min_spread_bps),
+        }
+    }
+
+    /// Expected profit an opportunity needs at `gas_price` to be worth simulating
+    pub fn min_expected_profit(&self, gas_price: U256) -> U256 {
+        self.min_profit.saturating_add(self.gas_estimate.saturating_mul(gas_price))
+    }
+
+    /// Use the chain profile's WETH and vault instead of Arbitrum One's
+    pub fn with_chain(mut self, chain: &ChainProfile) -> Self {
+        self.solver = self.solver.with_chain(chain);
+        self.weth = chain.weth;
+        self
+    }
+
+    /// Don't execute opportunities whose detection took longer than this (0 = no limit)
+    pub fn with_max_detection_latency(mut self, max_ms: u64) -> Self {
+        self.max_detection_latency = (max_ms > 0).then(|| std::time::Duration::from_millis(max_ms));
+        self
+    }
+
+    /// Whether data that took `latency` from trigger to detection is too old to trade on
+    pub fn is_stale(&self, latency: std::time::Duration) -> bool {
+        self.max_detection_latency.is_some_and(|max| latency > max)
+    }
+
+    /// Reject solver inputs above this multiple of the buy pool's reserve (0 = no bound)
+    pub fn with_max_reserve_multiple(mut self, multiple: f64) -> Self {
+        self.solver = self.solver.with_max_reserve_multiple(multiple);
+        self
+    }
+
+    /// Re-pick the venue pair at the clamped size when the liquidity clamp binds
+    pub fn with_reoptimize_after_clamp(mut self, enabled: bool) -> Self {
+        self.solver = self.solver.with_reoptimize_after_clamp(enabled);
+        self
+    }
+
+    /// Evaluate opportunities net of the flash-loan fee on the borrowed input
+    pub fn with_flash_fee_bps(mut self, fee_bps: u64) -> Self {
+        self.flash_fee_bps = fee_bps;
+        self.solver = self.solver.with_flash_fee_bps(fee_bps);
+        self
+    }
+
+    /// Drop opportunities whose optimal size is below `min_trade_size`, even
+    /// when they clear gas: tiny trades cost more in nonces and noise than they earn
+    pub fn with_min_trade_size(mut self, min_trade_size: U256) -> Self {
+        self.min_trade_size = min_trade_size;
+        self
+    }
+
+    /// Whether the opportunity is large enough to bother executing
+    fn meets_min_trade_size(&self, opp: &Opportunity) -> bool {
+        let trade_eth = opp.base_to_eth(opp.trade_amount);
+        if trade_eth < self.min_trade_size {
+            debug!(
+                "Skipping {}: size {} ETH below the {} ETH floor",
+                opp.token_name,
+                ethers::utils::format_ether(trade_eth),
+                ethers::utils::format_ether(self.min_trade_size)
+            );
+            return false;
+        }
+        true
+    }
+
+    /// Skip tokens with fewer than this many successful venue quotes
+    pub fn with_quote_quorum(mut self, quorum: usize) -> Self {
+        self.quote_quorum = quorum;
+        self
+    }
+
+    /// Whether enough venues quoted the token to act on its data
+    fn meets_quorum(&self, tq: &TokenQuotes) -> bool {
+        let successful = tq.quotes.iter()
+            .filter(|(_, q)| q.buy_amount > U256::zero() || q.sell_amount > U256::zero())
+            .count();
+
+        if successful < self.quote_quorum {
+            debug!(
+                "Skipping {}: {} of {} required venue quotes",
+                tq.token_name, successful, self.quote_quorum
+            );
+            return false;
         }
+        true
+    }
+
+    /// Size trades from pool reserves instead of estimating them from quotes
+    pub fn with_reserve_source(mut self, source: Arc<dyn ReserveSource>) -> Self {
+        self.reserve_source = Some(source);
+        self
     }
 
     /// Get reference to the solver for external use
@@ -44,9 +257,11 @@ impl OpportunityDetector {
     pub fn detect(&self, token_quotes: &[TokenQuotes], trade_amount: U256) -> Vec<Opportunity> {
         let mut opportunities = Vec::new();
         
-        for tq in token_quotes {
-            if let Some(opp) = self.find_best_opportunity(tq, trade_amount) {
-                if opp.spread_bps >= self.min_spread_bps && opp.expected_profit >= self.min_profit {
+        for tq in token_quotes.iter().filter(|tq| self.meets_quorum(tq)) {
+            let opp = self.find_best_opportunity(tq, trade_amount)
+                .and_then(|opp| self.profit_in_eth(opp, token_quotes));
+            if let Some(opp) = opp {
+                if opp.spread_bps >= self.min_spread_bps() && opp.expected_profit >= self.min_profit {
                     opportunities.push(opp);
                 }
             }
@@ -75,17 +290,19 @@ impl OpportunityDetector {
         
         match (best_buy, best_sell) {
             (Some((buy_venue, buy_quote)), Some((sell_venue, sell_quote))) => {
-                // Skip if same venue
-                if buy_venue == sell_venue {
+                // Skip if same pool - buying and selling one pool only loses fees
+                if same_pool(quote_pool(buy_venue, buy_quote), quote_pool(sell_venue, sell_quote)) {
                     // Try second best for sell
                     let second_best_sell = tq.quotes.iter()
-                        .filter(|(v, q)| v != buy_venue && q.sell_amount > U256::zero())
+                        .filter(|(v, q)| {
+                            !same_pool(quote_pool(v, q), quote_pool(buy_venue, buy_quote))
+                                && q.sell_amount > U256::zero()
+                        })
                         .max_by_key(|(_, q)| q.sell_amount);
                     
                     if let Some((sell_v, sell_q)) = second_best_sell {
                         return self.calculate_opportunity(
-                            tq.token,
-                            &tq.token_name,
+                            tq,
                             *buy_venue,
                             *sell_v,
                             buy_quote,
@@ -97,8 +314,7 @@ impl OpportunityDetector {
                 }
                 
                 self.calculate_opportunity(
-                    tq.token,
-                    &tq.token_name,
+                    tq,
                     *buy_venue,
                     *sell_venue,
                     buy_quote,
@@ -112,8 +328,7 @@ impl OpportunityDetector {
     
     fn calculate_opportunity(
         &self,
-        token: Address,
-        token_name: &str,
+        tq: &TokenQuotes,
         buy_venue: Venue,
         sell_venue: Venue,
         buy_quote: &Quote,
@@ -124,30 +339,45 @@ impl OpportunityDetector {
         // Buy: We spend `trade_amount` ETH, get `buy_amount` LST
         // Sell: We sell `buy_amount` LST, get some ETH back
         // Profit = ETH_out - ETH_in
+        // (for an LST/LST pair, the base LST stands in for ETH throughout)
+        let (token, token_name) = (tq.token, tq.token_name.as_str());
+        let base = tq.base_token.unwrap_or(self.weth);
         
+        // Never emit a round trip through a single pool
+        if same_pool(quote_pool(&buy_venue, buy_quote), quote_pool(&sell_venue, sell_quote)) {
+            return None;
+        }
+
         let lst_received = buy_quote.buy_amount;
         if lst_received.is_zero() {
             return None;
         }
-        
-        // Scale sell_amount proportionally
-        // sell_quote.sell_amount is ETH received for `trade_amount` worth of LST
-        // We need ETH received for `lst_received` LST
-        
-        // Simplified calculation assuming linear pricing:
-        // sell_amount is already based on trade_amount input
-        // For more accuracy, we'd need to re-quote with exact LST amount
-        let eth_received = sell_quote.sell_amount;
-        
+
+        // The sell leg sells what the buy leg actually returned, not a
+        // `trade_amount`-sized clip quoted independently
+        let sell_stable = self.is_solidly_stable(sell_venue, sell_quote.pool);
+        let sell_amp = pool_amplification(sell_venue, tq.curve_amp);
+        let eth_received = sell_output(sell_venue, sell_quote, sell_stable, sell_amp, lst_received, trade_amount)?;
+
         if eth_received <= trade_amount {
             return None; // No profit
         }
-        
-        let profit = eth_received - trade_amount;
+
+        // Borrowing the input isn't free with every flash provider
+        let gross_profit = eth_received - trade_amount;
+        let fee = flash_fee(trade_amount, self.flash_fee_bps);
+        if gross_profit <= fee {
+            debug!(
+                "{} {:?} -> {:?} only profitable ignoring the {} ETH flash-loan fee",
+                token_name, buy_venue, sell_venue, ethers::utils::format_ether(fee)
+            );
+            return None;
+        }
+        let profit = gross_profit - fee;
         
         // Calculate spread in basis points
         // spread = (eth_received - trade_amount) / trade_amount * 10000
-        let spread_bps = profit
+        let spread_bps = gross_profit
             .checked_mul(U256::from(10000u64))?
             .checked_div(trade_amount)?
             .as_u64();
@@ -158,35 +388,153 @@ impl OpportunityDetector {
             .as_millis() as u64;
         
         Some(Opportunity {
+            id: H256::zero(),
             token,
             token_name: token_name.to_string(),
+            base_token: base,
             buy_venue,
             sell_venue,
+            buy_fee_tier: buy_quote.fee_tier,
+            sell_fee_tier: sell_quote.fee_tier,
+            buy_pool: buy_quote.pool,
+            sell_pool: sell_quote.pool,
+            buy_stable: self.is_solidly_stable(buy_venue, buy_quote.pool),
+            sell_stable,
+            curve_amp: tq.curve_amp,
             buy_price: lst_received,
             sell_price: eth_received,
             spread_bps,
             expected_profit: profit,
+            base_profit: profit,
             trade_amount,
             timestamp_ms,
-        })
+            route: pair_route(base, token, (buy_venue, buy_quote.pool), (sell_venue, sell_quote.pool), trade_amount),
+            buy_reserves: buy_quote.reserves,
+            sell_reserves: sell_quote.reserves,
+        }.with_id())
+    }
+
+    /// Detect a three-leg cycle (WETH -> A -> B -> WETH) across all tokens and venues
+    ///
+    /// Rates come from quotes made with `trade_amount`, so the cycle is priced
+    /// at that size rather than optimally sized.
+    pub fn detect_triangular(&self, token_quotes: &[TokenQuotes], trade_amount: U256) -> Vec<Opportunity> {
+        let weth = self.weth;
+        let trusted: Vec<TokenQuotes> = token_quotes.iter()
+            .filter(|tq| self.meets_quorum(tq))
+            .cloned()
+            .collect();
+
+        let edges = triangular::edges_from_quotes(&trusted, weth, trade_amount);
+        let Some((legs, rate)) = triangular::find_cycle(&edges, weth) else {
+            return Vec::new();
+        };
+
+        self.triangular_opportunity(token_quotes, &legs, rate, trade_amount)
+            .filter(|opp| opp.spread_bps >= self.min_spread_bps() && opp.expected_profit >= self.min_profit)
+            .into_iter()
+            .collect()
+    }
+
+    fn triangular_opportunity(
+        &self,
+        token_quotes: &[TokenQuotes],
+        legs: &[triangular::QuoteEdge],
+        rate: f64,
+        trade_amount: U256,
+    ) -> Option<Opportunity> {
+        let (first, last) = (legs.first()?, legs.last()?);
+        let token_name = token_quotes.iter()
+            .find(|tq| tq.token == first.to)
+            .map(|tq| tq.token_name.clone())
+            .unwrap_or_else(|| format!("{:?}", first.to));
+
+        let amount = u256_to_f64(trade_amount)?;
+        let eth_received = f64_to_u256(amount * rate)?;
+        let profit = eth_received.checked_sub(trade_amount)?
+            .checked_sub(flash_fee(trade_amount, self.flash_fee_bps))
+            .filter(|profit| !profit.is_zero())?;
+
+        let timestamp_ms = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .ok()?
+            .as_millis() as u64;
+
+        Some(Opportunity {
+            id: H256::zero(),
+            token: first.to,
+            token_name,
+            base_token: self.weth,
+            buy_venue: first.venue,
+            sell_venue: last.venue,
+            buy_fee_tier: first.fee_tier,
+            sell_fee_tier: last.fee_tier,
+            buy_pool: first.pool,
+            sell_pool: last.pool,
+            buy_stable: self.is_solidly_stable(first.venue, first.pool),
+            sell_stable: self.is_solidly_stable(last.venue, last.pool),
+            curve_amp: None,
+            buy_price: f64_to_u256(amount * first.rate)?,
+            sell_price: eth_received,
+            spread_bps: ((rate - 1.0) * 10_000.0) as u64,
+            expected_profit: profit,
+            base_profit: profit,
+            trade_amount,
+            timestamp_ms,
+            route: legs.iter()
+                .enumerate()
+                .map(|(i, leg)| Leg {
+                    pool: leg.pool,
+                    venue: leg.venue,
+                    token_in: leg.from,
+                    token_out: leg.to,
+                    amount_in: if i == 0 { trade_amount } else { U256::zero() },
+                })
+                .collect(),
+            buy_reserves: None,
+            sell_reserves: None,
+        }.with_id())
     }
 
     /// Detect arbitrage opportunities with optimal trade sizing using convex optimization
     ///
     /// This method uses the solver to calculate mathematically optimal trade sizes
     /// instead of using fixed amounts, and clamps to 90% of Balancer Vault liquidity.
-    pub async fn detect_optimal(
+    /// Opportunities that can't cover `min_profit` plus the estimated gas at
+    /// `gas_price` are dropped here rather than spending a simulation on them.
+    /// Tokens without the depth of two pools to size against are priced at
+    /// `quote_amount`, the size they were quoted at.
+    pub async fn detect_optimal<M: Middleware + 'static>(
         &self,
-        client: Arc<WsClient>,
+        client: Arc<M>,
         token_quotes: &[TokenQuotes],
+        quote_amount: U256,
+        gas_price: U256,
     ) -> Vec<Opportunity> {
         let mut opportunities = Vec::new();
+        let min_expected_profit = self.min_expected_profit(gas_price);
 
-        for tq in token_quotes {
-            if let Some(opp) = self.find_optimal_opportunity(client.clone(), tq).await {
-                if opp.spread_bps >= self.min_spread_bps && opp.expected_profit >= self.min_profit {
-                    opportunities.push(opp);
+        for tq in token_quotes.iter().filter(|tq| self.meets_quorum(tq)) {
+            let opp = self.find_optimal_opportunity(client.clone(), tq, quote_amount).await
+                .and_then(|opp| self.profit_in_eth(opp, token_quotes));
+            if let Some(opp) = opp {
+                let Some(required_spread_bps) = self.required_spread_bps(opp.token) else {
+                    debug!("Skipping {}: rate feed is stale", opp.token_name);
+                    continue;
+                };
+                if opp.spread_bps < required_spread_bps || !self.meets_min_trade_size(&opp) {
+                    continue;
                 }
+                if opp.expected_profit < min_expected_profit {
+                    debug!(
+                        "Skipping {}: expected {} ETH doesn't clear min profit plus gas ({} ETH)",
+                        opp.token_name,
+                        ethers::utils::format_ether(opp.expected_profit),
+                        ethers::utils::format_ether(min_expected_profit)
+                    );
+                    continue;
+                }
+                opportunities.push(opp);
             }
         }
 
@@ -196,21 +544,180 @@ impl OpportunityDetector {
         opportunities
     }
 
+    /// Second detection pass: quote the opportunities from `detect_optimal`
+    /// on-chain at the solver's size instead of the probe size the first
+    /// pass quoted, and use the quoted round trip as the expected profit.
+    /// Buy legs go out in one multicall and sell legs, at what the buys
+    /// return, in another. Opportunities the quoted profit no longer clears,
+    /// or with a leg that fails at that size, are dropped; ones that can't be
+    /// requoted (LST/LST pairs, multi-leg routes, venues without an exact-size
+    /// quote, or a multicall that failed outright) keep the solver's estimate.
+    pub async fn requote_at_optimal_size<M: Middleware + 'static>(
+        &self,
+        quoter: &MulticallQuoter,
+        client: Arc<M>,
+        opportunities: Vec<Opportunity>,
+        gas_price: U256,
+    ) -> Vec<Opportunity> {
+        let leg = |opp: &Opportunity, is_buy: bool, amount: U256| {
+            let (venue, fee_tier, pool) = if is_buy {
+                (opp.buy_venue, opp.buy_fee_tier, opp.buy_pool)
+            } else {
+                (opp.sell_venue, opp.sell_fee_tier, opp.sell_pool)
+            };
+            QuoteLeg { base: opp.base_token, token: opp.token, venue, fee_tier, pool, is_buy, amount }
+        };
+        // Legs that can't be quoted at an exact size keep the solver's
+        // estimate; once quoted, a leg that fails drops the opportunity
+        let requotable = |opp: &Opportunity| {
+            opp.is_pair() && opp.base_token == self.weth && !opp.trade_amount.is_zero()
+                && quoter.can_quote(&leg(opp, true, opp.trade_amount))
+                && quoter.can_quote(&leg(opp, false, opp.trade_amount))
+        };
+
+        let buys: Vec<QuoteLeg> = opportunities.iter()
+            .filter(|opp| requotable(opp))
+            .map(|opp| leg(opp, true, opp.trade_amount))
+            .collect();
+        if buys.is_empty() {
+            return opportunities;
+        }
+        let bought = match quoter.quote_legs(client.clone(), &buys).await {
+            Ok(bought) => bought,
+            Err(e) => {
+                debug!("Optimal-size buy quotes failed, keeping solver estimates: {:?}", e);
+                return opportunities;
+            }
+        };
+
+        // Outer None = not requoted; inner None = the buy leg failed
+        let mut bought = bought.into_iter();
+        let sells: Vec<Option<Option<QuoteLeg>>> = opportunities.iter()
+            .map(|opp| requotable(opp).then(|| {
+                bought.next().flatten().map(|lst_out| leg(opp, false, lst_out))
+            }))
+            .collect();
+        let sell_legs: Vec<QuoteLeg> = sells.iter().flatten().flatten().copied().collect();
+        let sold = match quoter.quote_legs(client, &sell_legs).await {
+            Ok(sold) => sold,
+            Err(e) => {
+                debug!("Optimal-size sell quotes failed, keeping solver estimates: {:?}", e);
+                return opportunities;
+            }
+        };
+
+        let min_expected_profit = self.min_expected_profit(gas_price);
+        let mut sold = sold.into_iter();
+        let mut requoted: Vec<Opportunity> = opportunities.into_iter()
+            .zip(sells)
+            .filter_map(|(mut opp, sell)| {
+                let Some(sell) = sell else {
+                    return Some(opp);
+                };
+                // A leg reverting at the solver's size says the size is wrong
+                let Some(eth_out) = sell.and_then(|_| sold.next().flatten()) else {
+                    debug!(
+                        "{} failed to quote at {} ETH, dropping",
+                        opp.token_name, ethers::utils::format_ether(opp.trade_amount)
+                    );
+                    return None;
+                };
+                let gross_profit = eth_out.saturating_sub(opp.trade_amount);
+                let profit = gross_profit.saturating_sub(flash_fee(opp.trade_amount, self.flash_fee_bps));
+                debug!(
+                    "{} requoted at {} ETH: {} ETH profit (solver estimated {} ETH)",
+                    opp.token_name,
+                    ethers::utils::format_ether(opp.trade_amount),
+                    ethers::utils::format_ether(profit),
+                    ethers::utils::format_ether(opp.expected_profit)
+                );
+                if opp.base_to_eth(profit) < min_expected_profit {
+                    return None;
+                }
+                opp.sell_price = eth_out;
+                opp.expected_profit = opp.base_to_eth(profit);
+                opp.base_profit = profit;
+                // Gross, before the flash-loan fee, like the spread detection reports
+                opp.spread_bps = (gross_profit * U256::from(10_000u64) / opp.trade_amount).as_u64();
+                Some(opp)
+            })
+            .collect();
+
+        requoted.sort_by(|a, b| b.expected_profit.cmp(&a.expected_profit));
+        requoted
+    }
+
     /// Find the optimal opportunity for a token using convex optimization
-    async fn find_optimal_opportunity(
+    async fn find_optimal_opportunity<M: Middleware + 'static>(
         &self,
-        client: Arc<WsClient>,
+        client: Arc<M>,
         tq: &TokenQuotes,
+        quote_amount: U256,
     ) -> Option<Opportunity> {
         if tq.quotes.len() < 2 {
             return None;
         }
 
+        let quotes = self.tradable_quotes(tq);
+
+        // Reserves read in the same multicall as the quotes are used as-is;
+        // a configured reserve source fills in pools the multicall couldn't
+        // read (WETH pools only; it knows nothing of LST/LST pairs)
+        let reserves: Vec<Option<(U256, U256)>> = match self.reserve_source.as_ref().filter(|_| tq.base_token.is_none()) {
+            Some(source) => {
+                futures::future::join_all(quotes.iter().map(|(venue, quote)| async move {
+                    match quote.reserves {
+                        Some(r) => Some(r),
+                        None => source.fetch_reserves(tq.token, *venue, quote.fee_tier).await
+                            .ok()
+                            .map(|r| (r.reserve_x, r.reserve_y)),
+                    }
+                })).await
+            }
+            None => quotes.iter().map(|(_, quote)| quote.reserves).collect(),
+        };
+
+        let fee_bps = |venue: Venue, quote: &Quote| {
+            pool_fee_bps(venue, quote.fee_tier, self.is_solidly_stable(venue, quote.pool))
+        };
+
+        // Without read reserves, quotes at several sizes give the pool's effective depth
+        let reserves: Vec<Option<(U256, U256)>> = reserves.into_iter()
+            .zip(&quotes)
+            .map(|(read, (venue, quote))| read.or_else(|| {
+                let sized = tq.sized_quotes_for(*venue, quote)?;
+                implied_reserves(&sized.buy, fee_bps(*venue, quote))
+            }))
+            .collect();
+
+        // Sizing needs real depth on both legs; the placeholder reserves
+        // below carry no price, so without it price the best pair at the
+        // quoted size, still selling exactly what the buy leg returns
+        if reserves.iter().flatten().count() < 2 {
+            let quoted = TokenQuotes { quotes: quotes.iter().map(|quote| **quote).collect(), ..tq.clone() };
+            return self.find_best_opportunity(&quoted, quote_amount);
+        }
+
+        let amp = |venue: Venue| pool_amplification(venue, tq.curve_amp);
+
         // Build pool parameters from quotes
         // Use buy/sell amounts as proxy for reserves when liquidity data unavailable
-        let pools: Vec<PoolParams> = tq.quotes.iter()
-            .filter(|(_, q)| q.buy_amount > U256::zero() || q.sell_amount > U256::zero())
-            .map(|(venue, quote)| {
+        let pools: Vec<PoolParams> = quotes.iter()
+            .enumerate()
+            .map(|(i, (venue, quote))| {
+                if let Some(Some((reserve_x, reserve_y))) = reserves.get(i) {
+                    return PoolParams {
+                        venue: *venue,
+                        fee_tier: quote.fee_tier,
+                        pool: quote.pool,
+                        reserve_x: *reserve_x,
+                        reserve_y: *reserve_y,
+                        fee_bps: fee_bps(*venue, quote),
+                        amp: amp(*venue),
+                        stable: self.is_solidly_stable(*venue, quote.pool),
+                    };
+                }
+
                 // Estimate reserve from quote amounts (assuming ~1:1 ratio for LSTs)
                 // A quote of X LST for 1 ETH implies reserves of at least X * some_factor
                 let estimated_reserve = if quote.buy_amount > U256::zero() {
@@ -221,10 +728,13 @@ impl OpportunityDetector {
 
                 PoolParams {
                     venue: *venue,
+                    fee_tier: quote.fee_tier,
+                    pool: quote.pool,
                     reserve_x: estimated_reserve,
                     reserve_y: estimated_reserve,
-                    fee_bps: venue_fee_bps(*venue),
-                    amp: venue_amplification(*venue),
+                    fee_bps: fee_bps(*venue, quote),
+                    amp: amp(*venue),
+                    stable: self.is_solidly_stable(*venue, quote.pool),
                 }
             })
             .collect();
@@ -234,7 +744,7 @@ impl OpportunityDetector {
         }
 
         // Use solver to find optimal trade with liquidity clamping
-        let optimal_trade = match self.solver.find_optimal_trade_clamped(client, &pools).await {
+        let optimal_trade = match self.solver.find_optimal_trade_clamped(client, &pools, tq.base_token).await {
             Ok(Some(t)) => t,
             Ok(None) => return None,
             Err(e) => {
@@ -249,9 +759,11 @@ impl OpportunityDetector {
             .ok()?
             .as_millis() as u64;
 
-        // Calculate spread in basis points
+        // Spread in basis points, gross of the flash-loan fee the solver
+        // already took off the profit, as the other detection paths report it
+        let gross_profit = optimal_trade.expected_profit + flash_fee(optimal_trade.optimal_input, self.flash_fee_bps);
         let spread_bps = if optimal_trade.optimal_input > U256::zero() {
-            optimal_trade.expected_profit
+            gross_profit
                 .checked_mul(U256::from(10000u64))?
                 .checked_div(optimal_trade.optimal_input)?
                 .as_u64()
@@ -260,40 +772,161 @@ impl OpportunityDetector {
         };
 
         // Get buy/sell amounts from quotes for logging (search in Vec)
+        let buy_id = (optimal_trade.buy_venue, optimal_trade.buy_fee_tier, optimal_trade.buy_pool);
+        let sell_id = (optimal_trade.sell_venue, optimal_trade.sell_fee_tier, optimal_trade.sell_pool);
+
+        // The solver skips same-pool pairs, but never emit one regardless
+        if same_pool(buy_id, sell_id) {
+            return None;
+        }
+
         let buy_quote = tq.quotes.iter()
-            .find(|(v, _)| *v == optimal_trade.buy_venue)
-            .map(|(_, q)| q)?;
-        let sell_quote = tq.quotes.iter()
-            .find(|(v, _)| *v == optimal_trade.sell_venue)
+            .find(|(v, q)| quote_pool(v, q) == buy_id)
             .map(|(_, q)| q)?;
 
+        // Only reserves actually read are passed on, not the quote-derived estimates
+        let read_reserves = |id| quotes.iter()
+            .position(|(v, q)| quote_pool(v, q) == id)
+            .and_then(|i| reserves[i]);
+
         Some(Opportunity {
+            id: H256::zero(),
             token: tq.token,
             token_name: tq.token_name.clone(),
+            base_token: tq.base_token.unwrap_or(self.weth),
             buy_venue: optimal_trade.buy_venue,
             sell_venue: optimal_trade.sell_venue,
+            buy_fee_tier: optimal_trade.buy_fee_tier,
+            sell_fee_tier: optimal_trade.sell_fee_tier,
+            buy_pool: optimal_trade.buy_pool,
+            sell_pool: optimal_trade.sell_pool,
+            buy_stable: self.is_solidly_stable(optimal_trade.buy_venue, optimal_trade.buy_pool),
+            sell_stable: self.is_solidly_stable(optimal_trade.sell_venue, optimal_trade.sell_pool),
+            curve_amp: tq.curve_amp,
             buy_price: buy_quote.buy_amount,
-            sell_price: sell_quote.sell_amount,
+            // The solver chains the buy output into the sell leg, so this is
+            // base back from the whole sequence, as in `detect`
+            sell_price: optimal_trade.optimal_input + optimal_trade.expected_profit,
             spread_bps,
             expected_profit: optimal_trade.expected_profit,
+            base_profit: optimal_trade.expected_profit,
             trade_amount: optimal_trade.optimal_input,
             timestamp_ms,
-        })
+            route: pair_route(
+                tq.base_token.unwrap_or(self.weth),
+                tq.token,
+                (optimal_trade.buy_venue, optimal_trade.buy_pool),
+                (optimal_trade.sell_venue, optimal_trade.sell_pool),
+                optimal_trade.optimal_input,
+            ),
+            buy_reserves: read_reserves(buy_id),
+            sell_reserves: read_reserves(sell_id),
+        }.with_id())
+    }
+}
+
+impl OpportunityDetector {
+    /// Whether `pool` is a Solidly pool on the stable curve
+    fn is_solidly_stable(&self, venue: Venue, pool: Address) -> bool {
+        venue == Venue::Solidly && self.solidly_stable_pools.contains(&pool)
+    }
+
+    /// `opportunity` with its profit in ETH. An LST/LST pair's profit is in
+    /// its base LST, converted at that LST's own WETH quotes from the same
+    /// scan (`base_profit` keeps the base amount); without those it can't
+    /// be compared to anything and is dropped.
+    fn profit_in_eth(&self, mut opportunity: Opportunity, token_quotes: &[TokenQuotes]) -> Option<Opportunity> {
+        if opportunity.base_token == self.weth {
+            return Some(opportunity);
+        }
+        let Some(rate) = eth_per_token(token_quotes, opportunity.base_token) else {
+            debug!("Skipping {}: no WETH quotes to value its profit", opportunity.token_name);
+            return None;
+        };
+        opportunity.expected_profit = f64_to_u256(u256_to_f64(opportunity.expected_profit)? * rate)?;
+        Some(opportunity)
+    }
+}
+
+/// ETH per unit of `token` from its best WETH pool. Buy and sell quotes of
+/// the same size are `amount / price` and `amount * price` less the fee,
+/// so the square root of their ratio is the price, whatever the size.
+fn eth_per_token(token_quotes: &[TokenQuotes], token: Address) -> Option<f64> {
+    let tq = token_quotes.iter().find(|tq| tq.token == token && tq.base_token.is_none())?;
+    let (_, quote) = tq.quotes.iter()
+        .filter(|(_, q)| !q.buy_amount.is_zero() && !q.sell_amount.is_zero())
+        .max_by_key(|(_, q)| q.sell_amount)?;
+    let rate = (u256_to_f64(quote.sell_amount)? / u256_to_f64(quote.buy_amount)?).sqrt();
+    rate.is_finite().then_some(rate)
+}
+
+/// Pool identity of a quote
+fn quote_pool(venue: &Venue, quote: &Quote) -> (Venue, Option<u32>, Address) {
+    (*venue, quote.fee_tier, quote.pool)
+}
+
+/// ETH out for selling `lst_in` on the sell pool. With reserves the pool
+/// curve is applied to the exact amount; otherwise the rate quoted for
+/// `quoted_in` is scaled to it.
+fn sell_output(venue: Venue, quote: &Quote, stable: bool, amp: Option<u64>, lst_in: U256, quoted_in: U256) -> Option<U256> {
+    let fee_bps = pool_fee_bps(venue, quote.fee_tier, stable);
+    match (quote.reserves, amp) {
+        (Some((reserve_x, reserve_y)), Some(amp)) => {
+            stableswap::get_dy(1, 0, lst_in, [reserve_x, reserve_y], amp, fee_bps)
+        }
+        (Some((reserve_x, reserve_y)), None) => {
+            let lst_in_after_fee = lst_in * U256::from(10_000 - fee_bps) / U256::from(10_000u64);
+            if stable {
+                return solidly_stable_output(reserve_y, reserve_x, lst_in_after_fee);
+            }
+            reserve_x.checked_mul(lst_in_after_fee)?.checked_div(reserve_y + lst_in_after_fee)
+        }
+        (None, _) => quote.sell_amount.checked_mul(lst_in)?.checked_div(quoted_in),
+    }
+}
+
+/// Output of a Solidly stable pool holding `reserve_in`/`reserve_out` for
+/// `amount_in` already net of the fee
+pub(crate) fn solidly_stable_output(reserve_in: U256, reserve_out: U256, amount_in: U256) -> Option<U256> {
+    let out = solidly_get_dy(u256_to_f64(reserve_in)?, u256_to_f64(reserve_out)?, u256_to_f64(amount_in)?, true)?;
+    f64_to_u256(out)
+}
+
+/// Fee of a pool in basis points; Solidly stable pools charge less than
+/// volatile ones
+pub(crate) fn pool_fee_bps(venue: Venue, fee_tier: Option<u32>, stable: bool) -> u64 {
+    match venue {
+        Venue::Solidly if stable => SOLIDLY_STABLE_FEE_BPS,
+        _ => venue_fee_bps(venue, fee_tier),
     }
 }
 
 /// Get fee in basis points for each venue
-fn venue_fee_bps(venue: Venue) -> u64 {
+pub(crate) fn venue_fee_bps(venue: Venue, fee_tier: Option<u32>) -> u64 {
+    match (venue, fee_tier) {
+        // Uniswap V3 tiers are in hundredths of a bip (500 = 0.05%)
+        (Venue::UniswapV3, Some(tier)) => (tier / 100).max(1) as u64,
+        (Venue::Curve, _) => 4,       // 0.04% for StableSwap
+        (Venue::Balancer, _) => 10,   // 0.1% typical for Balancer stable pools
+        (Venue::UniswapV3, None) => 5, // 0.05% (lowest tier, LST pairs usually use this)
+        (Venue::Maverick, _) => 10,   // 0.1% typical
+        (Venue::Solidly, _) => 30,    // 0.3% volatile; stable pools charge SOLIDLY_STABLE_FEE_BPS
+    }
+}
+
+/// Typical Solidly stable pool fee (0.05%)
+pub(crate) const SOLIDLY_STABLE_FEE_BPS: u64 = 5;
+
+/// The Curve pool's on-chain A when fetched, otherwise the venue's typical value
+pub(crate) fn pool_amplification(venue: Venue, curve_amp: Option<u64>) -> Option<u64> {
     match venue {
-        Venue::Curve => 4,       // 0.04% for StableSwap
-        Venue::Balancer => 10,   // 0.1% typical for Balancer stable pools
-        Venue::UniswapV3 => 5,   // 0.05% (lowest tier, LST pairs usually use this)
-        Venue::Maverick => 10,   // 0.1% typical
+        Venue::Curve => curve_amp.or(venue_amplification(venue)),
+        _ => venue_amplification(venue),
     }
 }
 
 /// Get amplification factor for StableSwap venues
-fn venue_amplification(venue: Venue) -> Option<u64> {
+pub(crate) fn venue_amplification(venue: Venue) -> Option<u64> {
     match venue {
         Venue::Curve => Some(100),    // Typical A factor for Curve
         Venue::Balancer => Some(200), // Balancer stable pools use higher A
@@ -301,17 +934,710 @@ fn venue_amplification(venue: Venue) -> Option<u64> {
     }
 }
 
+/// First 8 hex digits of an opportunity ID
+pub fn short_id(id: H256) -> String {
+    hex::encode(&id.as_bytes()[..4])
+}
+
 impl Opportunity {
+    /// `amount` of the base token in ETH, at the rate the ETH and base
+    /// profit figures imply (as-is when they're equal or unknown)
+    pub fn base_to_eth(&self, amount: U256) -> U256 {
+        if self.base_profit.is_zero() || self.expected_profit.is_zero() || self.base_profit == self.expected_profit {
+            return amount;
+        }
+        amount * self.expected_profit / self.base_profit
+    }
+
+    /// `amount` of ETH in the base token; the inverse of `base_to_eth`
+    pub fn eth_to_base(&self, amount: U256) -> U256 {
+        if self.base_profit.is_zero() || self.expected_profit.is_zero() || self.base_profit == self.expected_profit {
+            return amount;
+        }
+        amount * self.base_profit / self.expected_profit
+    }
+
+    /// A plain buy/sell arb (two legs, or no route recorded), which the
+    /// contract executes by venue rather than leg by leg
+    pub fn is_pair(&self) -> bool {
+        self.route.len() <= 2
+    }
+
+    /// ID derived from what was detected, where and when: the token and
+    /// base, the venues and pools of every leg, the size and the detection time.
+    /// Identical detections get the same ID, any difference a new one.
+    pub fn derive_id(&self) -> H256 {
+        use ethers::abi::Token;
+        let pools = self.route.iter()
+            .map(|leg| Token::Tuple(vec![Token::String(format!("{:?}", leg.venue)), Token::Address(leg.pool)]))
+            .collect();
+        H256(ethers::utils::keccak256(ethers::abi::encode(&[
+            Token::Address(self.token),
+            Token::Address(self.base_token),
+            Token::String(self.buy_venue.label(self.buy_fee_tier)),
+            Token::String(self.sell_venue.label(self.sell_fee_tier)),
+            Token::Address(self.buy_pool),
+            Token::Address(self.sell_pool),
+            Token::Array(pools),
+            Token::Uint(self.trade_amount),
+            Token::Uint(self.timestamp_ms.into()),
+        ])))
+    }
+
+    /// Set the ID from the detected fields
+    fn with_id(mut self) -> Self {
+        self.id = self.derive_id();
+        self
+    }
+
+    /// First 8 hex digits of the ID, for log lines and alerts
+    pub fn short_id(&self) -> String {
+        short_id(self.id)
+    }
+
     pub fn log(&self) {
         info!(
-            "🎯 OPPORTUNITY: {} | Buy {} @ {:?} | Sell @ {:?} | Spread: {}bps | Profit: {} ETH | Size: {} ETH",
+            opportunity_id = %self.short_id(),
+            token = %self.token_name,
+            buy_venue = %self.buy_venue.label(self.buy_fee_tier),
+            sell_venue = %self.sell_venue.label(self.sell_fee_tier),
+            spread_bps = self.spread_bps,
+            profit_wei = %self.expected_profit,
+            trade_amount_wei = %self.trade_amount,
+            "🎯 OPPORTUNITY [{}]: {} | Buy {} @ {} | Sell @ {} | Spread: {}bps | Profit: {} ETH | Size: {} ETH",
+            self.short_id(),
             self.token_name,
             self.token,
-            self.buy_venue,
-            self.sell_venue,
+            self.buy_venue.label(self.buy_fee_tier),
+            self.sell_venue.label(self.sell_fee_tier),
             self.spread_bps,
             ethers::utils::format_ether(self.expected_profit),
             ethers::utils::format_ether(self.trade_amount)
         );
+        debug!("   Pools: buy {:?} -> sell {:?}", self.buy_pool, self.sell_pool);
+        if !self.is_pair() {
+            let legs: Vec<String> = self.route.iter()
+                .map(|leg| format!("{:?} {:?}", leg.venue, leg.pool))
+                .collect();
+            info!("   Route: {}", legs.join(" -> "));
+        }
+    }
+}
+
+#[cfg(test)]
+impl Opportunity {
+    /// A zero-sized Curve -> UniswapV3 wstETH opportunity at 30bps, for
+    /// tests to override with struct-update syntax
+    pub fn fixture() -> Self {
+        Self {
+            id: H256::zero(),
+            token: Address::from_low_u64_be(1),
+            token_name: "wstETH".into(),
+            base_token: Address::zero(),
+            buy_venue: Venue::Curve,
+            sell_venue: Venue::UniswapV3,
+            buy_fee_tier: None,
+            sell_fee_tier: Some(500),
+            buy_pool: Address::zero(),
+            sell_pool: Address::zero(),
+            buy_stable: false,
+            sell_stable: false,
+            curve_amp: None,
+            buy_price: U256::zero(),
+            sell_price: U256::zero(),
+            spread_bps: 30,
+            expected_profit: U256::zero(),
+            base_profit: U256::zero(),
+            trade_amount: U256::zero(),
+            timestamp_ms: 0,
+            route: Vec::new(),
+            buy_reserves: None,
+            sell_reserves: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn uni_quote(fee_tier: u32, buy: &str, sell: &str) -> (Venue, Quote) {
+        (Venue::UniswapV3, Quote {
+            buy_amount: ethers::utils::parse_ether(buy).unwrap(),
+            sell_amount: ethers::utils::parse_ether(sell).unwrap(),
+            fee_tier: Some(fee_tier),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_cross_tier_uniswap_arb() {
+        let detector = OpportunityDetector::new(10, U256::zero(), U256::zero());
+
+        // 1bp tier sells cheap, 5bp tier buys LST back at a premium
+        let tq = TokenQuotes {
+            token: Address::zero(),
+            token_name: "wstETH".into(),
+            quotes: vec![
+                uni_quote(100, "1.02", "0.98"),
+                uni_quote(500, "0.97", "1.01"),
+            ],
+            curve_amp: None,
+            sized_quotes: Vec::new(),
+            fetched_at_ms: 0,
+            block_number: None,
+            base_token: None,
+        };
+
+        let opportunities = detector.detect(&[tq], ethers::utils::parse_ether("1").unwrap());
+        assert_eq!(opportunities.len(), 1);
+
+        let opp = &opportunities[0];
+        assert_eq!(opp.buy_venue, Venue::UniswapV3);
+        assert_eq!(opp.sell_venue, Venue::UniswapV3);
+        assert_eq!(opp.buy_fee_tier, Some(100));
+        assert_eq!(opp.sell_fee_tier, Some(500));
+        // 1.02 LST bought, sold at 1.01 ETH each
+        assert_eq!(opp.spread_bps, 302);
+    }
+
+    #[test]
+    fn test_sell_leg_uses_buy_output() {
+        let detector = OpportunityDetector::new(10, U256::zero(), U256::zero());
+        let eth = |v: &str| ethers::utils::parse_ether(v).unwrap();
+
+        // The sell quote for a 1 LST clip looks like a loss, but the buy leg
+        // returns 1.05 LST and the sell pool's curve pays more for that
+        let mut sell = uni_quote(500, "0.90", "0.99");
+        sell.1.reserves = Some((eth("100"), eth("100")));
+        let tq = TokenQuotes {
+            token: Address::zero(),
+            token_name: "wstETH".into(),
+            quotes: vec![uni_quote(100, "1.05", "0.90"), sell],
+            curve_amp: None,
+            sized_quotes: Vec::new(),
+            fetched_at_ms: 0,
+            block_number: None,
+            base_token: None,
+        };
+
+        let opportunities = detector.detect(&[tq], eth("1"));
+        assert_eq!(opportunities.len(), 1);
+
+        // 1.05 LST less the 5bp fee into a 100/100 constant product pool
+        let lst_in = eth("1.05") * 9_995u64 / 10_000u64;
+        let expected = eth("100") * lst_in / (eth("100") + lst_in);
+        assert_eq!(opportunities[0].sell_price, expected);
+        assert_eq!(opportunities[0].expected_profit, expected - eth("1"));
+    }
+
+    #[tokio::test]
+    async fn test_optimal_detection_sells_the_buy_output() {
+        use ethers::providers::Provider;
+        let eth = |v: &str| ethers::utils::parse_ether(v).unwrap();
+        let to_f64 = |v: U256| ethers::utils::format_ether(v).parse::<f64>().unwrap();
+        // No Balancer vault, so nothing to clamp against and no RPC reads
+        let detector = OpportunityDetector::new(10, U256::zero(), U256::zero())
+            .with_chain(&ChainProfile::arbitrum_nova());
+        let (provider, _mock) = Provider::mocked();
+        let client = Arc::new(provider);
+        let quotes = |quotes| TokenQuotes {
+            token: Address::zero(),
+            token_name: "wstETH".into(),
+            quotes,
+            curve_amp: None,
+            sized_quotes: Vec::new(),
+            fetched_at_ms: 0,
+            block_number: None,
+            base_token: None,
+        };
+
+        // With both pools' reserves the solver sizes the trade, selling the
+        // LST the buy returned rather than an input-sized clip
+        let (mut buy, mut sell) = (uni_quote(100, "1.05", "0.95"), uni_quote(500, "0.99", "1.0"));
+        buy.1.reserves = Some((eth("1000"), eth("1050")));
+        sell.1.reserves = Some((eth("1000"), eth("1000")));
+        let opportunities = detector.detect_optimal(client.clone(), &[quotes(vec![buy, sell])], eth("1"), U256::zero()).await;
+        assert_eq!(opportunities.len(), 1);
+        let opp = &opportunities[0];
+        assert_eq!(opp.sell_price, opp.trade_amount + opp.expected_profit);
+
+        let input = to_f64(opp.trade_amount);
+        // The constant-product closed form takes the sell fee off the output
+        let lst = 1050.0 * input * 0.9999 / (1000.0 + input * 0.9999);
+        let chained = 0.9995 * 1000.0 * lst / (1000.0 + lst) - input;
+        let independent = 0.9995 * 1000.0 * input / (1000.0 + input) - input;
+        let profit = to_f64(opp.expected_profit);
+        assert!((profit - chained).abs() < chained * 1e-6, "{} vs {}", profit, chained);
+        assert!(independent < 0.0);
+
+        // Without the depth of two pools it's priced at the quoted size,
+        // the same sequential way
+        let mut sell = uni_quote(500, "0.90", "0.99");
+        sell.1.reserves = Some((eth("100"), eth("100")));
+        let opportunities = detector.detect_optimal(client, &[quotes(vec![uni_quote(100, "1.05", "0.90"), sell])], eth("1"), U256::zero()).await;
+        assert_eq!(opportunities.len(), 1);
+        let lst_in = eth("1.05") * 9_995u64 / 10_000u64;
+        let expected = eth("100") * lst_in / (eth("100") + lst_in);
+        assert_eq!(opportunities[0].sell_price, expected);
+        assert_eq!(opportunities[0].trade_amount, eth("1"));
+    }
+
+    #[tokio::test]
+    async fn test_optimal_spread_is_gross_of_flash_fee() {
+        use ethers::providers::Provider;
+        let eth = |v: &str| ethers::utils::parse_ether(v).unwrap();
+        let (provider, _mock) = Provider::mocked();
+        let client = Arc::new(provider);
+        let (mut buy, mut sell) = (uni_quote(100, "1.05", "0.95"), uni_quote(500, "0.99", "1.0"));
+        buy.1.reserves = Some((eth("1000"), eth("1050")));
+        sell.1.reserves = Some((eth("1000"), eth("1000")));
+        let tq = TokenQuotes {
+            token: Address::zero(),
+            token_name: "wstETH".into(),
+            quotes: vec![buy, sell],
+            curve_amp: None,
+            sized_quotes: Vec::new(),
+            fetched_at_ms: 0,
+            block_number: None,
+            base_token: None,
+        };
+
+        let detector = OpportunityDetector::new(10, U256::zero(), U256::zero())
+            .with_chain(&ChainProfile::arbitrum_nova())
+            .with_flash_fee_bps(5);
+        let opp = detector.detect_optimal(client, &[tq], eth("1"), U256::zero()).await.remove(0);
+
+        // The profit is net of the 5bp fee, but the spread counts it back in
+        let fee = flash_fee(opp.trade_amount, 5);
+        let gross_bps = ((opp.expected_profit + fee) * U256::from(10_000u64) / opp.trade_amount).as_u64();
+        let net_bps = (opp.expected_profit * U256::from(10_000u64) / opp.trade_amount).as_u64();
+        assert_eq!(opp.spread_bps, gross_bps);
+        assert!(opp.spread_bps > net_bps);
+    }
+
+    #[test]
+    fn test_stable_solidly_sell_uses_stable_curve() {
+        let eth = |v: &str| ethers::utils::parse_ether(v).unwrap();
+        let quote = Quote {
+            reserves: Some((eth("100"), eth("100"))),
+            ..Default::default()
+        };
+
+        // A balanced stable pool pays close to 1:1 less its 5bp fee, where
+        // x*y=k at 30bps would lose about 1.3% on a 1% clip
+        let stable = sell_output(Venue::Solidly, &quote, true, None, eth("1"), eth("1")).unwrap();
+        let volatile = sell_output(Venue::Solidly, &quote, false, None, eth("1"), eth("1")).unwrap();
+        assert!(stable > eth("0.999") && stable < eth("0.9995"));
+        assert!(volatile < eth("0.988"));
+    }
+
+    #[test]
+    fn test_curve_sell_uses_fetched_amp() {
+        let eth = |v: &str| ethers::utils::parse_ether(v).unwrap();
+        let quote = Quote {
+            reserves: Some((eth("100"), eth("150"))),
+            ..Default::default()
+        };
+        let sell = |curve_amp| {
+            sell_output(Venue::Curve, &quote, false, pool_amplification(Venue::Curve, curve_amp), eth("10"), eth("10")).unwrap()
+        };
+
+        // A lower on-chain A prices the imbalanced pool further from 1:1
+        assert_eq!(sell(None), sell(Some(100)));
+        assert!(sell(Some(10)) < sell(None));
+    }
+
+    #[test]
+    fn test_cross_tier_solver_pools_are_distinct() {
+        let solver = Solver::new();
+        let pool = |fee_tier: u32, x: &str, y: &str| PoolParams {
+            venue: Venue::UniswapV3,
+            fee_tier: Some(fee_tier),
+            pool: Address::zero(),
+            reserve_x: ethers::utils::parse_ether(x).unwrap(),
+            reserve_y: ethers::utils::parse_ether(y).unwrap(),
+            fee_bps: venue_fee_bps(Venue::UniswapV3, Some(fee_tier)),
+            amp: None,
+            stable: false,
+        };
+
+        let pools = vec![pool(100, "1000", "1030"), pool(500, "1000", "960")];
+        let trade = solver.find_optimal_trade(&pools).expect("cross-tier arb");
+        assert_eq!(trade.buy_fee_tier, Some(100));
+        assert_eq!(trade.sell_fee_tier, Some(500));
+
+        // The same tier is never paired with itself
+        assert!(solver.find_optimal_trade(&pools[..1]).is_none());
+    }
+
+    #[test]
+    fn test_same_venue_pools_arb_but_same_pool_rejected() {
+        let solver = Solver::new();
+        let pool_a = Address::from_low_u64_be(0xa);
+        let pool_b = Address::from_low_u64_be(0xb);
+
+        // Two Balancer pools - same venue type, no fee tier to tell them apart
+        let pool = |addr: Address, x: &str, y: &str| PoolParams {
+            venue: Venue::Balancer,
+            fee_tier: None,
+            pool: addr,
+            reserve_x: ethers::utils::parse_ether(x).unwrap(),
+            reserve_y: ethers::utils::parse_ether(y).unwrap(),
+            fee_bps: 5,
+            amp: None,
+            stable: false,
+        };
+
+        let pools = vec![pool(pool_a, "1000", "1030"), pool(pool_b, "1000", "960")];
+        let trade = solver.find_optimal_trade(&pools).expect("cross-pool arb");
+        assert_eq!(trade.buy_pool, pool_a);
+        assert_eq!(trade.sell_pool, pool_b);
+
+        // The same physical pool listed twice never pairs with itself
+        let duplicated = vec![pool(pool_a, "1000", "1030"), pool(pool_a, "1000", "960")];
+        assert!(solver.find_optimal_trade(&duplicated).is_none());
+
+        // Detector: quotes from the same pool address are never an opportunity
+        let detector = OpportunityDetector::new(10, U256::zero(), U256::zero());
+        let quote = |addr: Address, buy: &str, sell: &str| (Venue::Balancer, Quote {
+            buy_amount: ethers::utils::parse_ether(buy).unwrap(),
+            sell_amount: ethers::utils::parse_ether(sell).unwrap(),
+            pool: addr,
+            ..Default::default()
+        });
+        let trade_amount = ethers::utils::parse_ether("1").unwrap();
+
+        let cross = TokenQuotes {
+            token: Address::zero(),
+            token_name: "rETH".into(),
+            quotes: vec![quote(pool_a, "1.02", "0.98"), quote(pool_b, "0.97", "1.01")],
+            curve_amp: None,
+            sized_quotes: Vec::new(),
+            fetched_at_ms: 0,
+            block_number: None,
+            base_token: None,
+        };
+        let opportunities = detector.detect(&[cross], trade_amount);
+        assert_eq!(opportunities.len(), 1);
+        assert_eq!(opportunities[0].buy_pool, pool_a);
+        assert_eq!(opportunities[0].sell_pool, pool_b);
+
+        let same = TokenQuotes {
+            token: Address::zero(),
+            token_name: "rETH".into(),
+            quotes: vec![quote(pool_a, "1.02", "0.98"), quote(pool_a, "0.97", "1.01")],
+            curve_amp: None,
+            sized_quotes: Vec::new(),
+            fetched_at_ms: 0,
+            block_number: None,
+            base_token: None,
+        };
+        assert!(detector.detect(&[same], trade_amount).is_empty());
+    }
+
+    #[test]
+    fn test_token_below_quote_quorum_is_skipped() {
+        let detector = OpportunityDetector::new(10, U256::zero(), U256::zero());
+
+        // Second venue's calls failed - only one real quote
+        let sparse = TokenQuotes {
+            token: Address::zero(),
+            token_name: "rETH".into(),
+            quotes: vec![uni_quote(100, "1.02", "0.98"), uni_quote(500, "0", "0")],
+            curve_amp: None,
+            sized_quotes: Vec::new(),
+            fetched_at_ms: 0,
+            block_number: None,
+            base_token: None,
+        };
+        assert!(!detector.meets_quorum(&sparse));
+        assert!(detector.detect(&[sparse], ethers::utils::parse_ether("1").unwrap()).is_empty());
+
+        // A real arb is still skipped when the quorum isn't met
+        let tq = TokenQuotes {
+            token: Address::zero(),
+            token_name: "wstETH".into(),
+            quotes: vec![uni_quote(100, "1.02", "0.98"), uni_quote(500, "0.97", "1.01")],
+            curve_amp: None,
+            sized_quotes: Vec::new(),
+            fetched_at_ms: 0,
+            block_number: None,
+            base_token: None,
+        };
+        let amount = ethers::utils::parse_ether("1").unwrap();
+        assert_eq!(detector.detect(std::slice::from_ref(&tq), amount).len(), 1);
+        assert!(detector.with_quote_quorum(3).detect(&[tq], amount).is_empty());
+    }
+
+    #[test]
+    fn test_stale_detection_gate() {
+        let detector = OpportunityDetector::new(10, U256::zero(), U256::zero());
+        // No limit unless configured
+        assert!(!detector.is_stale(std::time::Duration::from_secs(5)));
+
+        let detector = detector.with_max_detection_latency(DEFAULT_MAX_DETECTION_LATENCY_MS);
+        assert!(!detector.is_stale(std::time::Duration::from_millis(120)));
+        assert!(!detector.is_stale(std::time::Duration::from_millis(250)));
+        assert!(detector.is_stale(std::time::Duration::from_millis(251)));
+
+        assert!(!detector.with_max_detection_latency(0).is_stale(std::time::Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn test_disabled_venue_skipped_live() {
+        let venues = EnabledVenues::default();
+        let detector = OpportunityDetector::new(10, U256::zero(), U256::zero())
+            .with_enabled_venues(venues.clone());
+        let tq = TokenQuotes {
+            token: Address::zero(),
+            token_name: "wstETH".into(),
+            quotes: vec![
+                uni_quote(500, "1.01", "0.99"),
+                (Venue::Balancer, Quote { buy_amount: U256::exp10(18), ..Default::default() }),
+            ],
+            curve_amp: None,
+            sized_quotes: Vec::new(),
+            fetched_at_ms: 0,
+            block_number: None,
+            base_token: None,
+        };
+        assert_eq!(detector.tradable_quotes(&tq).len(), 2);
+
+        // Switched off through the shared handle, no rebuild needed
+        venues.set(Venue::Balancer, false);
+        let quotes = detector.tradable_quotes(&tq);
+        assert_eq!(quotes.len(), 1);
+        assert_eq!(quotes[0].0, Venue::UniswapV3);
+        assert_eq!(venues.list(), vec![Venue::Curve, Venue::UniswapV3, Venue::Maverick, Venue::Solidly]);
+    }
+
+    #[test]
+    fn test_min_expected_profit_includes_gas() {
+        let min_profit = ethers::utils::parse_ether("0.001").unwrap();
+        let gwei = |v: &str| U256::from(ethers::utils::parse_units(v, "gwei").unwrap());
+        let detector = OpportunityDetector::new(10, min_profit, U256::from(400_000u64));
+
+        // 400k gas at 0.1 gwei = 0.00004 ETH on top of min_profit
+        assert_eq!(detector.min_expected_profit(gwei("0.1")), ethers::utils::parse_ether("0.00104").unwrap());
+        // An opportunity that clears min_profit alone but not gas is filtered out
+        let expected = ethers::utils::parse_ether("0.00102").unwrap();
+        assert!(expected >= min_profit && expected < detector.min_expected_profit(gwei("0.1")));
+
+        // Without a gas estimate (or gas price) only min_profit applies
+        assert_eq!(detector.min_expected_profit(U256::zero()), min_profit);
+        let no_estimate = OpportunityDetector::new(10, min_profit, U256::zero());
+        assert_eq!(no_estimate.min_expected_profit(gwei("5")), min_profit);
+    }
+
+    #[test]
+    fn test_tiny_profitable_opportunity_below_size_floor() {
+        let eth = |v: &str| ethers::utils::parse_ether(v).unwrap();
+        let detector = OpportunityDetector::new(10, eth("0.0001"), U256::from(400_000u64))
+            .with_min_trade_size(eth("0.5"));
+        let opportunity = |size: &str| Opportunity {
+            token: Address::zero(),
+            spread_bps: 40,
+            expected_profit: eth("0.001"),
+            trade_amount: eth(size),
+            ..Opportunity::fixture()
+        };
+
+        // Clears min profit plus gas, but only moves 0.1 ETH
+        let tiny = opportunity("0.1");
+        assert!(tiny.expected_profit > detector.min_expected_profit(U256::from(100_000_000u64)));
+        assert!(!detector.meets_min_trade_size(&tiny));
+        assert!(detector.meets_min_trade_size(&opportunity("0.5")));
+
+        // No floor by default
+        assert!(OpportunityDetector::new(10, U256::zero(), U256::zero()).meets_min_trade_size(&tiny));
+    }
+
+    #[test]
+    fn test_opportunity_id_deterministic() {
+        let tq = TokenQuotes {
+            token: Address::from_low_u64_be(1),
+            token_name: "wstETH".into(),
+            quotes: vec![uni_quote(100, "1", "0.99"), uni_quote(500, "0.99", "1.01")],
+            curve_amp: None,
+            sized_quotes: Vec::new(),
+            fetched_at_ms: 0,
+            block_number: None,
+            base_token: None,
+        };
+        let detector = OpportunityDetector::new(10, U256::zero(), U256::zero());
+        let opp = detector.detect(std::slice::from_ref(&tq), ethers::utils::parse_ether("1").unwrap()).remove(0);
+        assert!(!opp.id.is_zero());
+        assert_eq!(opp.id, opp.derive_id());
+
+        // Same inputs, same ID
+        assert_eq!(opp.clone().with_id().id, opp.id);
+        assert_eq!(opp.short_id(), format!("{:x}", opp.id)[..8]);
+
+        // Any difference in what, where or when gives a new one
+        let mut later = opp.clone();
+        later.timestamp_ms += 1;
+        let mut other_pool = opp.clone();
+        other_pool.sell_pool = Address::from_low_u64_be(2);
+        let mut other_tier = opp.clone();
+        other_tier.buy_fee_tier = Some(3000);
+        let ids: HashSet<H256> = [opp, later, other_pool, other_tier].iter().map(|o| o.derive_id()).collect();
+        assert_eq!(ids.len(), 4);
+    }
+
+    #[test]
+    fn test_flash_fee_filters_marginal_opportunity() {
+        use crate::detector::flash_fee::FlashProvider;
+        let eth = |v: &str| ethers::utils::parse_ether(v).unwrap();
+        let tq = TokenQuotes {
+            token: Address::zero(),
+            token_name: "wstETH".into(),
+            quotes: vec![uni_quote(100, "1", "0.99"), uni_quote(500, "0.99", "1.0004")],
+            curve_amp: None,
+            sized_quotes: Vec::new(),
+            fetched_at_ms: 0,
+            block_number: None,
+            base_token: None,
+        };
+
+        // 0.0004 ETH on a 1 ETH trade clears a free Balancer flash loan...
+        let balancer = OpportunityDetector::new(0, U256::zero(), U256::zero())
+            .with_flash_fee_bps(FlashProvider::Balancer.fee_bps());
+        let opportunities = balancer.detect(std::slice::from_ref(&tq), eth("1"));
+        assert_eq!(opportunities.len(), 1);
+        assert_eq!(opportunities[0].expected_profit, eth("0.0004"));
+
+        // ...but not Aave's 0.05% premium on the borrowed ETH
+        let aave = OpportunityDetector::new(0, U256::zero(), U256::zero())
+            .with_flash_fee_bps(FlashProvider::Aave.fee_bps());
+        assert!(aave.detect(std::slice::from_ref(&tq), eth("1")).is_empty());
+
+        // A wider one is kept, net of the fee
+        let mut wider = tq.clone();
+        wider.quotes[1] = uni_quote(500, "0.99", "1.002");
+        let opportunities = aave.detect(&[wider], eth("1"));
+        assert_eq!(opportunities[0].expected_profit, eth("0.0015"));
+    }
+
+    #[test]
+    fn test_lst_pair_trades_from_base_and_values_profit_in_eth() {
+        let eth = |v: &str| ethers::utils::parse_ether(v).unwrap();
+        let (wsteth, reth) = (Address::from_low_u64_be(1), Address::from_low_u64_be(2));
+        let detector = OpportunityDetector::new(10, U256::zero(), U256::zero());
+
+        // rETH quoted in wstETH: 1bp tier sells rETH cheap, 5bp tier buys it back dear
+        let pair = TokenQuotes {
+            token: reth,
+            token_name: "reth/wsteth".into(),
+            quotes: vec![uni_quote(100, "1.02", "0.98"), uni_quote(500, "0.97", "1.01")],
+            curve_amp: None,
+            sized_quotes: Vec::new(),
+            fetched_at_ms: 0,
+            block_number: None,
+            base_token: Some(wsteth),
+        };
+        // wstETH at 1.25 ETH: 0.8 for 1 ETH, 1.25 ETH for 1 wstETH
+        let wsteth_quotes = TokenQuotes {
+            token: wsteth,
+            token_name: "wsteth".into(),
+            quotes: vec![uni_quote(100, "0.8", "1.25")],
+            curve_amp: None,
+            sized_quotes: Vec::new(),
+            fetched_at_ms: 0,
+            block_number: None,
+            base_token: None,
+        };
+
+        let opportunities = detector.detect(&[pair.clone(), wsteth_quotes], eth("1"));
+        assert_eq!(opportunities.len(), 1);
+        let opp = &opportunities[0];
+        assert_eq!((opp.token, opp.base_token), (reth, wsteth));
+        assert_eq!(opp.route[0].token_in, wsteth);
+        assert_eq!(opp.route[1].token_out, wsteth);
+        // 1.02 rETH sold at 1.01 wstETH each: 0.0302 wstETH, worth 0.03775 ETH
+        let profit = ethers::utils::format_ether(opp.expected_profit).parse::<f64>().unwrap();
+        assert!((profit - 0.03775).abs() < 1e-9, "{}", profit);
+        // The contract still sees the profit and size in wstETH
+        assert_eq!(opp.base_profit, eth("0.0302"));
+        let size_eth = ethers::utils::format_ether(opp.base_to_eth(opp.trade_amount)).parse::<f64>().unwrap();
+        assert!((size_eth - 1.25).abs() < 1e-9, "{}", size_eth);
+        assert_eq!(opp.eth_to_base(opp.expected_profit), opp.base_profit);
+
+        // Without the base's WETH quotes the profit can't be valued
+        assert!(detector.detect(&[pair], eth("1")).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_optimal_size_requote_sets_final_profit() {
+        use crate::price::VenueAddresses;
+        use ethers::abi::Token;
+        use ethers::providers::Provider;
+        use ethers::types::Bytes;
+
+        let eth = |v: &str| ethers::utils::parse_ether(v).unwrap();
+        let detector = OpportunityDetector::new(10, eth("0.005"), U256::zero()).with_flash_fee_bps(5);
+        let weth: Address = ARBITRUM_WETH.parse().unwrap();
+        let quoter = MulticallQuoter::new(VenueAddresses {
+            multicall3: Address::from_low_u64_be(0xca11),
+            curve_pools: Default::default(),
+            balancer_vault: Address::zero(),
+            balancer_pools: Default::default(),
+            uniswap_quoter: Address::from_low_u64_be(0x9a07),
+            maverick_quoter: Address::zero(),
+            maverick_pools: Default::default(),
+            solidly_pools: Default::default(),
+            weth,
+        });
+
+        // The solver sized both at 10 ETH and expected 0.05 ETH from each
+        let opportunity = |token: u64| Opportunity {
+            token: Address::from_low_u64_be(token),
+            token_name: format!("lst{}", token),
+            base_token: weth,
+            buy_venue: Venue::UniswapV3,
+            buy_fee_tier: Some(100),
+            buy_pool: Address::from_low_u64_be(token * 10),
+            sell_pool: Address::from_low_u64_be(token * 10 + 1),
+            buy_price: eth("1.01"),
+            sell_price: eth("10.05"),
+            spread_bps: 50,
+            expected_profit: eth("0.05"),
+            trade_amount: eth("10"),
+            ..Opportunity::fixture()
+        };
+
+        // quoteExactInputSingle results, batched per leg by aggregate3
+        // (None = the call reverted)
+        let aggregate = |amounts: &[Option<&str>]| Bytes::from(ethers::abi::encode(&[Token::Array(
+            amounts.iter()
+                .map(|amount| Token::Tuple(vec![
+                    Token::Bool(amount.is_some()),
+                    Token::Bytes(amount.map_or_else(Vec::new, |amount| ethers::abi::encode(&[
+                        Token::Uint(eth(amount)),
+                        Token::Uint(U256::zero()),
+                        Token::Uint(U256::zero()),
+                        Token::Uint(U256::zero()),
+                    ]))),
+                ]))
+                .collect(),
+        )]));
+        let (provider, mock) = Provider::mocked();
+        // Answered last to first: sell legs, then buy legs. The third's buy
+        // and the fourth's sell revert at the solver's size.
+        mock.push::<Bytes, _>(aggregate(&[Some("10.02"), Some("9.99"), None])).unwrap();
+        mock.push::<Bytes, _>(aggregate(&[Some("9.9"), Some("9.9"), None, Some("9.9")])).unwrap();
+
+        let requoted = detector.requote_at_optimal_size(
+            &quoter, Arc::new(provider), (1..=4).map(opportunity).collect(), U256::zero(),
+        ).await;
+
+        // At the real size the first clears 0.02 ETH less the 0.005 ETH flash
+        // fee; the second loses, and the failed ones can't be trusted
+        assert_eq!(requoted.len(), 1);
+        assert_eq!(requoted[0].token, Address::from_low_u64_be(1));
+        assert_eq!(requoted[0].sell_price, eth("10.02"));
+        assert_eq!(requoted[0].expected_profit, eth("0.015"));
+        // The spread stays gross, as detection reports it
+        assert_eq!(requoted[0].spread_bps, 20);
     }
 }
@@ -0,0 +1,144 @@
+//! Minimum Spread Auto-Tuning
+//!
+//! A hand-set `min_spread_bps` is a guess: too low and gas goes on marginal
+//! trades that lose money, too high and profitable trades are skipped. The
+//! tuner watches resolved trades whose spread was close to the threshold
+//! and nudges it up when those marginal trades lose money on net, or down
+//! when they are consistently profitable, within configured bounds.
+
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tracing::info;
+
+/// Trades within this many bps above the threshold count as marginal
+const MARGINAL_BAND_BPS: u64 = 5;
+
+/// Marginal trades needed before the threshold is moved
+const MIN_MARGINAL_TRADES: usize = 5;
+
+/// Resolved trades remembered between adjustments
+const TRADE_WINDOW: usize = 50;
+
+/// Share of profitable marginal trades needed to lower the threshold
+const LOWER_WIN_RATE: f64 = 0.8;
+
+pub struct SpreadTuner {
+    floor_bps: u64,
+    ceiling_bps: u64,
+    step_bps: u64,
+    current_bps: AtomicU64,
+    /// (spread bps, net profit in ETH) of recently resolved trades
+    trades: Mutex<VecDeque<(u64, f64)>>,
+}
+
+impl SpreadTuner {
+    /// Start at `initial_bps`, clamped into [floor_bps, ceiling_bps]
+    pub fn new(initial_bps: u64, floor_bps: u64, ceiling_bps: u64, step_bps: u64) -> Self {
+        Self {
+            floor_bps,
+            ceiling_bps,
+            step_bps: step_bps.max(1),
+            current_bps: AtomicU64::new(initial_bps.clamp(floor_bps, ceiling_bps)),
+            trades: Mutex::new(VecDeque::with_capacity(TRADE_WINDOW)),
+        }
+    }
+
+    /// Effective minimum spread
+    pub fn current(&self) -> u64 {
+        self.current_bps.load(Ordering::Relaxed)
+    }
+
+    /// Record a resolved trade's spread and net profit (negative for a loss)
+    pub fn record(&self, spread_bps: u64, net_profit_eth: f64) {
+        let mut trades = self.trades.lock();
+        if trades.len() == TRADE_WINDOW {
+            trades.pop_front();
+        }
+        trades.push_back((spread_bps, net_profit_eth));
+    }
+
+    /// Move the threshold one step if the marginal trades call for it,
+    /// returning the new value
+    pub fn retune(&self) -> Option<u64> {
+        let mut trades = self.trades.lock();
+        let current = self.current();
+
+        let marginal: Vec<f64> = trades.iter()
+            .filter(|(spread, _)| *spread < current + MARGINAL_BAND_BPS)
+            .map(|(_, net)| *net)
+            .collect();
+        if marginal.len() < MIN_MARGINAL_TRADES {
+            return None;
+        }
+
+        let net: f64 = marginal.iter().sum();
+        let win_rate = marginal.iter().filter(|n| **n > 0.0).count() as f64 / marginal.len() as f64;
+
+        let next = if net < 0.0 {
+            (current + self.step_bps).min(self.ceiling_bps)
+        } else if win_rate >= LOWER_WIN_RATE {
+            current.saturating_sub(self.step_bps).max(self.floor_bps)
+        } else {
+            current
+        };
+        if next == current {
+            return None;
+        }
+
+        info!(
+            "🎚️ min_spread_bps {} -> {} ({} marginal trades, net {:.6} ETH, {:.0}% profitable)",
+            current, next, marginal.len(), net, win_rate * 100.0
+        );
+        self.current_bps.store(next, Ordering::Relaxed);
+        // Trades at the old threshold say nothing about the new one
+        trades.clear();
+        Some(next)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_threshold_follows_marginal_profitability() {
+        // Marginal trades losing money on net: raise
+        let tuner = SpreadTuner::new(20, 10, 40, 2);
+        for net in [-0.002, 0.001, -0.001, 0.0005, -0.0003] {
+            tuner.record(21, net);
+        }
+        // Well-above-threshold winners don't mask the marginal losses
+        tuner.record(60, 0.05);
+        assert_eq!(tuner.retune(), Some(22));
+        assert_eq!(tuner.current(), 22);
+
+        // Marginal trades consistently profitable: lower
+        let tuner = SpreadTuner::new(20, 10, 40, 2);
+        for _ in 0..5 {
+            tuner.record(22, 0.0008);
+        }
+        assert_eq!(tuner.retune(), Some(18));
+
+        // Too few marginal trades to judge
+        let tuner = SpreadTuner::new(20, 10, 40, 2);
+        tuner.record(21, -0.01);
+        assert_eq!(tuner.retune(), None);
+    }
+
+    #[test]
+    fn test_threshold_stays_within_bounds() {
+        let tuner = SpreadTuner::new(50, 10, 40, 5);
+        assert_eq!(tuner.current(), 40);
+        for _ in 0..5 {
+            tuner.record(41, -0.001);
+        }
+        assert_eq!(tuner.retune(), None);
+
+        let tuner = SpreadTuner::new(11, 10, 40, 5);
+        for _ in 0..5 {
+            tuner.record(12, 0.001);
+        }
+        assert_eq!(tuner.retune(), Some(10));
+    }
+}
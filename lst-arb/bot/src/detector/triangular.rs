@@ -0,0 +1,168 @@
+//! Triangular Arbitrage Detection
+//!
+//! Treats every quote as a directed edge between two tokens weighted by
+//! `-ln(rate)`, so a profitable cycle is a negative-weight cycle. Bellman-Ford
+//! is bounded to `MAX_CYCLE_LEN` relaxation rounds from WETH: a full run over
+//! every token is too slow for 250ms blocks, and the contract can only ever
+//! start and end a route in WETH anyway.
+
+use ethers::types::{Address, U256};
+
+use crate::price::{TokenQuotes, Venue};
+use super::solver::u256_to_f64;
+
+/// Longest route considered (WETH -> A -> B -> WETH)
+pub const MAX_CYCLE_LEN: usize = 3;
+
+/// One directed swap: `rate` units of `to` per unit of `from`
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuoteEdge {
+    pub from: Address,
+    pub to: Address,
+    pub venue: Venue,
+    pub fee_tier: Option<u32>,
+    pub pool: Address,
+    pub rate: f64,
+}
+
+/// Build base <-> token edges from quotes made with `amount` of input. The
+/// base is WETH except for directly quoted LST/LST pairs.
+pub fn edges_from_quotes(token_quotes: &[TokenQuotes], weth: Address, amount: U256) -> Vec<QuoteEdge> {
+    let Some(amount_f) = u256_to_f64(amount).filter(|a| *a > 0.0) else {
+        return Vec::new();
+    };
+
+    let mut edges = Vec::new();
+    for tq in token_quotes {
+        let base = tq.base_token.unwrap_or(weth);
+        for (venue, quote) in &tq.quotes {
+            let directions = [
+                (base, tq.token, quote.buy_amount),
+                (tq.token, base, quote.sell_amount),
+            ];
+
+            for (from, to, out) in directions {
+                let Some(out) = u256_to_f64(out).filter(|o| *o > 0.0) else {
+                    continue;
+                };
+                edges.push(QuoteEdge {
+                    from,
+                    to,
+                    venue: *venue,
+                    fee_tier: quote.fee_tier,
+                    pool: quote.pool,
+                    rate: out / amount_f,
+                });
+            }
+        }
+    }
+
+    edges
+}
+
+/// Most profitable `MAX_CYCLE_LEN`-leg cycle from `start`, if any
+///
+/// Returns the legs in order along with the cycle's combined rate (> 1.0).
+pub fn find_cycle(edges: &[QuoteEdge], start: Address) -> Option<(Vec<QuoteEdge>, f64)> {
+    let mut nodes = vec![start];
+    for edge in edges {
+        for token in [edge.from, edge.to] {
+            if !nodes.contains(&token) {
+                nodes.push(token);
+            }
+        }
+    }
+    let index = |token: Address| nodes.iter().position(|n| *n == token);
+
+    let valid: Vec<&QuoteEdge> = edges.iter()
+        .filter(|e| e.rate > 0.0 && e.rate.is_finite() && e.from != e.to)
+        .collect();
+    let weights: Vec<(usize, usize, f64)> = valid.iter()
+        .map(|e| (index(e.from).unwrap_or(0), index(e.to).unwrap_or(0), -e.rate.ln()))
+        .collect();
+
+    // dist[k][v]: lightest walk of exactly k legs from start to v
+    let mut dist = vec![vec![f64::INFINITY; nodes.len()]; MAX_CYCLE_LEN + 1];
+    let mut parent = vec![vec![None; nodes.len()]; MAX_CYCLE_LEN + 1];
+    dist[0][0] = 0.0;
+
+    for k in 1..=MAX_CYCLE_LEN {
+        for (edge_idx, &(u, v, w)) in weights.iter().enumerate() {
+            let candidate = dist[k - 1][u] + w;
+            if candidate < dist[k][v] {
+                dist[k][v] = candidate;
+                parent[k][v] = Some(edge_idx);
+            }
+        }
+    }
+
+    // No self-loops, so an exactly-3-leg walk back to start visits 3 distinct tokens
+    if dist[MAX_CYCLE_LEN][0] >= 0.0 {
+        return None;
+    }
+
+    let mut legs = Vec::with_capacity(MAX_CYCLE_LEN);
+    let mut node = 0;
+    for k in (1..=MAX_CYCLE_LEN).rev() {
+        let edge_idx = parent[k][node]?;
+        legs.push(valid[edge_idx].clone());
+        node = weights[edge_idx].0;
+    }
+    legs.reverse();
+
+    Some((legs, (-dist[MAX_CYCLE_LEN][0]).exp()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edge(from: u64, to: u64, pool: u64, rate: f64) -> QuoteEdge {
+        QuoteEdge {
+            from: Address::from_low_u64_be(from),
+            to: Address::from_low_u64_be(to),
+            venue: Venue::UniswapV3,
+            fee_tier: Some(100),
+            pool: Address::from_low_u64_be(pool),
+            rate,
+        }
+    }
+
+    #[test]
+    fn test_finds_three_leg_cycle() {
+        let weth = Address::from_low_u64_be(1);
+
+        // WETH -> wstETH -> rETH -> WETH nets ~0.5%, every round trip loses
+        let edges = vec![
+            edge(1, 2, 10, 0.85),   // WETH -> wstETH
+            edge(2, 1, 10, 1.17),   // wstETH -> WETH
+            edge(1, 3, 11, 0.90),   // WETH -> rETH
+            edge(3, 1, 11, 1.10),   // rETH -> WETH
+            edge(2, 3, 12, 1.075),  // wstETH -> rETH
+            edge(3, 2, 12, 0.92),   // rETH -> wstETH
+        ];
+
+        let (legs, rate) = find_cycle(&edges, weth).unwrap();
+        assert_eq!(legs.len(), 3);
+        assert_eq!(legs[0], edges[0]);
+        assert_eq!(legs[1], edges[4]);
+        assert_eq!(legs[2], edges[3]);
+        assert!((rate - 0.85 * 1.075 * 1.10).abs() < 1e-9);
+        assert!(rate > 1.0);
+    }
+
+    #[test]
+    fn test_no_cycle_without_profit() {
+        let weth = Address::from_low_u64_be(1);
+        let edges = vec![
+            edge(1, 2, 10, 0.85),
+            edge(2, 1, 10, 1.17),
+            edge(1, 3, 11, 0.90),
+            edge(3, 1, 11, 1.10),
+            edge(2, 3, 12, 1.05),
+            edge(3, 2, 12, 0.94),
+        ];
+
+        assert!(find_cycle(&edges, weth).is_none());
+    }
+}
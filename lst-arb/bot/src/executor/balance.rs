@@ -0,0 +1,175 @@
+//! WETH/ETH Balance Management
+//!
+//! Keeps enough native ETH on the bot wallet to pay for gas and holds the
+//! rest as WETH for trading. Profit that arrives as native ETH is wrapped,
+//! and WETH is unwrapped when the gas reserve runs low.
+
+use ethers::prelude::*;
+use ethers::types::{Address, U256};
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+use crate::rpc::WsClient;
+use super::Executor;
+
+abigen!(
+    IWETH,
+    r#"[
+        function deposit() external payable
+        function withdraw(uint256 wad) external
+        function balanceOf(address account) external view returns (uint256)
+    ]"#
+);
+
+/// Gas limit for WETH deposit/withdraw (both use well under 50k)
+const WRAP_GAS_LIMIT: u64 = 60_000;
+
+/// Action needed to restore the target WETH/ETH split
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WrapAction {
+    /// Split is within tolerance
+    None,
+    /// Wrap this much native ETH into WETH
+    Wrap(U256),
+    /// Unwrap this much WETH into native ETH
+    Unwrap(U256),
+}
+
+/// Decide whether to wrap or unwrap given the current balances
+///
+/// Native ETH above `reserve + tolerance` is wrapped down to the reserve.
+/// Native ETH below `reserve - tolerance` is topped back up to the reserve
+/// from WETH, limited by the WETH actually held.
+pub fn decide_wrap(native: U256, weth: U256, reserve: U256, tolerance: U256) -> WrapAction {
+    if native > reserve.saturating_add(tolerance) {
+        return WrapAction::Wrap(native - reserve);
+    }
+
+    if native < reserve.saturating_sub(tolerance) {
+        let shortfall = reserve - native;
+        let amount = shortfall.min(weth);
+        if !amount.is_zero() {
+            return WrapAction::Unwrap(amount);
+        }
+    }
+
+    WrapAction::None
+}
+
+/// Background manager that maintains the wallet's native ETH gas reserve
+pub struct BalanceManager {
+    weth: Address,
+    reserve: U256,
+    tolerance: U256,
+}
+
+impl BalanceManager {
+    pub fn new(weth: Address, reserve_eth: f64, tolerance_eth: f64) -> eyre::Result<Self> {
+        Ok(Self {
+            weth,
+            reserve: ethers::utils::parse_ether(reserve_eth)?,
+            tolerance: ethers::utils::parse_ether(tolerance_eth)?,
+        })
+    }
+
+    /// Check the wallet split and submit a wrap/unwrap if needed
+    pub async fn rebalance(
+        &self,
+        client: Arc<WsClient>,
+        executor: &Executor,
+    ) -> eyre::Result<WrapAction> {
+        let owner = executor.address();
+        let weth = IWETH::new(self.weth, client.clone());
+
+        let native = client.get_balance(owner, None).await?;
+        let weth_balance = weth.balance_of(owner).call().await?;
+
+        let action = decide_wrap(native, weth_balance, self.reserve, self.tolerance);
+
+        match action {
+            WrapAction::None => {
+                debug!(
+                    "Balance split OK: {} ETH native, {} WETH",
+                    ethers::utils::format_ether(native),
+                    ethers::utils::format_ether(weth_balance)
+                );
+            }
+            WrapAction::Wrap(amount) => {
+                let calldata = weth.deposit().calldata()
+                    .ok_or_else(|| eyre::eyre!("Failed to encode WETH deposit"))?;
+                let hash = executor.send_utility_tx(
+                    &*client,
+                    self.weth,
+                    calldata,
+                    amount,
+                    U256::from(WRAP_GAS_LIMIT),
+                ).await?;
+                info!(
+                    "🔄 Wrapping {} ETH into WETH: {:?}",
+                    ethers::utils::format_ether(amount),
+                    hash
+                );
+            }
+            WrapAction::Unwrap(amount) => {
+                let calldata = weth.withdraw(amount).calldata()
+                    .ok_or_else(|| eyre::eyre!("Failed to encode WETH withdraw"))?;
+                let hash = executor.send_utility_tx(
+                    &*client,
+                    self.weth,
+                    calldata,
+                    U256::zero(),
+                    U256::from(WRAP_GAS_LIMIT),
+                ).await?;
+                warn!(
+                    "🔄 Gas reserve low ({} ETH), unwrapping {} WETH: {:?}",
+                    ethers::utils::format_ether(native),
+                    ethers::utils::format_ether(amount),
+                    hash
+                );
+            }
+        }
+
+        Ok(action)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eth(v: &str) -> U256 {
+        ethers::utils::parse_ether(v).unwrap()
+    }
+
+    #[test]
+    fn test_split_within_tolerance() {
+        let action = decide_wrap(eth("0.006"), eth("1.0"), eth("0.005"), eth("0.002"));
+        assert_eq!(action, WrapAction::None);
+
+        let action = decide_wrap(eth("0.004"), eth("1.0"), eth("0.005"), eth("0.002"));
+        assert_eq!(action, WrapAction::None);
+    }
+
+    #[test]
+    fn test_excess_native_is_wrapped() {
+        // Profit arrived as native ETH
+        let action = decide_wrap(eth("0.105"), eth("1.0"), eth("0.005"), eth("0.002"));
+        assert_eq!(action, WrapAction::Wrap(eth("0.1")));
+    }
+
+    #[test]
+    fn test_low_native_is_unwrapped() {
+        let action = decide_wrap(eth("0.001"), eth("1.0"), eth("0.005"), eth("0.002"));
+        assert_eq!(action, WrapAction::Unwrap(eth("0.004")));
+    }
+
+    #[test]
+    fn test_unwrap_limited_by_weth_held() {
+        let action = decide_wrap(eth("0.001"), eth("0.001"), eth("0.005"), eth("0.002"));
+        assert_eq!(action, WrapAction::Unwrap(eth("0.001")));
+
+        // Nothing to unwrap
+        let action = decide_wrap(eth("0.001"), U256::zero(), eth("0.005"), eth("0.002"));
+        assert_eq!(action, WrapAction::None);
+    }
+}
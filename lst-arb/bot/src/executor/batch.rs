@@ -0,0 +1,63 @@
+//! Concurrent Simulation
+//!
+//! A scan can turn up several opportunities, and simulating them one after
+//! another puts every eth_call round trip on the critical path. Candidates
+//! are simulated together, at most `concurrency` at a time, so every
+//! execution decision is ready at once. Submission stays sequential so
+//! nonces go out in order.
+
+use futures::future::join_all;
+use std::future::Future;
+
+/// Default simulations in flight at once
+pub const DEFAULT_SIMULATION_CONCURRENCY: usize = 4;
+
+/// Run `simulate` on every item with at most `concurrency` in flight,
+/// returning the results in input order
+pub async fn simulate_concurrently<'a, T, R, F, Fut>(
+    items: &'a [T],
+    concurrency: usize,
+    mut simulate: F,
+) -> Vec<R>
+where
+    F: FnMut(&'a T) -> Fut,
+    Fut: Future<Output = R>,
+{
+    let mut results = Vec::with_capacity(items.len());
+    for chunk in items.chunks(concurrency.max(1)) {
+        results.extend(join_all(chunk.iter().map(&mut simulate)).await);
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::{Duration, Instant};
+
+    #[tokio::test]
+    async fn test_simulations_run_concurrently_in_order() {
+        let in_flight = AtomicUsize::new(0);
+        let max_in_flight = AtomicUsize::new(0);
+        let spreads: Vec<u64> = vec![40, 10, 30, 20, 50];
+
+        let start = Instant::now();
+        let results = simulate_concurrently(&spreads, 3, |spread| {
+            let (in_flight, max_in_flight) = (&in_flight, &max_in_flight);
+            async move {
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                max_in_flight.fetch_max(now, Ordering::SeqCst);
+                // Quicker simulations finish first but results keep input order
+                tokio::time::sleep(Duration::from_millis(*spread)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                *spread * 2
+            }
+        }).await;
+
+        assert_eq!(results, vec![80, 20, 60, 40, 100]);
+        assert_eq!(max_in_flight.load(Ordering::SeqCst), 3);
+        // Two rounds (40ms + 50ms), well under the 150ms of running them in turn
+        assert!(start.elapsed() < Duration::from_millis(140), "{:?}", start.elapsed());
+    }
+}
@@ -0,0 +1,81 @@
+//! Per-Block Execution Limit
+//!
+//! Arbs submitted into the same block compete for the same liquidity and
+//! flash-loan source, and the later ones mostly revert. Only so many
+//! submissions go out per block; the check happens at submit time, so a
+//! candidate failing simulation doesn't use up a slot, and the excess is
+//! re-quoted by a later scan against the updated pool state.
+
+use super::ExecutionResult;
+use super::shadow::Decision;
+
+pub const BLOCK_LIMIT_REASON: &str = "per-block submission limit reached";
+
+#[derive(Debug)]
+pub struct BlockLimit {
+    /// 0 disables the limit
+    max_per_block: usize,
+    block: u64,
+    submitted: usize,
+}
+
+impl BlockLimit {
+    pub fn new(max_per_block: usize) -> Self {
+        Self { max_per_block, block: 0, submitted: 0 }
+    }
+
+    /// Note the chain's latest block; a new block frees every slot
+    pub fn observe_block(&mut self, block: u64) {
+        if block > self.block {
+            self.block = block;
+            self.submitted = 0;
+        }
+    }
+
+    /// Submissions still allowed this block
+    pub fn remaining(&self) -> usize {
+        if self.max_per_block == 0 {
+            return usize::MAX;
+        }
+        self.max_per_block.saturating_sub(self.submitted)
+    }
+
+    /// Count an execution against this block if it went on-chain
+    pub fn record(&mut self, result: &eyre::Result<ExecutionResult>) {
+        if Decision::of(result).is_trade() {
+            self.submitted += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::H256;
+
+    #[test]
+    fn test_executions_capped_per_block() {
+        let submitted = || Ok(ExecutionResult::Submitted { hash: H256::zero() });
+        let mut limit = BlockLimit::new(2);
+        limit.observe_block(100);
+        assert_eq!(limit.remaining(), 2);
+
+        // A failed simulation doesn't use up a slot, a submission does
+        limit.record(&Ok(ExecutionResult::Failed { reason: "unprofitable".into() }));
+        assert_eq!(limit.remaining(), 2);
+        limit.record(&submitted());
+        limit.record(&submitted());
+        assert_eq!(limit.remaining(), 0);
+
+        // Same block read again, still full; the next block frees it
+        limit.observe_block(100);
+        assert_eq!(limit.remaining(), 0);
+        limit.observe_block(101);
+        assert_eq!(limit.remaining(), 2);
+
+        // 0 is unlimited
+        let mut unlimited = BlockLimit::new(0);
+        unlimited.record(&submitted());
+        assert_eq!(unlimited.remaining(), usize::MAX);
+    }
+}
@@ -0,0 +1,137 @@
+//! Execution Circuit Breaker
+//!
+//! When something structural breaks (wrong contract address, paused pool)
+//! every trade reverts and the bot burns gas indefinitely. After too many
+//! consecutive reverted or failed submissions the breaker opens and
+//! execution is refused for a cooldown period. Any confirmation resets it.
+//! Pre-flight rejections (unprofitable simulations, gas too high) never
+//! reach this far: the executor only records what it tried to send.
+
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::time::Duration;
+use tracing::error;
+
+use super::ExecutionResult;
+
+/// Consecutive reverts/failures that open the breaker
+pub const DEFAULT_BREAKER_THRESHOLD: u32 = 5;
+
+/// How long execution stays halted once the breaker opens
+pub const DEFAULT_BREAKER_COOLDOWN: Duration = Duration::from_secs(300);
+
+/// Reason reported for executions refused while the breaker is open
+pub const BREAKER_OPEN_REASON: &str = "circuit breaker open";
+
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    /// 0 disables the breaker
+    threshold: u32,
+    cooldown: Duration,
+    consecutive_failures: AtomicU32,
+    /// Unix ms until which execution is halted
+    open_until_ms: AtomicU64,
+}
+
+impl CircuitBreaker {
+    pub fn new(threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            threshold,
+            cooldown,
+            consecutive_failures: AtomicU32::new(0),
+            open_until_ms: AtomicU64::new(0),
+        }
+    }
+
+    pub fn is_open(&self, now_ms: u64) -> bool {
+        now_ms < self.open_until_ms.load(Ordering::Relaxed)
+    }
+
+    /// Count an execution result, returning true if it tripped the breaker
+    pub fn record(&self, result: &ExecutionResult, now_ms: u64) -> bool {
+        match result {
+            ExecutionResult::Confirmed { .. } => {
+                self.consecutive_failures.store(0, Ordering::Relaxed);
+                false
+            }
+            ExecutionResult::Failed { reason } if reason == BREAKER_OPEN_REASON => false,
+            ExecutionResult::Reverted { .. } | ExecutionResult::Failed { .. } => {
+                let failures = self.consecutive_failures.fetch_add(1, Ordering::Relaxed) + 1;
+                if self.threshold == 0 || failures < self.threshold {
+                    return false;
+                }
+
+                self.consecutive_failures.store(0, Ordering::Relaxed);
+                let until = now_ms + self.cooldown.as_millis() as u64;
+                self.open_until_ms.store(until, Ordering::Relaxed);
+                error!(
+                    "🛑 CIRCUIT BREAKER OPEN: {} consecutive failed executions, halting for {:?}",
+                    failures, self.cooldown
+                );
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self::new(DEFAULT_BREAKER_THRESHOLD, DEFAULT_BREAKER_COOLDOWN)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::{H256, U256};
+
+    fn reverted() -> ExecutionResult {
+        ExecutionResult::Reverted { hash: H256::zero(), reason: "reverted".into(), gas_cost: U256::zero(), opportunity_id: H256::zero() }
+    }
+
+    fn failed() -> ExecutionResult {
+        ExecutionResult::Failed { reason: "Submission failed".into() }
+    }
+
+    #[test]
+    fn test_breaker_opens_after_threshold_and_cools_down() {
+        let breaker = CircuitBreaker::new(3, Duration::from_secs(60));
+        let now = 1_000_000;
+
+        assert!(!breaker.record(&reverted(), now));
+        assert!(!breaker.record(&failed(), now));
+        assert!(!breaker.is_open(now));
+
+        assert!(breaker.record(&reverted(), now));
+        assert!(breaker.is_open(now));
+        assert!(breaker.is_open(now + 59_999));
+        assert!(!breaker.is_open(now + 60_000));
+    }
+
+    #[test]
+    fn test_confirmation_resets_count() {
+        let breaker = CircuitBreaker::new(3, Duration::from_secs(60));
+        let confirmed = ExecutionResult::Confirmed {
+            hash: H256::zero(),
+            profit: U256::zero(),
+            gas_cost: U256::zero(),
+            expected_profit: U256::zero(),
+            opportunity_id: H256::zero(),
+        };
+
+        breaker.record(&reverted(), 0);
+        breaker.record(&reverted(), 0);
+        breaker.record(&confirmed, 0);
+        assert!(!breaker.record(&reverted(), 0));
+        assert!(!breaker.record(&reverted(), 0));
+        assert!(!breaker.is_open(0));
+
+        // The breaker's own refusals don't count as failures
+        assert!(!breaker.record(&ExecutionResult::Failed { reason: BREAKER_OPEN_REASON.into() }, 0));
+
+        let disabled = CircuitBreaker::new(0, Duration::from_secs(60));
+        for _ in 0..10 {
+            assert!(!disabled.record(&reverted(), 0));
+        }
+    }
+}
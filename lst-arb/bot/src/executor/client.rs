@@ -0,0 +1,52 @@
+//! Transaction Client
+//!
+//! The handful of RPC methods the executor's submission path needs, behind a
+//! trait so the resubmission, confirmation and nonce logic can be driven by
+//! a scripted client in tests instead of a live node.
+
+use async_trait::async_trait;
+use ethers::prelude::*;
+use ethers::types::{Address, BlockId, Bytes, Transaction, TransactionReceipt, H256, U256};
+
+#[async_trait]
+pub trait TxClient: Send + Sync {
+    /// Broadcast a signed transaction, returning its hash
+    async fn send_raw_transaction(&self, tx: Bytes) -> eyre::Result<H256>;
+
+    async fn get_transaction_receipt(&self, hash: H256) -> eyre::Result<Option<TransactionReceipt>>;
+
+    async fn get_transaction(&self, hash: H256) -> eyre::Result<Option<Transaction>>;
+
+    async fn get_gas_price(&self) -> eyre::Result<U256>;
+
+    async fn get_transaction_count(&self, address: Address, block: Option<BlockId>) -> eyre::Result<U256>;
+
+    async fn get_balance(&self, address: Address) -> eyre::Result<U256>;
+}
+
+#[async_trait]
+impl<P: JsonRpcClient + 'static> TxClient for Provider<P> {
+    async fn send_raw_transaction(&self, tx: Bytes) -> eyre::Result<H256> {
+        Ok(Middleware::send_raw_transaction(self, tx).await?.tx_hash())
+    }
+
+    async fn get_transaction_receipt(&self, hash: H256) -> eyre::Result<Option<TransactionReceipt>> {
+        Ok(Middleware::get_transaction_receipt(self, hash).await?)
+    }
+
+    async fn get_transaction(&self, hash: H256) -> eyre::Result<Option<Transaction>> {
+        Ok(Middleware::get_transaction(self, hash).await?)
+    }
+
+    async fn get_gas_price(&self) -> eyre::Result<U256> {
+        Ok(Middleware::get_gas_price(self).await?)
+    }
+
+    async fn get_transaction_count(&self, address: Address, block: Option<BlockId>) -> eyre::Result<U256> {
+        Ok(Middleware::get_transaction_count(self, address, block).await?)
+    }
+
+    async fn get_balance(&self, address: Address) -> eyre::Result<U256> {
+        Ok(Middleware::get_balance(self, address, None).await?)
+    }
+}
@@ -0,0 +1,104 @@
+//! Shared-Pool Conflicts
+//!
+//! Opportunities from one scan are all priced against the same pool state.
+//! When two of them trade the same pool, executing the first moves that
+//! pool and the second's quote no longer holds. Each scan executes only an
+//! independent set - no two opportunities sharing a pool - taken greedily
+//! in priority order. The rest are skipped (the next scan re-quotes them)
+//! or re-validated with a fresh simulation once the independent set is out.
+
+use ethers::types::Address;
+use std::collections::HashSet;
+
+use crate::detector::Opportunity;
+
+/// What to do with opportunities that share a pool with a higher-priority one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SharedPoolHandling {
+    /// Drop them this scan
+    Skip,
+    /// Simulate them again, one at a time, after the independent set
+    Revalidate,
+}
+
+impl std::str::FromStr for SharedPoolHandling {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> eyre::Result<Self> {
+        match s {
+            "skip" => Ok(Self::Skip),
+            "revalidate" => Ok(Self::Revalidate),
+            other => Err(eyre::eyre!("Unknown shared pool handling: {}", other)),
+        }
+    }
+}
+
+/// Every pool an opportunity trades through
+pub fn pools(opportunity: &Opportunity) -> HashSet<Address> {
+    [opportunity.buy_pool, opportunity.sell_pool].into_iter()
+        .chain(opportunity.route.iter().map(|leg| leg.pool))
+        .filter(|pool| !pool.is_zero())
+        .collect()
+}
+
+/// Split opportunities (in priority order) into an independent set and the
+/// ones that share a pool with an earlier member of it
+pub fn partition(opportunities: Vec<Opportunity>) -> (Vec<Opportunity>, Vec<Opportunity>) {
+    let mut claimed = HashSet::new();
+    let mut independent = Vec::with_capacity(opportunities.len());
+    let mut conflicting = Vec::new();
+
+    for opportunity in opportunities {
+        let pools = pools(&opportunity);
+        if pools.is_disjoint(&claimed) {
+            claimed.extend(pools);
+            independent.push(opportunity);
+        } else {
+            conflicting.push(opportunity);
+        }
+    }
+    (independent, conflicting)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::price::Venue;
+    use ethers::types::U256;
+
+    fn opportunity(buy_pool: u64, sell_pool: u64) -> Opportunity {
+        Opportunity {
+            buy_pool: Address::from_low_u64_be(buy_pool),
+            sell_pool: Address::from_low_u64_be(sell_pool),
+            ..Opportunity::fixture()
+        }
+    }
+
+    #[test]
+    fn test_shared_pool_executes_only_one_per_scan() {
+        // The first two both sell into pool 10; the third is unrelated
+        let (independent, conflicting) = partition(vec![
+            opportunity(1, 10),
+            opportunity(2, 10),
+            opportunity(3, 20),
+        ]);
+
+        let buy_pools: Vec<_> = independent.iter().map(|opp| opp.buy_pool).collect();
+        assert_eq!(buy_pools, vec![Address::from_low_u64_be(1), Address::from_low_u64_be(3)]);
+        assert_eq!(conflicting.len(), 1);
+        assert_eq!(conflicting[0].buy_pool, Address::from_low_u64_be(2));
+
+        // A multi-hop route conflicts through any of its legs
+        let mut triangular = opportunity(4, 5);
+        triangular.route = vec![crate::detector::route::Leg {
+            pool: Address::from_low_u64_be(20),
+            venue: Venue::UniswapV3,
+            token_in: Address::from_low_u64_be(1),
+            token_out: Address::from_low_u64_be(2),
+            amount_in: U256::zero(),
+        }];
+        let (independent, conflicting) = partition(vec![opportunity(3, 20), triangular]);
+        assert_eq!(independent.len(), 1);
+        assert_eq!(conflicting.len(), 1);
+    }
+}
@@ -0,0 +1,191 @@
+//! Arb Contract Guard
+//!
+//! Operators who don't control the arb contract themselves can't rule out
+//! an admin action or exploit changing it under the bot. Its owner,
+//! approved targets and WETH balance are read periodically; the first
+//! deviation from what was expected at startup pauses execution (until a
+//! restart, once someone has looked) and raises an alert.
+//!
+//! Separately, an address with no code (wrong network, not yet deployed, a
+//! typo) makes every simulation revert cryptically; that's checked at
+//! startup and again whenever a simulation fails.
+
+use ethers::prelude::*;
+use ethers::types::{Address, U256};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::rpc::WsClient;
+
+/// Reason reported for executions refused while paused
+pub const CONTRACT_CHANGED_REASON: &str = "arb contract changed";
+
+/// How often the contracts are read
+pub const DEFAULT_CONTRACT_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+abigen!(
+    ArbContractState,
+    r#"[
+        function owner() external view returns (address)
+        function approvedTargets(address target) external view returns (bool)
+        function balanceOf(address account) external view returns (uint256)
+    ]"#
+);
+
+/// Err with a clear message if `contract` has no code
+pub async fn ensure_deployed<M: Middleware>(client: &M, contract: Address) -> eyre::Result<()>
+where
+    M::Error: 'static,
+{
+    let code = client.get_code(contract, None).await?;
+    if code.is_empty() {
+        eyre::bail!("arb contract not deployed at {:?}", contract);
+    }
+    Ok(())
+}
+
+/// What was read from one arb contract
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractState {
+    pub owner: Address,
+    /// Expected target -> whether it's still approved
+    pub approved_targets: HashMap<Address, bool>,
+    pub weth_balance: U256,
+}
+
+pub struct ContractGuard {
+    /// None = not checked
+    expected_owner: Option<Address>,
+    expected_targets: Vec<Address>,
+    /// WETH balance may fall this far below the first reading (None = not checked)
+    balance_drop_tolerance: Option<U256>,
+    /// Contract -> WETH balance at the first check
+    baseline: parking_lot::Mutex<HashMap<Address, U256>>,
+    /// Why execution is paused (None = running)
+    paused: parking_lot::Mutex<Option<String>>,
+}
+
+impl ContractGuard {
+    pub fn new(expected_owner: Option<Address>, expected_targets: Vec<Address>, balance_drop_tolerance: Option<U256>) -> Self {
+        Self {
+            expected_owner,
+            expected_targets,
+            balance_drop_tolerance,
+            baseline: parking_lot::Mutex::new(HashMap::new()),
+            paused: parking_lot::Mutex::new(None),
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.lock().is_some()
+    }
+
+    /// Every way `state` differs from what's expected of `contract`
+    pub fn deviations(&self, contract: Address, state: &ContractState) -> Vec<String> {
+        let mut deviations = Vec::new();
+        if let Some(expected) = self.expected_owner.filter(|owner| *owner != state.owner) {
+            deviations.push(format!("owner is {:?}, expected {:?}", state.owner, expected));
+        }
+        for target in &self.expected_targets {
+            if !state.approved_targets.get(target).copied().unwrap_or(false) {
+                deviations.push(format!("target {:?} no longer approved", target));
+            }
+        }
+
+        let baseline = *self.baseline.lock().entry(contract).or_insert(state.weth_balance);
+        if let Some(tolerance) = self.balance_drop_tolerance {
+            if state.weth_balance.saturating_add(tolerance) < baseline {
+                deviations.push(format!(
+                    "WETH balance fell from {} to {} ETH",
+                    ethers::utils::format_ether(baseline),
+                    ethers::utils::format_ether(state.weth_balance)
+                ));
+            }
+        }
+        deviations
+    }
+
+    /// Check a reading, pausing on any deviation. Returns the alert to send
+    /// when this reading is what paused execution.
+    pub fn record(&self, contract: Address, state: &ContractState) -> Option<String> {
+        let deviations = self.deviations(contract, state);
+        if deviations.is_empty() {
+            return None;
+        }
+
+        let mut paused = self.paused.lock();
+        if paused.is_some() {
+            return None;
+        }
+        let reason = format!("Arb contract {:?} changed: {}", contract, deviations.join("; "));
+        *paused = Some(reason.clone());
+        Some(format!("🛑 {}. Execution paused until restart", reason))
+    }
+
+    /// Read `contract`'s owner, expected targets and WETH balance
+    pub async fn read(&self, client: Arc<WsClient>, contract: Address, weth: Address) -> eyre::Result<ContractState> {
+        let arb = ArbContractState::new(contract, client.clone());
+        let mut approved_targets = HashMap::new();
+        for target in &self.expected_targets {
+            approved_targets.insert(*target, arb.approved_targets(*target).call().await?);
+        }
+        Ok(ContractState {
+            owner: arb.owner().call().await?,
+            approved_targets,
+            weth_balance: ArbContractState::new(weth, client).balance_of(contract).call().await?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_owner_change_pauses_execution() {
+        let (contract, owner, vault) = (Address::from_low_u64_be(1), Address::from_low_u64_be(2), Address::from_low_u64_be(3));
+        let guard = ContractGuard::new(Some(owner), vec![vault], Some(U256::from(100u64)));
+        let state = ContractState {
+            owner,
+            approved_targets: HashMap::from([(vault, true)]),
+            weth_balance: U256::from(1_000u64),
+        };
+
+        assert_eq!(guard.record(contract, &state), None);
+        // Small balance moves are tolerated
+        assert_eq!(guard.record(contract, &ContractState { weth_balance: U256::from(950u64), ..state.clone() }), None);
+        assert!(!guard.is_paused());
+
+        let hijacked = ContractState { owner: Address::from_low_u64_be(66), ..state.clone() };
+        let alert = guard.record(contract, &hijacked).unwrap();
+        assert!(alert.contains("owner is"), "{}", alert);
+        assert!(guard.is_paused());
+        // Alerted once; stays paused even if the reading recovers
+        assert_eq!(guard.record(contract, &hijacked), None);
+        assert_eq!(guard.record(contract, &state), None);
+        assert!(guard.is_paused());
+
+        // Drained balance and revoked targets are deviations too
+        let drained = ContractState {
+            approved_targets: HashMap::from([(vault, false)]),
+            weth_balance: U256::from(10u64),
+            ..state
+        };
+        assert_eq!(guard.deviations(contract, &drained).len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_codeless_address_reports_not_deployed() {
+        let (provider, mock) = Provider::mocked();
+        let contract = Address::from_low_u64_be(1);
+
+        mock.push::<Bytes, _>(Bytes::new()).unwrap();
+        let error = ensure_deployed(&provider, contract).await.unwrap_err();
+        assert_eq!(error.to_string(), format!("arb contract not deployed at {:?}", contract));
+        mock.assert_request("eth_getCode", (contract, "latest")).unwrap();
+
+        mock.push::<Bytes, _>(Bytes::from(vec![0x60, 0x80])).unwrap();
+        assert!(ensure_deployed(&provider, contract).await.is_ok());
+    }
+}
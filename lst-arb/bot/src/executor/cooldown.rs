@@ -0,0 +1,153 @@
+//! Per-Token Cooldown
+//!
+//! A pool that stays mispriced (or a model that's wrong about it) shows the
+//! "same" opportunity on every trigger, and executing each one burns gas on
+//! the same revert over and over. After an attempt on a token, further
+//! attempts are held off for a cooldown unless the spread has widened
+//! materially since. A confirmed trade lifts the cooldown straight away.
+
+use dashmap::DashMap;
+use ethers::types::{Address, H256};
+use std::time::{Duration, Instant};
+use tracing::info;
+
+use crate::detector::Opportunity;
+use super::ExecutionResult;
+use super::shadow::Decision;
+
+pub const DEFAULT_TOKEN_COOLDOWN: Duration = Duration::from_secs(5);
+
+/// Spread increase over the last attempt that lets a token through its cooldown
+pub const DEFAULT_COOLDOWN_SPREAD_INCREASE_BPS: u64 = 10;
+
+#[derive(Debug, Clone, Copy)]
+struct Attempt {
+    at: Instant,
+    spread_bps: u64,
+    opportunity_id: H256,
+}
+
+pub struct TokenCooldown {
+    cooldown: Duration,
+    min_spread_increase_bps: u64,
+    /// Keyed by (base token, token): an LST traded against WETH and against
+    /// another LST are separate markets
+    attempts: DashMap<(Address, Address), Attempt>,
+}
+
+impl TokenCooldown {
+    pub fn new(cooldown: Duration, min_spread_increase_bps: u64) -> Self {
+        Self {
+            cooldown,
+            min_spread_increase_bps,
+            attempts: DashMap::new(),
+        }
+    }
+
+    /// Whether `opportunity` may be attempted now: its token is out of
+    /// cooldown, or the spread has widened enough since the last attempt
+    pub fn admit(&self, opportunity: &Opportunity, now: Instant) -> bool {
+        let Some(last) = self.attempts.get(&market(opportunity)).map(|attempt| *attempt) else {
+            return true;
+        };
+        let elapsed = now.saturating_duration_since(last.at);
+        if elapsed >= self.cooldown
+            || opportunity.spread_bps >= last.spread_bps.saturating_add(self.min_spread_increase_bps)
+        {
+            return true;
+        }
+
+        info!(
+            "🧊 Suppressing {} [{}]: attempted {}ms ago at {}bps, now {}bps",
+            opportunity.token_name, opportunity.short_id(),
+            elapsed.as_millis(), last.spread_bps, opportunity.spread_bps
+        );
+        false
+    }
+
+    /// Split opportunities into the ones admitted now and the ones in cooldown
+    pub fn split(&self, opportunities: Vec<Opportunity>, now: Instant) -> (Vec<Opportunity>, Vec<Opportunity>) {
+        opportunities.into_iter().partition(|opportunity| self.admit(opportunity, now))
+    }
+
+    /// Start the token's cooldown if the opportunity went on-chain, or lift
+    /// it if the trade already confirmed
+    pub fn record(&self, opportunity: &Opportunity, result: &eyre::Result<ExecutionResult>, now: Instant) {
+        if let Ok(ExecutionResult::Confirmed { .. }) = result {
+            self.attempts.remove(&market(opportunity));
+        } else if Decision::of(result).is_trade() {
+            self.attempts.insert(market(opportunity), Attempt {
+                at: now,
+                spread_bps: opportunity.spread_bps,
+                opportunity_id: opportunity.id,
+            });
+        }
+    }
+
+    /// Lift the cooldown of the token whose attempt just confirmed
+    pub fn record_resolved(&self, result: &ExecutionResult) {
+        if let (ExecutionResult::Confirmed { .. }, Some(id)) = (result, result.opportunity_id()) {
+            self.attempts.retain(|_, attempt| attempt.opportunity_id != id);
+        }
+    }
+}
+
+fn market(opportunity: &Opportunity) -> (Address, Address) {
+    (opportunity.base_token, opportunity.token)
+}
+
+impl Default for TokenCooldown {
+    fn default() -> Self {
+        Self::new(DEFAULT_TOKEN_COOLDOWN, DEFAULT_COOLDOWN_SPREAD_INCREASE_BPS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::U256;
+
+    fn opportunity(token: u64, spread_bps: u64) -> Opportunity {
+        Opportunity {
+            id: H256::from_low_u64_be(token * 1_000 + spread_bps),
+            token: Address::from_low_u64_be(token),
+            token_name: format!("lst{}", token),
+            spread_bps,
+            ..Opportunity::fixture()
+        }
+    }
+
+    #[test]
+    fn test_token_suppressed_until_cooldown_or_wider_spread() {
+        let cooldown = TokenCooldown::new(Duration::from_secs(5), 10);
+        let start = Instant::now();
+        let attempted = opportunity(1, 30);
+
+        // A simulation failure never went on-chain: no cooldown
+        cooldown.record(&attempted, &Ok(ExecutionResult::Failed { reason: "unprofitable".into() }), start);
+        assert!(cooldown.admit(&attempted, start));
+
+        cooldown.record(&attempted, &Ok(ExecutionResult::Submitted { hash: H256::zero() }), start);
+        let soon = start + Duration::from_secs(1);
+        assert!(!cooldown.admit(&opportunity(1, 35), soon));
+        // Other tokens, the same token against another base, a materially
+        // wider spread, or the cooldown running out get through
+        assert!(cooldown.admit(&opportunity(2, 30), soon));
+        assert!(cooldown.admit(&Opportunity { base_token: Address::from_low_u64_be(9), ..opportunity(1, 30) }, soon));
+        assert!(cooldown.admit(&opportunity(1, 40), soon));
+        assert!(cooldown.admit(&opportunity(1, 30), start + Duration::from_secs(5)));
+
+        let (admitted, suppressed) = cooldown.split(vec![opportunity(1, 30), opportunity(2, 30)], soon);
+        assert_eq!((admitted.len(), suppressed.len()), (1, 1));
+
+        // The attempt confirming lifts the cooldown
+        cooldown.record_resolved(&ExecutionResult::Confirmed {
+            hash: H256::zero(),
+            profit: U256::zero(),
+            gas_cost: U256::zero(),
+            expected_profit: U256::zero(),
+            opportunity_id: attempted.id,
+        });
+        assert!(cooldown.admit(&opportunity(1, 30), soon));
+    }
+}
@@ -0,0 +1,113 @@
+//! Gas Price Smoothing
+//!
+//! A single spiky `eth_gasPrice` reading shouldn't make a good opportunity
+//! look unprofitable. The executor keeps an exponential moving average of
+//! recent readings and uses it for the profitability check. The spot reading
+//! still bounds it from above, so a falling gas price is picked up
+//! immediately. The gas cap applies to the spot price the tx actually pays.
+
+use ethers::types::U256;
+
+/// Default weight of the newest reading
+pub const DEFAULT_GAS_EMA_ALPHA: f64 = 0.2;
+
+#[derive(Debug)]
+pub struct GasPriceEma {
+    alpha: f64,
+    ema: Option<f64>,
+}
+
+impl GasPriceEma {
+    /// `alpha` is the weight of each new reading (1.0 disables smoothing)
+    pub fn new(alpha: f64) -> Self {
+        Self {
+            alpha: alpha.clamp(0.01, 1.0),
+            ema: None,
+        }
+    }
+
+    /// Record a spot reading and return the price to use for checks
+    pub fn update(&mut self, spot: U256) -> U256 {
+        let spot_f = spot.as_u128() as f64;
+
+        let ema = match self.ema {
+            Some(prev) => prev + self.alpha * (spot_f - prev),
+            None => spot_f,
+        };
+        self.ema = Some(ema);
+
+        U256::from(ema as u128).min(spot)
+    }
+
+    /// The smoothed price in wei, once there has been a reading
+    pub fn state(&self) -> Option<f64> {
+        self.ema
+    }
+
+    /// Start from a previously learned average instead of the first reading
+    pub fn seed(&mut self, ema: f64) {
+        if ema.is_finite() && ema >= 0.0 {
+            self.ema = Some(ema);
+        }
+    }
+
+    /// The price `update` would return for `spot`, without recording it
+    pub fn peek(&self, spot: U256) -> U256 {
+        match self.ema {
+            Some(prev) => {
+                let spot_f = spot.as_u128() as f64;
+                U256::from((prev + self.alpha * (spot_f - prev)) as u128).min(spot)
+            }
+            None => spot,
+        }
+    }
+}
+
+impl Default for GasPriceEma {
+    fn default() -> Self {
+        Self::new(DEFAULT_GAS_EMA_ALPHA)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gwei(value: f64) -> U256 {
+        ethers::utils::parse_units(value, "gwei").unwrap().into()
+    }
+
+    #[test]
+    fn test_single_spike_does_not_fail_profit_check() {
+        let mut gas = GasPriceEma::default();
+        let gas_units = U256::from(400_000u64);
+        let expected_profit = ethers::utils::parse_ether("0.001").unwrap();
+
+        for _ in 0..10 {
+            gas.update(gwei(0.1));
+        }
+
+        // A 5 gwei outlier would fail the profit check on its own
+        let spike = gwei(5.0);
+        assert!(gas_units * spike > expected_profit);
+
+        let smoothed = gas.update(spike);
+        assert!(gas_units * smoothed < expected_profit);
+    }
+
+    #[test]
+    fn test_spot_bounds_smoothed_price() {
+        let mut gas = GasPriceEma::default();
+        gas.update(gwei(1.0));
+
+        // Gas dropped - use the lower spot price right away
+        assert_eq!(gas.update(gwei(0.1)), gwei(0.1));
+
+        // Sustained high gas is eventually reflected
+        let mut smoothed = U256::zero();
+        for _ in 0..50 {
+            smoothed = gas.update(gwei(3.0));
+        }
+        assert!(smoothed > gwei(2.9));
+    }
+}
@@ -0,0 +1,170 @@
+//! Cheap Gas Windows
+//!
+//! Structural mispricings (e.g. a rate lag) can persist for minutes, so
+//! there's no need to pay peak gas for them. While gas is above the
+//! configured level, an opportunity is held back unless its profit is
+//! decaying quickly between sightings. Durable opportunities keep
+//! reappearing on later scans and are executed once gas drops; ones that
+//! stop reappearing simply age out.
+//!
+//! Urgency is the fractional profit lost per second between two sightings
+//! of the same route. A route seen for the first time has no urgency yet
+//! and is held for one more sighting.
+
+use ethers::types::{Address, U256};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+use crate::detector::Opportunity;
+use crate::price::Venue;
+
+/// Held routes not seen again within this long are forgotten
+pub(super) const QUEUE_TTL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(super) struct RouteKey {
+    base_token: Address,
+    token: Address,
+    buy_venue: Venue,
+    buy_fee_tier: Option<u32>,
+    sell_venue: Venue,
+    sell_fee_tier: Option<u32>,
+}
+
+impl RouteKey {
+    pub(super) fn of(opportunity: &Opportunity) -> Self {
+        Self {
+            base_token: opportunity.base_token,
+            token: opportunity.token,
+            buy_venue: opportunity.buy_venue,
+            buy_fee_tier: opportunity.buy_fee_tier,
+            sell_venue: opportunity.sell_venue,
+            sell_fee_tier: opportunity.sell_fee_tier,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Sighting {
+    pub(super) profit: U256,
+    pub(super) seen_at: Instant,
+}
+
+#[derive(Debug)]
+pub struct GasWindow {
+    /// Gas price at or below which everything executes immediately
+    cheap_gas_price: U256,
+    /// Profit decay per second (0.05 = 5%/s) at which an opportunity can't wait
+    urgency_threshold: f64,
+    queued: HashMap<RouteKey, Sighting>,
+}
+
+impl GasWindow {
+    pub fn new(cheap_gas_price_gwei: f64, urgency_threshold: f64) -> eyre::Result<Self> {
+        Ok(Self {
+            cheap_gas_price: ethers::utils::parse_units(cheap_gas_price_gwei, "gwei")?.into(),
+            urgency_threshold,
+            queued: HashMap::new(),
+        })
+    }
+
+    /// Decide whether to execute now; otherwise the opportunity is queued
+    /// until gas drops or its profit starts decaying
+    pub fn admit(&mut self, opportunity: &Opportunity, gas_price: U256, now: Instant) -> bool {
+        self.queued.retain(|_, s| now.duration_since(s.seen_at) < QUEUE_TTL);
+
+        let key = RouteKey::of(opportunity);
+        let sighting = Sighting { profit: opportunity.expected_profit, seen_at: now };
+
+        if gas_price <= self.cheap_gas_price {
+            self.queued.remove(&key);
+            return true;
+        }
+
+        let urgent = self.queued.get(&key)
+            .and_then(|previous| urgency(previous, &sighting))
+            .is_some_and(|u| u >= self.urgency_threshold);
+
+        if urgent {
+            self.queued.remove(&key);
+        } else {
+            debug!(
+                "Queued {} {:?}->{:?} until gas drops below {}",
+                opportunity.token_name, opportunity.buy_venue, opportunity.sell_venue,
+                self.cheap_gas_price
+            );
+            self.queued.insert(key, sighting);
+        }
+        urgent
+    }
+
+    pub fn queued_len(&self) -> usize {
+        self.queued.len()
+    }
+}
+
+/// Fractional profit lost per second between two sightings
+pub(super) fn urgency(previous: &Sighting, current: &Sighting) -> Option<f64> {
+    let elapsed = current.seen_at.checked_duration_since(previous.seen_at)?.as_secs_f64();
+    if elapsed <= 0.0 || previous.profit.is_zero() {
+        return None;
+    }
+    let previous_profit = previous.profit.as_u128() as f64;
+    let lost = previous_profit - current.profit.as_u128() as f64;
+    Some(lost / previous_profit / elapsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gwei(value: f64) -> U256 {
+        ethers::utils::parse_units(value, "gwei").unwrap().into()
+    }
+
+    fn opportunity(profit: &str) -> Opportunity {
+        Opportunity {
+            expected_profit: ethers::utils::parse_ether(profit).unwrap(),
+            trade_amount: ethers::utils::parse_ether("5").unwrap(),
+            ..Opportunity::fixture()
+        }
+    }
+
+    #[test]
+    fn test_durable_opportunity_waits_for_cheap_gas() {
+        let mut window = GasWindow::new(0.1, 0.05).unwrap();
+        let start = Instant::now();
+
+        // Stable profit while gas is high - keeps waiting
+        assert!(!window.admit(&opportunity("0.010"), gwei(0.5), start));
+        assert!(!window.admit(&opportunity("0.010"), gwei(0.5), start + Duration::from_secs(2)));
+        assert_eq!(window.queued_len(), 1);
+
+        // Gas drops - executes
+        assert!(window.admit(&opportunity("0.010"), gwei(0.08), start + Duration::from_secs(4)));
+        assert_eq!(window.queued_len(), 0);
+    }
+
+    #[test]
+    fn test_decaying_opportunity_executes_despite_high_gas() {
+        let mut window = GasWindow::new(0.1, 0.05).unwrap();
+        let start = Instant::now();
+
+        assert!(!window.admit(&opportunity("0.010"), gwei(0.5), start));
+        // Lost 20% in one second
+        assert!(window.admit(&opportunity("0.008"), gwei(0.5), start + Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn test_unseen_routes_age_out() {
+        let mut window = GasWindow::new(0.1, 0.05).unwrap();
+        let start = Instant::now();
+
+        window.admit(&opportunity("0.010"), gwei(0.5), start);
+        let mut other = opportunity("0.010");
+        other.token = Address::from_low_u64_be(2);
+        window.admit(&other, gwei(0.5), start + QUEUE_TTL);
+        assert_eq!(window.queued_len(), 1);
+    }
+}
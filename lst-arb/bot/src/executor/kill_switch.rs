@@ -0,0 +1,70 @@
+//! Kill Switch
+//!
+//! An emergency stop that doesn't need the process found and killed: while
+//! the kill-switch file exists, execution is refused. The file is polled,
+//! so removing it re-enables execution without a restart.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// Reason reported for executions refused while the kill switch is engaged
+pub const KILL_SWITCH_REASON: &str = "kill switch engaged";
+
+/// How often the kill-switch file is checked
+pub const KILL_SWITCH_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug)]
+pub struct KillSwitch {
+    path: PathBuf,
+    engaged: AtomicBool,
+}
+
+impl KillSwitch {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            engaged: AtomicBool::new(false),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn is_engaged(&self) -> bool {
+        self.engaged.load(Ordering::Relaxed)
+    }
+
+    /// Check for the file, returning the new state if it changed
+    pub fn poll(&self) -> Option<bool> {
+        let exists = self.path.exists();
+        let was = self.engaged.swap(exists, Ordering::Relaxed);
+        (was != exists).then_some(exists)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kill_switch_follows_file() {
+        let path = std::env::temp_dir().join(format!("lst-arb-kill-switch-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let kill_switch = KillSwitch::new(&path);
+
+        assert_eq!(kill_switch.poll(), None);
+        assert!(!kill_switch.is_engaged());
+
+        std::fs::write(&path, "").unwrap();
+        assert_eq!(kill_switch.poll(), Some(true));
+        assert!(kill_switch.is_engaged());
+        // Only transitions are reported
+        assert_eq!(kill_switch.poll(), None);
+
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(kill_switch.poll(), Some(false));
+        assert!(!kill_switch.is_engaged());
+    }
+}
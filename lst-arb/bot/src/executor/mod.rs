@@ -1,46 +1,178 @@
 use ethers::prelude::*;
 use ethers::types::{Address, U256, Bytes, TransactionRequest, H256};
+use ethers::types::transaction::eip2718::TypedTransaction;
 use ethers::signers::LocalWallet;
+use std::collections::HashMap;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use tokio::sync::RwLock;
-use tracing::{info, warn, error};
+use tracing::{debug, info, warn, error};
 
+use crate::competition::{CompetitorMonitor, CONTESTED_BUMP_MULTIPLIER, CONTESTED_RESUBMIT_AFTER, DEFAULT_STUCK_TIMEOUT};
 use crate::rpc::WsClient;
 use crate::detector::Opportunity;
-use crate::simulator::{Simulator, SimulationResult};
+use crate::detector::spread_tuner::SpreadTuner;
+use crate::price::Venue;
+use crate::scout::PoolBlacklist;
+use crate::simulator::{compute_min_profit, Simulator, SimulationResult, DEFAULT_MAX_SLIPPAGE_BPS};
+use crate::simulator::sweep::sweep_sizes_for;
+
+pub mod balance;
+pub mod batch;
+pub mod block_limit;
+pub mod breaker;
+pub mod client;
+pub mod conflicts;
+pub mod cooldown;
+pub mod contract_guard;
+pub mod gas;
+pub mod gas_window;
+pub mod kill_switch;
+pub mod probe;
+pub mod realized;
+pub mod replay;
+pub mod shadow;
+pub mod wallet_guard;
+pub mod warm_start;
+pub mod win_probability;
+
+pub use balance::{BalanceManager, WrapAction};
+pub use block_limit::BlockLimit;
+pub use breaker::CircuitBreaker;
+pub use conflicts::SharedPoolHandling;
+pub use contract_guard::ContractGuard;
+pub use cooldown::TokenCooldown;
+pub use gas::GasPriceEma;
+pub use gas_window::GasWindow;
+pub use kill_switch::KillSwitch;
+pub use probe::ProbeSizer;
+pub use shadow::ShadowExecutor;
+pub use wallet_guard::BalanceGuard;
+pub use warm_start::WarmState;
+pub use win_probability::WinEstimator;
+use client::TxClient;
+use replay::{ReplayGuard, ReplayOutcome};
+
+/// Reported for routed opportunities while route execution is off
+pub const ROUTE_UNSUPPORTED_REASON: &str = "arb contract has no executeRoute";
 
 pub struct Executor {
     wallet: LocalWallet,
+    /// Default contract, used for venue pairs without a dedicated one
     arb_contract: Address,
+    /// Contracts specialised for a (buy, sell) venue pair
+    contracts: HashMap<(Venue, Venue), Address>,
     simulator: Simulator,
     nonce: AtomicU64,
+    /// Nonces handed out but not yet sent or given up on
+    nonces_in_flight: AtomicUsize,
     use_flashbots: bool,
     flashbots_relay: String,
     pending_txs: RwLock<Vec<PendingTx>>,
+    replay_guard: parking_lot::Mutex<ReplayGuard>,
+    probes: Option<parking_lot::Mutex<ProbeSizer>>,
+    competition: Option<Arc<CompetitorMonitor>>,
+    gas_ema: parking_lot::Mutex<GasPriceEma>,
     max_gas_price: U256,
     max_priority_fee: U256,
+    gas_bump_percent: f64,
+    breaker: CircuitBreaker,
+    /// Refuses all execution while engaged
+    kill_switch: Option<Arc<KillSwitch>>,
+    /// Refuses execution while the wallet can't cover gas
+    balance_guard: Option<BalanceGuard>,
+    /// Refuses execution once the arb contract changed unexpectedly
+    contract_guard: Option<Arc<ContractGuard>>,
+    /// Check a failed simulation's contract has code at all
+    check_deployed: bool,
+    /// Adverse pool move since quoting that minProfit must tolerate
+    max_slippage_bps: u64,
+    /// Submissions allowed per block, checked right before submitting
+    block_limit: parking_lot::Mutex<BlockLimit>,
+    /// Don't bump a tx the node reports in a block (receipt query lagging)
+    skip_resubmit_if_known: bool,
+    /// Simulate only and report passing trades as confirmed, never submitting
+    paper_trading: bool,
+    /// Skips opportunities we're unlikely to win against competitors
+    win_filter: Option<parking_lot::Mutex<WinEstimator>>,
+    /// Fed resolved trades to auto-tune the detector's minimum spread
+    spread_tuner: Option<Arc<SpreadTuner>>,
+    /// Counts reverts per pool and blacklists repeat offenders
+    pool_blacklist: Option<Arc<PoolBlacklist>>,
+    /// Re-size trades whose solver estimate is off on-chain by more than this (bps)
+    size_sweep_threshold_bps: Option<u64>,
 }
 
 #[derive(Debug, Clone)]
 pub struct PendingTx {
     pub hash: H256,
+    pub nonce: u64,
     pub opportunity: Opportunity,
     pub submitted_at: std::time::Instant,
     pub gas_price: U256,
+    /// Treat as stuck after this long unconfirmed
+    pub stuck_timeout: std::time::Duration,
 }
 
 #[derive(Debug, Clone)]
 pub enum ExecutionResult {
     Submitted { hash: H256 },
-    Confirmed { hash: H256, profit: U256 },
-    Reverted { hash: H256, reason: String },
+    /// `profit` is what the arb contract reported (the estimate if it didn't
+    /// log one); `expected_profit` is the detector's estimate
+    Confirmed { hash: H256, profit: U256, gas_cost: U256, expected_profit: U256, opportunity_id: H256 },
+    /// `opportunity_id` is zero for a tx resolved without its opportunity
+    Reverted { hash: H256, reason: String, gas_cost: U256, opportunity_id: H256 },
     Failed { reason: String },
     /// Transaction aborted due to pre-flight check failure
     /// (price moved too much during the detection-to-execution window)
     Aborted { expected_profit: U256, actual_profit: U256 },
 }
 
+impl ExecutionResult {
+    /// ID of the opportunity a resolved tx came from, when known
+    pub fn opportunity_id(&self) -> Option<H256> {
+        match self {
+            Self::Confirmed { opportunity_id, .. } | Self::Reverted { opportunity_id, .. } => {
+                Some(*opportunity_id).filter(|id| !id.is_zero())
+            }
+            _ => None,
+        }
+    }
+}
+
+/// An opportunity that passed simulation, ready to build and submit
+struct Simulated {
+    /// Probe-sized when the pools are unproven
+    opportunity: Opportunity,
+    arb_contract: Address,
+    spot_gas_price: U256,
+    sim_result: SimulationResult,
+}
+
+/// Where an execution attempt stands after simulation
+enum Prepared {
+    /// Not attempted (breaker open, filtered out); not counted by the breaker
+    Skipped(ExecutionResult),
+    /// Attempted and ended before submission; not counted by the breaker
+    Done(ExecutionResult),
+    Simulated(Box<Simulated>),
+}
+
+/// Submission attempts per transaction (first send plus fee-bumped replacements)
+const MAX_SUBMIT_ATTEMPTS: u32 = 3;
+
+/// How long to wait for inclusion before bumping (~4 Arbitrum blocks)
+const RESUBMIT_AFTER: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Default max fee bump per resubmission
+pub const DEFAULT_GAS_BUMP_PERCENT: f64 = 12.5;
+
+/// How often to look for nonce gaps while no tracked tx is stuck
+pub const NONCE_GAP_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// How often to check whether in-flight txs have been mined at startup
+const PENDING_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(250);
+
 /// Result of pre-flight execution integrity verification
 #[derive(Debug, Clone)]
 enum VerificationResult {
@@ -53,75 +185,535 @@ enum VerificationResult {
 }
 
 impl Executor {
-    pub async fn new(
-        client: Arc<WsClient>,
+    #[allow(clippy::too_many_arguments)]
+    pub async fn new<C: TxClient + ?Sized>(
+        client: &C,
         wallet: LocalWallet,
         arb_contract: Address,
         use_flashbots: bool,
         flashbots_relay: String,
         max_gas_price_gwei: u64,
         max_priority_fee_gwei: u64,
+        gas_bump_percent: f64,
+        startup_pending_wait: std::time::Duration,
     ) -> eyre::Result<Self> {
-        // Fetch initial nonce
-        let nonce = client.get_transaction_count(wallet.address(), None).await?;
+        // Fetch initial nonce, skipping any txs the wallet already has in flight
+        let nonce = startup_nonce(client, wallet.address(), startup_pending_wait).await?;
         
         Ok(Self {
             wallet,
             arb_contract,
-            simulator: Simulator::new(arb_contract),
-            nonce: AtomicU64::new(nonce.as_u64()),
+            contracts: HashMap::new(),
+            simulator: Simulator::new(),
+            nonce: AtomicU64::new(nonce),
+            nonces_in_flight: AtomicUsize::new(0),
             use_flashbots,
             flashbots_relay,
             pending_txs: RwLock::new(Vec::new()),
+            replay_guard: parking_lot::Mutex::new(ReplayGuard::new()),
+            probes: None,
+            competition: None,
+            gas_ema: parking_lot::Mutex::new(GasPriceEma::default()),
             max_gas_price: ethers::utils::parse_units(max_gas_price_gwei, "gwei")?.into(),
             max_priority_fee: ethers::utils::parse_units(max_priority_fee_gwei, "gwei")?.into(),
+            gas_bump_percent,
+            skip_resubmit_if_known: true,
+            paper_trading: false,
+            win_filter: None,
+            spread_tuner: None,
+            pool_blacklist: None,
+            size_sweep_threshold_bps: None,
+            breaker: CircuitBreaker::default(),
+            kill_switch: None,
+            balance_guard: None,
+            contract_guard: None,
+            check_deployed: false,
+            max_slippage_bps: DEFAULT_MAX_SLIPPAGE_BPS,
+            block_limit: parking_lot::Mutex::new(BlockLimit::new(0)),
         })
     }
     
-    /// Execute an arbitrage opportunity
-    /// Optimized for Arbitrum's FIFO sequencer (no MEV, no priority fees)
+    /// Cap trades through pools without a confirmed trade at a probe size
+    pub fn with_probe_trades(mut self, sizer: ProbeSizer) -> Self {
+        self.probes = Some(parking_lot::Mutex::new(sizer));
+        self
+    }
+
+    /// Weight of each new gas price reading in the smoothed gas price
+    pub fn with_gas_smoothing(self, alpha: f64) -> Self {
+        *self.gas_ema.lock() = GasPriceEma::new(alpha);
+        self
+    }
+
+    /// Learned state worth carrying over a restart
+    pub fn warm_state(&self) -> WarmState {
+        WarmState {
+            saved_at_ms: now_ms(),
+            gas_price_ema: self.gas_ema.lock().state(),
+        }
+    }
+
+    /// Resume from state saved by a previous run
+    pub fn with_warm_state(self, state: &WarmState) -> Self {
+        if let Some(ema) = state.gas_price_ema {
+            self.gas_ema.lock().seed(ema);
+        }
+        self
+    }
+
+    /// Smoothed gas price for a spot reading, as `execute` would price it
+    pub fn smoothed_gas_price(&self, spot: U256) -> U256 {
+        self.gas_ema.lock().peek(spot)
+    }
+
+    /// Halt execution for `cooldown` after `threshold` consecutive reverts/failures
+    pub fn with_circuit_breaker(mut self, threshold: u32, cooldown: std::time::Duration) -> Self {
+        self.breaker = CircuitBreaker::new(threshold, cooldown);
+        self
+    }
+
+    /// Refuse to execute while `kill_switch` is engaged
+    pub fn with_kill_switch(mut self, kill_switch: Arc<KillSwitch>) -> Self {
+        self.kill_switch = Some(kill_switch);
+        self
+    }
+
+    /// Refuse to execute while the wallet's ETH balance (read every
+    /// `refresh_interval`) is below `min_balance`
+    pub fn with_balance_guard(mut self, min_balance: U256, refresh_interval: std::time::Duration) -> Self {
+        self.balance_guard = Some(BalanceGuard::new(min_balance, refresh_interval));
+        self
+    }
+
+    /// Refuse to execute once `guard` has seen the arb contract change
+    pub fn with_contract_guard(mut self, guard: Arc<ContractGuard>) -> Self {
+        self.contract_guard = Some(guard);
+        self
+    }
+
+    /// Note the latest block, which expires cached simulations and frees
+    /// the per-block submission slots
+    pub fn observe_block(&self, block: u64) {
+        self.block_limit.lock().observe_block(block);
+        self.simulator.observe_block(block);
+    }
+
+    /// The wallet balance, when it's low and not alerted on within the cooldown
+    pub fn low_balance_alert(&self) -> Option<U256> {
+        self.balance_guard.as_ref()?.take_alert(now_ms())
+    }
+
+    /// Whether the last wallet balance read is below the guard's minimum
+    pub fn is_balance_low(&self) -> bool {
+        self.balance_guard.as_ref().is_some_and(|guard| guard.is_low())
+    }
+
+    fn is_killed(&self) -> bool {
+        self.kill_switch.as_ref().is_some_and(|kill_switch| kill_switch.is_engaged())
+    }
+
+    /// Submit at most this many transactions per block (0 = unlimited)
+    pub fn with_max_per_block(self, max_per_block: usize) -> Self {
+        *self.block_limit.lock() = BlockLimit::new(max_per_block);
+        self
+    }
+
+    /// Submissions still allowed in the current block
+    pub fn block_slots_remaining(&self) -> usize {
+        self.block_limit.lock().remaining()
+    }
+
+    /// Size minProfit for pools moving this far against us after quoting
+    pub fn with_max_slippage_bps(mut self, bps: u64) -> Self {
+        self.max_slippage_bps = bps;
+        self
+    }
+
+    /// Sweep trade sizes on-chain before executing, trading the best swept
+    /// size when the solver's profit estimate is off by more than `threshold_bps`
+    pub fn with_size_sweep(mut self, multicall3: Address, threshold_bps: u64) -> Self {
+        self.simulator = self.simulator.with_multicall(multicall3);
+        self.size_sweep_threshold_bps = Some(threshold_bps);
+        self
+    }
+
+    /// The chain's WETH, which plain arbs flash-loan
+    pub fn with_weth(mut self, weth: Address) -> Self {
+        self.simulator = self.simulator.with_weth(weth);
+        self
+    }
+
+    /// Execute routed opportunities through the contract's `executeRoute`
+    pub fn with_route_execution(mut self, enabled: bool) -> Self {
+        self.simulator = self.simulator.with_routes(enabled);
+        self
+    }
+
+    /// Simulate against the pending block rather than the latest mined one
+    pub fn with_pending_simulation(mut self, pending: bool) -> Self {
+        self.simulator = self.simulator.with_pending_state(pending);
+        self
+    }
+
+    /// Price the Arbitrum L1 data fee into each simulation's gas cost
+    pub fn with_l1_fee_estimation(mut self, enabled: bool) -> Self {
+        self.simulator = self.simulator.with_l1_fee(enabled);
+        self
+    }
+
+    /// Reuse simulation outcomes within a block
+    pub fn with_simulation_cache(mut self) -> Self {
+        self.simulator = self.simulator.with_cache();
+        self
+    }
+
+    /// On a failed simulation, check the arb contract has code and report a
+    /// missing deployment as such
+    pub fn with_deployment_check(mut self, check_deployed: bool) -> Self {
+        self.check_deployed = check_deployed;
+        self
+    }
+
+    /// Whether execution is halted after too many consecutive failures
+    pub fn is_breaker_open(&self) -> bool {
+        self.breaker.is_open(now_ms())
+    }
+
+    /// Skip opportunities whose estimated win probability is below `min_probability`
+    pub fn with_win_probability(mut self, min_probability: f64) -> Self {
+        self.win_filter = Some(parking_lot::Mutex::new(WinEstimator::new(min_probability)));
+        self
+    }
+
+    /// Report each resolved trade's spread and net profit to `tuner`
+    pub fn with_spread_tuner(mut self, tuner: Arc<SpreadTuner>) -> Self {
+        self.spread_tuner = Some(tuner);
+        self
+    }
+
+    /// Report each resolved trade to `blacklist`, which blacklists pools
+    /// that keep reverting
+    pub fn with_pool_blacklist(mut self, blacklist: Arc<PoolBlacklist>) -> Self {
+        self.pool_blacklist = Some(blacklist);
+        self
+    }
+
+    /// Feed a confirmed or reverted trade to the spread tuner and pool blacklist
+    fn record_outcome(&self, opportunity: &Opportunity, result: &ExecutionResult) {
+        if let Some(blacklist) = &self.pool_blacklist {
+            blacklist.record_outcome(opportunity, result, now_ms());
+        }
+        let Some(tuner) = &self.spread_tuner else { return };
+        let (profit, gas_cost) = match result {
+            ExecutionResult::Confirmed { profit, gas_cost, .. } => (*profit, *gas_cost),
+            ExecutionResult::Reverted { gas_cost, .. } => (U256::zero(), *gas_cost),
+            _ => return,
+        };
+        let net_wei = profit.as_u128() as f64 - gas_cost.as_u128() as f64;
+        tuner.record(opportunity.spread_bps, net_wei / 1e18);
+    }
+
+    /// Paper trading: run the full simulation path but never sign or submit
+    pub fn with_paper_trading(mut self, enabled: bool) -> Self {
+        self.paper_trading = enabled;
+        self
+    }
+
+    /// Whether to check the node knows a tx before replacing it
+    pub fn with_resubmit_check(mut self, enabled: bool) -> Self {
+        self.skip_resubmit_if_known = enabled;
+        self
+    }
+
+    /// Route these (buy, sell) venue pairs to their own contracts
+    pub fn with_contracts(mut self, contracts: HashMap<(Venue, Venue), Address>) -> Self {
+        self.contracts = contracts;
+        self
+    }
+
+    /// Contract that executes `opportunity`'s venue pair
+    pub fn contract_for(&self, opportunity: &Opportunity) -> Address {
+        self.contracts
+            .get(&(opportunity.buy_venue, opportunity.sell_venue))
+            .copied()
+            .unwrap_or(self.arb_contract)
+    }
+
+    /// Submit more aggressively (or just give up on stuck transactions
+    /// sooner) when competitors work the same pools
+    pub fn with_competitor_monitor(mut self, monitor: Arc<CompetitorMonitor>) -> Self {
+        self.competition = Some(monitor);
+        self
+    }
+
+    /// Execute an arbitrage opportunity, unless the circuit breaker is open
     pub async fn execute(
         &self,
         client: Arc<WsClient>,
         opportunity: &Opportunity,
     ) -> eyre::Result<ExecutionResult> {
+        let prepared = self.prepare(client.clone(), opportunity).await;
+        self.finish(client, opportunity, prepared).await
+    }
+
+    /// Execute several opportunities, simulating up to `concurrency` of them
+    /// at once and then submitting in order. Results match `opportunities`.
+    pub async fn execute_all(
+        &self,
+        client: Arc<WsClient>,
+        opportunities: &[Opportunity],
+        concurrency: usize,
+    ) -> Vec<eyre::Result<ExecutionResult>> {
+        if let [opportunity] = opportunities {
+            info!("🎯 Attempting execution...");
+            return vec![self.execute(client, opportunity).await];
+        }
+
+        let prepared = batch::simulate_concurrently(opportunities, concurrency, |opportunity| {
+            self.prepare(client.clone(), opportunity)
+        }).await;
+
+        let mut results = Vec::with_capacity(opportunities.len());
+        for (opportunity, prepared) in opportunities.iter().zip(prepared) {
+            info!("🎯 Attempting execution...");
+            results.push(self.finish(client.clone(), opportunity, prepared).await);
+        }
+        results
+    }
+
+    /// Run the checks and simulation that come before submitting, or the
+    /// result that ends the attempt early
+    async fn prepare(
+        &self,
+        client: Arc<WsClient>,
+        opportunity: &Opportunity,
+    ) -> eyre::Result<Prepared> {
+        if let Some(skipped) = self.check_gates(opportunity, std::time::Instant::now()) {
+            return Ok(Prepared::Skipped(skipped));
+        }
+        self.simulate_opportunity(client, opportunity).await
+    }
+
+    /// The checks that skip an opportunity without touching the chain, or
+    /// None if it may go on to simulation
+    fn check_gates(&self, opportunity: &Opportunity, now: std::time::Instant) -> Option<ExecutionResult> {
+        if self.is_killed() {
+            return Some(ExecutionResult::Failed { reason: kill_switch::KILL_SWITCH_REASON.into() });
+        }
+
+        if self.is_breaker_open() {
+            return Some(ExecutionResult::Failed { reason: breaker::BREAKER_OPEN_REASON.into() });
+        }
+
+        if self.contract_guard.as_ref().is_some_and(|guard| guard.is_paused()) {
+            return Some(ExecutionResult::Failed { reason: contract_guard::CONTRACT_CHANGED_REASON.into() });
+        }
+
+        if self.is_balance_low() {
+            return Some(ExecutionResult::Failed { reason: wallet_guard::INSUFFICIENT_BALANCE_REASON.into() });
+        }
+
+        // Not counted by the breaker - nothing was attempted
+        if !self.simulator.can_execute(opportunity) {
+            return Some(ExecutionResult::Failed { reason: ROUTE_UNSUPPORTED_REASON.into() });
+        }
+
+        if let Some(filter) = &self.win_filter {
+            let mut filter = filter.lock();
+            if let Some(probability) = filter.reject(opportunity, now) {
+                return Some(ExecutionResult::Failed {
+                    reason: format!(
+                        "Win probability too low: {:.2} < {:.2}",
+                        probability, filter.min_probability()
+                    ),
+                });
+            }
+        }
+        None
+    }
+
+    /// Submit a prepared opportunity and record the outcome
+    async fn finish(
+        &self,
+        client: Arc<WsClient>,
+        opportunity: &Opportunity,
+        prepared: eyre::Result<Prepared>,
+    ) -> eyre::Result<ExecutionResult> {
+        let result = match prepared {
+            Ok(Prepared::Skipped(result)) => return Ok(result),
+            // Engaged while the batch was simulating
+            Ok(_) if self.is_killed() => return Ok(ExecutionResult::Failed {
+                reason: kill_switch::KILL_SWITCH_REASON.into(),
+            }),
+            // An earlier trade in the batch may have tripped the breaker
+            Ok(_) if self.is_breaker_open() => return Ok(ExecutionResult::Failed {
+                reason: breaker::BREAKER_OPEN_REASON.into(),
+            }),
+            // Earlier trades in the batch used up this block's submissions
+            Ok(Prepared::Simulated(_)) if self.block_slots_remaining() == 0 => {
+                info!("🧱 Deferring {} to a later block: submission limit reached", opportunity.token_name);
+                return Ok(ExecutionResult::Failed { reason: block_limit::BLOCK_LIMIT_REASON.into() });
+            }
+            // Ended before anything was sent: the breaker only counts what
+            // reached the chain (or the node), so a run of unprofitable
+            // simulations can't halt execution
+            Ok(Prepared::Done(result)) => {
+                self.record_outcome(opportunity, &result);
+                return Ok(result);
+            }
+            Ok(Prepared::Simulated(simulated)) => {
+                let result = self.submit_simulated(client, *simulated).await;
+                self.block_limit.lock().record(&result);
+                result
+            }
+            Err(e) => Err(e),
+        };
+
+        if let Ok(result) = &result {
+            self.breaker.record(result, now_ms());
+            // Paper trades resolve here rather than in check_pending
+            self.record_outcome(opportunity, result);
+        }
+        result
+    }
+
+    /// Price gas and simulate an arbitrage opportunity
+    /// Optimized for Arbitrum's FIFO sequencer (no MEV, no priority fees)
+    async fn simulate_opportunity(
+        &self,
+        client: Arc<WsClient>,
+        opportunity: &Opportunity,
+    ) -> eyre::Result<Prepared> {
+        // Unproven pools only get a probe-sized trade
+        let probe = self.probes.as_ref().and_then(|p| p.lock().size(opportunity));
+        if let Some(probe) = &probe {
+            info!(
+                "🧪 Probe trade: {} ETH instead of {} ETH on unproven pool",
+                ethers::utils::format_ether(probe.trade_amount),
+                ethers::utils::format_ether(opportunity.trade_amount)
+            );
+        }
+        let is_probe = probe.is_some();
+        let mut opportunity = probe.unwrap_or_else(|| opportunity.clone());
+
         // Step 1: Get current gas price
-        // On Arbitrum, the RPC estimate includes L1 data fee, so we trust it
-        let gas_price = client.get_gas_price().await?;
+        // On Arbitrum, the RPC estimate includes L1 data fee units; with L1
+        // fee estimation on, the simulator prices them separately.
+        // The profit check uses the smoothed price so one spiky reading
+        // doesn't reject the trade. The tx itself pays the spot price, so
+        // that's what the cap applies to (the smoothed price never exceeds it).
+        let spot_gas_price = client.get_gas_price().await?;
+        let gas_price = self.gas_ema.lock().update(spot_gas_price);
 
         // On Arbitrum L2, gas prices are typically very low (0.1 gwei)
         // No need to add priority fee - sequencer uses FIFO ordering
-        if gas_price > self.max_gas_price {
-            return Ok(ExecutionResult::Failed {
-                reason: format!("Gas price too high: {} > {}", gas_price, self.max_gas_price),
-            });
+        if spot_gas_price > self.max_gas_price {
+            return Ok(Prepared::Done(ExecutionResult::Failed {
+                reason: format!("Gas price too high: {} > {}", spot_gas_price, self.max_gas_price),
+            }));
         }
 
-        // Step 2: Simulate
+        // Step 2: Simulate, first correcting the solver's size if it's off on-chain
+        let arb_contract = self.contract_for(&opportunity);
+        if let (Some(threshold_bps), false) = (self.size_sweep_threshold_bps, is_probe) {
+            self.correct_size(client.clone(), arb_contract, &mut opportunity, threshold_bps).await;
+        }
         let sim_result = self.simulator.simulate(
             client.clone(),
-            opportunity,
+            arb_contract,
+            &opportunity,
             gas_price,
         ).await?;
 
         if !sim_result.success {
-            return Ok(ExecutionResult::Failed {
+            // A revert from an address with no code says nothing useful; name the real problem
+            if self.check_deployed {
+                if let Err(e) = contract_guard::ensure_deployed(&*client, arb_contract).await {
+                    return Ok(Prepared::Done(ExecutionResult::Failed { reason: e.to_string() }));
+                }
+            }
+            return Ok(Prepared::Done(ExecutionResult::Failed {
                 reason: sim_result.revert_reason.unwrap_or_else(|| "Simulation failed".into()),
-            });
+            }));
         }
 
         // Step 3: Check profitability after gas
         if sim_result.net_profit.is_zero() {
-            return Ok(ExecutionResult::Failed {
-                reason: "Not profitable after gas".into(),
-            });
+            return Ok(Prepared::Done(ExecutionResult::Failed {
+                reason: format!(
+                    "Not profitable after gas: {} ETH gas incl. {} ETH L1 fee",
+                    ethers::utils::format_ether(sim_result.gas_cost_wei),
+                    ethers::utils::format_ether(sim_result.l1_fee_wei)
+                ),
+            }));
+        }
+
+        Ok(Prepared::Simulated(Box::new(Simulated {
+            opportunity,
+            arb_contract,
+            spot_gas_price,
+            sim_result,
+        })))
+    }
+
+    /// Swap in the best on-chain size when the solver's estimate is off by
+    /// more than `threshold_bps`. A failed sweep keeps the solver's size.
+    async fn correct_size(
+        &self,
+        client: Arc<WsClient>,
+        arb_contract: Address,
+        opportunity: &mut Opportunity,
+        threshold_bps: u64,
+    ) {
+        let sizes = sweep_sizes_for(opportunity.trade_amount);
+        let sweep = match self.simulator.sweep_sizes(client, arb_contract, opportunity, &sizes).await {
+            Ok(sweep) => sweep,
+            Err(e) => {
+                warn!("Size sweep failed for {}, keeping solver size: {:?}", opportunity.token_name, e);
+                return;
+            }
+        };
+
+        // The sweep simulates the contract, so sizes and profits are in the base token
+        if let Some((size, profit)) = sweep.correction(opportunity.trade_amount, opportunity.base_profit, threshold_bps) {
+            let profit_eth = opportunity.base_to_eth(profit);
+            info!(
+                "📏 Size corrected for {}: {} -> {} (profit {} -> {} ETH on-chain)",
+                opportunity.token_name,
+                ethers::utils::format_ether(opportunity.trade_amount),
+                ethers::utils::format_ether(size),
+                ethers::utils::format_ether(opportunity.expected_profit),
+                ethers::utils::format_ether(profit_eth)
+            );
+            opportunity.trade_amount = size;
+            opportunity.expected_profit = profit_eth;
+            opportunity.base_profit = profit;
         }
+    }
 
-        // Step 4: Build transaction
-        let nonce = self.get_and_increment_nonce();
+    /// Build, verify and submit a simulated opportunity
+    async fn submit_simulated(
+        &self,
+        client: Arc<WsClient>,
+        simulated: Simulated,
+    ) -> eyre::Result<ExecutionResult> {
+        let Simulated { opportunity, arb_contract, spot_gas_price, sim_result } = simulated;
+        let opportunity = &opportunity;
+
+        // Step 4: Build transaction (paper trades never use up a nonce)
+        let reservation = (!self.paper_trading).then(|| self.reserve_nonce());
+        let nonce = reservation.as_ref()
+            .map_or_else(|| self.nonce.load(Ordering::SeqCst), |reservation| reservation.nonce);
 
-        // Set minProfit to 80% of expected to account for slippage
-        let min_profit = sim_result.net_profit * 80 / 100;
+        // minProfit is the worst case if both pools slip by max_slippage_bps;
+        // without read reserves fall back to 80% of expected. Never accept a
+        // loss. The contract checks it in the flash-loaned base token.
+        let min_profit = match (opportunity.buy_reserves, opportunity.sell_reserves) {
+            (Some(buy), Some(sell)) => compute_min_profit(opportunity, [buy, sell], self.max_slippage_bps),
+            _ => None,
+        }
+        .unwrap_or_else(|| opportunity.eth_to_base(sim_result.net_profit * 80 / 100))
+        .max(U256::one());
 
         let gas_limit = sim_result.gas_estimate * 120 / 100; // 20% buffer
 
@@ -129,10 +721,11 @@ impl Executor {
         let priority_fee = U256::zero();
 
         let tx = self.simulator.build_transaction(
+            arb_contract,
             opportunity,
             min_profit,
             gas_limit,
-            gas_price,
+            spot_gas_price,
             priority_fee,
             U256::from(nonce),
         );
@@ -183,13 +776,136 @@ impl Executor {
             }
         }
 
-        // Step 6: Sign transaction
-        let signature = self.wallet.sign_transaction(&tx).await?;
-        let signed_tx = tx.rlp_signed(&signature);
+        if self.paper_trading {
+            info!(
+                "📝 [PAPER] {} {:?}->{:?} would net {} ETH",
+                opportunity.token_name, opportunity.buy_venue, opportunity.sell_venue,
+                ethers::utils::format_ether(sim_result.net_profit)
+            );
+            // Profit is already net of the simulated gas; none was spent
+            return Ok(ExecutionResult::Confirmed {
+                hash: H256::zero(),
+                profit: sim_result.net_profit,
+                gas_cost: U256::zero(),
+                expected_profit: opportunity.expected_profit,
+                opportunity_id: opportunity.id,
+            });
+        }
 
-        // Step 7: Submit directly (Flashbots not available on Arbitrum)
+        // Step 6: Sign and submit directly (Flashbots not available on Arbitrum)
         // Arbitrum uses FIFO ordering, so direct submission is optimal
-        self.submit_direct(client.clone(), &signed_tx, opportunity).await
+        self.submit_with_resubmission(&*client, tx, nonce, spot_gas_price, opportunity).await
+    }
+
+    /// Submit, re-signing with a bumped max fee (same nonce) while not included
+    ///
+    /// Re-sending the identical signed tx is a no-op once the sequencer has
+    /// it queued, so every retry is a real EIP-1559 replacement.
+    async fn submit_with_resubmission<C: TxClient + ?Sized>(
+        &self,
+        client: &C,
+        mut tx: TypedTransaction,
+        nonce: u64,
+        gas_price: U256,
+        opportunity: &Opportunity,
+    ) -> eyre::Result<ExecutionResult> {
+        let mut max_fee = gas_price;
+        let mut last_hash = None;
+        let mut attempts = 0;
+
+        // A competitor is working these pools: resubmit sooner, bump harder
+        let escalated = self.competition.as_ref()
+            .is_some_and(|c| c.is_escalated(opportunity, std::time::Instant::now()));
+        let (resubmit_after, bump_percent) = if escalated {
+            (CONTESTED_RESUBMIT_AFTER, self.gas_bump_percent * CONTESTED_BUMP_MULTIPLIER)
+        } else {
+            (RESUBMIT_AFTER, self.gas_bump_percent)
+        };
+
+        while attempts < MAX_SUBMIT_ATTEMPTS {
+            if attempts > 0 {
+                let bumped = bump_fee(max_fee, bump_percent);
+                if bumped > self.max_gas_price {
+                    warn!("Not bumping past gas cap: {} > {}", bumped, self.max_gas_price);
+                    break;
+                }
+                max_fee = bumped;
+                if let TypedTransaction::Eip1559(ref mut eip1559) = tx {
+                    eip1559.max_fee_per_gas = Some(max_fee);
+                }
+            }
+            attempts += 1;
+
+            let signature = self.wallet.sign_transaction(&tx).await?;
+            let signed_tx = tx.rlp_signed(&signature);
+
+            match self.submit_direct(client, &signed_tx, nonce, max_fee, opportunity).await {
+                Ok(ExecutionResult::Submitted { hash }) => last_hash = Some(hash),
+                Ok(other) => return Ok(other),
+                Err(e) => {
+                    let message = e.to_string().to_lowercase();
+                    if message.contains("nonce too low") {
+                        // An earlier version already landed
+                        info!("Nonce {} already used after {} attempts", nonce, attempts);
+                        break;
+                    }
+                    if message.contains("insufficient funds") {
+                        return Ok(ExecutionResult::Failed { reason: "Insufficient funds".into() });
+                    }
+                    if last_hash.is_none() {
+                        return Err(e);
+                    }
+                    warn!("Resubmission {} failed: {:?}", attempts, e);
+                    break;
+                }
+            }
+
+            if self.wait_for_inclusion(client, last_hash, resubmit_after).await {
+                break;
+            }
+
+            // A missing receipt may just be the receipt index lagging. A tx
+            // that's merely pending is still bumped: that's what it's waiting on
+            if self.skip_resubmit_if_known && attempts < MAX_SUBMIT_ATTEMPTS {
+                if let Some(hash) = last_hash {
+                    if tx_mined(client, hash).await {
+                        info!("Tx {:?} is already in a block, not resubmitting", hash);
+                        break;
+                    }
+                }
+            }
+        }
+
+        info!(
+            "Submission finished after {} attempt(s): start {} gwei, final {} gwei",
+            attempts,
+            ethers::utils::format_units(gas_price, "gwei").unwrap_or_default(),
+            ethers::utils::format_units(max_fee, "gwei").unwrap_or_default()
+        );
+
+        match last_hash {
+            Some(hash) => Ok(ExecutionResult::Submitted { hash }),
+            None => Ok(ExecutionResult::Failed { reason: "Submission failed".into() }),
+        }
+    }
+
+    /// Poll for a receipt until the timeout; true once the tx is included
+    async fn wait_for_inclusion<C: TxClient + ?Sized>(
+        &self,
+        client: &C,
+        hash: Option<H256>,
+        timeout: std::time::Duration,
+    ) -> bool {
+        let Some(hash) = hash else { return false };
+        let deadline = std::time::Instant::now() + timeout;
+
+        while std::time::Instant::now() < deadline {
+            if let Ok(Some(_)) = client.get_transaction_receipt(hash).await {
+                return true;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(250)).await;
+        }
+        false
     }
 
     /// Verify execution integrity immediately before submission
@@ -207,10 +923,12 @@ impl Executor {
         expected_profit: U256,
     ) -> eyre::Result<VerificationResult> {
         // Simulate against the latest block
-        let gas_price = client.get_gas_price().await?;
+        let spot_gas_price = client.get_gas_price().await?;
+        let gas_price = self.gas_ema.lock().update(spot_gas_price);
 
         let sim_result = self.simulator.simulate(
             client.clone(),
+            self.contract_for(opportunity),
             opportunity,
             gas_price,
         ).await?;
@@ -240,29 +958,24 @@ impl Executor {
 
     /// Submit transaction directly to Arbitrum sequencer
     /// Optimized for FIFO ordering - no priority fee bumping needed
-    async fn submit_direct(
+    async fn submit_direct<C: TxClient + ?Sized>(
         &self,
-        client: Arc<WsClient>,
+        client: &C,
         signed_tx: &Bytes,
+        nonce: u64,
+        gas_price: U256,
         opportunity: &Opportunity,
     ) -> eyre::Result<ExecutionResult> {
         // On Arbitrum, transactions are processed in FIFO order by the sequencer
         // No need for priority fee optimization or replacement strategies
-        let pending = client.send_raw_transaction(signed_tx.clone()).await?;
-        let hash = pending.tx_hash();
+        let hash = client.send_raw_transaction(signed_tx.clone()).await?;
 
-        info!("📤 TX submitted to Arbitrum sequencer: {:?}", hash);
+        info!(
+            tx_hash = ?hash, token = %opportunity.token_name, opportunity_id = %opportunity.short_id(),
+            "📤 TX submitted to Arbitrum sequencer: {:?}", hash
+        );
 
-        // Track pending transaction
-        {
-            let mut pending_txs = self.pending_txs.write().await;
-            pending_txs.push(PendingTx {
-                hash,
-                opportunity: opportunity.clone(),
-                submitted_at: std::time::Instant::now(),
-                gas_price: U256::zero(),
-            });
-        }
+        self.track_pending(hash, nonce, gas_price, opportunity).await;
 
         Ok(ExecutionResult::Submitted { hash })
     }
@@ -271,6 +984,8 @@ impl Executor {
         &self,
         client: Arc<WsClient>,
         signed_tx: &Bytes,
+        nonce: u64,
+        gas_price: U256,
         opportunity: &Opportunity,
     ) -> eyre::Result<ExecutionResult> {
         // For Flashbots Protect, we submit to their RPC endpoint
@@ -296,18 +1011,12 @@ impl Executor {
         if let Some(hash) = result.get("result").and_then(|v| v.as_str()) {
             let hash: H256 = hash.parse()?;
             
-            info!("📤 TX submitted via Flashbots: {:?}", hash);
+            info!(
+                tx_hash = ?hash, token = %opportunity.token_name, opportunity_id = %opportunity.short_id(),
+                "📤 TX submitted via Flashbots: {:?}", hash
+            );
             
-            // Track pending transaction
-            {
-                let mut pending_txs = self.pending_txs.write().await;
-                pending_txs.push(PendingTx {
-                    hash,
-                    opportunity: opportunity.clone(),
-                    submitted_at: std::time::Instant::now(),
-                    gas_price: U256::zero(),
-                });
-            }
+            self.track_pending(hash, nonce, gas_price, opportunity).await;
             
             Ok(ExecutionResult::Submitted { hash })
         } else if let Some(error) = result.get("error") {
@@ -326,56 +1035,903 @@ impl Executor {
         }
     }
     
+    /// Track a submitted transaction, keeping one live version per nonce
+    async fn track_pending(
+        &self,
+        hash: H256,
+        nonce: u64,
+        gas_price: U256,
+        opportunity: &Opportunity,
+    ) {
+        let outcome = self.replay_guard.lock().submit(nonce, hash);
+
+        let mut pending_txs = self.pending_txs.write().await;
+        match outcome {
+            ReplayOutcome::Duplicate | ReplayOutcome::Stale => return,
+            ReplayOutcome::Replaced(previous) => {
+                info!("🔁 Nonce {} replaced: {:?} -> {:?}", nonce, previous, hash);
+                pending_txs.retain(|tx| tx.hash != previous);
+            }
+            ReplayOutcome::New => {}
+        }
+
+        let submitted_at = std::time::Instant::now();
+        let stuck_timeout = self.competition.as_ref()
+            .map(|c| c.stuck_timeout(opportunity, submitted_at))
+            .unwrap_or(DEFAULT_STUCK_TIMEOUT);
+
+        pending_txs.push(PendingTx {
+            hash,
+            nonce,
+            opportunity: opportunity.clone(),
+            submitted_at,
+            gas_price,
+            stuck_timeout,
+        });
+    }
+
+    /// Transactions submitted but not yet confirmed, reverted or dropped
+    pub async fn pending_count(&self) -> usize {
+        self.pending_txs.read().await.len()
+    }
+
     /// Check status of pending transactions
-    pub async fn check_pending(&self, client: Arc<WsClient>) -> Vec<ExecutionResult> {
+    pub async fn check_pending<C: TxClient + ?Sized>(&self, client: &C) -> Vec<ExecutionResult> {
+        if let Some(guard) = self.balance_guard.as_ref().filter(|guard| guard.refresh_due(now_ms())) {
+            match client.get_balance(self.address()).await {
+                Ok(balance) => guard.record(balance, now_ms()),
+                Err(e) => warn!("Failed to read wallet balance: {:?}", e),
+            }
+        }
+
         let mut results = Vec::new();
-        let mut completed_hashes = Vec::new();
+        let mut completed_nonces = Vec::new();
         
         let pending_txs = self.pending_txs.read().await;
         
         for pending in pending_txs.iter() {
             if let Ok(Some(receipt)) = client.get_transaction_receipt(pending.hash).await {
-                completed_hashes.push(pending.hash);
+                completed_nonces.push(pending.nonce);
+
+                if !self.replay_guard.lock().is_live(pending.nonce, pending.hash) {
+                    warn!("Superseded version of nonce {} landed: {:?}", pending.nonce, pending.hash);
+                }
+
+                let gas_cost = receipt.gas_used.unwrap_or_default()
+                    * receipt.effective_gas_price.unwrap_or(pending.gas_price);
                 
                 if receipt.status == Some(1.into()) {
-                    // Success! Calculate actual profit from logs if available
-                    info!("✅ TX confirmed: {:?}", pending.hash);
+                    if let Some(probes) = &self.probes {
+                        probes.lock().record_success(&pending.opportunity);
+                    }
+                    let expected_profit = pending.opportunity.expected_profit;
+                    // The profit event is in the base token
+                    let profit = realized::realized_profit(&receipt)
+                        .map(|profit| pending.opportunity.base_to_eth(profit))
+                        .unwrap_or_else(|| {
+                            debug!("No profit event in {:?}, reporting the estimate", pending.hash);
+                            expected_profit
+                        });
+                    info!(
+                        tx_hash = ?pending.hash, token = %pending.opportunity.token_name,
+                        opportunity_id = %pending.opportunity.short_id(),
+                        profit_wei = %profit, gas_cost_wei = %gas_cost,
+                        "✅ TX confirmed: {:?} [{}]", pending.hash, pending.opportunity.short_id()
+                    );
                     results.push(ExecutionResult::Confirmed {
                         hash: pending.hash,
-                        profit: pending.opportunity.expected_profit, // Could parse from logs
+                        profit,
+                        gas_cost,
+                        expected_profit,
+                        opportunity_id: pending.opportunity.id,
                     });
                 } else {
-                    warn!("❌ TX reverted: {:?}", pending.hash);
+                    warn!(
+                        tx_hash = ?pending.hash, token = %pending.opportunity.token_name,
+                        opportunity_id = %pending.opportunity.short_id(), gas_cost_wei = %gas_cost,
+                        "❌ TX reverted: {:?} [{}]", pending.hash, pending.opportunity.short_id()
+                    );
                     results.push(ExecutionResult::Reverted {
                         hash: pending.hash,
                         reason: "Transaction reverted".into(),
+                        gas_cost,
+                        opportunity_id: pending.opportunity.id,
                     });
                 }
-            } else if pending.submitted_at.elapsed() > std::time::Duration::from_secs(120) {
-                // TX stuck for >2 minutes
+                if let Some(result) = results.last() {
+                    self.record_outcome(&pending.opportunity, result);
+                }
+            } else if pending.submitted_at.elapsed() > pending.stuck_timeout {
+                // TX stuck (2 minutes, less on contested pools)
                 warn!("⏰ TX stuck: {:?}", pending.hash);
-                completed_hashes.push(pending.hash);
+                completed_nonces.push(pending.nonce);
             }
         }
         
-        // Remove completed transactions
+        // Remove completed transactions - once a nonce is used, no other
+        // version of it can land
         drop(pending_txs);
         {
             let mut pending_txs = self.pending_txs.write().await;
-            pending_txs.retain(|tx| !completed_hashes.contains(&tx.hash));
+            pending_txs.retain(|tx| !completed_nonces.contains(&tx.nonce));
+
+            let mut guard = self.replay_guard.lock();
+            for nonce in &completed_nonces {
+                guard.resolve(*nonce);
+            }
+        }
+
+        for result in &results {
+            self.breaker.record(result, now_ms());
+            if let Some(filter) = &self.win_filter {
+                filter.lock().record(result, std::time::Instant::now());
+            }
         }
         
         results
     }
     
+    /// Whether any tracked transaction has been pending past its stuck timeout
+    pub async fn has_stuck_pending(&self) -> bool {
+        self.pending_txs.read().await.iter().any(|tx| tx.submitted_at.elapsed() > tx.stuck_timeout)
+    }
+
+    /// Fill nonce gaps left by dropped transactions, which would otherwise
+    /// stall every later nonce. Stuck txs at unused nonces are replaced with
+    /// self-cancels; a gap with nothing of ours left in the mempool is closed
+    /// by resyncing the local nonce. Returns the cancel tx hashes.
+    ///
+    /// Call before `check_pending`, which stops tracking stuck txs.
+    pub async fn recover_nonce_gaps<C: TxClient + ?Sized>(&self, client: &C) -> eyre::Result<Vec<H256>> {
+        let address = self.wallet.address();
+        let mined = client.get_transaction_count(address, Some(BlockNumber::Latest.into())).await?.as_u64();
+        let local = self.nonce.load(Ordering::SeqCst);
+        if mined >= local {
+            return Ok(Vec::new());
+        }
+
+        // Highest fee seen per stuck, still unused nonce
+        let mut stuck: Vec<(u64, U256)> = Vec::new();
+        let mut live = false;
+        for tx in self.pending_txs.read().await.iter().filter(|tx| tx.nonce >= mined) {
+            if tx.submitted_at.elapsed() <= tx.stuck_timeout {
+                live = true;
+            } else if let Some(entry) = stuck.iter_mut().find(|(nonce, _)| *nonce == tx.nonce) {
+                entry.1 = entry.1.max(tx.gas_price);
+            } else {
+                stuck.push((tx.nonce, tx.gas_price));
+            }
+        }
+
+        if stuck.is_empty() {
+            let in_mempool = client.get_transaction_count(address, Some(BlockNumber::Pending.into())).await?.as_u64();
+            if !live && in_mempool <= mined {
+                warn!("🕳️ Nonce gap: {} mined, local {} with nothing pending, resyncing", mined, local);
+                self.resync_nonce(client).await?;
+            }
+            return Ok(Vec::new());
+        }
+
+        if self.is_killed() {
+            warn!("🛑 Nonce gap at {} left uncancelled: {}", stuck[0].0, kill_switch::KILL_SWITCH_REASON);
+            return Ok(Vec::new());
+        }
+
+        let gas_price = client.get_gas_price().await?;
+        let mut cancels = Vec::with_capacity(stuck.len());
+        for (nonce, fee) in stuck {
+            // A replacement must outbid the stuck version, not just the market
+            let tx = self.cancel_tx(nonce, bump_fee(fee.max(gas_price), self.gas_bump_percent));
+            let signature = self.wallet.sign_transaction(&tx).await?;
+            let cancel_hash = client.send_raw_transaction(tx.rlp_signed(&signature)).await?;
+            self.forget_nonce(nonce).await;
+            warn!("🕳️ Nonce gap at {} ({} mined, local {}): cancelling with {:?}", nonce, mined, local, cancel_hash);
+            cancels.push(cancel_hash);
+        }
+        Ok(cancels)
+    }
+
+    /// Manually resolve a stuck transaction. If it (or another version of
+    /// its nonce) has landed, report that; otherwise replace it with a
+    /// zero-value self-transfer at the same nonce and a bumped fee.
+    pub async fn force_resolve<C: TxClient + ?Sized>(
+        &self,
+        client: &C,
+        hash: H256,
+    ) -> eyre::Result<ExecutionResult> {
+        let tracked = self.pending_txs.read().await.iter()
+            .find(|tx| tx.hash == hash)
+            .map(|tx| (tx.nonce, tx.gas_price, tx.opportunity.clone()));
+
+        if let Some(receipt) = client.get_transaction_receipt(hash).await? {
+            if let Some((nonce, ..)) = tracked {
+                self.forget_nonce(nonce).await;
+            }
+            let gas_cost = receipt.gas_used.unwrap_or_default()
+                * receipt.effective_gas_price.unwrap_or_default();
+            info!("Tx {:?} already mined in block {:?}", hash, receipt.block_number);
+            let opportunity = tracked.map(|(.., opportunity)| opportunity);
+            let (expected_profit, opportunity_id) = opportunity.as_ref()
+                .map(|opportunity| (opportunity.expected_profit, opportunity.id))
+                .unwrap_or_default();
+            // The profit event is in the base token
+            let profit = realized::realized_profit(&receipt)
+                .map(|profit| opportunity.as_ref().map_or(profit, |opportunity| opportunity.base_to_eth(profit)))
+                .unwrap_or(expected_profit);
+            return Ok(if receipt.status == Some(1.into()) {
+                ExecutionResult::Confirmed {
+                    hash,
+                    profit,
+                    gas_cost,
+                    expected_profit,
+                    opportunity_id,
+                }
+            } else {
+                ExecutionResult::Reverted { hash, reason: "Transaction reverted".into(), gas_cost, opportunity_id }
+            });
+        }
+
+        let (nonce, fee) = match tracked {
+            Some((nonce, fee, ..)) => (nonce, fee),
+            None => match client.get_transaction(hash).await? {
+                Some(tx) => (tx.nonce.as_u64(), tx.max_fee_per_gas.or(tx.gas_price).unwrap_or_default()),
+                None => {
+                    return Ok(ExecutionResult::Failed {
+                        reason: format!("Transaction {:?} is neither pending nor known to the node", hash),
+                    });
+                }
+            },
+        };
+
+        let mined = client.get_transaction_count(self.wallet.address(), Some(BlockNumber::Latest.into())).await?;
+        if mined.as_u64() > nonce {
+            self.forget_nonce(nonce).await;
+            return Ok(ExecutionResult::Failed {
+                reason: format!("Nonce {} already used by another transaction", nonce),
+            });
+        }
+
+        // A replacement must outbid the stuck version, not just the market
+        let max_fee = bump_fee(fee.max(client.get_gas_price().await?), self.gas_bump_percent);
+        if max_fee > self.max_gas_price {
+            warn!("Cancelling above the gas cap: {} > {}", max_fee, self.max_gas_price);
+        }
+
+        let tx = self.cancel_tx(nonce, max_fee);
+        let signature = self.wallet.sign_transaction(&tx).await?;
+        let cancel_hash = client.send_raw_transaction(tx.rlp_signed(&signature)).await?;
+        self.forget_nonce(nonce).await;
+
+        info!("🧹 Cancelling stuck tx {:?} (nonce {}) with {:?}", hash, nonce, cancel_hash);
+        Ok(ExecutionResult::Submitted { hash: cancel_hash })
+    }
+
+    /// Zero-value self-transfer that takes over `nonce`
+    fn cancel_tx(&self, nonce: u64, max_fee: U256) -> TypedTransaction {
+        Eip1559TransactionRequest::new()
+            .from(self.wallet.address())
+            .to(self.wallet.address())
+            .value(U256::zero())
+            .gas(21_000u64)
+            .max_fee_per_gas(max_fee)
+            .max_priority_fee_per_gas(U256::zero())
+            .nonce(nonce)
+            .chain_id(self.wallet.chain_id())
+            .into()
+    }
+
+    /// Stop tracking every version of `nonce`
+    async fn forget_nonce(&self, nonce: u64) {
+        self.pending_txs.write().await.retain(|tx| tx.nonce != nonce);
+        self.replay_guard.lock().resolve(nonce);
+    }
+
+    /// Wallet address used for execution
+    pub fn address(&self) -> Address {
+        self.wallet.address()
+    }
+
+    /// Sign and submit a plain (non-arbitrage) transaction using the managed nonce
+    /// Used for housekeeping such as WETH wrapping
+    pub async fn send_utility_tx<C: TxClient + ?Sized>(
+        &self,
+        client: &C,
+        to: Address,
+        data: Bytes,
+        value: U256,
+        gas_limit: U256,
+    ) -> eyre::Result<H256> {
+        if self.is_killed() {
+            eyre::bail!("Utility tx to {:?} not sent: {}", to, kill_switch::KILL_SWITCH_REASON);
+        }
+
+        let gas_price = client.get_gas_price().await?;
+        let reservation = self.reserve_nonce();
+        let nonce = reservation.nonce;
+
+        let tx: TypedTransaction = Eip1559TransactionRequest::new()
+            .from(self.wallet.address())
+            .to(to)
+            .data(data)
+            .value(value)
+            .gas(gas_limit)
+            .max_fee_per_gas(gas_price)
+            .max_priority_fee_per_gas(U256::zero())
+            .nonce(nonce)
+            .chain_id(self.wallet.chain_id())
+            .into();
+
+        let signature = self.wallet.sign_transaction(&tx).await?;
+        client.send_raw_transaction(tx.rlp_signed(&signature)).await
+    }
+
     fn get_and_increment_nonce(&self) -> u64 {
         self.nonce.fetch_add(1, Ordering::SeqCst)
     }
+
+    /// Take the next nonce for a transaction about to be sent; resyncs hold
+    /// off until the reservation is dropped
+    fn reserve_nonce(&self) -> NonceReservation<'_> {
+        self.nonces_in_flight.fetch_add(1, Ordering::SeqCst);
+        NonceReservation { nonce: self.get_and_increment_nonce(), in_flight: &self.nonces_in_flight }
+    }
     
-    /// Reset nonce from chain (call after failed tx)
-    pub async fn resync_nonce(&self, client: Arc<WsClient>) -> eyre::Result<()> {
-        let nonce = client.get_transaction_count(self.wallet.address(), None).await?;
-        self.nonce.store(nonce.as_u64(), Ordering::SeqCst);
+    /// Reset nonce from chain (call after failed tx). Skipped while a
+    /// reserved nonce is still being sent, or if one was reserved during the
+    /// read: resetting under it would hand the same nonce out twice.
+    pub async fn resync_nonce<C: TxClient + ?Sized>(&self, client: &C) -> eyre::Result<()> {
+        let local = self.nonce.load(Ordering::SeqCst);
+        let nonce = client.get_transaction_count(self.wallet.address(), None).await?.as_u64();
+        if self.nonces_in_flight.load(Ordering::SeqCst) > 0
+            || self.nonce.compare_exchange(local, nonce, Ordering::SeqCst, Ordering::SeqCst).is_err()
+        {
+            debug!("Nonce resync to {} skipped: a nonce is reserved for a tx being sent", nonce);
+        }
         Ok(())
     }
 }
+
+/// A nonce handed out to a transaction that hasn't been sent or given up
+/// on yet
+struct NonceReservation<'a> {
+    nonce: u64,
+    in_flight: &'a AtomicUsize,
+}
+
+impl Drop for NonceReservation<'_> {
+    fn drop(&mut self) {
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// Starting nonce for `address`, counting txs still in the mempool
+///
+/// With a non-zero `wait`, also waits up to that long for those in-flight txs
+/// to be mined so the first trade doesn't race an external submission.
+pub async fn startup_nonce<C: TxClient + ?Sized>(client: &C, address: Address, wait: std::time::Duration) -> eyre::Result<u64> {
+    let pending = client
+        .get_transaction_count(address, Some(BlockNumber::Pending.into()))
+        .await?
+        .as_u64();
+
+    if wait.is_zero() {
+        return Ok(pending);
+    }
+
+    let deadline = std::time::Instant::now() + wait;
+    loop {
+        let mined = client.get_transaction_count(address, None).await?.as_u64();
+        if mined >= pending {
+            return Ok(pending.max(mined));
+        }
+
+        if std::time::Instant::now() >= deadline {
+            warn!(
+                "{} in-flight tx(s) still pending after {:?}, starting from nonce {}",
+                pending - mined, wait, pending
+            );
+            return Ok(pending);
+        }
+
+        info!("⏳ Waiting for {} in-flight tx(s) from {:?} to be mined", pending - mined, address);
+        tokio::time::sleep(PENDING_POLL_INTERVAL).await;
+    }
+}
+
+/// Whether the node has `hash` as pending or included; lookup errors count
+/// as unknown so the caller falls back to resubmitting
+pub async fn tx_mined<C: TxClient + ?Sized>(client: &C, hash: H256) -> bool {
+    matches!(client.get_transaction(hash).await, Ok(Some(tx)) if tx.block_number.is_some())
+}
+
+/// Raise a fee by `percent`, always by at least 1 wei so the replacement differs
+fn bump_fee(fee: U256, percent: f64) -> U256 {
+    let bump_bps = (percent * 100.0).max(0.0) as u64;
+    let bumped = fee * U256::from(10_000 + bump_bps) / U256::from(10_000u64);
+    bumped.max(fee + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bump_fee() {
+        let fee = U256::from(100_000_000u64); // 0.1 gwei
+        assert_eq!(bump_fee(fee, DEFAULT_GAS_BUMP_PERCENT), U256::from(112_500_000u64));
+        assert_eq!(bump_fee(bump_fee(fee, 12.5), 12.5), U256::from(126_562_500u64));
+        // A zero bump still produces a distinct replacement
+        assert_eq!(bump_fee(fee, 0.0), fee + 1);
+    }
+
+    #[tokio::test]
+    async fn test_startup_nonce_skips_in_flight_tx() {
+        let (provider, mock) = Provider::mocked();
+        let address = Address::from_low_u64_be(1);
+
+        // Mock responses are served last-in first-out: latest is 5 (one
+        // external tx in flight), then 6 once it's mined; pending is 6
+        mock.push(U256::from(6u64)).unwrap();
+        mock.push(U256::from(5u64)).unwrap();
+        mock.push(U256::from(6u64)).unwrap();
+
+        let nonce = startup_nonce(&provider, address, std::time::Duration::from_secs(5)).await.unwrap();
+        assert_eq!(nonce, 6);
+
+        mock.assert_request("eth_getTransactionCount", (address, "pending")).unwrap();
+        mock.assert_request("eth_getTransactionCount", (address, "latest")).unwrap();
+        mock.assert_request("eth_getTransactionCount", (address, "latest")).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_startup_nonce_without_wait_uses_pending_count() {
+        let (provider, mock) = Provider::mocked();
+        let address = Address::from_low_u64_be(1);
+        mock.push(U256::from(6u64)).unwrap();
+
+        let nonce = startup_nonce(&provider, address, std::time::Duration::ZERO).await.unwrap();
+        assert_eq!(nonce, 6);
+        mock.assert_request("eth_getTransactionCount", (address, "pending")).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_only_mined_tx_skips_resubmit() {
+        let (provider, mock) = Provider::mocked();
+        let hash = H256::from_low_u64_be(1);
+
+        // In a block, the receipt index just hasn't caught up
+        let mined = Transaction { hash, block_number: Some(100u64.into()), ..Default::default() };
+        mock.push(mined).unwrap();
+        assert!(tx_mined(&provider, hash).await);
+        mock.assert_request("eth_getTransactionByHash", [hash]).unwrap();
+
+        // Known to the node but still pending - bump it
+        let pending = Transaction { hash, block_number: None, ..Default::default() };
+        mock.push(pending).unwrap();
+        assert!(!tx_mined(&provider, hash).await);
+
+        // Unknown (dropped or never propagated) - resubmit
+        mock.push(serde_json::Value::Null).unwrap();
+        assert!(!tx_mined(&provider, hash).await);
+    }
+
+    /// Scripted node for driving the submission path: records every send,
+    /// mines one chosen submission and can reject one with an error
+    #[derive(Default)]
+    struct ScriptedClient {
+        sent: parking_lot::Mutex<Vec<H256>>,
+        /// (1-based submission that gets mined, receipt status)
+        mine_attempt: Option<(usize, u64)>,
+        /// (1-based submission rejected, error message)
+        reject_attempt: Option<(usize, &'static str)>,
+        /// Whether eth_getTransactionByHash reports sent txs as mined while
+        /// their receipts are still missing
+        indexes_sent: bool,
+        balance: parking_lot::Mutex<U256>,
+        /// Profit the mined receipt's ProfitRealized event logs, if any
+        realized_profit: Option<U256>,
+    }
+
+    #[async_trait::async_trait]
+    impl TxClient for ScriptedClient {
+        async fn send_raw_transaction(&self, tx: Bytes) -> eyre::Result<H256> {
+            let mut sent = self.sent.lock();
+            if let Some((attempt, message)) = self.reject_attempt {
+                if sent.len() + 1 == attempt {
+                    eyre::bail!("{}", message);
+                }
+            }
+            let hash = H256::from(ethers::utils::keccak256(&tx));
+            sent.push(hash);
+            Ok(hash)
+        }
+
+        async fn get_transaction_receipt(&self, hash: H256) -> eyre::Result<Option<TransactionReceipt>> {
+            let Some((attempt, status)) = self.mine_attempt else { return Ok(None) };
+            if self.sent.lock().get(attempt - 1) != Some(&hash) {
+                return Ok(None);
+            }
+            Ok(Some(TransactionReceipt {
+                transaction_hash: hash,
+                status: Some(status.into()),
+                gas_used: Some(U256::from(300_000u64)),
+                effective_gas_price: Some(U256::from(100_000_000u64)),
+                logs: self.realized_profit.iter()
+                    .map(|profit| Log {
+                        topics: vec![realized::profit_realized_topic()],
+                        data: ethers::abi::encode(&[ethers::abi::Token::Uint(*profit)]).into(),
+                        ..Default::default()
+                    })
+                    .collect(),
+                ..Default::default()
+            }))
+        }
+
+        async fn get_transaction(&self, hash: H256) -> eyre::Result<Option<Transaction>> {
+            let known = self.indexes_sent && self.sent.lock().contains(&hash);
+            Ok(known.then(|| Transaction { hash, block_number: Some(100u64.into()), ..Default::default() }))
+        }
+
+        async fn get_gas_price(&self) -> eyre::Result<U256> {
+            Ok(U256::from(100_000_000u64))
+        }
+
+        async fn get_transaction_count(&self, _address: Address, _block: Option<BlockId>) -> eyre::Result<U256> {
+            Ok(U256::from(7u64))
+        }
+
+        async fn get_balance(&self, _address: Address) -> eyre::Result<U256> {
+            Ok(*self.balance.lock())
+        }
+    }
+
+    async fn scripted_executor(client: &ScriptedClient) -> Executor {
+        let wallet = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80"
+            .parse::<LocalWallet>()
+            .unwrap()
+            .with_chain_id(42161u64);
+        Executor::new(client, wallet, Address::zero(), false, String::new(), 2, 0, DEFAULT_GAS_BUMP_PERCENT, std::time::Duration::ZERO)
+            .await
+            .unwrap()
+    }
+
+    fn opportunity() -> Opportunity {
+        Opportunity {
+            expected_profit: ethers::utils::parse_ether("0.01").unwrap(),
+            trade_amount: ethers::utils::parse_ether("1").unwrap(),
+            base_token: crate::detector::ARBITRUM_WETH.parse().unwrap(),
+            ..Opportunity::fixture()
+        }
+    }
+
+    /// Submit a tx at the executor's next nonce through the resubmission loop
+    async fn submit(executor: &Executor, client: &ScriptedClient) -> ExecutionResult {
+        let gas_price = U256::from(100_000_000u64);
+        let nonce = executor.get_and_increment_nonce();
+        let tx: TypedTransaction = Eip1559TransactionRequest::new()
+            .to(Address::zero())
+            .gas(500_000u64)
+            .max_fee_per_gas(gas_price)
+            .max_priority_fee_per_gas(U256::zero())
+            .nonce(nonce)
+            .chain_id(42161u64)
+            .into();
+        executor.submit_with_resubmission(client, tx, nonce, gas_price, &opportunity()).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_first_attempt_confirms() {
+        let client = ScriptedClient { mine_attempt: Some((1, 1)), ..Default::default() };
+        let executor = scripted_executor(&client).await;
+
+        let result = submit(&executor, &client).await;
+        let sent = client.sent.lock().clone();
+        assert_eq!(sent.len(), 1);
+        assert!(matches!(result, ExecutionResult::Submitted { hash } if hash == sent[0]));
+
+        let results = executor.check_pending(&client).await;
+        assert!(matches!(results[..], [ExecutionResult::Confirmed { hash, .. }] if hash == sent[0]));
+        assert_eq!(executor.pending_count().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_resubmit_then_confirm() {
+        let client = ScriptedClient { mine_attempt: Some((2, 1)), ..Default::default() };
+        let executor = scripted_executor(&client).await;
+
+        let result = submit(&executor, &client).await;
+        let sent = client.sent.lock().clone();
+        // The fee-bumped replacement is a distinct tx, and it's the one tracked
+        assert_eq!(sent.len(), 2);
+        assert_ne!(sent[0], sent[1]);
+        assert!(matches!(result, ExecutionResult::Submitted { hash } if hash == sent[1]));
+        assert_eq!(executor.pending_count().await, 1);
+
+        let results = executor.check_pending(&client).await;
+        assert!(matches!(results[..], [ExecutionResult::Confirmed { hash, .. }] if hash == sent[1]));
+    }
+
+    #[tokio::test]
+    async fn test_revert_reported_with_gas_cost() {
+        let client = ScriptedClient { mine_attempt: Some((1, 0)), ..Default::default() };
+        let executor = scripted_executor(&client).await;
+
+        submit(&executor, &client).await;
+        let results = executor.check_pending(&client).await;
+        let expected_gas = U256::from(300_000u64) * U256::from(100_000_000u64);
+        assert!(matches!(results[..], [ExecutionResult::Reverted { gas_cost, .. }] if gas_cost == expected_gas));
+        assert_eq!(executor.pending_count().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_stuck_tx_dropped_after_timeout() {
+        // Never mined: bumped up to the attempt limit, then left pending
+        let client = ScriptedClient::default();
+        let executor = scripted_executor(&client).await;
+
+        submit(&executor, &client).await;
+        assert_eq!(client.sent.lock().len(), MAX_SUBMIT_ATTEMPTS as usize);
+        assert!(executor.check_pending(&client).await.is_empty());
+        assert_eq!(executor.pending_count().await, 1);
+
+        executor.pending_txs.write().await[0].stuck_timeout = std::time::Duration::ZERO;
+        assert!(executor.check_pending(&client).await.is_empty());
+        assert_eq!(executor.pending_count().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_contested_opportunity_bumps_harder() {
+        let client = ScriptedClient::default();
+        let monitor = Arc::new(CompetitorMonitor::new(
+            Vec::new(),
+            std::time::Duration::from_secs(30),
+            crate::competition::CompetitionResponse::Aggressive,
+        ));
+        monitor.observe([opportunity().sell_pool], std::time::Instant::now());
+        let executor = scripted_executor(&client).await.with_competitor_monitor(monitor);
+
+        // Two 25% bumps instead of two 12.5% ones
+        submit(&executor, &client).await;
+        assert_eq!(client.sent.lock().len(), MAX_SUBMIT_ATTEMPTS as usize);
+        assert_eq!(executor.pending_txs.read().await[0].gas_price, U256::from(156_250_000u64));
+    }
+
+    #[tokio::test]
+    async fn test_mined_tx_without_receipt_not_resubmitted() {
+        // In a block per the tx index, receipt lagging: no replacement
+        let client = ScriptedClient { indexes_sent: true, ..Default::default() };
+        let executor = scripted_executor(&client).await;
+
+        let result = submit(&executor, &client).await;
+        let sent = client.sent.lock().clone();
+        assert_eq!(sent.len(), 1);
+        assert!(matches!(result, ExecutionResult::Submitted { hash } if hash == sent[0]));
+    }
+
+    #[tokio::test]
+    async fn test_nonce_too_low_stops_resubmission() {
+        // An earlier version landed: keep tracking it, send nothing more
+        let client = ScriptedClient { reject_attempt: Some((2, "nonce too low")), ..Default::default() };
+        let executor = scripted_executor(&client).await;
+
+        let result = submit(&executor, &client).await;
+        let sent = client.sent.lock().clone();
+        assert_eq!(sent.len(), 1);
+        assert!(matches!(result, ExecutionResult::Submitted { hash } if hash == sent[0]));
+
+        // Nonce already used before our first send
+        let client = ScriptedClient { reject_attempt: Some((1, "nonce too low")), ..Default::default() };
+        let executor = scripted_executor(&client).await;
+        let result = submit(&executor, &client).await;
+        assert!(matches!(result, ExecutionResult::Failed { .. }));
+        assert!(client.sent.lock().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_tx_at_stuck_nonce() {
+        let client = ScriptedClient::default();
+        let executor = scripted_executor(&client).await;
+        let attempts = MAX_SUBMIT_ATTEMPTS as usize;
+
+        let cancel = executor.cancel_tx(7, U256::from(150_000_000u64));
+        assert_eq!(cancel.from(), Some(&executor.address()));
+        assert_eq!(cancel.to_addr(), Some(&executor.address()));
+        assert_eq!(cancel.value(), Some(&U256::zero()));
+        assert_eq!(cancel.gas(), Some(&U256::from(21_000u64)));
+        assert_eq!(cancel.nonce(), Some(&U256::from(7u64)));
+        assert_eq!(cancel.chain_id(), Some(42161u64.into()));
+        assert!(matches!(cancel, TypedTransaction::Eip1559(ref tx) if tx.max_fee_per_gas == Some(U256::from(150_000_000u64))));
+
+        // Stuck at nonce 7 (the node reports 7 mined), so a cancel goes out
+        let ExecutionResult::Submitted { hash: stuck } = submit(&executor, &client).await else {
+            panic!("expected submission");
+        };
+        let result = executor.force_resolve(&client, stuck).await.unwrap();
+        let sent = client.sent.lock().clone();
+        assert_eq!(sent.len(), attempts + 1);
+        assert!(matches!(result, ExecutionResult::Submitted { hash } if hash == sent[attempts] && hash != stuck));
+        assert_eq!(executor.pending_count().await, 0);
+
+        // Unknown hashes are reported, not cancelled
+        let result = executor.force_resolve(&client, H256::from_low_u64_be(9)).await.unwrap();
+        assert!(matches!(result, ExecutionResult::Failed { .. }));
+        assert_eq!(client.sent.lock().len(), attempts + 1);
+    }
+
+    #[tokio::test]
+    async fn test_force_resolved_pair_profit_reported_in_eth() {
+        // An rETH/wstETH trade: the contract logs 0.01 wstETH, worth 0.012 ETH
+        let eth = |v: &str| ethers::utils::parse_ether(v).unwrap();
+        let client = ScriptedClient { mine_attempt: Some((1, 1)), realized_profit: Some(eth("0.01")), ..Default::default() };
+        let executor = scripted_executor(&client).await;
+        let pair = Opportunity {
+            base_token: Address::from_low_u64_be(0x5eb),
+            expected_profit: eth("0.012"),
+            base_profit: eth("0.01"),
+            ..opportunity()
+        };
+
+        let nonce = executor.get_and_increment_nonce();
+        let tx: TypedTransaction = Eip1559TransactionRequest::new()
+            .to(Address::zero())
+            .gas(500_000u64)
+            .max_fee_per_gas(U256::from(100_000_000u64))
+            .nonce(nonce)
+            .chain_id(42161u64)
+            .into();
+        let ExecutionResult::Submitted { hash } = executor
+            .submit_with_resubmission(&client, tx, nonce, U256::from(100_000_000u64), &pair).await.unwrap()
+        else {
+            panic!("expected submission");
+        };
+
+        let result = executor.force_resolve(&client, hash).await.unwrap();
+        assert!(matches!(result, ExecutionResult::Confirmed { profit, .. } if profit == eth("0.012")));
+    }
+
+    #[tokio::test]
+    async fn test_dropped_tx_nonce_gap_recovered() {
+        // The node has 7 mined and nothing of ours pending: the tx was dropped
+        let client = ScriptedClient::default();
+        let executor = scripted_executor(&client).await;
+        let attempts = MAX_SUBMIT_ATTEMPTS as usize;
+        submit(&executor, &client).await;
+        assert_eq!(executor.nonce.load(Ordering::SeqCst), 8);
+
+        // Not stuck yet: leave it alone
+        assert!(executor.recover_nonce_gaps(&client).await.unwrap().is_empty());
+        assert_eq!(client.sent.lock().len(), attempts);
+
+        // Stuck past its timeout with nonce 7 unused: cancelled at that
+        // nonce, but not while the kill switch is engaged
+        executor.pending_txs.write().await[0].stuck_timeout = std::time::Duration::ZERO;
+        assert!(executor.has_stuck_pending().await);
+        let stop = std::env::temp_dir().join(format!("lst-arb-nonce-gap-stop-{}", std::process::id()));
+        std::fs::write(&stop, "").unwrap();
+        let kill_switch = Arc::new(KillSwitch::new(&stop));
+        kill_switch.poll();
+        let executor = executor.with_kill_switch(kill_switch.clone());
+        assert!(executor.recover_nonce_gaps(&client).await.unwrap().is_empty());
+        assert!(executor.send_utility_tx(&client, Address::zero(), Bytes::new(), U256::zero(), U256::from(21_000u64)).await.is_err());
+        assert_eq!(client.sent.lock().len(), attempts);
+        std::fs::remove_file(&stop).unwrap();
+        kill_switch.poll();
+
+        let cancels = executor.recover_nonce_gaps(&client).await.unwrap();
+        let sent = client.sent.lock().clone();
+        assert_eq!(sent.len(), attempts + 1);
+        assert_eq!(cancels, vec![sent[attempts]]);
+        assert_eq!(executor.pending_count().await, 0);
+
+        // If that's dropped too, nothing tracked remains: resync to the mined
+        // nonce, but not under a nonce reserved for a tx still being sent
+        let reservation = executor.reserve_nonce();
+        executor.recover_nonce_gaps(&client).await.unwrap();
+        assert_eq!(executor.nonce.load(Ordering::SeqCst), 9);
+        drop(reservation);
+        executor.nonce.store(8, Ordering::SeqCst);
+        executor.recover_nonce_gaps(&client).await.unwrap();
+        assert_eq!(executor.nonce.load(Ordering::SeqCst), 7);
+        assert_eq!(client.sent.lock().len(), attempts + 1);
+    }
+
+    #[tokio::test]
+    async fn test_routed_opportunities_skipped_until_enabled() {
+        let client = ScriptedClient::default();
+        let now = std::time::Instant::now();
+        let pair = Opportunity {
+            base_token: Address::from_low_u64_be(0x57e7),
+            ..opportunity()
+        };
+
+        let executor = scripted_executor(&client).await;
+        assert!(executor.check_gates(&opportunity(), now).is_none());
+        assert!(matches!(
+            executor.check_gates(&pair, now),
+            Some(ExecutionResult::Failed { reason }) if reason == ROUTE_UNSUPPORTED_REASON
+        ));
+
+        let executor = scripted_executor(&client).await.with_route_execution(true);
+        assert!(executor.check_gates(&pair, now).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_shadow_decides_without_submitting() {
+        let live_client = ScriptedClient { mine_attempt: Some((1, 1)), ..Default::default() };
+        let shadow_client = ScriptedClient::default();
+        let now = std::time::Instant::now();
+
+        // The shadow demands a higher win probability than live runs with
+        let config = crate::config::Config {
+            shadow_execution: Some(toml::toml! { min_win_probability = 0.9 }),
+            ..Default::default()
+        };
+        let shadow_execution = config.shadow_execution().unwrap().unwrap();
+        assert_eq!(config.execution.min_win_probability, 0.0);
+        let live = scripted_executor(&live_client).await;
+        let shadow = ShadowExecutor::new(
+            scripted_executor(&shadow_client).await.with_win_probability(shadow_execution.min_win_probability),
+        );
+        assert!(shadow.executor().paper_trading);
+        assert!(!live.paper_trading);
+
+        // Live passes its gates and trades; the shadow's filter skips the
+        // same opportunity (0.01 ETH profit, no reverts = 0.85 estimate)
+        let opportunities = [opportunity()];
+        assert!(live.check_gates(&opportunities[0], now).is_none());
+        let live_results = vec![Ok(submit(&live, &live_client).await)];
+        let shadow_skip = shadow.executor().check_gates(&opportunities[0], now)
+            .expect("the shadow's override should skip it");
+        let divergences = shadow.compare(&opportunities, &live_results, &[Ok(shadow_skip)]);
+        assert_eq!(divergences.len(), 1);
+        assert_eq!(divergences[0].live, shadow::Decision::Trade);
+        assert!(matches!(&divergences[0].shadow, shadow::Decision::Skip(reason) if reason.starts_with("Win probability too low")));
+        assert_eq!(shadow.stats(), (1, 1));
+
+        // Without the override the shadow decides as live does
+        let agreeing = ShadowExecutor::new(scripted_executor(&shadow_client).await);
+        assert!(agreeing.executor().check_gates(&opportunities[0], now).is_none());
+
+        // Only the live executor touched the chain
+        assert_eq!(live_client.sent.lock().len(), 1);
+        assert_eq!(live.pending_count().await, 1);
+        assert!(shadow_client.sent.lock().is_empty());
+        assert_eq!(shadow.executor().pending_count().await, 0);
+        assert_eq!(shadow.executor().nonce.load(Ordering::SeqCst), 7);
+    }
+
+    #[tokio::test]
+    async fn test_low_wallet_balance_refuses_execution() {
+        let eth = |v: &str| ethers::utils::parse_ether(v).unwrap();
+        let client = ScriptedClient::default();
+        *client.balance.lock() = eth("0.001");
+        let refresh = std::time::Duration::from_millis(200);
+        let executor = scripted_executor(&client).await.with_balance_guard(eth("0.01"), refresh);
+
+        // Unread balance doesn't block anything
+        assert!(!executor.is_balance_low());
+        executor.check_pending(&client).await;
+        assert!(executor.is_balance_low());
+
+        // Alerted once per cooldown
+        assert_eq!(executor.low_balance_alert(), Some(eth("0.001")));
+        assert_eq!(executor.low_balance_alert(), None);
+
+        // Topped up: cached until the refresh interval has passed, with no
+        // blocks observed in between
+        *client.balance.lock() = eth("0.05");
+        executor.check_pending(&client).await;
+        assert!(executor.is_balance_low());
+        tokio::time::sleep(refresh).await;
+        executor.check_pending(&client).await;
+        assert!(!executor.is_balance_low());
+        assert_eq!(executor.low_balance_alert(), None);
+    }
+}
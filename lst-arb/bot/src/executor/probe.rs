@@ -0,0 +1,140 @@
+//! Probe Trades
+//!
+//! A pool the bot hasn't traded yet may not behave like its quotes suggest
+//! (fee-on-transfer tokens, hooks, hidden reverts). When probing is enabled,
+//! the first trades through an unproven pool are capped at a small probe
+//! size; once one of them confirms, the pool is marked proven and later
+//! trades use the full solver size.
+
+use ethers::types::{Address, U256};
+use std::collections::HashSet;
+use tracing::info;
+
+use crate::detector::Opportunity;
+use crate::price::Venue;
+
+/// Identifies a pool an opportunity trades through
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct PoolId {
+    token: Address,
+    venue: Venue,
+    fee_tier: Option<u32>,
+    pool: Address,
+}
+
+impl PoolId {
+    fn buy(opportunity: &Opportunity) -> Self {
+        Self {
+            token: opportunity.token,
+            venue: opportunity.buy_venue,
+            fee_tier: opportunity.buy_fee_tier,
+            pool: opportunity.buy_pool,
+        }
+    }
+
+    fn sell(opportunity: &Opportunity) -> Self {
+        Self {
+            token: opportunity.token,
+            venue: opportunity.sell_venue,
+            fee_tier: opportunity.sell_fee_tier,
+            pool: opportunity.sell_pool,
+        }
+    }
+}
+
+/// Caps trades through unproven pools at a probe size
+#[derive(Debug)]
+pub struct ProbeSizer {
+    probe_size: U256,
+    proven: HashSet<PoolId>,
+}
+
+impl ProbeSizer {
+    pub fn new(probe_size_eth: f64) -> eyre::Result<Self> {
+        Ok(Self {
+            probe_size: ethers::utils::parse_ether(probe_size_eth)?,
+            proven: HashSet::new(),
+        })
+    }
+
+    /// Probe-sized copy of the opportunity if it touches an unproven pool
+    ///
+    /// Returns None when the opportunity can trade at full size.
+    pub fn size(&self, opportunity: &Opportunity) -> Option<Opportunity> {
+        // The probe size is in ETH; the trade is in its base token
+        let probe_size = opportunity.eth_to_base(self.probe_size);
+        if self.is_proven(opportunity) || opportunity.trade_amount <= probe_size {
+            return None;
+        }
+
+        // Profit scales down roughly linearly at small sizes; the simulator
+        // recomputes the real figure before anything is sent
+        let mut probe = opportunity.clone();
+        probe.expected_profit = opportunity.expected_profit * probe_size / opportunity.trade_amount;
+        probe.base_profit = opportunity.base_profit * probe_size / opportunity.trade_amount;
+        probe.trade_amount = probe_size;
+        Some(probe)
+    }
+
+    /// Whether both pools of the opportunity have a confirmed trade
+    pub fn is_proven(&self, opportunity: &Opportunity) -> bool {
+        self.proven.contains(&PoolId::buy(opportunity))
+            && self.proven.contains(&PoolId::sell(opportunity))
+    }
+
+    /// Mark the opportunity's pools as proven after a confirmed trade
+    pub fn record_success(&mut self, opportunity: &Opportunity) {
+        for id in [PoolId::buy(opportunity), PoolId::sell(opportunity)] {
+            if self.proven.insert(id) {
+                info!(
+                    "🧪 Pool proven: {} {} ({:?})",
+                    opportunity.token_name,
+                    id.venue.label(id.fee_tier),
+                    id.pool
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn opportunity(trade_eth: &str) -> Opportunity {
+        Opportunity {
+            buy_pool: Address::from_low_u64_be(2),
+            sell_pool: Address::from_low_u64_be(3),
+            expected_profit: ethers::utils::parse_ether("0.02").unwrap(),
+            trade_amount: ethers::utils::parse_ether(trade_eth).unwrap(),
+            ..Opportunity::fixture()
+        }
+    }
+
+    #[test]
+    fn test_new_pool_is_probed_then_full_size() {
+        let mut sizer = ProbeSizer::new(0.01).unwrap();
+        let opp = opportunity("2");
+
+        // First trade on an unknown pool is probe-sized
+        let probe = sizer.size(&opp).unwrap();
+        assert_eq!(probe.trade_amount, ethers::utils::parse_ether("0.01").unwrap());
+        assert_eq!(probe.expected_profit, ethers::utils::parse_ether("0.0001").unwrap());
+
+        // After the probe confirms, the same pools trade at full size
+        sizer.record_success(&probe);
+        assert!(sizer.size(&opp).is_none());
+
+        // A different sell pool is still unproven
+        let mut other = opp.clone();
+        other.sell_fee_tier = Some(3000);
+        other.sell_pool = Address::from_low_u64_be(4);
+        assert!(sizer.size(&other).is_some());
+    }
+
+    #[test]
+    fn test_small_trades_are_not_resized() {
+        let sizer = ProbeSizer::new(0.5).unwrap();
+        assert!(sizer.size(&opportunity("0.2")).is_none());
+    }
+}
@@ -0,0 +1,72 @@
+//! Realized Profit
+//!
+//! The profit of a confirmed trade is what the arb contract measured
+//! on-chain, not the detector's estimate. The contract logs it as
+//! `ProfitRealized(uint256)`; contracts deployed before that event are read
+//! from the profit field of `FlashLoanExecuted` instead.
+
+use ethers::types::{Log, TransactionReceipt, H256, U256};
+use ethers::utils::keccak256;
+
+/// `ProfitRealized(uint256 profit)`
+pub fn profit_realized_topic() -> H256 {
+    H256::from(keccak256("ProfitRealized(uint256)"))
+}
+
+/// `FlashLoanExecuted(address[] tokens, uint256[] amounts, uint256 profit)`
+pub fn flash_loan_executed_topic() -> H256 {
+    H256::from(keccak256("FlashLoanExecuted(address[],uint256[],uint256)"))
+}
+
+/// Profit the arb contract logged in `receipt`, if it logged one
+pub fn realized_profit(receipt: &TransactionReceipt) -> Option<U256> {
+    let ours = |log: &&Log| receipt.to.is_none_or(|contract| log.address == contract);
+    let word = |log: &Log, index: usize| {
+        log.data.get(index * 32..(index + 1) * 32).map(U256::from_big_endian)
+    };
+
+    let (realized, executed) = (profit_realized_topic(), flash_loan_executed_topic());
+    let logs = || receipt.logs.iter().filter(ours);
+    logs().find(|log| log.topics.first() == Some(&realized))
+        .and_then(|log| word(log, 0))
+        // The two array offsets come first
+        .or_else(|| logs().find(|log| log.topics.first() == Some(&executed)).and_then(|log| word(log, 2)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::abi::{encode, Token};
+    use ethers::types::Address;
+
+    #[test]
+    fn test_realized_profit_from_receipt_logs() {
+        let contract = Address::from_low_u64_be(0xa4b);
+        let log = |address: Address, topic: H256, data: Vec<u8>| Log {
+            address,
+            topics: vec![topic],
+            data: data.into(),
+            ..Default::default()
+        };
+        let receipt = |logs: Vec<Log>| TransactionReceipt { to: Some(contract), logs, ..Default::default() };
+        let executed = encode(&[
+            Token::Array(vec![Token::Address(Address::from_low_u64_be(1))]),
+            Token::Array(vec![Token::Uint(U256::exp10(18))]),
+            Token::Uint(U256::from(7_000u64)),
+        ]);
+        let realized = encode(&[Token::Uint(U256::from(6_500u64))]);
+
+        // ProfitRealized wins; FlashLoanExecuted covers older contracts
+        let both = receipt(vec![
+            log(contract, flash_loan_executed_topic(), executed.clone()),
+            log(contract, profit_realized_topic(), realized.clone()),
+        ]);
+        assert_eq!(realized_profit(&both), Some(U256::from(6_500u64)));
+        let legacy = receipt(vec![log(contract, flash_loan_executed_topic(), executed)]);
+        assert_eq!(realized_profit(&legacy), Some(U256::from(7_000u64)));
+
+        // Events from other contracts (a pool, a token) don't count
+        let foreign = receipt(vec![log(Address::from_low_u64_be(5), profit_realized_topic(), realized)]);
+        assert_eq!(realized_profit(&foreign), None);
+    }
+}
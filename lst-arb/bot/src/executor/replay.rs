@@ -0,0 +1,109 @@
+//! Replay Guard
+//!
+//! Tracks which version of each nonce is "live". The same signed tx sent to
+//! several RPC endpoints is a harmless duplicate, but once a tx is gas-bumped
+//! the original and the replacement share a nonce and only one can land.
+//! The guard keeps exactly one live hash per nonce so pending tracking and
+//! nonce accounting never count both.
+
+use ethers::types::H256;
+use std::collections::{HashMap, HashSet};
+
+/// Outcome of registering a submitted transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayOutcome {
+    /// First version seen for this nonce
+    New,
+    /// Same signed tx seen again (e.g. sent to another endpoint)
+    Duplicate,
+    /// Replaced a previous version of this nonce, which is no longer live
+    Replaced(H256),
+    /// An already-superseded version was re-sent - ignore it
+    Stale,
+}
+
+#[derive(Debug, Default)]
+pub struct ReplayGuard {
+    /// nonce -> the one live tx hash
+    live: HashMap<u64, H256>,
+    /// nonce -> versions that have been replaced
+    superseded: HashMap<u64, HashSet<H256>>,
+}
+
+impl ReplayGuard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a submitted tx, making it the live version of its nonce
+    pub fn submit(&mut self, nonce: u64, hash: H256) -> ReplayOutcome {
+        if self.superseded.get(&nonce).is_some_and(|s| s.contains(&hash)) {
+            return ReplayOutcome::Stale;
+        }
+
+        match self.live.insert(nonce, hash) {
+            None => ReplayOutcome::New,
+            Some(previous) if previous == hash => ReplayOutcome::Duplicate,
+            Some(previous) => {
+                self.superseded.entry(nonce).or_default().insert(previous);
+                ReplayOutcome::Replaced(previous)
+            }
+        }
+    }
+
+    /// Whether this hash is the live version of its nonce
+    pub fn is_live(&self, nonce: u64, hash: H256) -> bool {
+        self.live.get(&nonce) == Some(&hash)
+    }
+
+    /// Stop tracking a nonce once one of its versions is mined (or abandoned)
+    pub fn resolve(&mut self, nonce: u64) {
+        self.live.remove(&nonce);
+        self.superseded.remove(&nonce);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    impl ReplayGuard {
+        fn live_hash(&self, nonce: u64) -> Option<H256> {
+            self.live.get(&nonce).copied()
+        }
+
+        fn live_count(&self) -> usize {
+            self.live.len()
+        }
+    }
+
+    #[test]
+    fn test_single_live_version_per_nonce() {
+        let mut guard = ReplayGuard::new();
+        let original = H256::from_low_u64_be(1);
+        let bumped = H256::from_low_u64_be(2);
+
+        assert_eq!(guard.submit(7, original), ReplayOutcome::New);
+        // Same signed tx via a second endpoint
+        assert_eq!(guard.submit(7, original), ReplayOutcome::Duplicate);
+        assert_eq!(guard.live_count(), 1);
+
+        // Gas bump supersedes the original
+        assert_eq!(guard.submit(7, bumped), ReplayOutcome::Replaced(original));
+        assert!(guard.is_live(7, bumped));
+        assert!(!guard.is_live(7, original));
+        assert_eq!(guard.live_count(), 1);
+
+        // A late re-send of the original must not become live again
+        assert_eq!(guard.submit(7, original), ReplayOutcome::Stale);
+        assert_eq!(guard.live_hash(7), Some(bumped));
+
+        // Other nonces are independent
+        assert_eq!(guard.submit(8, original), ReplayOutcome::New);
+        assert_eq!(guard.live_count(), 2);
+
+        guard.resolve(7);
+        assert_eq!(guard.live_hash(7), None);
+        assert_eq!(guard.live_count(), 1);
+    }
+}
@@ -0,0 +1,108 @@
+//! Shadow Executor
+//!
+//! Executor changes are hard to judge offline: the interesting cases are the
+//! real opportunities, gas prices and pool states of production. A shadow
+//! executor runs candidate execution settings on the same opportunities as
+//! the live one, in parallel, but always as a paper trader - it simulates
+//! and decides, never submits. Wherever it would have traded and live
+//! didn't (or the other way round), the divergence is logged and counted.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use tracing::info;
+
+use super::{ExecutionResult, Executor};
+use crate::detector::Opportunity;
+
+/// What an executor decided to do with an opportunity
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Decision {
+    /// Submitted (or, for a paper trader, would have)
+    Trade,
+    /// Didn't trade, and why
+    Skip(String),
+}
+
+impl Decision {
+    pub fn of(result: &eyre::Result<ExecutionResult>) -> Self {
+        match result {
+            Ok(ExecutionResult::Submitted { .. })
+            | Ok(ExecutionResult::Confirmed { .. })
+            | Ok(ExecutionResult::Reverted { .. }) => Self::Trade,
+            Ok(ExecutionResult::Failed { reason }) => Self::Skip(reason.clone()),
+            Ok(ExecutionResult::Aborted { .. }) => Self::Skip("Pre-flight check failed".into()),
+            Err(e) => Self::Skip(format!("Error: {}", e)),
+        }
+    }
+
+    pub fn is_trade(&self) -> bool {
+        matches!(self, Self::Trade)
+    }
+}
+
+/// An opportunity the live and shadow executors decided differently
+#[derive(Debug, Clone)]
+pub struct Divergence {
+    pub token_name: String,
+    pub live: Decision,
+    pub shadow: Decision,
+}
+
+pub struct ShadowExecutor {
+    executor: Executor,
+    compared: AtomicU64,
+    diverged: AtomicU64,
+}
+
+impl ShadowExecutor {
+    /// Shadow `executor`, which is switched to paper trading
+    pub fn new(executor: Executor) -> Self {
+        Self {
+            executor: executor.with_paper_trading(true),
+            compared: AtomicU64::new(0),
+            diverged: AtomicU64::new(0),
+        }
+    }
+
+    pub fn executor(&self) -> &Executor {
+        &self.executor
+    }
+
+    /// Record where the shadow's results for `opportunities` disagree with
+    /// the live executor's, logging each divergence
+    pub fn compare(
+        &self,
+        opportunities: &[Opportunity],
+        live: &[eyre::Result<ExecutionResult>],
+        shadow: &[eyre::Result<ExecutionResult>],
+    ) -> Vec<Divergence> {
+        let divergences: Vec<Divergence> = opportunities.iter()
+            .zip(live.iter().zip(shadow))
+            .map(|(opportunity, (live, shadow))| Divergence {
+                token_name: opportunity.token_name.clone(),
+                live: Decision::of(live),
+                shadow: Decision::of(shadow),
+            })
+            .filter(|d| d.live.is_trade() != d.shadow.is_trade())
+            .collect();
+
+        self.compared.fetch_add(opportunities.len().min(live.len()).min(shadow.len()) as u64, Ordering::Relaxed);
+        self.diverged.fetch_add(divergences.len() as u64, Ordering::Relaxed);
+        for divergence in &divergences {
+            info!(
+                "👥 Shadow diverged on {}: live {:?}, shadow {:?}",
+                divergence.token_name, divergence.live, divergence.shadow
+            );
+        }
+        divergences
+    }
+
+    /// (opportunities compared, divergences)
+    pub fn stats(&self) -> (u64, u64) {
+        (self.compared.load(Ordering::Relaxed), self.diverged.load(Ordering::Relaxed))
+    }
+
+    pub fn log_summary(&self) {
+        let (compared, diverged) = self.stats();
+        info!("👥 Shadow executor: {} of {} decisions diverged from live", diverged, compared);
+    }
+}
@@ -0,0 +1,83 @@
+//! Wallet Balance Guard
+//!
+//! Once the wallet can't pay for gas every submission fails, and the bot
+//! keeps simulating and signing trades that can't land. The wallet's ETH
+//! balance is read every few blocks' worth of wall-clock time (from
+//! `check_pending`) and cached; while it's below the minimum, execution is
+//! refused outright. Pacing by the clock rather than by observed blocks keeps
+//! the reads going when no block triggers arrive.
+
+use ethers::types::U256;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Reason reported for executions refused while the wallet is low
+pub const INSUFFICIENT_BALANCE_REASON: &str = "insufficient balance";
+
+/// Blocks between balance reads, converted to time at the chain's block time
+pub const DEFAULT_BALANCE_REFRESH_BLOCKS: u64 = 20;
+
+/// Low-balance alerts are repeated at most this often
+pub const LOW_BALANCE_ALERT_COOLDOWN: Duration = Duration::from_secs(600);
+
+const GWEI: u64 = 1_000_000_000;
+
+#[derive(Debug)]
+pub struct BalanceGuard {
+    min_balance: U256,
+    refresh_interval: Duration,
+    /// Last read balance in gwei (u64::MAX until the first read)
+    balance_gwei: AtomicU64,
+    /// Unix ms of the last read (None until the first)
+    read_at_ms: parking_lot::Mutex<Option<u64>>,
+    /// Unix ms of the last low-balance alert
+    last_alert_ms: AtomicU64,
+}
+
+impl BalanceGuard {
+    pub fn new(min_balance: U256, refresh_interval: Duration) -> Self {
+        Self {
+            min_balance,
+            refresh_interval,
+            balance_gwei: AtomicU64::new(u64::MAX),
+            read_at_ms: parking_lot::Mutex::new(None),
+            last_alert_ms: AtomicU64::new(0),
+        }
+    }
+
+    /// Whether the cached balance is due a refresh
+    pub fn refresh_due(&self, now_ms: u64) -> bool {
+        self.read_at_ms.lock()
+            .is_none_or(|read_at| now_ms >= read_at + self.refresh_interval.as_millis() as u64)
+    }
+
+    pub fn record(&self, balance: U256, now_ms: u64) {
+        let gwei = (balance / GWEI).min(U256::from(u64::MAX)).as_u64();
+        self.balance_gwei.store(gwei, Ordering::Relaxed);
+        *self.read_at_ms.lock() = Some(now_ms);
+    }
+
+    /// Cached balance (None until the first read)
+    pub fn balance(&self) -> Option<U256> {
+        match self.balance_gwei.load(Ordering::Relaxed) {
+            u64::MAX => None,
+            gwei => Some(U256::from(gwei) * GWEI),
+        }
+    }
+
+    /// Whether the last read balance is below the minimum (unread is not low)
+    pub fn is_low(&self) -> bool {
+        self.balance().is_some_and(|balance| balance < self.min_balance)
+    }
+
+    /// The low balance to alert on, at most once per cooldown
+    pub fn take_alert(&self, now_ms: u64) -> Option<U256> {
+        let balance = self.balance().filter(|_| self.is_low())?;
+        let last = self.last_alert_ms.load(Ordering::Relaxed);
+        if last != 0 && now_ms < last + LOW_BALANCE_ALERT_COOLDOWN.as_millis() as u64 {
+            return None;
+        }
+        self.last_alert_ms.store(now_ms, Ordering::Relaxed);
+        Some(balance)
+    }
+}
@@ -0,0 +1,87 @@
+//! Warm Start
+//!
+//! Smoothed estimates start cold after a restart: the first gas reading is
+//! taken at face value, spike or not, until enough readings accumulate. The
+//! learned state is saved periodically and on shutdown, and reloaded on
+//! startup when it is recent enough to still describe the market.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Saved state older than this is ignored
+pub const WARM_START_MAX_AGE: std::time::Duration = std::time::Duration::from_secs(3600);
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WarmState {
+    /// Unix ms when the state was saved
+    pub saved_at_ms: u64,
+    /// Smoothed gas price in wei
+    pub gas_price_ema: Option<f64>,
+}
+
+impl WarmState {
+    pub fn save(&self, path: &Path) -> eyre::Result<()> {
+        let json = serde_json::to_string(self)?;
+        // Write then rename so a crash mid-write can't corrupt the state
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// State saved by `save` within `max_age`, or None when there is none
+    pub fn load(path: &Path, max_age: std::time::Duration) -> eyre::Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let state: WarmState = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        let age_ms = (chrono::Utc::now().timestamp_millis() as u64).saturating_sub(state.saved_at_ms);
+        Ok((age_ms <= max_age.as_millis() as u64).then_some(state))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::GasPriceEma;
+    use ethers::types::U256;
+
+    fn gwei(value: f64) -> U256 {
+        ethers::utils::parse_units(value, "gwei").unwrap().into()
+    }
+
+    #[test]
+    fn test_saved_gas_ema_smooths_first_reading_after_restart() {
+        let path = std::env::temp_dir().join(format!("warm_start_{}.json", std::process::id()));
+        let gas_units = U256::from(400_000u64);
+        let expected_profit = ethers::utils::parse_ether("0.001").unwrap();
+
+        let mut before = GasPriceEma::default();
+        for _ in 0..20 {
+            before.update(gwei(0.1));
+        }
+        let state = WarmState { saved_at_ms: chrono::Utc::now().timestamp_millis() as u64, gas_price_ema: before.state() };
+        state.save(&path).unwrap();
+
+        // Restart: the first reading is a spike that would eat the profit
+        let loaded = WarmState::load(&path, WARM_START_MAX_AGE).unwrap().expect("recent state");
+        assert_eq!(loaded, state);
+        let mut warm = GasPriceEma::default();
+        warm.seed(loaded.gas_price_ema.unwrap());
+        let smoothed = warm.update(gwei(5.0));
+        assert!(smoothed < gwei(1.5));
+        assert!(gas_units * smoothed < expected_profit);
+
+        // Without it the spike is taken at face value and fails the profit check
+        let cold = GasPriceEma::default().update(gwei(5.0));
+        assert_eq!(cold, gwei(5.0));
+        assert!(gas_units * cold > expected_profit);
+
+        // Old state no longer describes the market
+        let old = WarmState { saved_at_ms: state.saved_at_ms - 2 * WARM_START_MAX_AGE.as_millis() as u64, ..state };
+        old.save(&path).unwrap();
+        assert_eq!(WarmState::load(&path, WARM_START_MAX_AGE).unwrap(), None);
+        std::fs::remove_file(&path).ok();
+    }
+}
@@ -0,0 +1,174 @@
+//! Win-Probability Filter
+//!
+//! Large opportunities draw the most competition, a string of reverts
+//! usually means we're losing races, and profit that evaporates between
+//! scans is being taken by someone faster. Each of these lowers the odds
+//! that our transaction lands first. A simple weighted model turns them
+//! into a win probability, and opportunities below the configured minimum
+//! are skipped before any simulation or gas is spent on them.
+//!
+//! Outcomes age out of the revert rate. A run of reverts can block every
+//! trade, and then no new outcomes arrive to pull the rate back down, so
+//! without expiry the filter would never let anything through again.
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use super::gas_window::{urgency, RouteKey, Sighting, QUEUE_TTL};
+use super::ExecutionResult;
+use crate::detector::Opportunity;
+
+/// Expected profit (ETH) at which the size term reaches one half
+const CONTESTED_PROFIT_ETH: f64 = 0.01;
+
+/// Profit decay per second treated as certain competition
+const MAX_DECAY_PER_SEC: f64 = 0.10;
+
+/// Executions remembered for the revert rate
+const OUTCOME_WINDOW: usize = 20;
+
+/// How long an execution counts towards the revert rate
+const OUTCOME_MAX_AGE: Duration = Duration::from_secs(600);
+
+/// Weights of the size, revert-rate and decay terms (sum to 1)
+const SIZE_WEIGHT: f64 = 0.3;
+const REVERT_WEIGHT: f64 = 0.4;
+const DECAY_WEIGHT: f64 = 0.3;
+
+#[derive(Debug)]
+pub struct WinEstimator {
+    min_probability: f64,
+    /// Recent executions and when they resolved, true if reverted
+    outcomes: VecDeque<(Instant, bool)>,
+    sightings: HashMap<RouteKey, Sighting>,
+}
+
+impl WinEstimator {
+    pub fn new(min_probability: f64) -> Self {
+        Self {
+            min_probability,
+            outcomes: VecDeque::with_capacity(OUTCOME_WINDOW),
+            sightings: HashMap::new(),
+        }
+    }
+
+    /// Count a confirmation or revert towards the recent revert rate
+    pub fn record(&mut self, result: &ExecutionResult, now: Instant) {
+        let reverted = match result {
+            ExecutionResult::Confirmed { .. } => false,
+            ExecutionResult::Reverted { .. } => true,
+            _ => return,
+        };
+        if self.outcomes.len() == OUTCOME_WINDOW {
+            self.outcomes.pop_front();
+        }
+        self.outcomes.push_back((now, reverted));
+    }
+
+    fn revert_rate(&mut self, now: Instant) -> f64 {
+        while self.outcomes.front().is_some_and(|(at, _)| now.saturating_duration_since(*at) >= OUTCOME_MAX_AGE) {
+            self.outcomes.pop_front();
+        }
+        if self.outcomes.is_empty() {
+            return 0.0;
+        }
+        self.outcomes.iter().filter(|(_, reverted)| *reverted).count() as f64 / self.outcomes.len() as f64
+    }
+
+    /// Estimated chance of landing `opportunity` before a competitor
+    pub fn estimate(&mut self, opportunity: &Opportunity, now: Instant) -> f64 {
+        self.sightings.retain(|_, s| now.duration_since(s.seen_at) < QUEUE_TTL);
+
+        let profit_eth = ethers::utils::format_ether(opportunity.expected_profit)
+            .parse::<f64>()
+            .unwrap_or_default();
+        let size = profit_eth / (profit_eth + CONTESTED_PROFIT_ETH);
+
+        let sighting = Sighting { profit: opportunity.expected_profit, seen_at: now };
+        let decay = self.sightings
+            .insert(RouteKey::of(opportunity), sighting)
+            .and_then(|previous| urgency(&previous, &sighting))
+            .map_or(0.0, |u| (u / MAX_DECAY_PER_SEC).clamp(0.0, 1.0));
+
+        let contested = SIZE_WEIGHT * size + REVERT_WEIGHT * self.revert_rate(now) + DECAY_WEIGHT * decay;
+        (1.0 - contested).clamp(0.0, 1.0)
+    }
+
+    /// Estimate and compare against the threshold, returning the estimate
+    /// if the opportunity should be skipped
+    pub fn reject(&mut self, opportunity: &Opportunity, now: Instant) -> Option<f64> {
+        let probability = self.estimate(opportunity, now);
+        (probability < self.min_probability).then_some(probability)
+    }
+
+    pub fn min_probability(&self) -> f64 {
+        self.min_probability
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::{H256, U256};
+    use std::time::Duration;
+
+    fn opportunity(profit: &str) -> Opportunity {
+        Opportunity {
+            token_name: "weETH".into(),
+            spread_bps: 80,
+            expected_profit: ethers::utils::parse_ether(profit).unwrap(),
+            trade_amount: ethers::utils::parse_ether("10").unwrap(),
+            ..Opportunity::fixture()
+        }
+    }
+
+    fn reverted() -> ExecutionResult {
+        ExecutionResult::Reverted { hash: H256::zero(), reason: "lost race".into(), gas_cost: U256::zero(), opportunity_id: H256::zero() }
+    }
+
+    #[test]
+    fn test_contested_opportunity_skipped() {
+        let mut estimator = WinEstimator::new(0.5);
+        let start = Instant::now();
+        for _ in 0..8 {
+            estimator.record(&reverted(), start);
+        }
+
+        // Large profit, halving within a second, after a run of reverts
+        estimator.estimate(&opportunity("0.2"), start);
+        let probability = estimator.reject(&opportunity("0.1"), start + Duration::from_secs(1));
+        assert!(probability.is_some_and(|p| p < 0.1));
+    }
+
+    #[test]
+    fn test_old_reverts_age_out() {
+        let mut estimator = WinEstimator::new(0.6);
+        let start = Instant::now();
+        for _ in 0..OUTCOME_WINDOW {
+            estimator.record(&reverted(), start);
+        }
+
+        // Blocked while the reverts are recent, with nothing new to learn from
+        assert!(estimator.reject(&opportunity("0.002"), start).is_some());
+        assert!(estimator.reject(&opportunity("0.002"), start + OUTCOME_MAX_AGE).is_none());
+    }
+
+    #[test]
+    fn test_small_stable_opportunity_admitted() {
+        let mut estimator = WinEstimator::new(0.5);
+        let start = Instant::now();
+        estimator.record(&reverted(), start);
+        for _ in 0..9 {
+            estimator.record(&ExecutionResult::Confirmed {
+                hash: H256::zero(),
+                profit: U256::zero(),
+                gas_cost: U256::zero(),
+                expected_profit: U256::zero(),
+                opportunity_id: H256::zero(),
+            }, start);
+        }
+
+        assert!(estimator.reject(&opportunity("0.002"), start).is_none());
+        assert!(estimator.reject(&opportunity("0.002"), start + Duration::from_secs(1)).is_none());
+    }
+}
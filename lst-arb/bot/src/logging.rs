@@ -0,0 +1,187 @@
+//! Log Output
+//!
+//! Logs go to stdout either as compact human-readable lines or as one JSON
+//! object per line for log shippers (Loki, Vector). In JSON mode an event's
+//! structured fields (`token`, `spread_bps`, `profit_wei`, `tx_hash`, ...)
+//! become top-level keys next to `timestamp`, `level` and `message`, so they
+//! can be queried without parsing the message. Span fields are recorded the
+//! same way, as keys of each span's `fields` object.
+
+use serde_json::{Map, Value};
+use std::fmt;
+use tracing::field::{Field, Visit};
+use tracing::{span, Event, Subscriber};
+use tracing_subscriber::field::RecordFields;
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields, FormattedFields};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{EnvFilter, FmtSubscriber};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    #[default]
+    Compact,
+    Json,
+}
+
+impl std::str::FromStr for LogFormat {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> eyre::Result<Self> {
+        match s {
+            "compact" => Ok(Self::Compact),
+            "json" => Ok(Self::Json),
+            other => Err(eyre::eyre!("Unknown log format: {}", other)),
+        }
+    }
+}
+
+/// Install the global subscriber, logging at `level` ("info", "debug", or
+/// per-module directives such as "info,lst_arb::executor=debug")
+pub fn init(format: LogFormat, level: &str) -> eyre::Result<()> {
+    let filter = EnvFilter::try_new(level)
+        .map_err(|e| eyre::eyre!("Invalid log level {:?}: {}", level, e))?;
+    let builder = FmtSubscriber::builder()
+        .with_env_filter(filter)
+        .with_target(false);
+    match format {
+        LogFormat::Compact => builder.with_thread_ids(false).compact().init(),
+        LogFormat::Json => builder.fmt_fields(JsonFields).event_format(JsonFormat).init(),
+    }
+    Ok(())
+}
+
+/// Collects an event's fields as JSON values
+struct JsonVisitor<'a>(&'a mut Map<String, Value>);
+
+impl Visit for JsonVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().into(), value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().into(), value.into());
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().into(), value.into());
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.0.insert(field.name().into(), value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().into(), value.into());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0.insert(field.name().into(), format!("{:?}", value).into());
+    }
+}
+
+/// Formats span fields as a JSON object, so `JsonFormat` can nest them as
+/// keys instead of one preformatted string
+pub struct JsonFields;
+
+impl<'writer> FormatFields<'writer> for JsonFields {
+    fn format_fields<R: RecordFields>(&self, mut writer: Writer<'writer>, fields: R) -> fmt::Result {
+        let mut map = Map::new();
+        fields.record(&mut JsonVisitor(&mut map));
+        write!(writer, "{}", Value::Object(map))
+    }
+
+    /// Fields recorded after the span was created join the same object
+    fn add_fields(&self, current: &'writer mut FormattedFields<Self>, fields: &span::Record<'_>) -> fmt::Result {
+        let mut map: Map<String, Value> = serde_json::from_str(&current.fields).unwrap_or_default();
+        fields.record(&mut JsonVisitor(&mut map));
+        current.fields = Value::Object(map).to_string();
+        Ok(())
+    }
+}
+
+/// One JSON object per event: timestamp, level, message, the event's
+/// fields, and the spans it happened in. Pair it with `JsonFields`; span
+/// fields any other formatter wrote are kept as a string.
+pub struct JsonFormat;
+
+impl<S, N> FormatEvent<S, N> for JsonFormat
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(&self, ctx: &FmtContext<'_, S, N>, mut writer: Writer<'_>, event: &Event<'_>) -> fmt::Result {
+        let mut line = Map::new();
+        line.insert("timestamp".into(), chrono::Utc::now().to_rfc3339().into());
+        line.insert("level".into(), event.metadata().level().as_str().into());
+        event.record(&mut JsonVisitor(&mut line));
+
+        if let Some(scope) = ctx.event_scope() {
+            let spans: Vec<Value> = scope.from_root()
+                .map(|span| {
+                    let extensions = span.extensions();
+                    let fields = extensions.get::<FormattedFields<N>>()
+                        .map(|fields| serde_json::from_str(&fields.fields).unwrap_or_else(|_| fields.fields.clone().into()))
+                        .unwrap_or_else(|| Value::Object(Map::new()));
+                    serde_json::json!({ "name": span.name(), "fields": fields })
+                })
+                .collect();
+            if !spans.is_empty() {
+                line.insert("spans".into(), spans.into());
+            }
+        }
+
+        writeln!(writer, "{}", Value::Object(line))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+    use tracing::info;
+
+    #[derive(Clone, Default)]
+    struct Capture(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Capture {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_json_lines_carry_event_fields() {
+        let capture = Capture::default();
+        let writer = capture.clone();
+        let subscriber = FmtSubscriber::builder()
+            .fmt_fields(JsonFields)
+            .event_format(JsonFormat)
+            .with_writer(move || writer.clone())
+            .finish();
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("execute", token = "wstETH", attempt = tracing::field::Empty);
+            span.record("attempt", 2u64);
+            let _entered = span.enter();
+            info!(token = "wstETH", spread_bps = 25u64, profit_wei = %1_000u64, "🎯 OPPORTUNITY");
+        });
+
+        let output = String::from_utf8(capture.0.lock().unwrap().clone()).unwrap();
+        let line: Value = serde_json::from_str(output.trim()).unwrap();
+        assert_eq!(line["level"], "INFO");
+        assert_eq!(line["message"], "🎯 OPPORTUNITY");
+        assert_eq!(line["token"], "wstETH");
+        assert_eq!(line["spread_bps"], 25);
+        assert_eq!(line["profit_wei"], "1000");
+        assert_eq!(line["spans"][0]["name"], "execute");
+        assert_eq!(line["spans"][0]["fields"], serde_json::json!({ "token": "wstETH", "attempt": 2 }));
+        assert!(EnvFilter::try_new("info,lst_arb::executor=debug").is_ok());
+        assert!("json".parse::<LogFormat>().is_ok() && "xml".parse::<LogFormat>().is_err());
+    }
+}
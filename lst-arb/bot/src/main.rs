@@ -7,9 +7,9 @@ use ethers::types::{Address, U256};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::time::interval;
-use tracing::{info, warn, error, debug, Level};
-use tracing_subscriber::FmtSubscriber;
+use tracing::{info, warn, error, debug};
 
+mod chain;
 mod config;
 mod rpc;
 mod price;
@@ -20,48 +20,77 @@ mod monitor;
 mod watcher;
 mod scout;
 mod scheduler;
+mod competition;
+mod logging;
 
-use config::{Config, ParsedConfig};
-use rpc::RpcLoadBalancer;
-use price::{MulticallQuoter, VenueAddresses};
+use competition::{CompetitionResponse, CompetitorMonitor};
+use config::{Config, ExecutionConfig, ParsedConfig};
+use rpc::{ChainClock, RpcCapabilities, RpcLoadBalancer, RpcMethod};
+use price::{DisabledTokens, EnabledVenues, LstPair, MulticallQuoter, PriceCache, VenueAddresses};
+use price::reserves::{FallbackReserves, OnChainReserves, ReserveSource, SubgraphReserves};
+use logging::LogFormat;
 use detector::OpportunityDetector;
-use executor::Executor;
-use monitor::Monitor;
-use watcher::{CombinedWatcher, WatcherConfig, DetectionTrigger};
+use detector::flash_fee::FlashProvider;
+use detector::quote_age::QuoteAges;
+use detector::rate_staleness::{RateStaleness, StaleRateResponse};
+use detector::spread_tuner::SpreadTuner;
+use executor::{BalanceManager, ContractGuard, Executor, GasWindow, KillSwitch, ProbeSizer, ShadowExecutor, SharedPoolHandling, TokenCooldown, WarmState, WrapAction, NONCE_GAP_CHECK_INTERVAL};
+use executor::kill_switch::KILL_SWITCH_POLL_INTERVAL;
+use executor::warm_start::WARM_START_MAX_AGE;
+use monitor::{AlertPolicy, AlertSink, DiscordSink, Monitor, OpportunityLogger, PerformanceFee, RotationPolicy, SessionReport, TelegramSink, TsdbSink, WebhookSink};
+use scheduler::PoolScheduler;
+use scout::{DexScreenerSource, DiscoveredPool, PoolBlacklist, PoolDiscovery, PoolSource, SafetyChecker, StaticPoolSource, SubgraphPoolSource};
+use watcher::{CombinedWatcher, WatcherConfig, WatcherMode, DetectionTrigger};
 
-// Arbitrum block time is ~250ms, backup poll every 2 blocks
-const BACKUP_POLL_INTERVAL_MS: u64 = 500;
-
-// Arbitrum chain ID
-const ARBITRUM_CHAIN_ID: u64 = 42161;
 
 #[tokio::main]
 async fn main() -> eyre::Result<()> {
     // Load environment
     dotenv::dotenv().ok();
 
+    // Load configuration (before logging, which it configures)
+    let config = Config::load_or_default();
+
     // Initialize logging
-    let _subscriber = FmtSubscriber::builder()
-        .with_max_level(Level::INFO)
-        .with_target(false)
-        .with_thread_ids(false)
-        .compact()
-        .init();
+    logging::init(config.monitoring.log_format.parse::<LogFormat>()?, &config.monitoring.log_level)?;
 
     info!("═══════════════════════════════════════════");
     info!("    LST/LRT ARBITRAGE BOT v0.2.0");
     info!("    Arbitrum Event-Driven Mode");
     info!("═══════════════════════════════════════════");
 
-    // Load configuration
-    let config = Config::load_or_default();
     let parsed = ParsedConfig::from_config(&config)?;
+    parsed.validate()?;
+    // Backup poll every 2 blocks (~500ms on Arbitrum)
+    let backup_poll_interval_ms = parsed.chain.backup_poll_interval_ms();
 
     info!("Configuration loaded");
     info!("  Min spread: {}bps", parsed.min_spread_bps);
     info!("  Min profit: {} ETH", ethers::utils::format_ether(parsed.min_profit));
     info!("  Trade sizing: Convex optimization with 90% liquidity clamping");
-    info!("  Mode: Event-driven with {}ms backup polling", BACKUP_POLL_INTERVAL_MS);
+    info!("  Chain: {} ({})", parsed.chain.name, parsed.chain.chain_id);
+    info!("  Mode: Event-driven with {}ms backup polling", backup_poll_interval_ms);
+
+    let effective_config = config.effective_redacted();
+    info!("Effective configuration:\n{}", effective_config);
+    if let Some(path) = &config.monitoring.effective_config_file {
+        if let Err(e) = std::fs::write(path, &effective_config) {
+            warn!("Failed to write effective config to {}: {:?}", path, e);
+        }
+    }
+
+    // Offline: `--replay-journal <path>` recomputes stats and P&L from
+    // recorded opportunity logs, then exits
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(path) = args.iter().position(|arg| arg == "--replay-journal").and_then(|i| args.get(i + 1)) {
+        let monitor = Monitor::new(Vec::new())
+            .with_paper_trading(config.execution.paper_trading)
+            .with_performance_fee(PerformanceFee::new(config.monitoring.performance_fee_rate)?);
+        let replayed = monitor::journal::replay(std::path::Path::new(path), &monitor).await?;
+        info!("📼 Replayed {} journal rows from {}", replayed, path);
+        monitor.log_summary().await;
+        return Ok(());
+    }
 
     // Initialize RPC load balancer
     let rpc_lb = Arc::new(RpcLoadBalancer::new(
@@ -72,51 +101,417 @@ async fn main() -> eyre::Result<()> {
 
     info!("RPC connections established");
 
-    // Initialize wallet with Arbitrum chain ID
+    if config.rpc.probe_capabilities {
+        rpc_lb.probe_capabilities().await;
+    }
+
+    // Don't sign for one chain while talking to another
+    if let Some(client) = rpc_lb.get_client().await {
+        parsed.chain.check_chain_id(client.get_chainid().await?.as_u64())?;
+    }
+
+    // Initialize wallet with the profile's chain ID
     let private_key = std::env::var("PRIVATE_KEY")
         .expect("PRIVATE_KEY environment variable required");
     let wallet: LocalWallet = private_key.parse()?;
-    let wallet = wallet.with_chain_id(ARBITRUM_CHAIN_ID);
+    let wallet = wallet.with_chain_id(parsed.chain.chain_id);
 
     info!("Wallet loaded: {:?}", wallet.address());
 
+    // Multi-hop Uniswap routes: WETH -> via... -> LST
+    let mut uniswap_routes = Vec::new();
+    for route in &config.strategy.uniswap_routes {
+        let token = *parsed.tokens.get(&route.token)
+            .ok_or_else(|| eyre::eyre!("Unknown route token: {}", route.token))?;
+        if route.fees.len() != route.via.len() + 1 {
+            eyre::bail!("Route for {} needs {} fees", route.token, route.via.len() + 1);
+        }
+
+        let mut hops = vec![parsed.weth];
+        for via in &route.via {
+            hops.push(via.parse()?);
+        }
+        hops.push(token);
+
+        let fees = route.fees.iter().copied().chain(std::iter::once(0));
+        uniswap_routes.push((token, hops.into_iter().zip(fees).collect()));
+    }
+
+    // LST/LST pairs quoted directly, trading from the base LST and back
+    let mut lst_pairs = Vec::new();
+    for pair in &config.strategy.lst_pairs {
+        let token_of = |name: &String| parsed.tokens.get(name).copied()
+            .ok_or_else(|| eyre::eyre!("Unknown LST pair token: {}", name));
+        lst_pairs.push(LstPair {
+            base: token_of(&pair.base)?,
+            token: token_of(&pair.token)?,
+            name: format!("{}/{}", pair.token, pair.base),
+            balancer_pool: pair.balancer_pool.as_deref().map(str::parse).transpose()?,
+        });
+    }
+    if !lst_pairs.is_empty() {
+        info!("🔁 Quoting {} LST/LST pairs directly", lst_pairs.len());
+    }
+
+    // Shared by the quoter and detector, so a venue can be switched off live
+    let enabled_venues = EnabledVenues::new(parsed.enabled_venues.clone());
+    info!("Active venues: {:?}", enabled_venues.list());
+
     // Initialize components
-    let quoter = Arc::new(MulticallQuoter::new(VenueAddresses {
+    let mut quoter = MulticallQuoter::new(VenueAddresses {
         multicall3: parsed.venues.multicall3,
-        curve_steth: parsed.venues.curve_steth,
-        curve_reth: parsed.venues.curve_reth,
+        curve_pools: parsed.venues.curve_pools.clone(),
         balancer_vault: parsed.venues.balancer_vault,
+        balancer_pools: parsed.venues.balancer_pools.clone(),
         uniswap_quoter: parsed.venues.uniswap_quoter,
+        maverick_quoter: parsed.venues.maverick_quoter,
+        maverick_pools: parsed.venues.maverick_pools.clone(),
+        solidly_pools: parsed.venues.solidly_pools.iter()
+            .map(|(token, pools)| (*token, pools.iter().map(|(pool, _)| *pool).collect()))
+            .collect(),
         weth: parsed.weth,
-    }));
+    })
+    .with_decode_failure_threshold(config.strategy.decode_failure_warn_rate)
+    .with_negative_impact_check(
+        config.strategy.negative_impact.parse()?,
+        config.strategy.negative_impact_tolerance_bps,
+    )
+    .with_uniswap_fee_tiers(config.strategy.uniswap_fee_tiers.clone())
+    .with_uniswap_routes(uniswap_routes)
+    .with_lst_pairs(lst_pairs)
+    .with_enabled_venues(enabled_venues.clone())
+    .with_balancer_fallback(config.venues.balancer_standalone_fallback);
+
+    if config.venues.curve_reserve_quotes {
+        quoter = quoter.with_curve_reserve_quotes(config.venues.curve_amp, config.venues.curve_fee_bps);
+    }
+    if config.strategy.adaptive_multicall {
+        quoter = quoter.with_adaptive_calls(
+            config.strategy.adaptive_drop_after_failures,
+            Duration::from_secs(config.strategy.adaptive_reprobe_secs),
+        );
+    }
+
+    if !parsed.impact_quote_amounts.is_empty() || !parsed.token_impact_quote_amounts.is_empty() {
+        quoter = quoter.with_impact_quote_amounts(
+            parsed.impact_quote_amounts.clone(),
+            parsed.token_impact_quote_amounts.clone(),
+        );
+    }
+
+    let quoter = Arc::new(quoter);
 
-    let detector = Arc::new(OpportunityDetector::new(
+    let flash_provider = config.strategy.flash_provider.parse::<FlashProvider>()?;
+    let flash_fee_bps = config.strategy.flash_fee_bps.unwrap_or_else(|| flash_provider.fee_bps());
+    if flash_fee_bps > 0 {
+        info!("🏦 Netting a {}bps {:?} flash-loan fee out of profits", flash_fee_bps, flash_provider);
+    }
+
+    let mut detector = OpportunityDetector::new(
         parsed.min_spread_bps,
         parsed.min_profit,
+        U256::from(config.strategy.gas_estimate),
+    )
+    .with_quote_quorum(config.strategy.quote_quorum)
+    .with_max_detection_latency(config.strategy.max_detection_latency_ms)
+    .with_max_reserve_multiple(config.strategy.max_reserve_multiple)
+    .with_reoptimize_after_clamp(config.strategy.reoptimize_after_clamp)
+    .with_flash_fee_bps(flash_fee_bps)
+    .with_min_trade_size(ethers::utils::parse_ether(config.strategy.min_trade_size_eth)?)
+    .with_chain(&parsed.chain)
+    .with_enabled_venues(enabled_venues.clone())
+    .with_solidly_stable_pools(parsed.venues.solidly_stable_pools());
+
+    // Tokens whose oracle rate stops updating are traded conservatively
+    if !parsed.rate_feeds.is_empty() {
+        let response = match config.strategy.stale_rate_response.parse::<StaleRateResponse>()? {
+            StaleRateResponse::Widen(_) => StaleRateResponse::Widen(config.strategy.stale_rate_extra_spread_bps),
+            pause => pause,
+        };
+        info!("Rate feed staleness check on {} tokens ({:?} after {}s)",
+            parsed.rate_feeds.len(), response, config.strategy.rate_max_age_secs);
+        let staleness = Arc::new(RateStaleness::new(parsed.rate_feeds.clone(), config.strategy.rate_max_age_secs, response));
+        let staleness_refresh = staleness.clone();
+        let rpc_lb_rates = rpc_lb.clone();
+        tokio::spawn(async move {
+            let mut interval = interval(Duration::from_secs(60));
+            loop {
+                interval.tick().await;
+                if let Some(client) = rpc_lb_rates.get_client().await {
+                    staleness_refresh.refresh(client).await;
+                }
+            }
+        });
+        detector = detector.with_rate_staleness(staleness);
+    }
+
+    let spread_tuner = if config.strategy.auto_tune_spread {
+        if config.strategy.min_spread_floor_bps > config.strategy.min_spread_ceiling_bps {
+            eyre::bail!("strategy.min_spread_floor_bps must not exceed min_spread_ceiling_bps");
+        }
+        let tuner = Arc::new(SpreadTuner::new(
+            parsed.min_spread_bps,
+            config.strategy.min_spread_floor_bps,
+            config.strategy.min_spread_ceiling_bps,
+            config.strategy.spread_tune_step_bps,
+        ));
+        info!(
+            "Auto-tuning min spread within {}-{}bps (starting at {}bps)",
+            config.strategy.min_spread_floor_bps, config.strategy.min_spread_ceiling_bps, tuner.current()
+        );
+        detector = detector.with_spread_tuner(tuner.clone());
+        Some(tuner)
+    } else {
+        None
+    };
+
+    // Compare the local clock to chain time before anything relies on it
+    let clock = Arc::new(ChainClock::new(
+        Duration::from_secs(config.rpc.max_clock_skew_secs),
+        config.rpc.use_chain_time,
     ));
+    if let Some(client) = rpc_lb.get_client().await {
+        if let Err(e) = clock.sync(client.as_ref()).await {
+            warn!("Clock skew check failed: {:?}", e);
+        }
+    }
+
+    if config.venues.use_pool_reserves {
+        let onchain: Arc<dyn ReserveSource> = Arc::new(OnChainReserves::new(rpc_lb.clone(), parsed.weth));
+        let source = match &config.venues.reserves_subgraph_url {
+            Some(url) => {
+                info!("Pool reserves: on-chain with subgraph fallback ({})", url);
+                Arc::new(FallbackReserves::new(
+                    onchain,
+                    Arc::new(SubgraphReserves::new(
+                        url.clone(),
+                        parsed.weth,
+                        config.venues.subgraph_max_lag_secs,
+                    ).with_clock(clock.clone())),
+                ))
+            }
+            None => onchain,
+        };
+        detector = detector.with_reserve_source(source);
+    }
+
+    // Catch a broken solver before it sizes real trades
+    let verify_solver = config.strategy.verify_solver
+        || std::env::args().any(|arg| arg == "--verify-solver");
+    if verify_solver {
+        info!("Verifying solver against fixtures...");
+        let failures = detector::fixtures::verify_solver(detector.solver());
+        if failures.is_empty() {
+            info!("✅ Solver passed all fixtures");
+        } else if config.strategy.verify_solver_strict {
+            eyre::bail!("Solver failed {} fixture(s): {:?}", failures.len(), failures);
+        } else {
+            warn!("⚠️ Solver failed {} fixture(s), continuing anyway", failures.len());
+        }
+    }
+
+    let detector = Arc::new(detector);
 
     let client = rpc_lb.get_client().await
         .ok_or_else(|| eyre::eyre!("No healthy RPC available"))?;
+    let rpc_capabilities = rpc_lb.capabilities_of(&client);
 
-    let executor = Arc::new(Executor::new(
-        client.clone(),
-        wallet,
-        parsed.arb_contract,
-        config.execution.use_flashbots,
-        config.execution.flashbots_relay.clone(),
-        config.execution.max_gas_price_gwei,
-        config.execution.max_priority_fee_gwei,
-    ).await?);
+    let paper_trading = config.execution.paper_trading
+        || std::env::args().any(|arg| arg == "--paper");
+    if paper_trading {
+        info!("📝 Paper trading: opportunities are simulated, never submitted");
+    }
 
-    let monitor = Arc::new(Monitor::new(
-        config.monitoring.telegram_bot_token.clone(),
-        config.monitoring.telegram_chat_id.clone(),
-    ));
+    // Candidate execution settings, tried on paper next to the live executor
+    let shadow = match config.shadow_execution()? {
+        Some(execution) => {
+            let overridden: Vec<_> = config.shadow_execution.iter().flat_map(|table| table.keys()).collect();
+            info!("👥 Shadow executor enabled, overriding {:?}", overridden);
+            let executor = build_executor(&client, wallet.clone(), &execution, &parsed, &rpc_capabilities).await?;
+            Some(ShadowExecutor::new(executor))
+        }
+        None => None,
+    };
+
+    if config.execution.check_contract_deployed {
+        let mut contracts: Vec<Address> = parsed.contracts.values().copied().collect();
+        contracts.push(parsed.arb_contract);
+        contracts.sort();
+        contracts.dedup();
+        for contract in contracts {
+            executor::contract_guard::ensure_deployed(&*client, contract).await?;
+        }
+        info!("✅ Arb contracts deployed");
+    }
+
+    // Pools that kept reverting stay blacklisted across restarts
+    let pool_blacklist = match &config.execution.pool_blacklist_file {
+        Some(path) => PoolBlacklist::load(std::path::Path::new(path))?,
+        None => PoolBlacklist::default(),
+    };
+    let pool_blacklist_ttl = match config.execution.pool_blacklist_ttl_secs {
+        0 => None,
+        secs => Some(Duration::from_secs(secs)),
+    };
+    let pool_blacklist = Arc::new(pool_blacklist
+        .with_ttl(pool_blacklist_ttl)
+        .with_revert_threshold(config.execution.pool_blacklist_after_reverts));
+    let blacklisted = pool_blacklist.active(chrono::Utc::now().timestamp_millis() as u64);
+    if !blacklisted.is_empty() {
+        info!("🚫 {} pools blacklisted: {:?}", blacklisted.len(), blacklisted);
+    }
+
+    let mut executor = build_executor(&client, wallet, &config.execution, &parsed, &rpc_capabilities).await?
+        .with_paper_trading(paper_trading)
+        .with_pool_blacklist(pool_blacklist.clone());
+
+    // Only live outcomes tune the spread
+    if let Some(tuner) = &spread_tuner {
+        executor = executor.with_spread_tuner(tuner.clone());
+    }
+
+    let shared_pool_handling = config.execution.shared_pool_handling.parse::<SharedPoolHandling>()?;
+
+    if config.execution.warm_start {
+        let path = std::path::Path::new(&config.execution.warm_start_file);
+        match WarmState::load(path, WARM_START_MAX_AGE) {
+            Ok(Some(state)) => {
+                info!("🔥 Warm start from {}: {:?}", path.display(), state);
+                executor = executor.with_warm_state(&state);
+            }
+            Ok(None) => info!("No recent warm start state in {}, starting cold", path.display()),
+            Err(e) => warn!("Failed to load warm start state: {:?}", e),
+        }
+    }
+
+    if config.strategy.probe_new_pools {
+        info!("Probe trades enabled: {} ETH on unproven pools", config.strategy.probe_size_eth);
+        executor = executor.with_probe_trades(ProbeSizer::new(config.strategy.probe_size_eth)?);
+    }
+
+    // Manual recovery: `--force-resolve <tx hash>` resolves or cancels one
+    // stuck tx, then exits
+    if let Some(hash) = args.iter().position(|arg| arg == "--force-resolve").and_then(|i| args.get(i + 1)) {
+        let result = executor.force_resolve(&*client, hash.parse()?).await?;
+        info!("Force-resolve {}: {:?}", hash, result);
+        return Ok(());
+    }
+
+    let mut competition = None;
+    if !config.monitoring.competitor_addresses.is_empty() {
+        let competitors = config.monitoring.competitor_addresses.iter()
+            .map(|a| a.parse())
+            .collect::<Result<Vec<Address>, _>>()?;
+        let monitor = Arc::new(CompetitorMonitor::new(
+            competitors,
+            Duration::from_secs(config.monitoring.competition_window_secs),
+            config.monitoring.competition_response.parse::<CompetitionResponse>()?,
+        ));
+        info!("Competitor monitoring enabled ({:?} on contested pools)", monitor.response());
+        tokio::spawn(monitor.clone().run(rpc_lb.clone()));
+        executor = executor.with_competitor_monitor(monitor.clone());
+        competition = Some(monitor);
+    }
+
+    let min_wallet_balance = U256::from_dec_str(&config.execution.min_wallet_balance_wei)?;
+    if !min_wallet_balance.is_zero() {
+        info!("Wallet balance guard: execution halts below {} ETH", ethers::utils::format_ether(min_wallet_balance));
+        let refresh = Duration::from_millis(config.execution.balance_refresh_blocks.max(1) * parsed.chain.block_time_ms);
+        executor = executor.with_balance_guard(min_wallet_balance, refresh);
+    }
+
+    let mut contract_guard = None;
+    if config.monitoring.contract_watch {
+        let expected_owner = config.monitoring.contract_expected_owner.as_deref()
+            .map(str::parse)
+            .transpose()?;
+        let expected_targets = config.monitoring.contract_expected_targets.iter()
+            .map(|target| target.parse())
+            .collect::<Result<Vec<Address>, _>>()?;
+        let balance_drop_tolerance = config.monitoring.contract_balance_drop_tolerance_eth
+            .map(ethers::utils::parse_ether)
+            .transpose()?;
+        let guard = Arc::new(ContractGuard::new(expected_owner, expected_targets, balance_drop_tolerance));
+        executor = executor.with_contract_guard(guard.clone());
+        contract_guard = Some(guard);
+    }
+
+    let kill_switch = Arc::new(KillSwitch::new(&config.execution.kill_switch_file));
+    let executor = Arc::new(executor.with_kill_switch(kill_switch.clone()));
+
+    let mut gas_window = None;
+    if config.execution.gas_window {
+        info!(
+            "Gas windows enabled: non-urgent opportunities wait for gas <= {} gwei",
+            config.execution.gas_window_max_gwei
+        );
+        gas_window = Some(GasWindow::new(
+            config.execution.gas_window_max_gwei,
+            config.execution.gas_window_urgency,
+        )?);
+    }
+
+    let http = reqwest::Client::new();
+    let mut alert_sinks: Vec<Box<dyn AlertSink>> = Vec::new();
+    if let (Some(token), Some(chat_id)) = (&config.monitoring.telegram_bot_token, &config.monitoring.telegram_chat_id) {
+        alert_sinks.push(Box::new(TelegramSink::new(http.clone(), token.clone(), chat_id.clone())));
+    }
+    if let Some(url) = &config.monitoring.discord_webhook_url {
+        alert_sinks.push(Box::new(DiscordSink::new(http.clone(), url.clone())));
+    }
+    if let Some(url) = &config.monitoring.alert_webhook_url {
+        alert_sinks.push(Box::new(WebhookSink::new(http.clone(), url.clone())));
+    }
+    info!("Alerting to: {:?}", alert_sinks.iter().map(|sink| sink.name()).collect::<Vec<_>>());
+
+    let mut monitor = Monitor::new(alert_sinks).with_alerting(
+        AlertPolicy {
+            min_profit: ethers::utils::parse_ether(config.monitoring.alert_min_profit_eth)?,
+            alert_on_reverts: config.monitoring.alert_on_reverts,
+        },
+        config.monitoring.alert_rate_limit,
+    ).with_paper_trading(paper_trading)
+    .with_profit_drift(config.monitoring.profit_drift_min_ratio, config.monitoring.profit_drift_alert_after);
+
+    if config.monitoring.alert_digest {
+        let digest_interval = config.monitoring.alert_digest_interval_secs.max(1);
+        info!("Alert digest enabled: trade alerts batched every {}s", digest_interval);
+        monitor = monitor.with_digest(Duration::from_secs(digest_interval));
+    }
+
+    let performance_fee = PerformanceFee::new(config.monitoring.performance_fee_rate)?;
+    if performance_fee.is_enabled() {
+        info!("Performance fee: {}% of net profit", config.monitoring.performance_fee_rate * 100.0);
+        monitor = monitor.with_performance_fee(performance_fee);
+    }
+
+    if let Some(url) = &config.monitoring.tsdb_url {
+        monitor = monitor.with_tsdb(TsdbSink::spawn(
+            url.clone(),
+            config.monitoring.tsdb_token.clone(),
+            config.monitoring.tsdb_batch_size,
+            Duration::from_millis(config.monitoring.tsdb_flush_interval_ms),
+        ));
+    }
+
+    let log_rotation = RotationPolicy {
+        max_bytes: config.monitoring.log_rotate_max_mb.map(|mb| mb * 1024 * 1024),
+        keep: config.monitoring.log_keep_files,
+        compress: config.monitoring.log_compress,
+    };
+
+    if let Some(dir) = &config.monitoring.opportunity_log_dir {
+        monitor = monitor.with_recorder(
+            OpportunityLogger::new(std::path::Path::new(dir))?.with_rotation(log_rotation.clone()),
+        );
+    }
+
+    let monitor = Arc::new(monitor);
 
     monitor.send_startup_message().await;
 
     // Build token list
-    let tokens: Vec<(Address, String)> = config.strategy.enabled_tokens.iter()
+    let mut tokens: Vec<(Address, String)> = config.strategy.enabled_tokens.iter()
         .filter_map(|name| {
             parsed.tokens.get(name).map(|addr| (*addr, name.clone()))
         })
@@ -125,17 +520,160 @@ async fn main() -> eyre::Result<()> {
     info!("Monitoring {} tokens: {:?}", tokens.len(),
         tokens.iter().map(|(_, n)| n.as_str()).collect::<Vec<_>>());
 
+    // Disabled tokens stay in the list but aren't quoted until re-enabled
+    let disabled_tokens = DisabledTokens::default();
+    disabled_tokens.replace(&parsed.disabled_tokens, chrono::Utc::now().timestamp_millis() as u64);
+    for (token, name) in &tokens {
+        if let Some(disabled) = disabled_tokens.reason(*token) {
+            info!("⏸️ {} disabled: {}", name, disabled.reason);
+        }
+    }
+
+    // Discover pools for the monitored tokens from the configured sources
+    if !config.discovery.sources.is_empty() {
+        let mut sources: Vec<Arc<dyn PoolSource>> = Vec::new();
+        for name in &config.discovery.sources {
+            match (name.as_str(), &config.discovery.subgraph_url, &config.discovery.static_file) {
+                ("dexscreener", _, _) => sources.push(Arc::new(DexScreenerSource::new(parsed.chain.dexscreener_id))),
+                ("subgraph", Some(url), _) => sources.push(Arc::new(SubgraphPoolSource::new(url.clone(), "uniswap"))),
+                ("static", _, Some(path)) => sources.push(Arc::new(StaticPoolSource::new(path))),
+                _ => warn!("Skipping pool source {}: unknown or not configured", name),
+            }
+        }
+
+        let token_addresses: Vec<Address> = tokens.iter().map(|(addr, _)| *addr).collect();
+        let discovery = PoolDiscovery::new(sources)
+            .with_cache_ttl(Duration::from_secs(config.discovery.cache_ttl_secs))
+            .with_blacklist(pool_blacklist.clone());
+        let safety = SafetyChecker::new(executor.address());
+        let mut checked = std::collections::HashSet::new();
+        let discovered = discovery.discover_cached(&token_addresses).await;
+        check_pool_safety(&discovered, &mut checked, &safety, &client, &pool_blacklist).await;
+
+        // Spawn rediscovery; the sources are only queried again once the cache expires
+        if config.discovery.rediscover_interval_secs > 0 {
+            let rpc_lb_discovery = rpc_lb.clone();
+            let pool_blacklist = pool_blacklist.clone();
+            let rediscover_interval = Duration::from_secs(config.discovery.rediscover_interval_secs);
+            tokio::spawn(async move {
+                let mut interval = interval(rediscover_interval);
+                interval.tick().await; // First tick fires immediately
+                loop {
+                    interval.tick().await;
+                    let discovered = discovery.discover_cached(&token_addresses).await;
+                    if let Some(client) = rpc_lb_discovery.get_client().await {
+                        check_pool_safety(&discovered, &mut checked, &safety, &client, &pool_blacklist).await;
+                    }
+                }
+            });
+        }
+    }
+
+    // Reload the last price snapshot so restarts don't start from an empty cache
+    let price_cache = Arc::new(PriceCache::new());
+    if let Some(path) = &config.strategy.price_cache_file {
+        let max_age_ms = config.strategy.price_cache_max_age_secs * 1000;
+        match price_cache.load_snapshot(std::path::Path::new(path), max_age_ms) {
+            Ok(loaded) => info!("Loaded {} cached quotes from {}", loaded, path),
+            Err(e) => warn!("Failed to load price cache snapshot: {:?}", e),
+        }
+    }
+
+    // Ctrl-C fires the shutdown channel; the watcher and main loop stop on it
+    let (shutdown_tx, mut shutdown_rx) = tokio::sync::broadcast::channel::<()>(1);
+    let shutdown_signal_tx = shutdown_tx.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            info!("🛑 Shutdown requested");
+            let _ = shutdown_signal_tx.send(());
+        }
+    });
+
+    // Resume pool tiers from the last run; the cleanup task saves them every
+    // cycle and once more at shutdown
+    let scheduler = config.strategy.scheduler_state_file.as_ref().map(|path| {
+        Arc::new(PoolScheduler::new().with_state_file(path).with_shutdown(shutdown_tx.clone()))
+    });
+    let mut scheduler_task = None;
+    if let Some(scheduler) = &scheduler {
+        if let Err(e) = scheduler.load_state().await {
+            warn!("Failed to load scheduler state: {:?}", e);
+        }
+        scheduler_task = Some(scheduler.clone().start_cleanup_task());
+    }
+
+    // SIGHUP re-reads strategy.enabled_venues and disabled_tokens from
+    // config.toml, so a venue returning garbage or a depegging token can be
+    // switched off without a restart
+    let venues_reload = enabled_venues.clone();
+    let tokens_reload = disabled_tokens.clone();
+    let token_names: std::collections::HashMap<Address, String> = tokens.iter().cloned().collect();
+    let monitor_reload = monitor.clone();
+    tokio::spawn(async move {
+        let Ok(mut hangup) = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup()) else {
+            return;
+        };
+        while hangup.recv().await.is_some() {
+            let reloaded = Config::load("config.toml")
+                .and_then(|config| ParsedConfig::from_config(&config));
+            match reloaded {
+                Ok(parsed) => {
+                    venues_reload.replace(&parsed.enabled_venues);
+                    info!("🔀 Reloaded venues, active: {:?}", venues_reload.list());
+
+                    let now_ms = chrono::Utc::now().timestamp_millis() as u64;
+                    for (token, disabled) in tokens_reload.replace(&parsed.disabled_tokens, now_ms) {
+                        let name = token_names.get(&token).cloned().unwrap_or_else(|| format!("{:?}", token));
+                        let message = match tokens_reload.reason(token).filter(|_| disabled) {
+                            Some(reason) => format!("⏸️ {} disabled: {}", name, reason.reason),
+                            None => format!("▶️ {} re-enabled", name),
+                        };
+                        info!("{}", message);
+                        monitor_reload.send_alert(&message).await;
+                    }
+                }
+                Err(e) => warn!("Failed to reload config.toml: {:?}", e),
+            }
+        }
+    });
+
     // Quote amount for price discovery (actual trade size determined by solver)
-    let quote_amount = ethers::utils::parse_ether("1.0")?;
+    let quote_amount = parsed.quote_amount;
+
+    // A token no venue can quote would silently produce nothing forever
+    if let Some(client) = rpc_lb.get_client().await {
+        match quoter.find_unreachable_tokens(client, &tokens, quote_amount).await {
+            Ok(unreachable) => {
+                for (_, name) in &unreachable {
+                    warn!("⚠️ No venue returned a quote for {} - check its pools and liquidity", name);
+                }
+                if config.strategy.disable_unreachable_tokens && !unreachable.is_empty() {
+                    tokens.retain(|token| !unreachable.contains(token));
+                    info!("Disabled {} unreachable tokens, monitoring {}", unreachable.len(), tokens.len());
+                    if tokens.is_empty() {
+                        eyre::bail!("No enabled token has a reachable venue");
+                    }
+                }
+            }
+            Err(e) => warn!("Token reachability check failed: {:?}", e),
+        }
+    }
 
     // Initialize event watcher for Arbitrum
-    let watcher_config = WatcherConfig::arbitrum_lst_pools();
-    let combined_watcher = CombinedWatcher::new(watcher_config, BACKUP_POLL_INTERVAL_MS);
+    let mut watcher_mode = config.rpc.watcher_mode.parse::<WatcherMode>()?;
+    if watcher_mode == WatcherMode::PendingTxs
+        && !rpc_capabilities.allows("Pending tx watching", RpcMethod::PendingTxFeed, true)
+    {
+        watcher_mode = WatcherMode::ConfirmedLogs;
+    }
+    let watcher_config = WatcherConfig::for_chain(&parsed.chain).with_mode(watcher_mode);
+    let combined_watcher = CombinedWatcher::new(watcher_config, backup_poll_interval_ms)
+        .with_shutdown(shutdown_tx.clone());
 
     info!("═══════════════════════════════════════════");
     info!("Starting event-driven main loop");
     info!("  Watching: Uniswap V3 Swaps, Curve TokenExchange, Balancer Swaps");
-    info!("  Backup poll: {}ms", BACKUP_POLL_INTERVAL_MS);
+    info!("  Backup poll: {}ms", backup_poll_interval_ms);
     info!("═══════════════════════════════════════════");
 
     // Spawn health check task
@@ -148,27 +686,225 @@ async fn main() -> eyre::Result<()> {
         }
     });
 
+    // Spawn periodic clock skew check
+    if config.rpc.clock_check_interval_secs > 0 {
+        let rpc_lb_clock = rpc_lb.clone();
+        let clock = clock.clone();
+        let check_interval = Duration::from_secs(config.rpc.clock_check_interval_secs);
+        tokio::spawn(async move {
+            let mut interval = interval(check_interval);
+            loop {
+                interval.tick().await;
+                if let Some(client) = rpc_lb_clock.get_client().await {
+                    if let Err(e) = clock.sync(client.as_ref()).await {
+                        debug!("Clock skew check failed: {:?}", e);
+                    }
+                }
+            }
+        });
+    }
+
     // Spawn stats logging task
     let monitor_stats = monitor.clone();
+    let quoter_stats = quoter.clone();
     tokio::spawn(async move {
         let mut interval = interval(Duration::from_secs(300)); // Every 5 minutes
         loop {
             interval.tick().await;
             monitor_stats.log_summary().await;
+            quoter_stats.decode_stats().log_summary();
+        }
+    });
+
+    // Spawn kill-switch watcher: the file's existence halts execution
+    let monitor_kill = monitor.clone();
+    tokio::spawn(async move {
+        let mut interval = interval(KILL_SWITCH_POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            match kill_switch.poll() {
+                Some(true) => {
+                    error!("🛑🛑🛑 KILL SWITCH ENGAGED ({} exists): no transactions will be submitted",
+                        kill_switch.path().display());
+                    monitor_kill.log_summary().await;
+                    monitor_kill.send_alert(&format!(
+                        "🛑 Kill switch engaged: execution halted until {} is removed",
+                        kill_switch.path().display()
+                    )).await;
+                    monitor_kill.flush_alerts().await;
+                }
+                Some(false) => {
+                    warn!("▶️ KILL SWITCH RELEASED ({} removed): execution resumed", kill_switch.path().display());
+                    monitor_kill.send_alert("▶️ Kill switch released: execution resumed").await;
+                }
+                None => {}
+            }
+        }
+    });
+
+    // Spawn arb contract watch: pause if the contracts change under us
+    if let Some(guard) = contract_guard {
+        let mut contracts: Vec<Address> = parsed.contracts.values().copied().collect();
+        contracts.push(parsed.arb_contract);
+        contracts.sort();
+        contracts.dedup();
+        info!("Watching {} arb contracts for unexpected changes", contracts.len());
+
+        let weth = parsed.weth;
+        let rpc_lb_contracts = rpc_lb.clone();
+        let monitor_contracts = monitor.clone();
+        let check_interval = Duration::from_secs(config.monitoring.contract_check_interval_secs.max(1));
+        tokio::spawn(async move {
+            let mut interval = interval(check_interval);
+            loop {
+                interval.tick().await;
+                let Some(client) = rpc_lb_contracts.get_client().await else { continue };
+                for contract in &contracts {
+                    match guard.read(client.clone(), *contract, weth).await {
+                        Ok(state) => {
+                            if let Some(alert) = guard.record(*contract, &state) {
+                                error!("{}", alert);
+                                monitor_contracts.send_alert(&alert).await;
+                            }
+                        }
+                        Err(e) => warn!("Failed to read arb contract {:?}: {:?}", contract, e),
+                    }
+                }
+            }
+        });
+    }
+
+    // Spawn alert digest flusher (sends alerts held back by the rate limiter,
+    // and the trade digest once its interval has passed)
+    let monitor_alerts = monitor.clone();
+    tokio::spawn(async move {
+        let mut interval = interval(Duration::from_secs(10));
+        loop {
+            interval.tick().await;
+            monitor_alerts.flush_digest().await;
+            monitor_alerts.flush_alerts().await;
         }
     });
 
+    // Spawn daily Telegram summary
+    if config.monitoring.alert_daily_summary {
+        let monitor_daily = monitor.clone();
+        tokio::spawn(async move {
+            let mut interval = interval(Duration::from_secs(24 * 3600));
+            interval.tick().await; // First tick fires immediately
+            loop {
+                interval.tick().await;
+                monitor_daily.send_daily_summary().await;
+            }
+        });
+    }
+
+    // Spawn price cache snapshots
+    if let Some(path) = config.strategy.price_cache_file.clone() {
+        let cache_snapshot = price_cache.clone();
+        tokio::spawn(async move {
+            let mut interval = interval(Duration::from_secs(30));
+            interval.tick().await; // First tick fires immediately
+            loop {
+                interval.tick().await;
+                if let Err(e) = cache_snapshot.save_snapshot(std::path::Path::new(&path)) {
+                    warn!("Failed to save price cache snapshot: {:?}", e);
+                }
+            }
+        });
+    }
+
+    // Spawn warm start snapshots
+    if config.execution.warm_start {
+        let executor_warm = executor.clone();
+        let path = config.execution.warm_start_file.clone();
+        tokio::spawn(async move {
+            let mut interval = interval(Duration::from_secs(60));
+            interval.tick().await; // First tick fires immediately
+            loop {
+                interval.tick().await;
+                if let Err(e) = executor_warm.warm_state().save(std::path::Path::new(&path)) {
+                    warn!("Failed to save warm start state: {:?}", e);
+                }
+            }
+        });
+    }
+
+    // Spawn WETH/ETH balance manager (keeps a native gas reserve)
+    // Wrapping sends real txs, so it stays off while paper trading
+    if config.execution.auto_wrap && !paper_trading {
+        let balance_manager = BalanceManager::new(
+            parsed.weth,
+            config.execution.gas_reserve_eth,
+            config.execution.wrap_tolerance_eth,
+        )?;
+        let executor_balance = executor.clone();
+        let rpc_lb_balance = rpc_lb.clone();
+        let monitor_balance = monitor.clone();
+        let check_interval = config.execution.balance_check_interval_secs;
+        info!(
+            "Auto-wrap enabled: keeping {} ETH native gas reserve",
+            config.execution.gas_reserve_eth
+        );
+        tokio::spawn(async move {
+            let mut interval = interval(Duration::from_secs(check_interval));
+            loop {
+                interval.tick().await;
+                if let Some(client) = rpc_lb_balance.get_client().await {
+                    match balance_manager.rebalance(client, &executor_balance).await {
+                        Ok(WrapAction::Unwrap(amount)) => {
+                            monitor_balance.send_alert(&format!(
+                                "Gas reserve low: unwrapping {} WETH",
+                                ethers::utils::format_ether(amount)
+                            )).await;
+                        }
+                        Ok(_) => {}
+                        Err(e) => warn!("Balance manager error: {:?}", e),
+                    }
+                }
+            }
+        });
+    }
+
+    // Periodically re-tune the minimum spread from resolved trades
+    if let Some(tuner) = spread_tuner.clone() {
+        let tune_interval = Duration::from_secs(config.strategy.spread_tune_interval_secs.max(1));
+        tokio::spawn(async move {
+            let mut interval = interval(tune_interval);
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                tuner.retune();
+            }
+        });
+    }
+
     // Spawn pending TX checker (faster for Arbitrum ~250ms blocks)
     let executor_pending = executor.clone();
     let monitor_pending = monitor.clone();
     let rpc_lb_pending = rpc_lb.clone();
+    let token_cooldown = Arc::new(TokenCooldown::new(
+        Duration::from_millis(config.execution.token_cooldown_ms),
+        config.execution.cooldown_spread_increase_bps,
+    ));
+    let token_cooldown_pending = token_cooldown.clone();
     tokio::spawn(async move {
         let mut interval = interval(Duration::from_millis(500)); // Every 2 Arbitrum blocks
+        let mut last_gap_check = Instant::now();
         loop {
             interval.tick().await;
             if let Some(client) = rpc_lb_pending.get_client().await {
-                let results = executor_pending.check_pending(client).await;
+                // A dropped tx blocks every later nonce; fill the gap before
+                // check_pending stops tracking the stuck tx
+                if executor_pending.has_stuck_pending().await || last_gap_check.elapsed() >= NONCE_GAP_CHECK_INTERVAL {
+                    last_gap_check = Instant::now();
+                    if let Err(e) = executor_pending.recover_nonce_gaps(&*client).await {
+                        warn!("Nonce gap recovery failed: {:?}", e);
+                    }
+                }
+                let results = executor_pending.check_pending(&*client).await;
                 for result in results {
+                    token_cooldown_pending.record_resolved(&result);
                     monitor_pending.record_execution(&result).await;
                 }
             }
@@ -177,29 +913,48 @@ async fn main() -> eyre::Result<()> {
 
     // Start the combined watcher
     let mut trigger_rx = combined_watcher.start(client.clone()).await?;
+    let mut reconnect_rx = rpc_lb.subscribe_reconnects();
 
     // Track statistics
     let mut event_triggers = 0u64;
+    let mut pending_triggers = 0u64;
     let mut backup_triggers = 0u64;
     let mut block_triggers = 0u64;
     let mut last_stats_log = Instant::now();
+    let mut breaker_alerted = false;
 
     // Event-driven main loop
     loop {
         // Wait for a detection trigger
-        let trigger = match trigger_rx.recv().await {
+        let next_trigger = tokio::select! {
+            t = trigger_rx.recv() => t,
+            // Subscriptions on a replaced connection are dead - rebuild them
+            Ok(url) = reconnect_rx.recv() => {
+                info!("Resubscribing watcher after reconnect to {}", url);
+                if let Some(new_client) = rpc_lb.get_client().await {
+                    let watcher_config = WatcherConfig::for_chain(&parsed.chain).with_mode(watcher_mode);
+                    let combined_watcher = CombinedWatcher::new(watcher_config, backup_poll_interval_ms)
+                        .with_shutdown(shutdown_tx.clone());
+                    trigger_rx = combined_watcher.start(new_client).await?;
+                }
+                continue;
+            }
+            _ = shutdown_rx.recv() => break,
+        };
+        let trigger = match next_trigger {
             Some(t) => t,
             None => {
                 error!("Watcher channel closed, restarting...");
                 // Try to restart the watcher
                 if let Some(new_client) = rpc_lb.get_client().await {
-                    let watcher_config = WatcherConfig::arbitrum_lst_pools();
-                    let combined_watcher = CombinedWatcher::new(watcher_config, BACKUP_POLL_INTERVAL_MS);
+                    let watcher_config = WatcherConfig::for_chain(&parsed.chain).with_mode(watcher_mode);
+                    let combined_watcher = CombinedWatcher::new(watcher_config, backup_poll_interval_ms)
+                        .with_shutdown(shutdown_tx.clone());
                     trigger_rx = combined_watcher.start(new_client).await?;
                     continue;
                 }
                 warn!("Could not restart watcher, using fallback polling");
-                tokio::time::sleep(Duration::from_millis(BACKUP_POLL_INTERVAL_MS)).await;
+                tokio::time::sleep(Duration::from_millis(backup_poll_interval_ms)).await;
                 DetectionTrigger::BackupPoll
             }
         };
@@ -212,6 +967,10 @@ async fn main() -> eyre::Result<()> {
                 event_triggers += 1;
                 debug!("Triggered by swap event: {:?}", event);
             }
+            DetectionTrigger::PendingSwap { target, tx_hash } => {
+                pending_triggers += 1;
+                debug!("Triggered by pending swap {:?} on {:?}", tx_hash, target);
+            }
             DetectionTrigger::NewBlock(num) => {
                 block_triggers += 1;
                 debug!("Triggered by new block: {}", num);
@@ -225,10 +984,11 @@ async fn main() -> eyre::Result<()> {
         // Log trigger statistics periodically
         if last_stats_log.elapsed() > Duration::from_secs(60) {
             info!(
-                "Trigger stats (1min): events={}, blocks={}, backup={}",
-                event_triggers, block_triggers, backup_triggers
+                "Trigger stats (1min): events={}, pending={}, blocks={}, backup={}",
+                event_triggers, pending_triggers, block_triggers, backup_triggers
             );
             event_triggers = 0;
+            pending_triggers = 0;
             block_triggers = 0;
             backup_triggers = 0;
             last_stats_log = Instant::now();
@@ -243,13 +1003,47 @@ async fn main() -> eyre::Result<()> {
             }
         };
 
+        // Read the head every scan rather than trusting the trigger: event
+        // and backup-poll scans carry no block, and a stalled block stream
+        // would otherwise keep the per-block state from ever moving on
+        match client.get_block_number().await {
+            Ok(head) => {
+                executor.observe_block(head.as_u64());
+                if let Some(shadow) = &shadow {
+                    shadow.executor().observe_block(head.as_u64());
+                }
+            }
+            Err(e) => debug!("Failed to read the head block: {:?}", e),
+        }
+
         // Fetch all quotes in single multicall
         let fetch_start = Instant::now();
-        let token_quotes = match quoter.fetch_all_quotes(
-            client.clone(),
-            &tokens,
-            quote_amount,
-        ).await {
+        let alternate = |current: &Arc<_>| {
+            config.rpc.retry_on_alternate
+                .then(|| rpc_lb.get_alternate_client(current))
+                .flatten()
+        };
+        let quote_client = if config.rpc.weighted_reads {
+            rpc_lb.get_client_weighted().await.unwrap_or_else(|| client.clone())
+        } else {
+            client.clone()
+        };
+        let active_tokens = disabled_tokens.get_token_pairs(&tokens);
+        // Gas price is fetched alongside the quotes so detection can net it out
+        let (token_quotes, spot_gas_price) = tokio::join!(
+            quoter.fetch_all_quotes_retry(
+                quote_client, alternate, &active_tokens, quote_amount, config.rpc.quote_retries,
+            ),
+            client.get_gas_price(),
+        );
+        let gas_price = match spot_gas_price {
+            Ok(spot) => executor.smoothed_gas_price(spot),
+            Err(e) => {
+                debug!("Failed to fetch gas price, filtering on min profit only: {:?}", e);
+                U256::zero()
+            }
+        };
+        let mut token_quotes = match token_quotes {
             Ok(q) => q,
             Err(e) => {
                 warn!("Failed to fetch quotes: {:?}", e);
@@ -258,9 +1052,39 @@ async fn main() -> eyre::Result<()> {
         };
         let fetch_time = fetch_start.elapsed();
 
+        let now_ms = chrono::Utc::now().timestamp_millis() as u64;
+        for tq in &mut token_quotes {
+            for (venue, quote) in &tq.quotes {
+                price_cache.update(tq.token, tq.base_token, *venue, *quote);
+            }
+            // Pools whose calls failed this scan fall back to recent cached
+            // quotes, including those restored from the snapshot
+            if config.strategy.price_cache_file.is_some() {
+                let max_age_ms = config.strategy.price_cache_max_age_secs * 1000;
+                let filled = price_cache.fill_missing(tq, max_age_ms, now_ms);
+                if filled > 0 {
+                    debug!("Filled {} {} quotes from the price cache", filled, tq.token_name);
+                }
+            }
+        }
+
         // Detect opportunities with optimal trade sizing using convex optimization
         let detect_start = Instant::now();
-        let opportunities = detector.detect_optimal(client.clone(), &token_quotes).await;
+        let mut opportunities = detector.detect_optimal(client.clone(), &token_quotes, quote_amount, gas_price).await;
+        if config.strategy.requote_optimal_size && !opportunities.is_empty() {
+            opportunities = detector.requote_at_optimal_size(&quoter, client.clone(), opportunities, gas_price).await;
+        }
+        let detection_latency = loop_start.elapsed();
+
+        // Triangular cycles need executeRoute, which the bundled arb contract
+        // doesn't implement, so they are recorded for analysis but not sent
+        for opp in detector.detect_triangular(&token_quotes, quote_amount) {
+            opp.log();
+            monitor.record_opportunity(&opp).await;
+        }
+        if let Some(competition) = &competition {
+            competition.prioritize(&mut opportunities, Instant::now());
+        }
         let detect_time = detect_start.elapsed();
 
         // Log timing for successful scans
@@ -280,17 +1104,135 @@ async fn main() -> eyre::Result<()> {
             );
         }
 
+        // Quotes that took too long to assemble have likely moved since
+        let stale = detector.is_stale(detection_latency);
+        monitor.record_detection_latency(detection_latency, stale).await;
+        if stale && !opportunities.is_empty() {
+            warn!(
+                "⏱️ Detection took {}ms after trigger, skipping execution of {} stale opportunities",
+                detection_latency.as_millis(), opportunities.len()
+            );
+        }
+
+        // Alert once each time the circuit breaker halts trading
+        let breaker_open = executor.is_breaker_open();
+        if breaker_open && !breaker_alerted {
+            monitor.send_alert("Circuit breaker open: execution halted after consecutive failures").await;
+        }
+        breaker_alerted = breaker_open;
+
+        if let Some(balance) = executor.low_balance_alert() {
+            monitor.send_alert(&format!(
+                "Wallet balance low: {} ETH, execution halted until topped up",
+                ethers::utils::format_ether(balance)
+            )).await;
+        }
+
+        // Gas price for the cheap-window check, read once per scan
+        let window_gas_price = match &gas_window {
+            Some(_) if !opportunities.is_empty() && !stale => client.get_gas_price().await.ok(),
+            _ => None,
+        };
+
         // Process opportunities
+        let mut candidates = Vec::with_capacity(opportunities.len());
         for opp in opportunities {
             opp.log();
             monitor.record_opportunity(&opp).await;
+            if let Some(scheduler) = &scheduler {
+                for (pool, venue) in [(opp.buy_pool, opp.buy_venue), (opp.sell_pool, opp.sell_venue)] {
+                    if !pool.is_zero() {
+                        scheduler.promote(pool, &format!("{} {:?}", opp.token_name, venue)).await;
+                    }
+                }
+            }
+
+            if stale {
+                continue;
+            }
 
-            // Execute if profitable
-            info!("🎯 Attempting execution...");
+            if pool_blacklist.blocks(&opp, chrono::Utc::now().timestamp_millis() as u64) {
+                debug!("🚫 Skipping {}: trades through a blacklisted pool", opp.token_name);
+                continue;
+            }
 
-            match executor.execute(client.clone(), &opp).await {
+            if let (Some(window), Some(gas_price)) = (gas_window.as_mut(), window_gas_price) {
+                if !window.admit(&opp, gas_price, Instant::now()) {
+                    info!("⛽ Waiting for cheaper gas ({} queued)", window.queued_len());
+                    continue;
+                }
+            }
+            candidates.push(opp);
+        }
+
+        // A token just attempted waits out its cooldown unless the spread widened
+        let (candidates, _) = token_cooldown.split(candidates, Instant::now());
+
+        // Executing one opportunity moves its pools, so only one per pool goes this scan
+        let (candidates, conflicting) = executor::conflicts::partition(candidates);
+        if !conflicting.is_empty() {
+            info!("🔀 {} opportunities share a pool with a better one ({:?})", conflicting.len(), shared_pool_handling);
+        }
+
+        // Quotes kept ageing through the checks above; don't act on ones past the budget
+        let mut quote_ages = QuoteAges::new(&token_quotes, parsed.weth, config.strategy.max_quote_age_ms);
+        if let Some(max_age_blocks) = config.strategy.max_quote_age_blocks.filter(|_| !candidates.is_empty()) {
+            match client.get_block_number().await {
+                Ok(head) => quote_ages = quote_ages.with_block_limit(head.as_u64(), max_age_blocks),
+                Err(e) => debug!("Failed to read the head block, checking quote age by time only: {:?}", e),
+            }
+        }
+        let (candidates, expired) = quote_ages.split(candidates, chrono::Utc::now().timestamp_millis() as u64);
+        if !expired.is_empty() {
+            warn!(
+                "⏱️ Rejecting {} opportunities with quotes older than {}ms or {:?} blocks",
+                expired.len(), config.strategy.max_quote_age_ms, config.strategy.max_quote_age_blocks
+            );
+            monitor.record_stale_quotes(expired.len()).await;
+        }
+
+        // Simulate every candidate at once, then execute the profitable ones in order.
+        // A shadow executor decides on the same candidates in parallel, on paper.
+        let concurrency = config.execution.simulation_concurrency;
+        let (mut results, shadow_results) = tokio::join!(
+            executor.execute_all(client.clone(), &candidates, concurrency),
+            async {
+                match &shadow {
+                    Some(shadow) => Some(shadow.executor().execute_all(client.clone(), &candidates, concurrency).await),
+                    None => None,
+                }
+            },
+        );
+        if let (Some(shadow), Some(shadow_results)) = (&shadow, shadow_results) {
+            shadow.compare(&candidates, &results, &shadow_results);
+        }
+        for (opp, result) in candidates.iter().zip(&results) {
+            token_cooldown.record(opp, result, Instant::now());
+        }
+        let mut executed = candidates;
+        if shared_pool_handling == SharedPoolHandling::Revalidate {
+            // Simulated afresh one at a time, so a trade the others invalidated fails here
+            for opp in conflicting {
+                if executor.block_slots_remaining() == 0 {
+                    break;
+                }
+                if !quote_ages.is_fresh(&opp, chrono::Utc::now().timestamp_millis() as u64) {
+                    monitor.record_stale_quotes(1).await;
+                    continue;
+                }
+                if !token_cooldown.admit(&opp, Instant::now()) {
+                    continue;
+                }
+                let result = executor.execute(client.clone(), &opp).await;
+                token_cooldown.record(&opp, &result, Instant::now());
+                results.push(result);
+                executed.push(opp);
+            }
+        }
+        for (opp, result) in executed.iter().zip(results) {
+            match result {
                 Ok(result) => {
-                    monitor.record_execution(&result).await;
+                    monitor.record_execution_of(opp, &result).await;
                 }
                 Err(e) => {
                     error!("Execution error: {:?}", e);
@@ -303,4 +1245,135 @@ async fn main() -> eyre::Result<()> {
             warn!("Slow loop: {:?}", loop_time);
         }
     }
+
+    // No new triggers are taken from here - let in-flight txs resolve
+    let drain_timeout = Duration::from_secs(config.execution.shutdown_drain_secs);
+    let drain_deadline = Instant::now() + drain_timeout;
+    while executor.pending_count().await > 0 && Instant::now() < drain_deadline {
+        info!("Draining {} pending transactions...", executor.pending_count().await);
+        if let Some(client) = rpc_lb.get_client().await {
+            for result in executor.check_pending(&*client).await {
+                token_cooldown.record_resolved(&result);
+                monitor.record_execution(&result).await;
+            }
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+    let remaining = executor.pending_count().await;
+    if remaining > 0 {
+        warn!("Shutting down with {} transactions still pending after {:?}", remaining, drain_timeout);
+    }
+
+    monitor.log_summary().await;
+    if let Some(shadow) = &shadow {
+        shadow.log_summary();
+    }
+    if let Some(dir) = &config.monitoring.report_dir {
+        let report = monitor.session_report(&rpc_lb.get_health_stats()).await;
+        match report.write_to(std::path::Path::new(dir)) {
+            Ok(path) => {
+                info!("📝 Session report written to {}", path.display());
+                log_rotation.prune(std::path::Path::new(dir), SessionReport::FILE_PREFIX, None);
+            }
+            Err(e) => warn!("Failed to write session report: {:?}", e),
+        }
+    }
+    if let Some(path) = &config.strategy.price_cache_file {
+        if let Err(e) = price_cache.save_snapshot(std::path::Path::new(path)) {
+            warn!("Failed to save price cache snapshot: {:?}", e);
+        }
+    }
+
+    // The cleanup task saves the scheduler's tiers on shutdown
+    if let Some(task) = scheduler_task {
+        let _ = task.await;
+    }
+
+    if config.execution.warm_start {
+        if let Err(e) = executor.warm_state().save(std::path::Path::new(&config.execution.warm_start_file)) {
+            warn!("Failed to save warm start state: {:?}", e);
+        }
+    }
+
+    info!("👋 Shutdown complete");
+    Ok(())
+}
+
+/// Safety-check the tokens of pools not seen before; pools of paused or
+/// taxed tokens go straight on the blacklist
+async fn check_pool_safety(
+    discovered: &[DiscoveredPool],
+    checked: &mut std::collections::HashSet<Address>,
+    safety: &SafetyChecker,
+    client: &Arc<rpc::WsClient>,
+    blacklist: &PoolBlacklist,
+) {
+    let mut by_token: std::collections::HashMap<Address, Vec<Address>> = std::collections::HashMap::new();
+    for pool in discovered.iter().filter(|pool| checked.insert(pool.address)) {
+        debug!("Discovered {} pool {:?} for {:?}", pool.dex, pool.address, pool.token);
+        by_token.entry(pool.token).or_default().push(pool.address);
+    }
+
+    let now_ms = chrono::Utc::now().timestamp_millis() as u64;
+    for (token, pools) in by_token {
+        let result = safety.check_token_safety_detailed(token, client.clone()).await;
+        blacklist.record_safety(token, &pools, &result, now_ms);
+    }
+}
+
+/// An executor configured from `execution`; the live and shadow executors
+/// are both built here so they differ only in their settings
+async fn build_executor(
+    client: &Arc<rpc::WsClient>,
+    wallet: LocalWallet,
+    execution: &ExecutionConfig,
+    parsed: &ParsedConfig,
+    capabilities: &RpcCapabilities,
+) -> eyre::Result<Executor> {
+    let mut executor = Executor::new(
+        &**client,
+        wallet,
+        parsed.arb_contract,
+        execution.use_flashbots,
+        execution.flashbots_relay.clone(),
+        execution.max_gas_price_gwei,
+        execution.max_priority_fee_gwei,
+        execution.gas_bump_percent,
+        Duration::from_secs(execution.startup_pending_wait_secs),
+    ).await?
+    .with_gas_smoothing(execution.gas_ema_alpha)
+    .with_circuit_breaker(
+        execution.breaker_threshold,
+        Duration::from_secs(execution.breaker_cooldown_secs),
+    )
+    .with_max_slippage_bps(execution.max_slippage_bps)
+    .with_max_per_block(execution.max_per_block)
+    .with_contracts(parsed.contracts.clone())
+    .with_weth(parsed.weth)
+    .with_resubmit_check(execution.skip_resubmit_if_known)
+    .with_deployment_check(execution.check_contract_deployed)
+    .with_l1_fee_estimation(execution.estimate_l1_fee)
+    .with_route_execution(execution.route_execution);
+
+    if execution.min_win_probability > 0.0 {
+        info!("Win-probability filter: skipping opportunities below {:.2}", execution.min_win_probability);
+        executor = executor.with_win_probability(execution.min_win_probability);
+    }
+
+    if execution.size_sweep {
+        info!("📏 Size sweep enabled: correcting solver sizes off by more than {} bps", execution.size_sweep_threshold_bps);
+        executor = executor.with_size_sweep(parsed.venues.multicall3, execution.size_sweep_threshold_bps);
+    }
+
+    if capabilities.allows("Pending-state simulation", RpcMethod::PendingState, execution.simulate_pending) {
+        info!("⏳ Simulating against the pending block");
+        executor = executor.with_pending_simulation(true);
+    }
+
+    if execution.simulation_cache {
+        info!("🗃️ Caching simulation outcomes per block");
+        executor = executor.with_simulation_cache();
+    }
+
+    Ok(executor)
 }
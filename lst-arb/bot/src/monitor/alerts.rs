@@ -0,0 +1,182 @@
+//! Alert Filtering and Rate Limiting
+//!
+//! Keeps Telegram alerts useful during busy periods: small confirmations are
+//! filtered out, and a token bucket caps the send rate. Alerts that arrive
+//! while the bucket is empty are coalesced into a single digest message
+//! instead of being dropped by Telegram's own rate limits.
+
+use ethers::types::U256;
+use std::time::Instant;
+
+use crate::executor::ExecutionResult;
+
+/// Which execution results are worth an alert
+#[derive(Debug, Clone)]
+pub struct AlertPolicy {
+    /// Only alert on confirmations with at least this profit
+    pub min_profit: U256,
+    /// Alert on reverted transactions
+    pub alert_on_reverts: bool,
+}
+
+impl AlertPolicy {
+    pub fn should_alert(&self, result: &ExecutionResult) -> bool {
+        match result {
+            ExecutionResult::Confirmed { profit, .. } => *profit >= self.min_profit,
+            ExecutionResult::Reverted { .. } => self.alert_on_reverts,
+            _ => false,
+        }
+    }
+}
+
+impl Default for AlertPolicy {
+    fn default() -> Self {
+        Self {
+            min_profit: U256::zero(),
+            alert_on_reverts: true,
+        }
+    }
+}
+
+/// Token bucket allowing `capacity` alerts in a burst, refilled continuously
+#[derive(Debug)]
+struct TokenBucket {
+    capacity: f64,
+    tokens: f64,
+    refill_per_sec: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(per_minute: u32, now: Instant) -> Self {
+        let capacity = per_minute.max(1) as f64;
+        Self {
+            capacity,
+            tokens: capacity,
+            refill_per_sec: capacity / 60.0,
+            last_refill: now,
+        }
+    }
+
+    fn try_take(&mut self, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        self.last_refill = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Rate limiter that coalesces alerts over the limit into a digest
+#[derive(Debug)]
+pub struct AlertLimiter {
+    bucket: TokenBucket,
+    pending: Vec<String>,
+}
+
+impl AlertLimiter {
+    pub fn new(per_minute: u32) -> Self {
+        Self {
+            bucket: TokenBucket::new(per_minute, Instant::now()),
+            pending: Vec::new(),
+        }
+    }
+
+    /// Offer an alert; returns the message to send now, if any
+    ///
+    /// When there are held-back alerts, they go out together with this one.
+    pub fn submit(&mut self, message: String, now: Instant) -> Option<String> {
+        self.pending.push(message);
+        self.flush(now)
+    }
+
+    /// Send held-back alerts as a digest once the bucket allows it
+    pub fn flush(&mut self, now: Instant) -> Option<String> {
+        if self.pending.is_empty() || !self.bucket.try_take(now) {
+            return None;
+        }
+
+        let mut pending = std::mem::take(&mut self.pending);
+        if pending.len() == 1 {
+            return pending.pop();
+        }
+
+        Some(format!(
+            "📦 {} alerts (rate limited)\n\n{}",
+            pending.len(),
+            pending.join("\n\n")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::H256;
+    use std::time::Duration;
+
+    impl AlertLimiter {
+        fn pending_count(&self) -> usize {
+            self.pending.len()
+        }
+    }
+
+    #[test]
+    fn test_threshold_filter() {
+        let policy = AlertPolicy {
+            min_profit: ethers::utils::parse_ether("0.01").unwrap(),
+            alert_on_reverts: false,
+        };
+
+        let confirmed = |profit: &str| ExecutionResult::Confirmed {
+            hash: H256::zero(),
+            profit: ethers::utils::parse_ether(profit).unwrap(),
+            gas_cost: U256::zero(),
+            expected_profit: U256::zero(),
+            opportunity_id: H256::zero(),
+        };
+
+        assert!(!policy.should_alert(&confirmed("0.001")));
+        assert!(policy.should_alert(&confirmed("0.01")));
+        assert!(policy.should_alert(&confirmed("0.5")));
+
+        let reverted = ExecutionResult::Reverted {
+            hash: H256::zero(),
+            reason: "Transaction reverted".into(),
+            gas_cost: U256::zero(),
+            opportunity_id: H256::zero(),
+        };
+        assert!(!policy.should_alert(&reverted));
+        assert!(AlertPolicy::default().should_alert(&reverted));
+    }
+
+    #[test]
+    fn test_burst_is_coalesced_into_digest() {
+        let start = Instant::now();
+        let mut limiter = AlertLimiter {
+            bucket: TokenBucket::new(2, start),
+            pending: Vec::new(),
+        };
+
+        // Burst of 5: the bucket lets 2 through, the rest are held
+        let sent: Vec<_> = (0..5)
+            .filter_map(|i| limiter.submit(format!("alert {}", i), start))
+            .collect();
+        assert_eq!(sent, vec!["alert 0".to_string(), "alert 1".to_string()]);
+        assert_eq!(limiter.pending_count(), 3);
+
+        // Still no tokens
+        assert!(limiter.flush(start).is_none());
+
+        // After a refill the held alerts go out as one message
+        let digest = limiter.flush(start + Duration::from_secs(30)).unwrap();
+        assert!(digest.starts_with("📦 3 alerts"));
+        assert!(digest.contains("alert 2") && digest.contains("alert 4"));
+        assert_eq!(limiter.pending_count(), 0);
+    }
+}
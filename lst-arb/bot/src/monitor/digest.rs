@@ -0,0 +1,96 @@
+//! Periodic Alert Digest
+//!
+//! In digest mode per-trade Telegram alerts are replaced by one summary
+//! every interval: trade counts, profit and gas since the last digest, plus
+//! a short list of notable events. Critical alerts (breaker trips) still go
+//! out immediately.
+
+use ethers::types::U256;
+use std::time::{Duration, Instant};
+
+/// Notable events kept per digest; older ones are counted but not listed
+const MAX_NOTABLE: usize = 10;
+
+#[derive(Debug)]
+pub struct Digest {
+    interval: Duration,
+    since: Instant,
+    confirmed: u64,
+    reverted: u64,
+    profit_wei: U256,
+    gas_wei: U256,
+    notable: Vec<String>,
+    notable_dropped: usize,
+}
+
+impl Digest {
+    pub fn new(interval: Duration, now: Instant) -> Self {
+        Self {
+            interval,
+            since: now,
+            confirmed: 0,
+            reverted: 0,
+            profit_wei: U256::zero(),
+            gas_wei: U256::zero(),
+            notable: Vec::new(),
+            notable_dropped: 0,
+        }
+    }
+
+    pub fn record_confirmed(&mut self, profit: U256, gas_cost: U256) {
+        self.confirmed += 1;
+        self.profit_wei += profit;
+        self.gas_wei += gas_cost;
+    }
+
+    pub fn record_reverted(&mut self, gas_cost: U256) {
+        self.reverted += 1;
+        self.gas_wei += gas_cost;
+    }
+
+    /// List an event in the next digest
+    pub fn note(&mut self, event: String) {
+        if self.notable.len() < MAX_NOTABLE {
+            self.notable.push(event);
+        } else {
+            self.notable_dropped += 1;
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.confirmed == 0 && self.reverted == 0 && self.notable.is_empty()
+    }
+
+    /// The digest message once the interval has passed, resetting the
+    /// accumulator. Quiet intervals produce no message.
+    pub fn take(&mut self, now: Instant) -> Option<String> {
+        let elapsed = now.saturating_duration_since(self.since);
+        if elapsed < self.interval {
+            return None;
+        }
+
+        let digest = std::mem::replace(self, Digest::new(self.interval, now));
+        if digest.is_empty() {
+            return None;
+        }
+
+        let net = digest.profit_wei.saturating_sub(digest.gas_wei);
+        let mut message = format!(
+            "🗞️ Digest (last {}m)\nConfirmed: {} | Reverted: {}\nProfit: {} ETH\nGas: {} ETH\nNet: {} ETH",
+            elapsed.as_secs() / 60,
+            digest.confirmed,
+            digest.reverted,
+            ethers::utils::format_ether(digest.profit_wei),
+            ethers::utils::format_ether(digest.gas_wei),
+            ethers::utils::format_ether(net),
+        );
+        if !digest.notable.is_empty() {
+            message.push_str("\n\n");
+            message.push_str(&digest.notable.join("\n"));
+        }
+        if digest.notable_dropped > 0 {
+            message.push_str(&format!("\n(+{} more)", digest.notable_dropped));
+        }
+        Some(message)
+    }
+}
@@ -0,0 +1,80 @@
+//! Profit Drift
+//!
+//! A confirmed trade that lands well under its estimate now and then is
+//! noise. Trade after trade landing short means the pricing model has
+//! drifted from the pools, or something (a sandwich, a competitor ahead of
+//! us in the block) is taking a cut.
+
+use ethers::types::U256;
+
+/// Realized profit below this fraction of the estimate counts as short
+pub const DEFAULT_MIN_REALIZED_RATIO: f64 = 0.7;
+
+/// Short trades in a row that trigger an alert
+pub const DEFAULT_DRIFT_ALERT_AFTER: u32 = 3;
+
+#[derive(Debug)]
+pub struct ProfitDrift {
+    min_ratio_bps: u64,
+    alert_after: u32,
+    consecutive_short: u32,
+}
+
+impl ProfitDrift {
+    pub fn new(min_ratio: f64, alert_after: u32) -> Self {
+        Self {
+            min_ratio_bps: (min_ratio.max(0.0) * 10_000.0) as u64,
+            alert_after: alert_after.max(1),
+            consecutive_short: 0,
+        }
+    }
+
+    /// Record a confirmed trade. Every `alert_after` short trades in a row,
+    /// returns the last one's realized fraction of its estimate to alert on.
+    pub fn record(&mut self, expected: U256, realized: U256) -> Option<f64> {
+        if expected.is_zero() {
+            return None;
+        }
+        let ratio_bps = (realized.saturating_mul(U256::from(10_000u64)) / expected).min(U256::from(u64::MAX)).as_u64();
+        if ratio_bps >= self.min_ratio_bps {
+            self.consecutive_short = 0;
+            return None;
+        }
+
+        self.consecutive_short += 1;
+        self.consecutive_short.is_multiple_of(self.alert_after).then_some(ratio_bps as f64 / 10_000.0)
+    }
+
+    pub fn consecutive_short(&self) -> u32 {
+        self.consecutive_short
+    }
+}
+
+impl Default for ProfitDrift {
+    fn default() -> Self {
+        Self::new(DEFAULT_MIN_REALIZED_RATIO, DEFAULT_DRIFT_ALERT_AFTER)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alerts_on_repeated_short_profit() {
+        let mut drift = ProfitDrift::new(0.7, 3);
+        let wei = U256::from;
+
+        assert_eq!(drift.record(wei(1000), wei(500)), None);
+        assert_eq!(drift.record(wei(1000), wei(600)), None);
+        // A trade landing near its estimate breaks the streak
+        assert_eq!(drift.record(wei(1000), wei(950)), None);
+        assert_eq!(drift.consecutive_short(), 0);
+
+        assert_eq!(drift.record(wei(1000), wei(500)), None);
+        assert_eq!(drift.record(wei(1000), wei(600)), None);
+        assert_eq!(drift.record(wei(1000), wei(400)), Some(0.4));
+        // Still short: reminded every third trade, not every one
+        assert_eq!(drift.record(wei(1000), wei(400)), None);
+    }
+}
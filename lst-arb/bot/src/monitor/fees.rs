@@ -0,0 +1,82 @@
+//! Performance Fee Accounting
+//!
+//! When the bot trades on behalf of a principal, a share of each confirmed
+//! trade's net profit (after gas) is accrued as the operator's performance
+//! fee. Trades that lose money after gas accrue nothing; fees are not
+//! clawed back against later losses.
+
+use ethers::types::U256;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PerformanceFee {
+    rate_bps: u64,
+}
+
+impl PerformanceFee {
+    /// `rate` is the share of net profit taken as a fee (0.2 = 20%)
+    pub fn new(rate: f64) -> eyre::Result<Self> {
+        if !(0.0..=1.0).contains(&rate) {
+            eyre::bail!("Performance fee rate must be between 0 and 1, got {}", rate);
+        }
+        Ok(Self {
+            rate_bps: (rate * 10_000.0).round() as u64,
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.rate_bps > 0
+    }
+
+    /// Fee owed on one confirmed trade
+    pub fn fee_on(&self, profit: U256, gas_cost: U256) -> U256 {
+        let net = profit.saturating_sub(gas_cost);
+        net * U256::from(self.rate_bps) / U256::from(10_000u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::ExecutionResult;
+    use crate::monitor::Monitor;
+    use ethers::types::H256;
+
+    fn eth(value: &str) -> U256 {
+        ethers::utils::parse_ether(value).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_fee_accrues_across_trades() {
+        let monitor = Monitor::new(Vec::new())
+            .with_performance_fee(PerformanceFee::new(0.2).unwrap());
+
+        let trades = [
+            ("0.010", "0.001"), // net 0.009 -> fee 0.0018
+            ("0.005", "0.001"), // net 0.004 -> fee 0.0008
+            ("0.001", "0.002"), // loses after gas -> no fee
+        ];
+        for (profit, gas_cost) in trades {
+            monitor.record_execution(&ExecutionResult::Confirmed {
+                hash: H256::zero(),
+                profit: eth(profit),
+                gas_cost: eth(gas_cost),
+                expected_profit: eth(profit),
+                opportunity_id: H256::zero(),
+            }).await;
+        }
+
+        let stats = monitor.get_stats().await;
+        assert_eq!(stats.total_profit_wei, eth("0.016"));
+        assert_eq!(stats.total_gas_spent_wei, eth("0.004"));
+        assert_eq!(stats.fees_accrued_wei, eth("0.0026"));
+        assert_eq!(stats.net_to_principal(), eth("0.0094"));
+    }
+
+    #[test]
+    fn test_rate_bounds() {
+        assert!(PerformanceFee::new(1.5).is_err());
+        assert!(PerformanceFee::new(-0.1).is_err());
+        assert!(!PerformanceFee::new(0.0).unwrap().is_enabled());
+        assert_eq!(PerformanceFee::new(1.0).unwrap().fee_on(eth("1"), eth("0.25")), eth("0.75"));
+    }
+}
@@ -0,0 +1,231 @@
+//! Journal Replay
+//!
+//! `--replay-journal <path>` feeds the rows of recorded opportunity logs back
+//! through a fresh [`Monitor`], recomputing its stats and P&L offline (say,
+//! after fixing the profit accounting) without touching the chain. Rows are
+//! replayed in the order they were written; files gzipped by log rotation
+//! are read through `gzip -dc`.
+
+use ethers::types::{Address, H256, U256};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::io::{BufRead, BufReader, Cursor};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tracing::{info, warn};
+
+use crate::detector::Opportunity;
+use crate::executor::ExecutionResult;
+use crate::price::Venue;
+use super::recorder::FILE_PREFIX;
+use super::Monitor;
+
+/// A recorded row, decoded back into what the monitor was given
+#[derive(Debug)]
+pub enum JournalEntry {
+    Opportunity(Box<Opportunity>),
+    Result(ExecutionResult),
+}
+
+fn field<T: DeserializeOwned>(row: &Value, key: &str) -> Option<T> {
+    serde_json::from_value(row.get(key)?.clone()).ok()
+}
+
+/// Amounts are recorded as decimal strings
+fn amount(row: &Value, key: &str) -> Option<U256> {
+    U256::from_dec_str(row.get(key)?.as_str()?).ok()
+}
+
+/// Decode one row; None for rows this version can't interpret
+pub fn parse_row(row: &Value) -> Option<JournalEntry> {
+    match row.get("kind")?.as_str()? {
+        "opportunity" => {
+            let (buy_venue, buy_fee_tier) = Venue::from_label(row.get("buy_venue")?.as_str()?)?;
+            let (sell_venue, sell_fee_tier) = Venue::from_label(row.get("sell_venue")?.as_str()?)?;
+            Some(JournalEntry::Opportunity(Box::new(Opportunity {
+                id: field(row, "opportunity_id").unwrap_or_default(),
+                token: field(row, "token_address")?,
+                token_name: field(row, "token")?,
+                // Not recorded; the monitor doesn't need it
+                base_token: Address::zero(),
+                buy_venue,
+                sell_venue,
+                buy_fee_tier,
+                sell_fee_tier,
+                buy_pool: field(row, "buy_pool")?,
+                sell_pool: field(row, "sell_pool")?,
+                buy_stable: false,
+                sell_stable: false,
+                curve_amp: None,
+                buy_price: U256::zero(),
+                sell_price: U256::zero(),
+                spread_bps: field(row, "spread_bps")?,
+                expected_profit: amount(row, "expected_profit_wei")?,
+                // Not recorded either; taken as WETH-based
+                base_profit: amount(row, "expected_profit_wei")?,
+                trade_amount: amount(row, "trade_amount_wei")?,
+                timestamp_ms: field(row, "timestamp_ms")?,
+                route: Vec::new(),
+                buy_reserves: None,
+                sell_reserves: None,
+            })))
+        }
+        "status" => {
+            let hash = || field::<H256>(row, "tx_hash");
+            let opportunity_id = field(row, "opportunity_id").unwrap_or_default();
+            let result = match row.get("status")?.as_str()? {
+                "submitted" => ExecutionResult::Submitted { hash: hash()? },
+                "confirmed" => ExecutionResult::Confirmed {
+                    hash: hash()?,
+                    profit: amount(row, "profit_wei")?,
+                    gas_cost: amount(row, "gas_cost_wei")?,
+                    expected_profit: amount(row, "expected_profit_wei")?,
+                    opportunity_id,
+                },
+                "reverted" => ExecutionResult::Reverted {
+                    hash: hash()?,
+                    reason: field(row, "reason")?,
+                    gas_cost: amount(row, "gas_cost_wei")?,
+                    opportunity_id,
+                },
+                "failed" => ExecutionResult::Failed { reason: field(row, "reason")? },
+                "aborted" => ExecutionResult::Aborted {
+                    expected_profit: amount(row, "expected_profit_wei")?,
+                    actual_profit: amount(row, "profit_wei")?,
+                },
+                _ => return None,
+            };
+            Some(JournalEntry::Result(result))
+        }
+        _ => None,
+    }
+}
+
+/// The journal files at `path`: the file itself, or the opportunity logs
+/// in a directory, oldest first
+pub fn journal_files(path: &Path) -> eyre::Result<Vec<PathBuf>> {
+    if path.is_file() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+    let mut files: Vec<PathBuf> = std::fs::read_dir(path)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|file| file.file_name().is_some_and(|name| name.to_string_lossy().starts_with(FILE_PREFIX)))
+        .filter(|file| {
+            let name = file.to_string_lossy();
+            let journal = name.ends_with(".jsonl") || name.ends_with(".jsonl.gz");
+            if !journal {
+                warn!("Skipping {} (not a .jsonl or .jsonl.gz file)", file.display());
+            }
+            journal
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Lines of a journal file, decompressing it if it was gzipped
+fn read_journal(file: &Path) -> eyre::Result<Box<dyn BufRead>> {
+    if file.extension().is_none_or(|ext| ext != "gz") {
+        return Ok(Box::new(BufReader::new(std::fs::File::open(file)?)));
+    }
+    let output = Command::new("gzip").arg("-dc").arg(file).output()
+        .map_err(|e| eyre::eyre!("Failed to run gzip on {}: {}", file.display(), e))?;
+    if !output.status.success() {
+        eyre::bail!(
+            "gzip -dc {} exited with {}: {}",
+            file.display(), output.status, String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(Box::new(Cursor::new(output.stdout)))
+}
+
+/// Replay every row at `path` into `monitor`, returning how many were replayed
+pub async fn replay(path: &Path, monitor: &Monitor) -> eyre::Result<usize> {
+    let mut replayed = 0;
+    let mut unreadable = 0;
+    for file in journal_files(path)? {
+        info!("📼 Replaying {}", file.display());
+        for line in read_journal(&file)?.lines() {
+            let entry = serde_json::from_str::<Value>(&line?).ok().and_then(|row| parse_row(&row));
+            match entry {
+                Some(JournalEntry::Opportunity(opportunity)) => monitor.record_opportunity(&opportunity).await,
+                Some(JournalEntry::Result(result)) => monitor.record_execution(&result).await,
+                None => {
+                    unreadable += 1;
+                    continue;
+                }
+            }
+            replayed += 1;
+        }
+    }
+    if unreadable > 0 {
+        warn!("Skipped {} unreadable journal rows", unreadable);
+    }
+    Ok(replayed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitor::{OpportunityLogger, PerformanceFee};
+
+    #[tokio::test]
+    async fn test_replayed_journal_recomputes_pnl() {
+        let dir = std::env::temp_dir().join(format!("lst-arb-journal-{}", std::process::id()));
+        let logger = OpportunityLogger::new(&dir).unwrap();
+
+        let opportunity = Opportunity {
+            id: H256::from_low_u64_be(42),
+            buy_pool: Address::from_low_u64_be(10),
+            sell_pool: Address::from_low_u64_be(11),
+            spread_bps: 25,
+            expected_profit: U256::from(1_000u64),
+            trade_amount: U256::exp10(18),
+            timestamp_ms: 1_700_000_000_000,
+            ..Opportunity::fixture()
+        };
+        let (confirmed, reverted) = (H256::from_low_u64_be(7), H256::from_low_u64_be(8));
+        logger.record_opportunity(&opportunity);
+        logger.record_result(Some(&opportunity), &ExecutionResult::Submitted { hash: confirmed });
+        logger.record_result(None, &ExecutionResult::Confirmed {
+            hash: confirmed,
+            profit: U256::from(900u64),
+            gas_cost: U256::from(100u64),
+            expected_profit: U256::from(1_000u64),
+            opportunity_id: opportunity.id,
+        });
+        logger.record_opportunity(&opportunity);
+        logger.record_result(Some(&opportunity), &ExecutionResult::Submitted { hash: reverted });
+        logger.record_result(None, &ExecutionResult::Reverted {
+            hash: reverted,
+            reason: "slippage".into(),
+            gas_cost: U256::from(50u64),
+            opportunity_id: opportunity.id,
+        });
+        logger.record_opportunity(&opportunity);
+        logger.record_result(Some(&opportunity), &ExecutionResult::Failed { reason: "Unprofitable: 3 wei".into() });
+
+        // Rolled logs are gzipped; replay reads them all the same
+        drop(logger);
+        let logged = journal_files(&dir).unwrap();
+        assert_eq!(logged.len(), 1);
+        assert!(std::process::Command::new("gzip").arg(&logged[0]).status().unwrap().success());
+        assert!(journal_files(&dir).unwrap()[0].to_string_lossy().ends_with(".jsonl.gz"));
+
+        // Recomputed with a performance fee the live run didn't have
+        let monitor = Monitor::new(Vec::new()).with_performance_fee(PerformanceFee::new(0.1).unwrap());
+        let replayed = replay(&dir, &monitor).await.unwrap();
+        std::fs::remove_dir_all(&dir).ok();
+
+        let stats = monitor.get_stats().await;
+        assert_eq!(replayed, 8);
+        assert_eq!(stats.opportunities_found, 3);
+        assert_eq!(stats.by_route["Curve->UniswapV3_500"].opportunities, 3);
+        assert_eq!((stats.txs_submitted, stats.txs_confirmed, stats.txs_reverted), (2, 1, 1));
+        assert_eq!(stats.total_profit_wei, U256::from(900u64));
+        assert_eq!(stats.total_gas_spent_wei, U256::from(150u64));
+        assert_eq!(stats.fees_accrued_wei, U256::from(80u64));
+        assert_eq!(stats.net_to_principal(), U256::from(670u64));
+        assert_eq!(stats.skip_reasons["Unprofitable"], 1);
+    }
+}
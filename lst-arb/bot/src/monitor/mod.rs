@@ -1,47 +1,221 @@
 use ethers::types::{U256, H256};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{info, warn, error};
 
-use crate::detector::Opportunity;
+use crate::detector::{short_id, Opportunity};
 use crate::executor::ExecutionResult;
 
-#[derive(Debug, Clone, Default)]
+pub mod alerts;
+pub mod digest;
+pub mod drift;
+pub mod fees;
+pub mod journal;
+pub mod recorder;
+pub mod report;
+pub mod rotation;
+pub mod sinks;
+pub mod tsdb;
+pub use alerts::{AlertLimiter, AlertPolicy};
+pub use digest::Digest;
+pub use drift::ProfitDrift;
+pub use fees::PerformanceFee;
+pub use recorder::OpportunityLogger;
+pub use report::SessionReport;
+pub use rotation::RotationPolicy;
+pub use sinks::{AlertSink, DiscordSink, TelegramSink, WebhookSink};
+pub use tsdb::TsdbSink;
+
+/// Opportunities seen for one token or route
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Breakdown {
+    pub opportunities: u64,
+    pub expected_profit_wei: U256,
+}
+
+/// Compact record of a recent opportunity for the session report
+#[derive(Debug, Clone, Serialize)]
+pub struct RecentOpportunity {
+    pub timestamp_ms: u64,
+    pub token: String,
+    pub route: String,
+    pub spread_bps: u64,
+    pub expected_profit_wei: U256,
+    pub trade_amount_wei: U256,
+}
+
+/// Opportunities kept for the session report
+const RECENT_OPPORTUNITIES: usize = 50;
+
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct Stats {
     pub opportunities_found: u64,
     pub simulations_passed: u64,
     pub txs_submitted: u64,
     pub txs_confirmed: u64,
     pub txs_reverted: u64,
+    /// Paper trades that passed simulation (counted instead of txs_confirmed)
+    pub paper_confirmed: u64,
     pub total_profit_wei: U256,
+    /// Estimated profit of the on-chain confirmations, to compare with the
+    /// realized profit they contributed to total_profit_wei
+    pub expected_profit_wei: U256,
+    pub realized_profit_wei: U256,
     pub total_gas_spent_wei: U256,
+    /// Performance fee owed to the operator
+    pub fees_accrued_wei: U256,
+    /// Scans timed from trigger to detection, and their summed latency
+    pub detection_scans: u64,
+    pub detection_latency_total_ms: u64,
+    /// Scans whose opportunities were skipped as too stale to execute
+    pub stale_scans_skipped: u64,
+    /// Opportunities rejected at execution because their quotes had aged out
+    pub stale_quotes_rejected: u64,
+    /// Per-token and per-route ("Curve->UniswapV3_500") opportunity counts
+    pub by_token: HashMap<String, Breakdown>,
+    pub by_route: HashMap<String, Breakdown>,
+    /// Executions that didn't go ahead, by reason
+    pub skip_reasons: HashMap<String, u64>,
+    #[serde(skip)]
     pub start_time: Option<std::time::Instant>,
 }
 
+impl Stats {
+    /// Profit after gas and performance fees
+    pub fn net_to_principal(&self) -> U256 {
+        self.total_profit_wei
+            .saturating_sub(self.total_gas_spent_wei)
+            .saturating_sub(self.fees_accrued_wei)
+    }
+}
+
 pub struct Monitor {
     stats: RwLock<Stats>,
-    telegram_bot_token: Option<String>,
-    telegram_chat_id: Option<String>,
-    http_client: reqwest::Client,
+    /// Every alert goes to each of these
+    sinks: Vec<Box<dyn AlertSink>>,
+    tsdb: Option<TsdbSink>,
+    recorder: Option<OpportunityLogger>,
+    alert_policy: AlertPolicy,
+    alert_limiter: parking_lot::Mutex<AlertLimiter>,
+    /// Trade alerts held for the next periodic digest (None = send each one)
+    digest: Option<parking_lot::Mutex<Digest>>,
+    performance_fee: PerformanceFee,
+    /// Alerts when realized profit keeps falling short of the estimate
+    profit_drift: parking_lot::Mutex<ProfitDrift>,
+    /// Executions are simulated only; alerts are tagged [PAPER]
+    paper_trading: bool,
+    recent: parking_lot::Mutex<VecDeque<RecentOpportunity>>,
 }
 
+/// Default Telegram alert rate (Telegram allows ~20 messages/minute per chat)
+const DEFAULT_ALERTS_PER_MINUTE: u32 = 20;
+
 impl Monitor {
-    pub fn new(telegram_bot_token: Option<String>, telegram_chat_id: Option<String>) -> Self {
+    pub fn new(sinks: Vec<Box<dyn AlertSink>>) -> Self {
         Self {
             stats: RwLock::new(Stats {
                 start_time: Some(std::time::Instant::now()),
                 ..Default::default()
             }),
-            telegram_bot_token,
-            telegram_chat_id,
-            http_client: reqwest::Client::new(),
+            sinks,
+            tsdb: None,
+            recorder: None,
+            alert_policy: AlertPolicy::default(),
+            alert_limiter: parking_lot::Mutex::new(AlertLimiter::new(DEFAULT_ALERTS_PER_MINUTE)),
+            digest: None,
+            performance_fee: PerformanceFee::default(),
+            profit_drift: parking_lot::Mutex::new(ProfitDrift::default()),
+            paper_trading: false,
+            recent: parking_lot::Mutex::new(VecDeque::with_capacity(RECENT_OPPORTUNITIES)),
         }
     }
+
+    /// Count confirmations as paper trades and tag alerts [PAPER]
+    pub fn with_paper_trading(mut self, enabled: bool) -> Self {
+        self.paper_trading = enabled;
+        self
+    }
+
+    /// Filter execution alerts and cap the alert send rate
+    pub fn with_alerting(mut self, policy: AlertPolicy, alerts_per_minute: u32) -> Self {
+        self.alert_policy = policy;
+        self.alert_limiter = parking_lot::Mutex::new(AlertLimiter::new(alerts_per_minute));
+        self
+    }
+
+    /// Batch trade alerts into one digest per `interval`; critical
+    /// alerts are still sent immediately
+    pub fn with_digest(mut self, interval: std::time::Duration) -> Self {
+        self.digest = Some(parking_lot::Mutex::new(Digest::new(interval, std::time::Instant::now())));
+        self
+    }
+
+    /// Accrue a performance fee on each confirmed trade's net profit
+    pub fn with_performance_fee(mut self, fee: PerformanceFee) -> Self {
+        self.performance_fee = fee;
+        self
+    }
+
+    /// Alert when `alert_after` confirmations in a row realize less than
+    /// `min_ratio` of their expected profit
+    pub fn with_profit_drift(mut self, min_ratio: f64, alert_after: u32) -> Self {
+        self.profit_drift = parking_lot::Mutex::new(ProfitDrift::new(min_ratio, alert_after));
+        self
+    }
+
+    /// Stream opportunities and executions to a time-series database
+    pub fn with_tsdb(mut self, sink: TsdbSink) -> Self {
+        self.tsdb = Some(sink);
+        self
+    }
+
+    /// Persist every opportunity and its execution outcome for offline analysis
+    pub fn with_recorder(mut self, recorder: OpportunityLogger) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
     
     pub async fn record_opportunity(&self, opportunity: &Opportunity) {
-        let mut stats = self.stats.write().await;
+        if let Some(tsdb) = &self.tsdb {
+            tsdb.write_opportunity(opportunity);
+        }
+        if let Some(recorder) = &self.recorder {
+            recorder.record_opportunity(opportunity);
+        }
+
+        let route = format!(
+            "{}->{}",
+            opportunity.buy_venue.label(opportunity.buy_fee_tier),
+            opportunity.sell_venue.label(opportunity.sell_fee_tier)
+        );
+        {
+            let mut recent = self.recent.lock();
+            if recent.len() == RECENT_OPPORTUNITIES {
+                recent.pop_front();
+            }
+            recent.push_back(RecentOpportunity {
+                timestamp_ms: opportunity.timestamp_ms,
+                token: opportunity.token_name.clone(),
+                route: route.clone(),
+                spread_bps: opportunity.spread_bps,
+                expected_profit_wei: opportunity.expected_profit,
+                trade_amount_wei: opportunity.trade_amount,
+            });
+        }
+
+        let mut guard = self.stats.write().await;
+        let stats = &mut *guard;
         stats.opportunities_found += 1;
+        for breakdown in [
+            stats.by_token.entry(opportunity.token_name.clone()).or_default(),
+            stats.by_route.entry(route).or_default(),
+        ] {
+            breakdown.opportunities += 1;
+            breakdown.expected_profit_wei += opportunity.expected_profit;
+        }
         
         // Log opportunity
         info!(
@@ -53,53 +227,142 @@ impl Monitor {
         );
     }
     
+    /// Record how long a scan took from trigger to detection
+    pub async fn record_detection_latency(&self, latency: std::time::Duration, stale: bool) {
+        if let Some(tsdb) = &self.tsdb {
+            tsdb.write_detection_latency(latency, stale);
+        }
+
+        let mut stats = self.stats.write().await;
+        stats.detection_scans += 1;
+        stats.detection_latency_total_ms += latency.as_millis() as u64;
+        if stale {
+            stats.stale_scans_skipped += 1;
+        }
+    }
+    
+    /// Record opportunities rejected at execution for quotes past the age budget
+    pub async fn record_stale_quotes(&self, rejected: usize) {
+        self.stats.write().await.stale_quotes_rejected += rejected as u64;
+    }
+
     pub async fn record_simulation_passed(&self) {
         let mut stats = self.stats.write().await;
         stats.simulations_passed += 1;
     }
     
     pub async fn record_execution(&self, result: &ExecutionResult) {
+        self.record_execution_inner(None, result).await
+    }
+
+    /// Record the immediate result of executing `opportunity`
+    pub async fn record_execution_of(&self, opportunity: &Opportunity, result: &ExecutionResult) {
+        self.record_execution_inner(Some(opportunity), result).await
+    }
+
+    async fn record_execution_inner(&self, opportunity: Option<&Opportunity>, result: &ExecutionResult) {
+        if let Some(tsdb) = &self.tsdb {
+            tsdb.write_execution(result);
+        }
+        if let Some(recorder) = &self.recorder {
+            recorder.record_result(opportunity, result);
+        }
+
+        let alert = self.alert_policy.should_alert(result);
+        // Every line about this result names the opportunity it came from
+        let id_tag = opportunity.map(|opportunity| opportunity.id)
+            .or_else(|| result.opportunity_id())
+            .map(|id| format!(" [{}]", short_id(id)))
+            .unwrap_or_default();
         let mut stats = self.stats.write().await;
         
         match result {
             ExecutionResult::Submitted { hash } => {
                 stats.txs_submitted += 1;
-                info!("📤 TX #{} submitted: {:?}", stats.txs_submitted, hash);
+                info!("📤 TX #{} submitted{}: {:?}", stats.txs_submitted, id_tag, hash);
             }
-            ExecutionResult::Confirmed { hash, profit } => {
-                stats.txs_confirmed += 1;
+            ExecutionResult::Confirmed { hash, profit, gas_cost, expected_profit, .. } => {
+                if self.paper_trading {
+                    stats.paper_confirmed += 1;
+                } else {
+                    stats.txs_confirmed += 1;
+                }
+                // Paper trades report simulated profit; only on-chain ones show drift
+                let drift = if self.paper_trading || hash.is_zero() {
+                    None
+                } else {
+                    stats.expected_profit_wei += *expected_profit;
+                    stats.realized_profit_wei += *profit;
+                    self.profit_drift.lock().record(*expected_profit, *profit)
+                };
                 stats.total_profit_wei += *profit;
+                stats.total_gas_spent_wei += *gas_cost;
+                stats.fees_accrued_wei += self.performance_fee.fee_on(*profit, *gas_cost);
                 
-                let msg = format!(
-                    "✅ TX CONFIRMED\nHash: {:?}\nProfit: {} ETH\nTotal P&L: {} ETH",
-                    hash,
-                    ethers::utils::format_ether(*profit),
-                    ethers::utils::format_ether(stats.total_profit_wei)
-                );
+                let msg = if self.paper_trading {
+                    format!(
+                        "✅ PAPER TRADE #{}{}\nProfit: {} ETH\nPaper P&L: {} ETH",
+                        stats.paper_confirmed,
+                        id_tag,
+                        ethers::utils::format_ether(*profit),
+                        ethers::utils::format_ether(stats.total_profit_wei)
+                    )
+                } else {
+                    format!(
+                        "✅ TX CONFIRMED{}\nHash: {:?}\nProfit: {} ETH\nTotal P&L: {} ETH",
+                        id_tag,
+                        hash,
+                        ethers::utils::format_ether(*profit),
+                        ethers::utils::format_ether(stats.total_profit_wei)
+                    )
+                };
                 
                 info!("{}", msg);
                 drop(stats); // Release lock before async call
-                self.send_telegram(&msg).await;
+                if let Some(digest) = &self.digest {
+                    digest.lock().record_confirmed(*profit, *gas_cost);
+                } else if alert {
+                    self.notify(msg).await;
+                }
+                if let Some(ratio) = drift {
+                    self.send_alert(&format!(
+                        "📉 Realized profit keeps falling short: {} trades in a row under target, last one {:.0}% of expected. Model drift or sandwiching?",
+                        self.profit_drift.lock().consecutive_short(), ratio * 100.0
+                    )).await;
+                }
             }
-            ExecutionResult::Reverted { hash, reason } => {
-                let mut stats = self.stats.write().await;
+            ExecutionResult::Reverted { hash, reason, gas_cost, .. } => {
                 stats.txs_reverted += 1;
+                stats.total_gas_spent_wei += *gas_cost;
                 
                 let msg = format!(
-                    "❌ TX REVERTED\nHash: {:?}\nReason: {}",
-                    hash, reason
+                    "❌ TX REVERTED{}\nHash: {:?}\nReason: {}",
+                    id_tag, hash, reason
                 );
                 
                 warn!("{}", msg);
                 drop(stats);
-                self.send_telegram(&msg).await;
+                if let Some(digest) = &self.digest {
+                    let mut digest = digest.lock();
+                    digest.record_reverted(*gas_cost);
+                    if alert {
+                        digest.note(format!("❌ Reverted{} {:?}: {}", id_tag, hash, reason));
+                    }
+                } else if alert {
+                    self.notify(msg).await;
+                }
             }
             ExecutionResult::Failed { reason } => {
-                warn!("TX Failed: {}", reason);
+                // Tally by the reason's fixed prefix, not the numbers after it
+                let category = reason.split(':').next().unwrap_or_default().trim();
+                *stats.skip_reasons.entry(category.to_string()).or_default() += 1;
+                warn!("TX Failed{}: {}", id_tag, reason);
             }
             ExecutionResult::Aborted { expected_profit, actual_profit } => {
+                *stats.skip_reasons.entry("Pre-flight check failed".into()).or_default() += 1;
                 info!(
-                    "⏸️ TX Aborted (pre-flight check). Expected: {} ETH, Actual: {} ETH. Gas saved.",
+                    "⏸️ TX Aborted{} (pre-flight check). Expected: {} ETH, Actual: {} ETH. Gas saved.",
+                    id_tag,
                     ethers::utils::format_ether(*expected_profit),
                     ethers::utils::format_ether(*actual_profit)
                 );
@@ -116,6 +379,13 @@ impl Monitor {
         self.stats.read().await.clone()
     }
     
+    /// Everything the monitor knows about the session, for writing to disk
+    pub async fn session_report(&self, rpc: &[crate::rpc::RpcHealth]) -> SessionReport {
+        let stats = self.get_stats().await;
+        let recent = self.recent.lock().iter().cloned().collect();
+        SessionReport::new(stats, rpc, recent)
+    }
+
     pub async fn log_summary(&self) {
         let stats = self.stats.read().await;
         
@@ -126,11 +396,7 @@ impl Monitor {
         let hours = uptime / 3600;
         let minutes = (uptime % 3600) / 60;
         
-        let net_profit = if stats.total_profit_wei > stats.total_gas_spent_wei {
-            stats.total_profit_wei - stats.total_gas_spent_wei
-        } else {
-            U256::zero()
-        };
+        let net_profit = stats.total_profit_wei.saturating_sub(stats.total_gas_spent_wei);
         
         let win_rate = if stats.txs_submitted > 0 {
             (stats.txs_confirmed as f64 / stats.txs_submitted as f64) * 100.0
@@ -147,41 +413,216 @@ impl Monitor {
         info!("TXs Submitted:       {}", stats.txs_submitted);
         info!("TXs Confirmed:       {}", stats.txs_confirmed);
         info!("TXs Reverted:        {}", stats.txs_reverted);
+        if self.paper_trading {
+            info!("Paper Confirmed:     {}", stats.paper_confirmed);
+        }
         info!("Win Rate:            {:.1}%", win_rate);
+        if let Some(avg_latency) = stats.detection_latency_total_ms.checked_div(stats.detection_scans) {
+            info!(
+                "Avg Detect Latency:  {}ms ({} stale scans skipped)",
+                avg_latency, stats.stale_scans_skipped
+            );
+        }
+        if stats.stale_quotes_rejected > 0 {
+            info!("Stale Quotes:        {} opportunities rejected", stats.stale_quotes_rejected);
+        }
         info!("Gross Profit:        {} ETH", ethers::utils::format_ether(stats.total_profit_wei));
+        if !stats.expected_profit_wei.is_zero() {
+            info!(
+                "Realized/Expected:   {} / {} ETH",
+                ethers::utils::format_ether(stats.realized_profit_wei),
+                ethers::utils::format_ether(stats.expected_profit_wei)
+            );
+        }
         info!("Gas Spent:           {} ETH", ethers::utils::format_ether(stats.total_gas_spent_wei));
         info!("Net Profit:          {} ETH", ethers::utils::format_ether(net_profit));
+        if self.performance_fee.is_enabled() {
+            info!("Fees Accrued:        {} ETH", ethers::utils::format_ether(stats.fees_accrued_wei));
+            info!("Net to Principal:    {} ETH", ethers::utils::format_ether(stats.net_to_principal()));
+        }
         info!("═══════════════════════════════════════════");
     }
     
-    async fn send_telegram(&self, message: &str) {
-        if let (Some(token), Some(chat_id)) = (&self.telegram_bot_token, &self.telegram_chat_id) {
-            let url = format!(
-                "https://api.telegram.org/bot{}/sendMessage",
-                token
-            );
-            
-            let params = serde_json::json!({
-                "chat_id": chat_id,
-                "text": message,
-                "parse_mode": "HTML"
-            });
-            
-            match self.http_client.post(&url).json(&params).send().await {
-                Ok(_) => {}
-                Err(e) => warn!("Failed to send Telegram alert: {:?}", e),
-            }
+    /// Send a rate-limited alert (held back alerts go out as a digest)
+    async fn notify(&self, message: String) {
+        let message = self.tag(message);
+        let to_send = self.alert_limiter.lock().submit(message, std::time::Instant::now());
+        if let Some(message) = to_send {
+            self.broadcast(&message).await;
+        }
+    }
+
+    /// Send any alerts held back by the rate limiter
+    pub async fn flush_alerts(&self) {
+        let digest = self.alert_limiter.lock().flush(std::time::Instant::now());
+        if let Some(digest) = digest {
+            self.broadcast(&digest).await;
+        }
+    }
+
+    /// The trade digest, if one is enabled, due and non-empty
+    fn take_digest(&self) -> Option<String> {
+        self.digest.as_ref()?.lock().take(std::time::Instant::now())
+    }
+
+    /// Send the trade digest once its interval has passed
+    pub async fn flush_digest(&self) {
+        if let Some(digest) = self.take_digest() {
+            self.notify(digest).await;
         }
     }
+
+    /// Send the daily P&L summary to the alert sinks
+    pub async fn send_daily_summary(&self) {
+        let stats = self.stats.read().await;
+        let msg = format!(
+            "📊 Daily Summary\nOpportunities: {}\nConfirmed: {} / Submitted: {} (Reverted: {})\nGross Profit: {} ETH\nGas Spent: {} ETH",
+            stats.opportunities_found,
+            stats.txs_confirmed,
+            stats.txs_submitted,
+            stats.txs_reverted,
+            ethers::utils::format_ether(stats.total_profit_wei),
+            ethers::utils::format_ether(stats.total_gas_spent_wei)
+        );
+        let msg = if self.paper_trading {
+            format!("{}\nPaper Confirmed: {}", msg, stats.paper_confirmed)
+        } else {
+            msg
+        };
+        let msg = if self.performance_fee.is_enabled() {
+            format!(
+                "{}\nFees Accrued: {} ETH\nNet to Principal: {} ETH",
+                msg,
+                ethers::utils::format_ether(stats.fees_accrued_wei),
+                ethers::utils::format_ether(stats.net_to_principal())
+            )
+        } else {
+            msg
+        };
+        drop(stats);
+        self.notify(msg).await;
+    }
+
+    /// Deliver to every sink at once; one failing sink doesn't hold up the rest
+    async fn broadcast(&self, message: &str) {
+        let sends = self.sinks.iter().map(|sink| async move {
+            if let Err(e) = sink.send(message).await {
+                warn!("Failed to send {} alert: {:?}", sink.name(), e);
+            }
+        });
+        futures::future::join_all(sends).await;
+    }
     
     pub async fn send_alert(&self, message: &str) {
         info!("🚨 ALERT: {}", message);
-        self.send_telegram(&format!("🚨 {}", message)).await;
+        self.notify(format!("🚨 {}", message)).await;
     }
     
     pub async fn send_startup_message(&self) {
-        let msg = "🚀 LST Arbitrage Bot Started\n\nMonitoring for opportunities...";
+        let msg = self.tag("🚀 LST Arbitrage Bot Started\n\nMonitoring for opportunities...".into());
         info!("{}", msg);
-        self.send_telegram(msg).await;
+        self.broadcast(&msg).await;
+    }
+
+    /// Prefix alerts with [PAPER] so they can't be mistaken for real trades
+    fn tag(&self, message: String) -> String {
+        if self.paper_trading {
+            format!("[PAPER] {}", message)
+        } else {
+            message
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_paper_confirmations_counted_separately() {
+        let monitor = Monitor::new(Vec::new()).with_paper_trading(true);
+        let profit = ethers::utils::parse_ether("0.01").unwrap();
+        monitor.record_execution(&ExecutionResult::Confirmed {
+            hash: H256::zero(),
+            profit,
+            gas_cost: U256::zero(),
+            expected_profit: profit,
+            opportunity_id: H256::zero(),
+        }).await;
+
+        let stats = monitor.get_stats().await;
+        assert_eq!(stats.paper_confirmed, 1);
+        assert_eq!(stats.txs_confirmed, 0);
+        assert_eq!(stats.total_profit_wei, profit);
+        assert!(monitor.tag("Bot started".into()).starts_with("[PAPER] "));
+    }
+
+    #[tokio::test]
+    async fn test_trade_alerts_batched_into_digest() {
+        let interval = std::time::Duration::from_millis(50);
+        // One alert a minute: any per-trade send would use up the budget
+        let monitor = Monitor::new(Vec::new())
+            .with_alerting(AlertPolicy::default(), 1)
+            .with_digest(interval);
+        let profit = ethers::utils::parse_ether("0.01").unwrap();
+        for i in 0..3 {
+            monitor.record_execution(&ExecutionResult::Confirmed {
+                hash: H256::from_low_u64_be(i),
+                profit,
+                gas_cost: U256::zero(),
+                expected_profit: profit,
+                opportunity_id: H256::zero(),
+            }).await;
+        }
+        monitor.record_execution(&ExecutionResult::Reverted {
+            hash: H256::from_low_u64_be(9),
+            reason: "slippage".into(),
+            gas_cost: U256::zero(),
+            opportunity_id: H256::zero(),
+        }).await;
+
+        // Nothing goes out before the interval
+        assert_eq!(monitor.take_digest(), None);
+        assert!(monitor.alert_limiter.lock().submit("probe".into(), std::time::Instant::now()).is_some());
+
+        tokio::time::sleep(interval).await;
+        let digest = monitor.take_digest().unwrap();
+        assert!(digest.contains("Confirmed: 3 | Reverted: 1"), "{digest}");
+        assert!(digest.contains("Profit: 0.030000000000000000 ETH"), "{digest}");
+        assert!(digest.contains("slippage"), "{digest}");
+
+        // Flushed: the next interval starts empty
+        tokio::time::sleep(interval).await;
+        assert_eq!(monitor.take_digest(), None);
+    }
+
+    struct TestSink {
+        fail: bool,
+        sent: Arc<parking_lot::Mutex<Vec<String>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl AlertSink for TestSink {
+        fn name(&self) -> &'static str {
+            "test"
+        }
+
+        async fn send(&self, msg: &str) -> eyre::Result<()> {
+            if self.fail {
+                eyre::bail!("webhook down");
+            }
+            self.sent.lock().push(msg.to_string());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failing_sink_does_not_block_others() {
+        let sent = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let sink = |fail| Box::new(TestSink { fail, sent: sent.clone() }) as Box<dyn AlertSink>;
+        let monitor = Monitor::new(vec![sink(true), sink(false), sink(true), sink(false)]);
+
+        monitor.send_alert("Circuit breaker tripped").await;
+        assert_eq!(*sent.lock(), vec!["🚨 Circuit breaker tripped"; 2]);
     }
 }
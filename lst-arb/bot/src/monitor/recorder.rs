@@ -0,0 +1,280 @@
+//! Opportunity Recorder
+//!
+//! Every detected opportunity, executed or not, is appended as a JSON line
+//! to a daily file for offline analysis (hit rate, realized vs expected
+//! profit). Execution outcomes are appended as separate status rows carrying
+//! the opportunity's (session, id), so the status column is joined in
+//! afterwards rather than rewritten in place. Rows are newline-delimited
+//! JSON so the files load directly into SQLite, DuckDB or pandas. Finished
+//! files are compressed and pruned per the [`RotationPolicy`].
+
+use ethers::types::{Address, H256};
+use parking_lot::Mutex;
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+use crate::detector::Opportunity;
+use crate::executor::ExecutionResult;
+use super::rotation::RotationPolicy;
+
+/// Opportunities remembered for linking an execution result back to its row
+const RECENT_ROWS: usize = 256;
+
+/// Names of the log's files, live and finished
+pub(crate) const FILE_PREFIX: &str = "opportunities-";
+
+/// Identifies a detected opportunity until its execution result arrives
+type OpportunityKey = (Address, u64, Address, Address);
+
+fn opportunity_key(opportunity: &Opportunity) -> OpportunityKey {
+    (opportunity.token, opportunity.timestamp_ms, opportunity.buy_pool, opportunity.sell_pool)
+}
+
+struct RecorderState {
+    /// (day, path, file) currently being appended to
+    file: Option<(String, PathBuf, File)>,
+    next_id: u64,
+    recent: VecDeque<(OpportunityKey, u64)>,
+    /// Submitted tx -> opportunity row, until the tx resolves
+    pending: HashMap<H256, u64>,
+}
+
+pub struct OpportunityLogger {
+    dir: PathBuf,
+    /// Session start (unix ms); row ids are unique within a session
+    session: u64,
+    rotation: RotationPolicy,
+    state: Mutex<RecorderState>,
+}
+
+impl OpportunityLogger {
+    /// Append to daily `opportunities-YYYYmmdd.jsonl` files in `dir`
+    pub fn new(dir: &Path) -> eyre::Result<Self> {
+        std::fs::create_dir_all(dir)?;
+        info!("📒 Recording opportunities to {}", dir.display());
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            session: chrono::Utc::now().timestamp_millis() as u64,
+            rotation: RotationPolicy::default(),
+            state: Mutex::new(RecorderState {
+                file: None,
+                next_id: 0,
+                recent: VecDeque::with_capacity(RECENT_ROWS),
+                pending: HashMap::new(),
+            }),
+        })
+    }
+
+    /// Roll, compress and prune the log's files per `rotation`
+    pub fn with_rotation(mut self, rotation: RotationPolicy) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn record_opportunity(&self, opportunity: &Opportunity) {
+        let mut state = self.state.lock();
+        let id = state.next_id;
+        state.next_id += 1;
+
+        if state.recent.len() == RECENT_ROWS {
+            state.recent.pop_front();
+        }
+        state.recent.push_back((opportunity_key(opportunity), id));
+
+        let row = json!({
+            "session": self.session,
+            "id": id,
+            "kind": "opportunity",
+            "opportunity_id": opportunity.id,
+            "timestamp_ms": opportunity.timestamp_ms,
+            "token": opportunity.token_name,
+            "token_address": opportunity.token,
+            "buy_venue": opportunity.buy_venue.label(opportunity.buy_fee_tier),
+            "sell_venue": opportunity.sell_venue.label(opportunity.sell_fee_tier),
+            "buy_pool": opportunity.buy_pool,
+            "sell_pool": opportunity.sell_pool,
+            "spread_bps": opportunity.spread_bps,
+            "expected_profit_wei": opportunity.expected_profit.to_string(),
+            "trade_amount_wei": opportunity.trade_amount.to_string(),
+            "status": Value::Null,
+        });
+        self.append(&mut state, &row);
+    }
+
+    /// Record an execution outcome against the opportunity it came from, or,
+    /// without one, against the opportunity whose tx it resolves
+    pub fn record_result(&self, opportunity: Option<&Opportunity>, result: &ExecutionResult) {
+        let mut state = self.state.lock();
+
+        let id = match (opportunity, result_hash(result)) {
+            (Some(opportunity), _) => {
+                let key = opportunity_key(opportunity);
+                state.recent.iter().rev().find(|(k, _)| *k == key).map(|(_, id)| *id)
+            }
+            (None, Some(hash)) => state.pending.get(&hash).copied(),
+            (None, None) => None,
+        };
+        let Some(id) = id else { return };
+
+        match result {
+            ExecutionResult::Submitted { hash } => {
+                state.pending.insert(*hash, id);
+            }
+            _ => {
+                if let Some(hash) = result_hash(result) {
+                    state.pending.remove(&hash);
+                }
+            }
+        }
+
+        let mut row = json!({
+            "session": self.session,
+            "id": id,
+            "kind": "status",
+            "timestamp_ms": chrono::Utc::now().timestamp_millis(),
+        });
+        let row_fields = row.as_object_mut().expect("status row is an object");
+        if let Some(opportunity_id) = opportunity.map(|opportunity| opportunity.id).or_else(|| result.opportunity_id()) {
+            row_fields.insert("opportunity_id".into(), json!(opportunity_id));
+        }
+        row_fields.extend(status_fields(result));
+        self.append(&mut state, &row);
+    }
+
+    fn append(&self, state: &mut RecorderState, row: &Value) {
+        let day = chrono::Utc::now().format("%Y%m%d").to_string();
+        if state.file.as_ref().map(|(d, _, _)| d != &day).unwrap_or(true) {
+            let path = self.dir.join(format!("{}{}.jsonl", FILE_PREFIX, day));
+            if let Some((_, finished, _)) = state.file.take() {
+                self.rotation.retire(&finished, FILE_PREFIX, Some(&path));
+            }
+            match OpenOptions::new().create(true).append(true).open(&path) {
+                Ok(file) => state.file = Some((day, path, file)),
+                Err(e) => {
+                    warn!("Failed to open opportunity log {}: {:?}", path.display(), e);
+                    return;
+                }
+            }
+        }
+
+        let Some((_, path, file)) = &mut state.file else { return };
+        if let Err(e) = writeln!(file, "{}", row) {
+            warn!("Failed to write opportunity log: {:?}", e);
+        }
+
+        let full = file.metadata().is_ok_and(|metadata| self.rotation.is_full(metadata.len()));
+        if full {
+            // Reopened (as a fresh file) on the next row
+            let path = path.clone();
+            state.file = None;
+            match self.rotation.roll(&path) {
+                Ok(rolled) => self.rotation.retire(&rolled, FILE_PREFIX, Some(&path)),
+                Err(e) => warn!("Failed to rotate opportunity log {}: {:?}", path.display(), e),
+            }
+        }
+    }
+}
+
+fn result_hash(result: &ExecutionResult) -> Option<H256> {
+    match result {
+        ExecutionResult::Submitted { hash }
+        | ExecutionResult::Confirmed { hash, .. }
+        | ExecutionResult::Reverted { hash, .. } => Some(*hash),
+        _ => None,
+    }
+}
+
+fn status_fields(result: &ExecutionResult) -> serde_json::Map<String, Value> {
+    let value = match result {
+        ExecutionResult::Submitted { hash } => json!({ "status": "submitted", "tx_hash": hash }),
+        ExecutionResult::Confirmed { hash, profit, gas_cost, expected_profit, .. } => json!({
+            "status": "confirmed",
+            "tx_hash": hash,
+            "profit_wei": profit.to_string(),
+            "gas_cost_wei": gas_cost.to_string(),
+            "expected_profit_wei": expected_profit.to_string(),
+        }),
+        ExecutionResult::Reverted { hash, reason, gas_cost, .. } => json!({
+            "status": "reverted",
+            "tx_hash": hash,
+            "reason": reason,
+            "gas_cost_wei": gas_cost.to_string(),
+        }),
+        ExecutionResult::Failed { reason } => json!({ "status": "failed", "reason": reason }),
+        ExecutionResult::Aborted { expected_profit, actual_profit } => json!({
+            "status": "aborted",
+            "profit_wei": actual_profit.to_string(),
+            "expected_profit_wei": expected_profit.to_string(),
+        }),
+    };
+    match value {
+        Value::Object(fields) => fields,
+        _ => unreachable!("status fields are an object"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::U256;
+
+    #[test]
+    fn test_rows_linked_from_detection_to_confirmation() {
+        let dir = std::env::temp_dir().join(format!("lst-arb-recorder-{}", std::process::id()));
+        let logger = OpportunityLogger::new(&dir).unwrap();
+
+        let opportunity = |token: u64| Opportunity {
+            token: Address::from_low_u64_be(token),
+            buy_pool: Address::from_low_u64_be(10),
+            sell_pool: Address::from_low_u64_be(11),
+            spread_bps: 25,
+            expected_profit: U256::from(1_000u64),
+            trade_amount: U256::exp10(18),
+            timestamp_ms: 1_700_000_000_000,
+            ..Opportunity::fixture()
+        };
+        let executed = opportunity(1);
+        let hash = H256::from_low_u64_be(7);
+
+        logger.record_opportunity(&opportunity(2));
+        logger.record_opportunity(&executed);
+        logger.record_result(Some(&executed), &ExecutionResult::Submitted { hash });
+        // The confirmation arrives later with only the tx hash
+        logger.record_result(None, &ExecutionResult::Confirmed {
+            hash,
+            profit: U256::from(900u64),
+            gas_cost: U256::from(50u64),
+            expected_profit: U256::from(1000u64),
+            opportunity_id: H256::zero(),
+        });
+        // Unknown txs are ignored
+        logger.record_result(None, &ExecutionResult::Confirmed {
+            hash: H256::from_low_u64_be(8),
+            profit: U256::zero(),
+            gas_cost: U256::zero(),
+            expected_profit: U256::zero(),
+            opportunity_id: H256::zero(),
+        });
+
+        let file = std::fs::read_dir(&dir).unwrap().next().unwrap().unwrap().path();
+        let rows: Vec<Value> = std::fs::read_to_string(&file).unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[1]["kind"], "opportunity");
+        assert_eq!(rows[1]["sell_venue"], "UniswapV3_500");
+        assert!(rows[1]["status"].is_null());
+        let id = rows[1]["id"].clone();
+        assert_ne!(rows[0]["id"], id);
+        assert_eq!((rows[2]["id"].clone(), rows[2]["status"].clone()), (id.clone(), "submitted".into()));
+        assert_eq!((rows[3]["id"].clone(), rows[3]["status"].clone()), (id, "confirmed".into()));
+        assert_eq!(rows[3]["profit_wei"], "900");
+    }
+}
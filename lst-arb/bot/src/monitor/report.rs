@@ -0,0 +1,140 @@
+//! Session Report
+//!
+//! On clean shutdown the monitor's stats, per-token and per-route
+//! breakdowns, skip-reason tallies, RPC health and the recent opportunities
+//! are written as one JSON file, so each session leaves a durable record
+//! beyond the log.
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use super::{RecentOpportunity, Stats};
+use crate::rpc::RpcHealth;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionReport {
+    /// RFC 3339 time the report was generated
+    pub generated_at: String,
+    pub uptime_secs: u64,
+    pub net_profit_eth: String,
+    pub stats: Stats,
+    pub rpc: Vec<RpcReport>,
+    pub recent_opportunities: Vec<RecentOpportunity>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RpcReport {
+    /// Scheme and host only - paths often carry API keys
+    pub endpoint: String,
+    pub latency_ms: u64,
+    pub success_rate: f64,
+    pub is_healthy: bool,
+    pub consecutive_failures: u32,
+    pub reconnect_count: u32,
+    /// Optional methods the endpoint was found not to serve
+    pub unsupported_methods: Vec<&'static str>,
+}
+
+impl From<&RpcHealth> for RpcReport {
+    fn from(health: &RpcHealth) -> Self {
+        Self {
+            endpoint: redact_endpoint(&health.url),
+            latency_ms: health.latency_ms,
+            success_rate: health.success_rate,
+            is_healthy: health.is_healthy,
+            consecutive_failures: health.consecutive_failures,
+            reconnect_count: health.reconnect_count,
+            unsupported_methods: health.capabilities.unsupported().map(|method| method.name()).collect(),
+        }
+    }
+}
+
+impl SessionReport {
+    pub fn new(stats: Stats, rpc: &[RpcHealth], recent_opportunities: Vec<RecentOpportunity>) -> Self {
+        let uptime_secs = stats.start_time.map(|t| t.elapsed().as_secs()).unwrap_or(0);
+        let net_profit = stats.total_profit_wei.saturating_sub(stats.total_gas_spent_wei);
+        Self {
+            generated_at: chrono::Utc::now().to_rfc3339(),
+            uptime_secs,
+            net_profit_eth: ethers::utils::format_ether(net_profit),
+            stats,
+            rpc: rpc.iter().map(RpcReport::from).collect(),
+            recent_opportunities,
+        }
+    }
+
+    /// Names of the report files written to a directory
+    pub const FILE_PREFIX: &'static str = "session-";
+
+    /// Write to a timestamped file in `dir`, creating it if needed
+    pub fn write_to(&self, dir: &Path) -> eyre::Result<PathBuf> {
+        std::fs::create_dir_all(dir)?;
+        let path = dir.join(format!("{}{}.json", Self::FILE_PREFIX, chrono::Utc::now().format("%Y%m%d-%H%M%S")));
+        std::fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(path)
+    }
+}
+
+fn redact_endpoint(url: &str) -> String {
+    match reqwest::Url::parse(url) {
+        Ok(parsed) => format!("{}://{}", parsed.scheme(), parsed.host_str().unwrap_or_default()),
+        Err(_) => "<unparseable>".into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::U256;
+    use std::time::Instant;
+
+    #[test]
+    fn test_end_of_session_report_serializes() {
+        let mut stats = Stats {
+            opportunities_found: 12,
+            txs_submitted: 3,
+            txs_confirmed: 2,
+            txs_reverted: 1,
+            total_profit_wei: ethers::utils::parse_ether("0.03").unwrap(),
+            total_gas_spent_wei: ethers::utils::parse_ether("0.001").unwrap(),
+            start_time: Some(Instant::now()),
+            ..Default::default()
+        };
+        stats.by_token.entry("wstETH".into()).or_default().opportunities = 12;
+        stats.skip_reasons.insert("Gas price too high".into(), 4);
+
+        let rpc = [RpcHealth {
+            url: "wss://arbitrum-mainnet.infura.io/ws/v3/SECRET_KEY".into(),
+            latency_ms: 40,
+            success_rate: 0.98,
+            last_check: Instant::now(),
+            is_healthy: true,
+            consecutive_failures: 0,
+            reconnect_count: 0,
+            capabilities: Default::default(),
+        }];
+        let recent = vec![RecentOpportunity {
+            timestamp_ms: 1_700_000_000_000,
+            token: "wstETH".into(),
+            route: "Curve->UniswapV3_500".into(),
+            spread_bps: 35,
+            expected_profit_wei: ethers::utils::parse_ether("0.01").unwrap(),
+            trade_amount_wei: U256::exp10(18),
+        }];
+
+        let report = SessionReport::new(stats, &rpc, recent);
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["net_profit_eth"], "0.029000000000000000");
+        assert_eq!(json["stats"]["skip_reasons"]["Gas price too high"], 4);
+        assert_eq!(json["recent_opportunities"][0]["route"], "Curve->UniswapV3_500");
+
+        let text = json.to_string();
+        assert!(!text.contains("SECRET_KEY"));
+        assert!(text.contains("wss://arbitrum-mainnet.infura.io"));
+
+        let dir = std::env::temp_dir().join(format!("lst-arb-report-{}", std::process::id()));
+        let path = report.write_to(&dir).unwrap();
+        assert!(std::fs::read_to_string(&path).unwrap().contains("\"uptime_secs\""));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,167 @@
+//! Log File Rotation
+//!
+//! The opportunity log and session reports only ever grow, and over a long
+//! deployment they fill the disk. The live opportunity log is rolled aside
+//! once it passes a size limit (it also starts a new file each day),
+//! finished files are optionally gzipped, and only the newest few of each
+//! kind are kept.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tracing::{info, warn};
+
+/// Finished files kept per log by default
+pub const DEFAULT_KEEP_FILES: usize = 30;
+
+/// One retirement at a time, so a prune never sees a file and its
+/// half-written .gz as two files
+static RETIRING: parking_lot::Mutex<()> = parking_lot::Mutex::new(());
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RotationPolicy {
+    /// Roll the live file once it reaches this size (None = daily only)
+    pub max_bytes: Option<u64>,
+    /// Finished files kept per log; older ones are deleted (0 = keep all)
+    pub keep: usize,
+    /// gzip files once they're finished
+    pub compress: bool,
+}
+
+impl Default for RotationPolicy {
+    fn default() -> Self {
+        Self {
+            max_bytes: None,
+            keep: DEFAULT_KEEP_FILES,
+            compress: false,
+        }
+    }
+}
+
+impl RotationPolicy {
+    /// Whether a live file of `len` bytes should be rolled
+    pub fn is_full(&self, len: u64) -> bool {
+        self.max_bytes.is_some_and(|max| len >= max)
+    }
+
+    /// Move the live file at `path` aside as `<stem>.NNN.<ext>`, numbered
+    /// after the parts already rolled from it so names sort in write order
+    pub fn roll(&self, path: &Path) -> io::Result<PathBuf> {
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        let ext = path.extension().unwrap_or_default().to_string_lossy();
+        let rolled = (1..)
+            .map(|part| path.with_file_name(format!("{}.{:03}.{}", stem, part, ext)))
+            .find(|rolled| !rolled.exists() && !gz_path(rolled).exists())
+            .expect("some part number is free");
+        std::fs::rename(path, &rolled)?;
+        info!("🗂️ Rotated {} to {}", path.display(), rolled.display());
+        Ok(rolled)
+    }
+
+    /// Finish with a file that's no longer written to: gzip it if
+    /// configured, then prune the log it belongs to, leaving `live` alone.
+    /// Callers hold locks on the detection path, so inside the runtime
+    /// this happens on a blocking thread.
+    pub fn retire(&self, path: &Path, prefix: &str, live: Option<&Path>) {
+        let policy = self.clone();
+        let (path, prefix, live) = (path.to_path_buf(), prefix.to_string(), live.map(Path::to_path_buf));
+        let retire = move || {
+            let _guard = RETIRING.lock();
+            policy.compress_and_prune(&path, &prefix, live.as_deref());
+        };
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => drop(handle.spawn_blocking(retire)),
+            Err(_) => retire(),
+        }
+    }
+
+    fn compress_and_prune(&self, path: &Path, prefix: &str, live: Option<&Path>) {
+        if self.compress {
+            match Command::new("gzip").arg("-f").arg(path).status() {
+                Ok(status) if status.success() => {}
+                Ok(status) => warn!("gzip {} exited with {}", path.display(), status),
+                Err(e) => warn!("Failed to run gzip on {}: {:?}", path.display(), e),
+            }
+        }
+        if let Some(dir) = path.parent() {
+            self.prune(dir, prefix, live);
+        }
+    }
+
+    /// Delete all but the newest `keep` finished files in `dir` whose names
+    /// start with `prefix`; `live`, still being written, doesn't count.
+    /// Names sort by date, then part, so the oldest go first.
+    pub fn prune(&self, dir: &Path, prefix: &str, live: Option<&Path>) {
+        if self.keep == 0 {
+            return;
+        }
+        let mut files: Vec<PathBuf> = match std::fs::read_dir(dir) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.is_file())
+                .filter(|path| path.file_name().is_some_and(|name| name.to_string_lossy().starts_with(prefix)))
+                .filter(|path| live.is_none_or(|live| path != live))
+                .collect(),
+            Err(e) => {
+                warn!("Failed to list {} for pruning: {:?}", dir.display(), e);
+                return;
+            }
+        };
+        files.sort();
+
+        let excess = files.len().saturating_sub(self.keep);
+        for old in &files[..excess] {
+            match std::fs::remove_file(old) {
+                Ok(()) => info!("🗑️ Pruned {}", old.display()),
+                Err(e) => warn!("Failed to prune {}: {:?}", old.display(), e),
+            }
+        }
+    }
+}
+
+fn gz_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".gz");
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_full_file_rolled_and_oldest_pruned() {
+        let dir = std::env::temp_dir().join(format!("lst-arb-rotation-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let policy = RotationPolicy { max_bytes: Some(10), keep: 2, compress: false };
+
+        // Yesterday's file and an earlier part of today's are already finished
+        std::fs::write(dir.join("opportunities-20260101.jsonl"), "old").unwrap();
+        std::fs::write(dir.join("opportunities-20260102.001.jsonl"), "part one").unwrap();
+        let live = dir.join("opportunities-20260102.jsonl");
+        std::fs::write(&live, "over ten bytes").unwrap();
+        std::fs::write(dir.join("unrelated.txt"), "kept").unwrap();
+
+        assert!(!policy.is_full(9));
+        assert!(policy.is_full(std::fs::metadata(&live).unwrap().len()));
+        let rolled = policy.roll(&live).unwrap();
+        assert_eq!(rolled, dir.join("opportunities-20260102.002.jsonl"));
+        assert!(!live.exists());
+        // The next row has already reopened the live file; it isn't one of the two kept
+        std::fs::write(&live, "new").unwrap();
+        policy.retire(&rolled, "opportunities-", Some(&live));
+
+        let mut left: Vec<String> = std::fs::read_dir(&dir).unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        left.sort();
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(left, vec![
+            "opportunities-20260102.001.jsonl",
+            "opportunities-20260102.002.jsonl",
+            "opportunities-20260102.jsonl",
+            "unrelated.txt",
+        ]);
+    }
+}
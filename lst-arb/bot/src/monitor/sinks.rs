@@ -0,0 +1,107 @@
+//! Alert Sinks
+//!
+//! Where alerts are delivered. The monitor fans every alert out to each
+//! configured sink independently, so a failing Discord webhook doesn't
+//! silence Telegram and vice versa.
+
+use async_trait::async_trait;
+
+/// Discord rejects message content longer than this
+const DISCORD_MAX_CONTENT: usize = 2000;
+
+#[async_trait]
+pub trait AlertSink: Send + Sync {
+    /// Short name used when logging delivery failures
+    fn name(&self) -> &'static str;
+
+    async fn send(&self, msg: &str) -> eyre::Result<()>;
+}
+
+/// Telegram bot message to one chat
+pub struct TelegramSink {
+    http: reqwest::Client,
+    bot_token: String,
+    chat_id: String,
+}
+
+impl TelegramSink {
+    pub fn new(http: reqwest::Client, bot_token: String, chat_id: String) -> Self {
+        Self { http, bot_token, chat_id }
+    }
+}
+
+#[async_trait]
+impl AlertSink for TelegramSink {
+    fn name(&self) -> &'static str {
+        "Telegram"
+    }
+
+    async fn send(&self, msg: &str) -> eyre::Result<()> {
+        let url = format!("https://api.telegram.org/bot{}/sendMessage", self.bot_token);
+        let params = serde_json::json!({
+            "chat_id": self.chat_id,
+            "text": msg,
+            "parse_mode": "HTML"
+        });
+        self.http.post(&url).json(&params).send().await?.error_for_status()?;
+        Ok(())
+    }
+}
+
+/// Discord channel webhook
+pub struct DiscordSink {
+    http: reqwest::Client,
+    webhook_url: String,
+}
+
+impl DiscordSink {
+    pub fn new(http: reqwest::Client, webhook_url: String) -> Self {
+        Self { http, webhook_url }
+    }
+}
+
+#[async_trait]
+impl AlertSink for DiscordSink {
+    fn name(&self) -> &'static str {
+        "Discord"
+    }
+
+    async fn send(&self, msg: &str) -> eyre::Result<()> {
+        let params = serde_json::json!({ "content": truncate(msg, DISCORD_MAX_CONTENT) });
+        self.http.post(&self.webhook_url).json(&params).send().await?.error_for_status()?;
+        Ok(())
+    }
+}
+
+/// Generic JSON webhook (`{"text": ...}`), e.g. a PagerDuty or Slack integration
+pub struct WebhookSink {
+    http: reqwest::Client,
+    url: String,
+}
+
+impl WebhookSink {
+    pub fn new(http: reqwest::Client, url: String) -> Self {
+        Self { http, url }
+    }
+}
+
+#[async_trait]
+impl AlertSink for WebhookSink {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    async fn send(&self, msg: &str) -> eyre::Result<()> {
+        let params = serde_json::json!({ "text": msg });
+        self.http.post(&self.url).json(&params).send().await?.error_for_status()?;
+        Ok(())
+    }
+}
+
+/// The first `max_chars` characters of `msg`
+fn truncate(msg: &str, max_chars: usize) -> &str {
+    match msg.char_indices().nth(max_chars) {
+        Some((end, _)) => &msg[..end],
+        None => msg,
+    }
+}
@@ -0,0 +1,247 @@
+//! Time-Series Database Sink
+//!
+//! Streams opportunities and executions to a TSDB using the InfluxDB line
+//! protocol over HTTP. Points are queued on a bounded channel and written in
+//! batches by a background task, so recording never blocks the hot path.
+
+use ethers::types::U256;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
+
+use crate::detector::Opportunity;
+use crate::executor::ExecutionResult;
+
+/// Maximum queued points before new points are dropped
+const CHANNEL_CAPACITY: usize = 10_000;
+
+/// Handle for writing points to the TSDB writer task
+pub struct TsdbSink {
+    tx: mpsc::Sender<String>,
+}
+
+impl TsdbSink {
+    /// Spawn the background writer and return a sink handle
+    pub fn spawn(
+        url: String,
+        token: Option<String>,
+        batch_size: usize,
+        flush_interval: Duration,
+    ) -> Self {
+        let (tx, rx) = mpsc::channel(CHANNEL_CAPACITY);
+
+        info!("TSDB sink enabled: {} (batch {}, flush {:?})", url, batch_size, flush_interval);
+
+        tokio::spawn(run_writer(url, token, batch_size.max(1), flush_interval, rx));
+
+        Self { tx }
+    }
+
+    /// Queue an opportunity point
+    pub fn write_opportunity(&self, opportunity: &Opportunity) {
+        let timestamp_ns = opportunity.timestamp_ms as u128 * 1_000_000;
+        self.enqueue(opportunity_line(opportunity, timestamp_ns));
+    }
+
+    /// Queue an execution point
+    pub fn write_execution(&self, result: &ExecutionResult) {
+        let timestamp_ns = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        self.enqueue(execution_line(result, timestamp_ns));
+    }
+
+    /// Queue a trigger-to-detection latency point
+    pub fn write_detection_latency(&self, latency: Duration, stale: bool) {
+        let timestamp_ns = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        self.enqueue(format!(
+            "detection latency_ms={}i,stale={} {}",
+            latency.as_millis(), stale, timestamp_ns
+        ));
+    }
+
+    fn enqueue(&self, line: String) {
+        // Never wait on the writer - drop the point if it has fallen behind
+        if self.tx.try_send(line).is_err() {
+            debug!("TSDB queue full, dropping point");
+        }
+    }
+}
+
+/// Background task that batches points and posts them to the TSDB
+async fn run_writer(
+    url: String,
+    token: Option<String>,
+    batch_size: usize,
+    flush_interval: Duration,
+    mut rx: mpsc::Receiver<String>,
+) {
+    let http_client = reqwest::Client::new();
+    let mut buffer: Vec<String> = Vec::with_capacity(batch_size);
+    let mut ticker = tokio::time::interval(flush_interval);
+
+    loop {
+        tokio::select! {
+            line = rx.recv() => {
+                match line {
+                    Some(line) => {
+                        buffer.push(line);
+                        if buffer.len() >= batch_size {
+                            flush(&http_client, &url, token.as_deref(), &mut buffer).await;
+                        }
+                    }
+                    None => {
+                        // All sinks dropped - write what's left and stop
+                        flush(&http_client, &url, token.as_deref(), &mut buffer).await;
+                        break;
+                    }
+                }
+            }
+            _ = ticker.tick() => {
+                flush(&http_client, &url, token.as_deref(), &mut buffer).await;
+            }
+        }
+    }
+}
+
+async fn flush(
+    http_client: &reqwest::Client,
+    url: &str,
+    token: Option<&str>,
+    buffer: &mut Vec<String>,
+) {
+    if buffer.is_empty() {
+        return;
+    }
+
+    let body = buffer.join("\n");
+    let points = buffer.len();
+    buffer.clear();
+
+    let mut request = http_client.post(url).body(body);
+    if let Some(token) = token {
+        request = request.header("Authorization", format!("Token {}", token));
+    }
+
+    match request.send().await {
+        Ok(response) if response.status().is_success() => {
+            debug!("Wrote {} points to TSDB", points);
+        }
+        Ok(response) => {
+            warn!("TSDB write of {} points rejected: {}", points, response.status());
+        }
+        Err(e) => {
+            warn!("TSDB write of {} points failed: {:?}", points, e);
+        }
+    }
+}
+
+/// Format an opportunity as an InfluxDB line protocol point
+pub fn opportunity_line(opportunity: &Opportunity, timestamp_ns: u128) -> String {
+    format!(
+        "opportunity,token={},buy_venue={},sell_venue={} spread_bps={}i,expected_profit_eth={},trade_amount_eth={},opportunity_id=\"{:?}\" {}",
+        escape_tag(&opportunity.token_name),
+        opportunity.buy_venue.label(opportunity.buy_fee_tier),
+        opportunity.sell_venue.label(opportunity.sell_fee_tier),
+        opportunity.spread_bps,
+        eth_field(opportunity.expected_profit),
+        eth_field(opportunity.trade_amount),
+        opportunity.id,
+        timestamp_ns
+    )
+}
+
+/// Format an execution result as an InfluxDB line protocol point
+pub fn execution_line(result: &ExecutionResult, timestamp_ns: u128) -> String {
+    let (status, fields) = match result {
+        ExecutionResult::Submitted { hash } => {
+            ("submitted", format!("tx_hash=\"{:?}\"", hash))
+        }
+        ExecutionResult::Confirmed { hash, profit, gas_cost, opportunity_id, .. } => (
+            "confirmed",
+            format!(
+                "profit_eth={},gas_cost_eth={},tx_hash=\"{:?}\",opportunity_id=\"{:?}\"",
+                eth_field(*profit),
+                eth_field(*gas_cost),
+                hash,
+                opportunity_id
+            ),
+        ),
+        ExecutionResult::Reverted { hash, gas_cost, opportunity_id, .. } => (
+            "reverted",
+            format!(
+                "gas_cost_eth={},tx_hash=\"{:?}\",opportunity_id=\"{:?}\"",
+                eth_field(*gas_cost), hash, opportunity_id
+            ),
+        ),
+        ExecutionResult::Failed { reason } => {
+            ("failed", format!("reason=\"{}\"", escape_string_field(reason)))
+        }
+        ExecutionResult::Aborted { expected_profit, actual_profit } => (
+            "aborted",
+            format!(
+                "expected_profit_eth={},actual_profit_eth={}",
+                eth_field(*expected_profit),
+                eth_field(*actual_profit)
+            ),
+        ),
+    };
+
+    format!("execution,status={} {} {}", status, fields, timestamp_ns)
+}
+
+/// Wei amounts are written as ETH floats - wei values overflow 64-bit integer fields
+fn eth_field(wei: U256) -> String {
+    ethers::utils::format_ether(wei)
+}
+
+/// Escape commas, spaces and equals signs in tag values
+fn escape_tag(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(',', "\\,")
+        .replace(' ', "\\ ")
+        .replace('=', "\\=")
+}
+
+/// Escape quotes and backslashes in string field values
+fn escape_string_field(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::Address;
+
+    #[test]
+    fn test_opportunity_line_format() {
+        let opportunity = Opportunity {
+            token: Address::zero(),
+            spread_bps: 25,
+            expected_profit: ethers::utils::parse_ether("0.0125").unwrap(),
+            trade_amount: ethers::utils::parse_ether("5").unwrap(),
+            timestamp_ms: 1_700_000_000_000,
+            ..Opportunity::fixture()
+        };
+
+        let line = opportunity_line(&opportunity, opportunity.timestamp_ms as u128 * 1_000_000);
+
+        assert_eq!(
+            line,
+            "opportunity,token=wstETH,buy_venue=Curve,sell_venue=UniswapV3_500 \
+             spread_bps=25i,expected_profit_eth=0.012500000000000000,trade_amount_eth=5.000000000000000000,\
+             opportunity_id=\"0x0000000000000000000000000000000000000000000000000000000000000000\" \
+             1700000000000000000"
+        );
+    }
+
+    #[test]
+    fn test_tag_escaping() {
+        assert_eq!(escape_tag("a b,c=d"), "a\\ b\\,c\\=d");
+    }
+}
@@ -1,14 +1,22 @@
 use dashmap::DashMap;
 use ethers::types::{Address, U256};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 use std::time::Instant;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+use super::multicall::TokenQuotes;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Venue {
     Curve,
     Balancer,
     UniswapV3,
     Maverick,
+    /// Solidly fork (Velodrome/Aerodrome/Ramses) stable or volatile pool
+    Solidly,
 }
 
 impl Venue {
@@ -18,20 +26,160 @@ impl Venue {
             Venue::Balancer => 2,
             Venue::UniswapV3 => 3,
             Venue::Maverick => 4,
+            Venue::Solidly => 5,
         }
     }
     
     pub fn all() -> Vec<Venue> {
-        vec![Venue::Curve, Venue::Balancer, Venue::UniswapV3]
+        vec![Venue::Curve, Venue::Balancer, Venue::UniswapV3, Venue::Maverick, Venue::Solidly]
+    }
+
+    /// Display label including the fee tier for venues quoted per tier
+    pub fn label(&self, fee_tier: Option<u32>) -> String {
+        match fee_tier {
+            Some(tier) => format!("{:?}_{}", self, tier),
+            None => format!("{:?}", self),
+        }
+    }
+
+    /// Parse a `label` back into the venue and fee tier
+    pub fn from_label(label: &str) -> Option<(Venue, Option<u32>)> {
+        let (name, fee_tier) = match label.split_once('_') {
+            Some((name, tier)) => (name, Some(tier.parse().ok()?)),
+            None => (label, None),
+        };
+        let venue = Venue::all().into_iter().find(|venue| format!("{:?}", venue) == name)?;
+        Some((venue, fee_tier))
+    }
+}
+
+/// Venues currently quoted and traded. Clones share one set, so a venue
+/// switched off through any handle is skipped everywhere.
+#[derive(Debug, Clone)]
+pub struct EnabledVenues(Arc<parking_lot::RwLock<HashSet<Venue>>>);
+
+impl EnabledVenues {
+    pub fn new(venues: HashSet<Venue>) -> Self {
+        Self(Arc::new(parking_lot::RwLock::new(venues)))
+    }
+
+    pub fn contains(&self, venue: Venue) -> bool {
+        self.0.read().contains(&venue)
+    }
+
+    /// Switch a venue on or off at runtime
+    pub fn set(&self, venue: Venue, enabled: bool) {
+        let mut venues = self.0.write();
+        if enabled {
+            venues.insert(venue);
+        } else {
+            venues.remove(&venue);
+        }
+    }
+
+    /// Enable exactly `venues`
+    pub fn replace(&self, venues: &HashSet<Venue>) {
+        for venue in Venue::all() {
+            self.set(venue, venues.contains(&venue));
+        }
+    }
+
+    /// Enabled venues in `Venue::all` order
+    pub fn list(&self) -> Vec<Venue> {
+        let venues = self.0.read();
+        Venue::all().into_iter().filter(|v| venues.contains(v)).collect()
+    }
+}
+
+impl Default for EnabledVenues {
+    fn default() -> Self {
+        Self::new(Venue::all().into_iter().collect())
+    }
+}
+
+/// Why and when a token was switched off
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisableReason {
+    pub reason: String,
+    pub disabled_at_ms: u64,
+}
+
+/// Tokens temporarily switched off (e.g. during a depeg). They keep their
+/// configuration and stay in the token list, but aren't quoted. Clones share
+/// one set, like `EnabledVenues`.
+#[derive(Debug, Clone, Default)]
+pub struct DisabledTokens(Arc<parking_lot::RwLock<HashMap<Address, DisableReason>>>);
+
+impl DisabledTokens {
+    /// Switch a token off; an already disabled token keeps its original time
+    pub fn disable_token(&self, token: Address, reason: String, now_ms: u64) {
+        let mut disabled = self.0.write();
+        match disabled.get_mut(&token) {
+            Some(existing) => existing.reason = reason,
+            None => {
+                disabled.insert(token, DisableReason { reason, disabled_at_ms: now_ms });
+            }
+        }
+    }
+
+    /// Switch a token back on, returning why it was off
+    pub fn enable_token(&self, token: Address) -> Option<DisableReason> {
+        self.0.write().remove(&token)
+    }
+
+    pub fn reason(&self, token: Address) -> Option<DisableReason> {
+        self.0.read().get(&token).cloned()
+    }
+
+    /// Disable exactly `tokens` (token -> reason), returning the tokens that
+    /// changed state as (token, now disabled)
+    pub fn replace(&self, tokens: &HashMap<Address, String>, now_ms: u64) -> Vec<(Address, bool)> {
+        let mut changed = Vec::new();
+        let currently: Vec<Address> = self.0.read().keys().copied().collect();
+        for token in currently.into_iter().filter(|token| !tokens.contains_key(token)) {
+            self.enable_token(token);
+            changed.push((token, false));
+        }
+        for (token, reason) in tokens {
+            if self.reason(*token).is_none() {
+                changed.push((*token, true));
+            }
+            self.disable_token(*token, reason.clone(), now_ms);
+        }
+        changed
+    }
+
+    /// The `tokens` still to be quoted
+    pub fn get_token_pairs(&self, tokens: &[(Address, String)]) -> Vec<(Address, String)> {
+        let disabled = self.0.read();
+        tokens.iter().filter(|(token, _)| !disabled.contains_key(token)).cloned().collect()
+    }
+}
+
+impl std::str::FromStr for Venue {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> eyre::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "curve" => Ok(Venue::Curve),
+            "balancer" => Ok(Venue::Balancer),
+            "uniswapv3" | "uniswap_v3" => Ok(Venue::UniswapV3),
+            "maverick" => Ok(Venue::Maverick),
+            "solidly" => Ok(Venue::Solidly),
+            other => Err(eyre::eyre!("Unknown venue: {}", other)),
+        }
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Quote {
     pub buy_amount: U256,   // LST received per ETH spent
     pub sell_amount: U256,  // ETH received per LST sold
     pub liquidity: U256,    // Available liquidity
     pub timestamp_ms: u64,
+    pub fee_tier: Option<u32>, // Uniswap V3 fee tier (hundredths of a bip), None for single-pool venues
+    pub pool: Address,      // Pool the quote came from (zero if unknown)
+    pub reserves: Option<(U256, U256)>, // (WETH, LST) pool reserves, if fetched
 }
 
 impl Default for Quote {
@@ -41,14 +189,33 @@ impl Default for Quote {
             sell_amount: U256::zero(),
             liquidity: U256::zero(),
             timestamp_ms: 0,
+            fee_tier: None,
+            pool: Address::zero(),
+            reserves: None,
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+/// Whether two (venue, fee tier, pool address) identities are the same pool
+///
+/// Pool addresses are compared when both are known; otherwise fall back to
+/// venue and fee tier.
+pub fn same_pool(a: (Venue, Option<u32>, Address), b: (Venue, Option<u32>, Address)) -> bool {
+    if !a.2.is_zero() && !b.2.is_zero() {
+        return a.2 == b.2;
+    }
+    a.0 == b.0 && a.1 == b.1
+}
+
+/// One pool's quote for a market. UniswapV3 tiers of the same pair and an
+/// LST's WETH and LST/LST quotes are separate entries.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Serialize, Deserialize)]
 pub struct QuoteKey {
     pub token: Address,
+    /// None = quoted against WETH
+    pub base_token: Option<Address>,
     pub venue: Venue,
+    pub fee_tier: Option<u32>,
 }
 
 pub struct PriceCache {
@@ -66,8 +233,8 @@ impl PriceCache {
         }
     }
     
-    pub fn update(&self, token: Address, venue: Venue, quote: Quote) {
-        let key = QuoteKey { token, venue };
+    pub fn update(&self, token: Address, base_token: Option<Address>, venue: Venue, quote: Quote) {
+        let key = QuoteKey { token, base_token, venue, fee_tier: quote.fee_tier };
         self.quotes.insert(key, quote);
         self.update_count.fetch_add(1, Ordering::Relaxed);
         self.last_update_ms.store(
@@ -79,19 +246,38 @@ impl PriceCache {
         );
     }
     
-    pub fn get(&self, token: Address, venue: Venue) -> Option<Quote> {
-        let key = QuoteKey { token, venue };
+    pub fn get(&self, token: Address, base_token: Option<Address>, venue: Venue, fee_tier: Option<u32>) -> Option<Quote> {
+        let key = QuoteKey { token, base_token, venue, fee_tier };
         self.quotes.get(&key).map(|q| *q)
     }
     
-    pub fn get_all_for_token(&self, token: Address) -> Vec<(Venue, Quote)> {
-        let mut results = Vec::new();
-        for venue in Venue::all() {
-            if let Some(quote) = self.get(token, venue) {
-                results.push((venue, quote));
+    pub fn get_all_for_token(&self, token: Address, base_token: Option<Address>) -> Vec<(Venue, Quote)> {
+        self.quotes.iter()
+            .filter(|entry| entry.key().token == token && entry.key().base_token == base_token)
+            .map(|entry| (entry.key().venue, *entry.value()))
+            .collect()
+    }
+
+    /// Add cached quotes for pools missing from a fresh fetch, such as
+    /// everything restored from a snapshot whose calls failed this scan
+    ///
+    /// Only quotes newer than `max_age_ms` are used. The market is then
+    /// stamped with the oldest filled quote's time and no block, so the
+    /// quote-age budget still judges it by its stalest quote. Returns how
+    /// many quotes were filled.
+    pub fn fill_missing(&self, tq: &mut TokenQuotes, max_age_ms: u64, now_ms: u64) -> usize {
+        let mut filled = 0;
+        for (venue, quote) in self.get_all_for_token(tq.token, tq.base_token) {
+            let present = tq.quotes.iter().any(|(v, q)| *v == venue && q.fee_tier == quote.fee_tier);
+            if present || now_ms.saturating_sub(quote.timestamp_ms) > max_age_ms {
+                continue;
             }
+            tq.quotes.push((venue, quote));
+            tq.fetched_at_ms = tq.fetched_at_ms.min(quote.timestamp_ms);
+            tq.block_number = None;
+            filled += 1;
         }
-        results
+        filled
     }
     
     pub fn is_stale(&self, max_age_ms: u64) -> bool {
@@ -106,6 +292,45 @@ impl PriceCache {
     pub fn update_count(&self) -> u64 {
         self.update_count.load(Ordering::Relaxed)
     }
+
+    /// Write all cached quotes to `path`
+    pub fn save_snapshot(&self, path: &Path) -> eyre::Result<()> {
+        let entries: Vec<(QuoteKey, Quote)> = self.quotes.iter()
+            .map(|entry| (*entry.key(), *entry.value()))
+            .collect();
+
+        let json = serde_json::to_string(&entries)?;
+        // Write then rename so a crash mid-write can't corrupt the snapshot
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Load quotes saved by `save_snapshot`, skipping any older than `max_age_ms`
+    ///
+    /// Returns how many quotes were loaded. A missing file loads nothing.
+    pub fn load_snapshot(&self, path: &Path, max_age_ms: u64) -> eyre::Result<usize> {
+        if !path.exists() {
+            return Ok(0);
+        }
+
+        let entries: Vec<(QuoteKey, Quote)> = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)?
+            .as_millis() as u64;
+
+        let mut loaded = 0;
+        for (key, quote) in entries {
+            if now.saturating_sub(quote.timestamp_ms) > max_age_ms {
+                continue;
+            }
+            self.quotes.insert(key, quote);
+            self.last_update_ms.fetch_max(quote.timestamp_ms, Ordering::Relaxed);
+            loaded += 1;
+        }
+        Ok(loaded)
+    }
 }
 
 impl Default for PriceCache {
@@ -113,3 +338,115 @@ impl Default for PriceCache {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_round_trip_drops_stale_quotes() {
+        let path = std::env::temp_dir().join(format!("price_cache_{}.json", std::process::id()));
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+
+        let fresh_token = Address::from_low_u64_be(1);
+        let stale_token = Address::from_low_u64_be(2);
+
+        let cache = PriceCache::new();
+        cache.update(fresh_token, None, Venue::UniswapV3, Quote {
+            buy_amount: U256::from(995u64),
+            sell_amount: U256::from(1003u64),
+            fee_tier: Some(100),
+            timestamp_ms: now - 5_000,
+            reserves: Some((U256::from(10u64), U256::from(20u64))),
+            ..Default::default()
+        });
+        cache.update(stale_token, None, Venue::Curve, Quote {
+            buy_amount: U256::from(990u64),
+            timestamp_ms: now - 600_000,
+            ..Default::default()
+        });
+        cache.save_snapshot(&path).unwrap();
+
+        let restored = PriceCache::new();
+        assert_eq!(restored.load_snapshot(&path, 60_000).unwrap(), 1);
+        std::fs::remove_file(&path).unwrap();
+
+        let quote = restored.get(fresh_token, None, Venue::UniswapV3, Some(100)).unwrap();
+        assert_eq!(quote.buy_amount, U256::from(995u64));
+        assert_eq!(quote.sell_amount, U256::from(1003u64));
+        assert_eq!(quote.fee_tier, Some(100));
+        assert_eq!(quote.reserves, Some((U256::from(10u64), U256::from(20u64))));
+        assert!(restored.get(stale_token, None, Venue::Curve, None).is_none());
+        assert!(!restored.is_stale(60_000));
+    }
+
+    #[test]
+    fn test_fee_tiers_cached_apart_and_fill_missing_pools() {
+        let now = 1_000_000;
+        let token = Address::from_low_u64_be(1);
+        let quote = |fee_tier, buy_amount: u64, timestamp_ms| Quote {
+            buy_amount: U256::from(buy_amount),
+            fee_tier,
+            timestamp_ms,
+            ..Default::default()
+        };
+
+        let cache = PriceCache::new();
+        cache.update(token, None, Venue::UniswapV3, quote(Some(100), 995, now - 2_000));
+        cache.update(token, None, Venue::UniswapV3, quote(Some(500), 990, now - 2_000));
+        cache.update(token, None, Venue::Curve, quote(None, 993, now - 120_000));
+        // The same token quoted against another LST is a different market
+        cache.update(token, Some(Address::from_low_u64_be(9)), Venue::Balancer, quote(None, 1, now));
+        assert_eq!(cache.get(token, None, Venue::UniswapV3, Some(100)).unwrap().buy_amount, U256::from(995u64));
+        assert_eq!(cache.get(token, None, Venue::UniswapV3, Some(500)).unwrap().buy_amount, U256::from(990u64));
+
+        // This scan only got the 100bp tier back
+        let mut tq = TokenQuotes {
+            token,
+            token_name: "wsteth".into(),
+            quotes: vec![(Venue::UniswapV3, quote(Some(100), 996, now))],
+            curve_amp: None,
+            sized_quotes: Vec::new(),
+            fetched_at_ms: now,
+            block_number: Some(42),
+            base_token: None,
+        };
+        assert_eq!(cache.fill_missing(&mut tq, 60_000, now), 1);
+        assert_eq!(tq.quotes.len(), 2);
+        assert!(tq.quotes.iter().any(|(v, q)| *v == Venue::UniswapV3 && q.fee_tier == Some(500)));
+        // Aged by the filled quote, so the quote-age budget still applies
+        assert_eq!(tq.fetched_at_ms, now - 2_000);
+        assert_eq!(tq.block_number, None);
+    }
+
+    #[test]
+    fn test_missing_snapshot_loads_nothing() {
+        let cache = PriceCache::new();
+        let path = std::env::temp_dir().join("price_cache_does_not_exist.json");
+        assert_eq!(cache.load_snapshot(&path, 60_000).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_disabled_token_excluded_from_quoting_but_kept() {
+        let disabled = DisabledTokens::default();
+        let (wsteth, ezeth) = (Address::from_low_u64_be(1), Address::from_low_u64_be(2));
+        let tokens = vec![(wsteth, "wsteth".to_string()), (ezeth, "ezeth".to_string())];
+
+        disabled.disable_token(ezeth, "depeg".into(), 1_000);
+        assert_eq!(disabled.get_token_pairs(&tokens), vec![tokens[0].clone()]);
+        // Still listed, with why and when it was switched off
+        assert_eq!(tokens.len(), 2);
+        assert_eq!(disabled.reason(ezeth), Some(DisableReason { reason: "depeg".into(), disabled_at_ms: 1_000 }));
+
+        // A config reload keeps the original time for tokens that stay off
+        let changed = disabled.replace(&HashMap::from([(ezeth, "depeg, LRT withdrawals paused".into())]), 5_000);
+        assert!(changed.is_empty());
+        assert_eq!(disabled.reason(ezeth).unwrap().disabled_at_ms, 1_000);
+
+        assert_eq!(disabled.replace(&HashMap::new(), 9_000), vec![(ezeth, false)]);
+        assert_eq!(disabled.get_token_pairs(&tokens), tokens);
+    }
+}
@@ -0,0 +1,120 @@
+//! Adaptive Multicall Composition
+//!
+//! Some venue calls fail for a token every single time - a Curve pool that
+//! doesn't take `get_dy` for those indices, a Balancer pool that was
+//! retired. Sending them anyway bloats every multicall for nothing. Each
+//! (token, venue)'s quote calls are tracked, dropped after enough failures
+//! in a row, and re-probed once per cooldown to notice a recovery.
+
+use ethers::types::Address;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+use super::cache::Venue;
+
+/// Consecutive failed scans before a (token, venue)'s calls are dropped
+pub const DEFAULT_DROP_AFTER_FAILURES: u32 = 20;
+
+/// How long dropped calls stay out of the multicall before a re-probe
+pub const DEFAULT_REPROBE_AFTER: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone, Copy, Default)]
+struct PairHealth {
+    consecutive_failures: u32,
+    /// Left out of the multicall until then
+    dropped_until: Option<Instant>,
+}
+
+pub struct CallHealth {
+    pairs: Mutex<HashMap<(Address, Venue), PairHealth>>,
+    drop_after: u32,
+    reprobe_after: Duration,
+}
+
+impl CallHealth {
+    pub fn new(drop_after: u32, reprobe_after: Duration) -> Self {
+        Self {
+            pairs: Mutex::new(HashMap::new()),
+            drop_after: drop_after.max(1),
+            reprobe_after,
+        }
+    }
+
+    /// Whether to include `token`'s calls to `venue` in the next multicall
+    pub fn should_call(&self, token: Address, venue: Venue, now: Instant) -> bool {
+        self.pairs.lock()
+            .get(&(token, venue))
+            .and_then(|health| health.dropped_until)
+            .is_none_or(|until| now >= until)
+    }
+
+    /// Record whether any of `token`'s quote calls to `venue` succeeded this scan
+    pub fn record(&self, token: Address, venue: Venue, success: bool, now: Instant) {
+        let mut pairs = self.pairs.lock();
+        let health = pairs.entry((token, venue)).or_default();
+
+        if success {
+            if health.dropped_until.is_some() {
+                info!("{:?} quotes for {:?} recovered, calling it again", venue, token);
+            }
+            *health = PairHealth::default();
+            return;
+        }
+
+        health.consecutive_failures += 1;
+        if health.consecutive_failures >= self.drop_after {
+            if health.dropped_until.is_none() {
+                warn!(
+                    "⚠️ {:?} quotes for {:?} failed {} times in a row, dropping them from the multicall",
+                    venue, token, health.consecutive_failures
+                );
+            }
+            // A failed re-probe drops it for another cooldown
+            health.dropped_until = Some(now + self.reprobe_after);
+        }
+    }
+}
+
+impl Default for CallHealth {
+    fn default() -> Self {
+        Self::new(DEFAULT_DROP_AFTER_FAILURES, DEFAULT_REPROBE_AFTER)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failing_call_dropped_then_reprobed() {
+        let health = CallHealth::new(3, Duration::from_secs(60));
+        let (reth, weeth) = (Address::from_low_u64_be(1), Address::from_low_u64_be(2));
+        let start = Instant::now();
+
+        for _ in 0..2 {
+            health.record(reth, Venue::Curve, false, start);
+        }
+        assert!(health.should_call(reth, Venue::Curve, start));
+
+        // Third failure in a row drops it; other pairs are unaffected
+        health.record(reth, Venue::Curve, false, start);
+        assert!(!health.should_call(reth, Venue::Curve, start));
+        assert!(health.should_call(reth, Venue::UniswapV3, start));
+        assert!(health.should_call(weeth, Venue::Curve, start));
+
+        // Re-probed after the cooldown; failing again drops it for another one
+        let reprobe = start + Duration::from_secs(60);
+        assert!(health.should_call(reth, Venue::Curve, reprobe));
+        health.record(reth, Venue::Curve, false, reprobe);
+        assert!(!health.should_call(reth, Venue::Curve, reprobe + Duration::from_secs(30)));
+
+        // A successful re-probe restores it
+        let recovered = reprobe + Duration::from_secs(60);
+        assert!(health.should_call(reth, Venue::Curve, recovered));
+        health.record(reth, Venue::Curve, true, recovered);
+        health.record(reth, Venue::Curve, false, recovered);
+        assert!(health.should_call(reth, Venue::Curve, recovered));
+    }
+}
@@ -0,0 +1,165 @@
+//! Per-venue quote decode health
+//!
+//! Multicall results that fail to decode are skipped so one broken venue
+//! can't take down the others. These counters make those skips visible: a
+//! venue whose decode-failure rate stays high almost always has an
+//! encoding/ABI mismatch rather than a transient issue.
+
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use tracing::{info, warn};
+
+use super::cache::Venue;
+
+/// Decode attempts per evaluation window
+const DECODE_WINDOW: u64 = 50;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VenueDecodeCounts {
+    pub attempts: u64,
+    pub failures: u64,
+    window_attempts: u64,
+    window_failures: u64,
+    /// Failure rate exceeded the threshold in the last full window
+    pub unhealthy: bool,
+}
+
+impl VenueDecodeCounts {
+    pub fn failure_rate(&self) -> f64 {
+        if self.attempts == 0 {
+            0.0
+        } else {
+            self.failures as f64 / self.attempts as f64
+        }
+    }
+}
+
+pub struct DecodeStats {
+    venues: Mutex<HashMap<Venue, VenueDecodeCounts>>,
+    warn_rate: f64,
+}
+
+impl DecodeStats {
+    pub fn new(warn_rate: f64) -> Self {
+        Self {
+            venues: Mutex::new(HashMap::new()),
+            warn_rate,
+        }
+    }
+
+    /// Record one decode attempt for a venue
+    ///
+    /// Returns true when this attempt closes a window that flips the venue
+    /// from healthy to unhealthy, so the warning fires once per incident.
+    pub fn record(&self, venue: Venue, success: bool) -> bool {
+        let mut venues = self.venues.lock();
+        let counts = venues.entry(venue).or_default();
+
+        counts.attempts += 1;
+        counts.window_attempts += 1;
+        if !success {
+            counts.failures += 1;
+            counts.window_failures += 1;
+        }
+
+        if counts.window_attempts < DECODE_WINDOW {
+            return false;
+        }
+
+        let rate = counts.window_failures as f64 / counts.window_attempts as f64;
+        let was_unhealthy = counts.unhealthy;
+        counts.unhealthy = rate > self.warn_rate;
+        counts.window_attempts = 0;
+        counts.window_failures = 0;
+
+        if counts.unhealthy && !was_unhealthy {
+            warn!(
+                "⚠️ {:?} decode failures at {:.0}% of the last {} quotes - likely encoding/ABI mismatch",
+                venue,
+                rate * 100.0,
+                DECODE_WINDOW
+            );
+            return true;
+        }
+
+        if was_unhealthy && !counts.unhealthy {
+            info!("{:?} quote decoding recovered ({:.0}% failures)", venue, rate * 100.0);
+        }
+
+        false
+    }
+
+    pub fn snapshot(&self) -> Vec<(Venue, VenueDecodeCounts)> {
+        let mut snapshot: Vec<_> = self.venues.lock().iter().map(|(v, c)| (*v, *c)).collect();
+        snapshot.sort_by_key(|(v, _)| v.to_u8());
+        snapshot
+    }
+
+    pub fn log_summary(&self) {
+        for (venue, counts) in self.snapshot() {
+            info!(
+                "Decode {:?}: {} attempts, {} failures ({:.1}%){}",
+                venue,
+                counts.attempts,
+                counts.failures,
+                counts.failure_rate() * 100.0,
+                if counts.unhealthy { " ⚠️ UNHEALTHY" } else { "" }
+            );
+        }
+    }
+}
+
+impl Default for DecodeStats {
+    fn default() -> Self {
+        Self::new(0.5)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    impl DecodeStats {
+        fn get(&self, venue: Venue) -> VenueDecodeCounts {
+            self.venues.lock().get(&venue).copied().unwrap_or_default()
+        }
+    }
+
+    #[test]
+    fn test_failing_venue_trips_warning_other_stays_healthy() {
+        let stats = DecodeStats::new(0.5);
+        let mut tripped = 0;
+
+        for _ in 0..DECODE_WINDOW * 3 {
+            if stats.record(Venue::Balancer, false) {
+                tripped += 1;
+            }
+            assert!(!stats.record(Venue::UniswapV3, true));
+        }
+
+        // Warns once when the venue goes unhealthy, not every window
+        assert_eq!(tripped, 1);
+        assert!(stats.get(Venue::Balancer).unhealthy);
+        assert_eq!(stats.get(Venue::Balancer).failures, DECODE_WINDOW * 3);
+
+        let uni = stats.get(Venue::UniswapV3);
+        assert!(!uni.unhealthy);
+        assert_eq!(uni.failures, 0);
+        assert_eq!(uni.attempts, DECODE_WINDOW * 3);
+    }
+
+    #[test]
+    fn test_venue_recovers() {
+        let stats = DecodeStats::new(0.5);
+
+        for _ in 0..DECODE_WINDOW {
+            stats.record(Venue::Curve, false);
+        }
+        assert!(stats.get(Venue::Curve).unhealthy);
+
+        for _ in 0..DECODE_WINDOW {
+            stats.record(Venue::Curve, true);
+        }
+        assert!(!stats.get(Venue::Curve).unhealthy);
+    }
+}
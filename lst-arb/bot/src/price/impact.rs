@@ -0,0 +1,118 @@
+//! Negative Price Impact Check
+//!
+//! An AMM can't fill a larger trade at a better rate than its spot price,
+//! so a quote that beats the spot rate implied by the pool's own reserves is
+//! wrong: a decode bug, the wrong pool, or a manipulated read. Such quotes
+//! are flagged and, by default, dropped before they reach the detector.
+
+use ethers::types::U256;
+
+use super::cache::{Quote, Venue};
+use super::stableswap;
+
+/// Default slack for rounding in the reserve reads, in basis points
+pub const DEFAULT_IMPACT_TOLERANCE_BPS: u64 = 10;
+
+/// What to do with a quote showing negative price impact
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImpactCheck {
+    Off,
+    /// Log it but keep the quote
+    Warn,
+    /// Log it and discard the quote
+    Drop,
+}
+
+impl std::str::FromStr for ImpactCheck {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> eyre::Result<Self> {
+        match s {
+            "off" => Ok(Self::Off),
+            "warn" => Ok(Self::Warn),
+            "drop" => Ok(Self::Drop),
+            other => Err(eyre::eyre!("Unknown negative impact handling: {}", other)),
+        }
+    }
+}
+
+/// Output for `amount_in` at the pool's spot rate, before fees. Curve's
+/// marginal rate is taken from a swap of a thousandth of the probe size.
+fn spot_output(venue: Venue, reserves: (U256, U256), amount_in: U256, is_buy: bool, amp: Option<u64>) -> Option<U256> {
+    let (weth, lst) = reserves;
+    let (reserve_in, reserve_out) = if is_buy { (weth, lst) } else { (lst, weth) };
+
+    match venue {
+        Venue::UniswapV3 => amount_in.checked_mul(reserve_out)?.checked_div(reserve_in),
+        Venue::Curve => {
+            let dx = amount_in / 1000;
+            if dx.is_zero() {
+                return None;
+            }
+            let (i, j) = if is_buy { (0, 1) } else { (1, 0) };
+            let dy = stableswap::get_dy(i, j, dx, [weth, lst], amp?, 0)?;
+            dy.checked_mul(amount_in)?.checked_div(dx)
+        }
+        _ => None,
+    }
+}
+
+/// How far, in basis points, the better of the quote's two directions beats
+/// the spot rate for a trade of `amount`. None when the quote is at or below
+/// spot, or there are no reserves to compare against.
+pub fn negative_impact_bps(venue: Venue, quote: &Quote, amount: U256, amp: Option<u64>) -> Option<u64> {
+    let reserves = quote.reserves?;
+
+    [(quote.buy_amount, true), (quote.sell_amount, false)]
+        .into_iter()
+        .filter(|(quoted, _)| !quoted.is_zero())
+        .filter_map(|(quoted, is_buy)| {
+            let spot = spot_output(venue, reserves, amount, is_buy, amp)?;
+            if spot.is_zero() || quoted <= spot {
+                return None;
+            }
+            ((quoted - spot).checked_mul(U256::from(10_000u64))? / spot).try_into().ok()
+        })
+        .max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eth(value: u64) -> U256 {
+        U256::from(value) * U256::exp10(18)
+    }
+
+    #[test]
+    fn test_quote_better_than_spot_flagged() {
+        // 1000 WETH / 1100 LST: spot buys 1.1 LST per WETH
+        let reserves = Some((eth(1000), eth(1100)));
+        let honest = Quote {
+            buy_amount: U256::from(1_098_000_000_000_000_000u64),
+            sell_amount: U256::from(905_000_000_000_000_000u64),
+            reserves,
+            ..Default::default()
+        };
+        assert_eq!(negative_impact_bps(Venue::UniswapV3, &honest, eth(1), None), None);
+
+        // 1.2 LST per WETH at size is impossible
+        let bogus = Quote { buy_amount: U256::from(1_200_000_000_000_000_000u64), ..honest };
+        let impact = negative_impact_bps(Venue::UniswapV3, &bogus, eth(1), None).unwrap();
+        assert!((908..=910).contains(&impact), "impact {impact}");
+
+        // Without reserves there is nothing to compare against
+        assert_eq!(negative_impact_bps(Venue::UniswapV3, &Quote { reserves: None, ..bogus }, eth(1), None), None);
+    }
+
+    #[test]
+    fn test_curve_spot_from_balances() {
+        let reserves = (eth(1000), eth(1000));
+        let (buy, sell) = stableswap::quotes_from_balances(reserves, eth(1), 100, 4).unwrap();
+        let quote = Quote { buy_amount: buy, sell_amount: sell, reserves: Some(reserves), ..Default::default() };
+        assert_eq!(negative_impact_bps(Venue::Curve, &quote, eth(1), Some(100)), None);
+
+        let inflated = Quote { buy_amount: eth(1) * 101 / 100, ..quote };
+        assert!(negative_impact_bps(Venue::Curve, &inflated, eth(1), Some(100)).unwrap() >= 90);
+    }
+}
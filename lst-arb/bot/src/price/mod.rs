@@ -1,5 +1,10 @@
 pub mod cache;
+pub mod call_health;
+pub mod decode_stats;
+pub mod impact;
 pub mod multicall;
+pub mod reserves;
+pub mod stableswap;
 pub mod venues;
 
 pub use cache::*;
@@ -1,10 +1,17 @@
 use ethers::prelude::*;
-use ethers::types::{Bytes, Address, U256};
+use ethers::types::{Bytes, Address, H256, I256, U256};
+use std::collections::HashMap;
+use std::future::Future;
 use std::sync::Arc;
-use tracing::debug;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
 
-use super::cache::{Quote, Venue};
-use crate::rpc::WsClient;
+use super::cache::{EnabledVenues, Quote, Venue};
+use super::call_health::CallHealth;
+use super::decode_stats::DecodeStats;
+use super::impact::{negative_impact_bps, ImpactCheck, DEFAULT_IMPACT_TOLERANCE_BPS};
+use super::reserves::uniswap_v3_pool_address;
+use crate::rpc::{with_backoff, TransportError, WsClient};
 
 // Multicall3 ABI - only contract we actually call via abigen
 abigen!(
@@ -22,15 +29,106 @@ abigen!(
 #[derive(Debug, Clone)]
 pub struct VenueAddresses {
     pub multicall3: Address,
-    pub curve_steth: Address,
-    pub curve_reth: Address,
+    /// LST -> Curve ETH/LST pool (coin 0 ETH, coin 1 the LST)
+    pub curve_pools: HashMap<Address, Address>,
     pub balancer_vault: Address,
+    /// LST -> Balancer WETH/LST pool ID
+    pub balancer_pools: HashMap<Address, H256>,
     pub uniswap_quoter: Address,
+    /// Maverick V2 quoter (zero disables Maverick quotes)
+    pub maverick_quoter: Address,
+    /// LST -> Maverick WETH/LST pool
+    pub maverick_pools: std::collections::HashMap<Address, Address>,
+    /// LST -> Solidly WETH/LST pools (stable and/or volatile)
+    pub solidly_pools: HashMap<Address, Vec<Address>>,
     pub weth: Address,
 }
 
+/// ArbSys precompile. Multicall3's `getBlockNumber()` returns `block.number`,
+/// which on Arbitrum is an estimate of the L1 block; `arbBlockNumber()` is
+/// the L2 block that eth_blockNumber reports.
+pub const ARB_SYS: Address = H160([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x64]);
+
+/// Uniswap V3 fee tiers quoted by default (0.01%, 0.05%, 0.3%)
+pub const DEFAULT_UNISWAP_FEE_TIERS: [u32; 3] = [100, 500, 3000];
+
+/// Curve ramps A over days, so a fetched value is reused for this long
+const CURVE_AMP_TTL: Duration = Duration::from_secs(300);
+
+/// An LST quoted directly against another LST (e.g. rETH/wstETH) rather
+/// than through WETH, on the Uniswap V3 fee tiers and optionally a Balancer pool
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LstPair {
+    /// What the trade starts and ends in (the flash-loaned asset)
+    pub base: Address,
+    pub token: Address,
+    /// e.g. "reth/wsteth"
+    pub name: String,
+    pub balancer_pool: Option<H256>,
+}
+
+/// A Uniswap V3 multi-hop path: each hop is (token, fee to the next token).
+/// The last token's fee is ignored.
+pub type UniswapPath = Vec<(Address, u32)>;
+
 pub struct MulticallQuoter {
     addresses: VenueAddresses,
+    decode_stats: DecodeStats,
+    uniswap_fee_tiers: Vec<u32>,
+    /// (LST, WETH -> LST path) routes quoted with quoteExactInput
+    uniswap_routes: Vec<(Address, UniswapPath)>,
+    /// (amp, fee_bps) to derive Curve quotes from pool balances instead of get_dy
+    curve_reserve_quotes: Option<(u64, u64)>,
+    /// Curve pool -> (A, when fetched)
+    curve_amps: parking_lot::Mutex<HashMap<Address, (u64, Instant)>>,
+    /// Handling of quotes better than spot, and the tolerance in bps
+    impact_check: (ImpactCheck, u64),
+    /// Venues to build quote calls for
+    enabled_venues: EnabledVenues,
+    /// Retry Balancer quotes that fail inside the multicall as standalone eth_calls
+    balancer_fallback: bool,
+    /// Drops (token, venue) quote calls that keep failing
+    call_health: Option<CallHealth>,
+    /// Extra WETH amounts to quote each token at, for its pools' price impact
+    impact_quote_amounts: Vec<U256>,
+    /// Per-token overrides of `impact_quote_amounts`
+    token_impact_quote_amounts: HashMap<Address, Vec<U256>>,
+    /// LST/LST pairs quoted directly
+    lst_pairs: Vec<LstPair>,
+}
+
+/// Identifies the pool a multicall quote came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct PoolKey {
+    /// What `token` is quoted against: WETH, or the other LST of a direct pair
+    base: Address,
+    token: Address,
+    venue: Venue,
+    fee_tier: Option<u32>,
+    pool: Address,
+}
+
+/// Reserve reads batched alongside the quotes
+#[derive(Debug, Clone, Copy)]
+enum ReserveCall {
+    /// Curve `balances(i)`: coin 0 is ETH, coin 1 the LST
+    CurveBalance(usize),
+    /// Curve `A()`, only while the cached value is stale
+    CurveAmp,
+    UniswapSlot0,
+    UniswapLiquidity,
+    /// Solidly `getReserves()`: (reserve0, reserve1, blockTimestampLast)
+    SolidlyReserves,
+}
+
+/// Raw reserve data collected for one pool
+#[derive(Debug, Default)]
+struct ReserveParts {
+    curve_balances: [Option<U256>; 2],
+    sqrt_price_x96: Option<U256>,
+    liquidity: Option<U256>,
+    /// Solidly (reserve0, reserve1)
+    solidly_reserves: Option<(U256, U256)>,
 }
 
 #[derive(Debug, Clone)]
@@ -38,13 +136,274 @@ pub struct TokenQuotes {
     pub token: Address,
     pub token_name: String,
     pub quotes: Vec<(Venue, Quote)>,
+    /// On-chain amplification of the token's Curve pool, if fetched
+    pub curve_amp: Option<u64>,
+    /// Buy quotes at several sizes for pools whose reserves weren't read
+    pub sized_quotes: Vec<SizedQuotes>,
+    /// When the multicall was sent (unix ms); every quote is at least this old
+    pub fetched_at_ms: u64,
+    /// L2 block the multicall executed against, read from ArbSys in the same
+    /// batch (None if that read failed)
+    pub block_number: Option<u64>,
+    /// The LST quoted against for a direct LST/LST pair; None = WETH.
+    /// Amounts are then in that LST rather than ETH.
+    pub base_token: Option<Address>,
+}
+
+/// WETH -> LST quotes of one pool at several sizes, tracing its price impact
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SizedQuotes {
+    pub venue: Venue,
+    pub fee_tier: Option<u32>,
+    pub pool: Address,
+    /// (WETH in, LST out), smallest first; includes the main quote amount
+    pub buy: Vec<(U256, U256)>,
+}
+
+/// One swap to quote at an exact size: WETH (or a pair's base LST) into
+/// `token` when `is_buy`, `token` back out otherwise
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuoteLeg {
+    pub base: Address,
+    pub token: Address,
+    pub venue: Venue,
+    pub fee_tier: Option<u32>,
+    pub pool: Address,
+    pub is_buy: bool,
+    pub amount: U256,
+}
+
+impl QuoteLeg {
+    fn pool_key(&self) -> PoolKey {
+        PoolKey { base: self.base, token: self.token, venue: self.venue, fee_tier: self.fee_tier, pool: self.pool }
+    }
+}
+
+impl TokenQuotes {
+    /// Sized quotes for the pool a quote came from
+    pub fn sized_quotes_for(&self, venue: Venue, quote: &Quote) -> Option<&SizedQuotes> {
+        self.sized_quotes.iter()
+            .find(|sized| sized.venue == venue && sized.fee_tier == quote.fee_tier && sized.pool == quote.pool)
+    }
+}
+
+impl TokenQuotes {
+    /// Whether any venue returned a usable quote
+    pub fn is_quoted(&self) -> bool {
+        self.quotes.iter().any(|(_, q)| q.buy_amount > U256::zero() || q.sell_amount > U256::zero())
+    }
+}
+
+/// Tokens that no venue returned a usable quote for
+pub fn unreachable_tokens(tokens: &[(Address, String)], quotes: &[TokenQuotes]) -> Vec<(Address, String)> {
+    tokens.iter()
+        .filter(|(token, _)| !quotes.iter().any(|tq| tq.token == *token && tq.is_quoted()))
+        .cloned()
+        .collect()
 }
 
 impl MulticallQuoter {
     pub fn new(addresses: VenueAddresses) -> Self {
-        Self { addresses }
+        Self {
+            addresses,
+            decode_stats: DecodeStats::default(),
+            uniswap_fee_tiers: DEFAULT_UNISWAP_FEE_TIERS.to_vec(),
+            uniswap_routes: Vec::new(),
+            curve_reserve_quotes: None,
+            curve_amps: parking_lot::Mutex::new(HashMap::new()),
+            impact_check: (ImpactCheck::Drop, DEFAULT_IMPACT_TOLERANCE_BPS),
+            enabled_venues: EnabledVenues::default(),
+            balancer_fallback: true,
+            call_health: None,
+            impact_quote_amounts: Vec::new(),
+            token_impact_quote_amounts: HashMap::new(),
+            lst_pairs: Vec::new(),
+        }
+    }
+
+    /// Also quote these LST/LST pairs directly, each as its own `TokenQuotes`
+    /// with `base_token` set. `amount` is quoted in the base LST.
+    pub fn with_lst_pairs(mut self, pairs: Vec<LstPair>) -> Self {
+        self.lst_pairs = pairs;
+        self
+    }
+
+    /// Whether Balancer quotes that fail in the batch are retried on their own
+    pub fn with_balancer_fallback(mut self, enabled: bool) -> Self {
+        self.balancer_fallback = enabled;
+        self
+    }
+
+    /// Leave a (token, venue)'s quote calls out of the multicall after
+    /// `drop_after` failed scans in a row, re-probing every `reprobe_after`
+    pub fn with_adaptive_calls(mut self, drop_after: u32, reprobe_after: Duration) -> Self {
+        self.call_health = Some(CallHealth::new(drop_after, reprobe_after));
+        self
+    }
+
+    /// Also quote each pool's buy side at these WETH amounts (per-token
+    /// overrides in `per_token`), so the detector can fit its price impact
+    /// when the pool's reserves aren't read. Empty = only the main amount.
+    pub fn with_impact_quote_amounts(mut self, amounts: Vec<U256>, per_token: HashMap<Address, Vec<U256>>) -> Self {
+        self.impact_quote_amounts = amounts;
+        self.token_impact_quote_amounts = per_token;
+        self
+    }
+
+    /// Extra amounts `token` is quoted at
+    fn impact_quote_amounts_for(&self, token: Address) -> &[U256] {
+        self.token_impact_quote_amounts.get(&token).unwrap_or(&self.impact_quote_amounts)
+    }
+
+    /// Only quote these venues; the set can be changed while running
+    pub fn with_enabled_venues(mut self, venues: EnabledVenues) -> Self {
+        self.enabled_venues = venues;
+        self
+    }
+
+    /// A for a Curve pool if fetched within the last CURVE_AMP_TTL
+    fn cached_curve_amp(&self, pool: Address) -> Option<u64> {
+        self.curve_amps.lock()
+            .get(&pool)
+            .filter(|(_, fetched)| fetched.elapsed() < CURVE_AMP_TTL)
+            .map(|(amp, _)| *amp)
+    }
+
+    /// Derive both Curve quote directions from the pool balances already
+    /// read for sizing, instead of two get_dy calls per pool
+    pub fn with_curve_reserve_quotes(mut self, amp: u64, fee_bps: u64) -> Self {
+        self.curve_reserve_quotes = Some((amp, fee_bps));
+        self
+    }
+
+    /// Multi-hop Uniswap V3 routes (e.g. WETH -> USDC -> LST) to quote per token
+    ///
+    /// Each path runs from WETH to the LST; the sell side quotes it reversed.
+    pub fn with_uniswap_routes(mut self, routes: Vec<(Address, UniswapPath)>) -> Self {
+        self.uniswap_routes = routes;
+        self
+    }
+
+    /// Uniswap V3 fee tiers to quote - each tier is tracked as its own quote
+    pub fn with_uniswap_fee_tiers(mut self, fee_tiers: Vec<u32>) -> Self {
+        self.uniswap_fee_tiers = fee_tiers;
+        self
+    }
+
+    /// Warn when a venue's decode-failure rate exceeds this fraction
+    pub fn with_decode_failure_threshold(mut self, warn_rate: f64) -> Self {
+        self.decode_stats = DecodeStats::new(warn_rate);
+        self
+    }
+
+    /// How to handle quotes that beat their pool's spot rate by more than
+    /// `tolerance_bps`
+    pub fn with_negative_impact_check(mut self, check: ImpactCheck, tolerance_bps: u64) -> Self {
+        self.impact_check = (check, tolerance_bps);
+        self
+    }
+
+    /// Whether a quote passes the negative price impact check, warning
+    /// about any that fail
+    fn check_impact(&self, token_name: &str, venue: Venue, quote: &Quote, amount: U256) -> bool {
+        let (check, tolerance_bps) = self.impact_check;
+        if check == ImpactCheck::Off {
+            return true;
+        }
+
+        let amp = self.cached_curve_amp(quote.pool).or(self.curve_reserve_quotes.map(|(amp, _)| amp));
+        let Some(impact) = negative_impact_bps(venue, quote, amount, amp) else {
+            return true;
+        };
+        if impact <= tolerance_bps {
+            return true;
+        }
+
+        warn!(
+            "⚠️ {} {} quote beats spot by {} bps (negative price impact) from pool {:?}{}",
+            token_name,
+            venue.label(quote.fee_tier),
+            impact,
+            quote.pool,
+            if check == ImpactCheck::Drop { ", dropping" } else { "" }
+        );
+        check == ImpactCheck::Warn
+    }
+
+    /// Per-venue decode success/failure counters
+    pub fn decode_stats(&self) -> &DecodeStats {
+        &self.decode_stats
     }
     
+    /// `fetch_all_quotes`, retrying transport failures up to `max_retries`
+    /// times with capped backoff, rotating to the client `alternate` picks
+    /// between attempts. Individual venue calls are `allowFailure`, so a
+    /// multicall that comes back at all returns whatever venues succeeded.
+    pub async fn fetch_all_quotes_retry(
+        &self,
+        client: Arc<WsClient>,
+        alternate: impl FnMut(&Arc<WsClient>) -> Option<Arc<WsClient>>,
+        tokens: &[(Address, String)],
+        amount: U256,
+        max_retries: u32,
+    ) -> eyre::Result<Vec<TokenQuotes>> {
+        with_backoff(client, alternate, max_retries, |c| {
+            self.fetch_all_quotes(c, tokens, amount)
+        }).await
+    }
+
+    /// Quote every token once and return those no venue can quote: no
+    /// configured pool, no liquidity, or every venue call failing
+    pub async fn find_unreachable_tokens(
+        &self,
+        client: Arc<WsClient>,
+        tokens: &[(Address, String)],
+        amount: U256,
+    ) -> eyre::Result<Vec<(Address, String)>> {
+        let quotes = self.fetch_all_quotes(client, tokens, amount).await?;
+        Ok(unreachable_tokens(tokens, &quotes))
+    }
+
+    /// Whether `leg`'s venue and pool can be quoted at an exact size at all
+    pub fn can_quote(&self, leg: &QuoteLeg) -> bool {
+        self.quote_call(&leg.pool_key(), leg.amount, leg.is_buy).is_some()
+    }
+
+    /// Quote each leg at its own amount in one multicall. A leg that can't
+    /// be encoded, fails or decodes to nothing comes back as None.
+    pub async fn quote_legs<M: Middleware + 'static>(
+        &self,
+        client: Arc<M>,
+        legs: &[QuoteLeg],
+    ) -> eyre::Result<Vec<Option<U256>>> {
+        let mut calls = Vec::new();
+        let call_indices: Vec<Option<usize>> = legs.iter()
+            .map(|leg| {
+                let call = self.quote_call(&leg.pool_key(), leg.amount, leg.is_buy)?;
+                calls.push(call);
+                Some(calls.len() - 1)
+            })
+            .collect();
+        if calls.is_empty() {
+            return Ok(vec![None; legs.len()]);
+        }
+
+        let multicall = Multicall3::new(self.addresses.multicall3, client);
+        let results = multicall.aggregate_3(calls).call().await?;
+        Ok(call_indices.into_iter()
+            .zip(legs)
+            .map(|(idx, leg)| {
+                let (success, data) = results.get(idx?)?;
+                if !success || data.is_empty() {
+                    return None;
+                }
+                let decoded = self.decode_quote_result(data, leg.venue);
+                self.decode_stats.record(leg.venue, decoded.is_ok());
+                decoded.ok().filter(|amount_out| !amount_out.is_zero())
+            })
+            .collect())
+    }
+
     /// Fetch all quotes for multiple tokens in a SINGLE RPC call
     /// This is the key to speed - one call gets everything
     pub async fn fetch_all_quotes(
@@ -56,106 +415,363 @@ impl MulticallQuoter {
         let multicall = Multicall3::new(self.addresses.multicall3, client.clone());
         
         let mut calls: Vec<Call3> = Vec::new();
-        let mut call_mapping: Vec<(usize, Address, Venue, bool)> = Vec::new(); // (call_idx, token, venue, is_buy)
+        let mut call_mapping: Vec<(usize, PoolKey, bool)> = Vec::new(); // (call_idx, pool, is_buy)
+        let mut reserve_mapping: Vec<(usize, PoolKey, ReserveCall)> = Vec::new();
+        // Balancer calls kept for the standalone fallback: (call_idx, pool, is_buy, calldata)
+        let mut balancer_calls: Vec<(usize, PoolKey, bool, Bytes)> = Vec::new();
         
         let timestamp_ms = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)?
             .as_millis() as u64;
+
+        // First call: the block every quote in the batch reflects
+        calls.push(block_number_call());
+
+        // Disabled venues get no calls at all
+        let enabled = |venue| self.enabled_venues.contains(venue);
+        // Nor do quotes that keep failing, between re-probes
+        let now = Instant::now();
+        let healthy = |token: Address, venue| {
+            self.call_health.as_ref().is_none_or(|health| health.should_call(token, venue, now))
+        };
+        let (curve_enabled, maverick_enabled) = (enabled(Venue::Curve), enabled(Venue::Maverick));
+        let solidly_enabled = enabled(Venue::Solidly);
+        let balancer_enabled = enabled(Venue::Balancer) && !self.addresses.balancer_vault.is_zero();
+        let (uniswap_fee_tiers, uniswap_routes) = if enabled(Venue::UniswapV3) {
+            (&self.uniswap_fee_tiers[..], &self.uniswap_routes[..])
+        } else {
+            (&[][..], &[][..])
+        };
         
         for (token, name) in tokens {
             // ===== CURVE QUOTES =====
-            // Only for supported tokens (stETH, rETH)
-            if let Some(curve_pool) = self.get_curve_pool(*token) {
-                // Buy LST (ETH -> LST): get_dy(0, 1, amount)
-                let buy_data = self.encode_curve_get_dy(0, 1, amount);
+            // Only for tokens with a configured pool
+            let curve_pool = self.get_curve_pool(*token).filter(|_| curve_enabled);
+            if let Some(curve_pool) = curve_pool {
+                let key = PoolKey { base: self.addresses.weth, token: *token, venue: Venue::Curve, fee_tier: None, pool: curve_pool };
+
+                // With reserve quotes, both directions come from the balances below
+                if self.curve_reserve_quotes.is_none() && healthy(*token, Venue::Curve) {
+                    // Buy LST (ETH -> LST): get_dy(0, 1, amount)
+                    let buy_data = self.encode_curve_get_dy(0, 1, amount);
+                    calls.push(Call3 {
+                        target: curve_pool,
+                        allow_failure: true,
+                        call_data: buy_data,
+                    });
+                    call_mapping.push((calls.len() - 1, key, true));
+
+                    // Sell LST (LST -> ETH): get_dy(1, 0, amount)
+                    let sell_data = self.encode_curve_get_dy(1, 0, amount);
+                    calls.push(Call3 {
+                        target: curve_pool,
+                        allow_failure: true,
+                        call_data: sell_data,
+                    });
+                    call_mapping.push((calls.len() - 1, key, false));
+                }
+
+                // Pool balances for solver sizing
+                for i in 0..2 {
+                    calls.push(Call3 {
+                        target: curve_pool,
+                        allow_failure: true,
+                        call_data: self.encode_curve_balances(i),
+                    });
+                    reserve_mapping.push((calls.len() - 1, key, ReserveCall::CurveBalance(i)));
+                }
+
+                if self.cached_curve_amp(curve_pool).is_none() {
+                    calls.push(Call3 {
+                        target: curve_pool,
+                        allow_failure: true,
+                        call_data: Bytes::from(ethers::utils::id("A()").to_vec()),
+                    });
+                    reserve_mapping.push((calls.len() - 1, key, ReserveCall::CurveAmp));
+                }
+            }
+            
+            // ===== BALANCER QUOTES =====
+            let balancer_pool = self.addresses.balancer_pools.get(token)
+                .filter(|_| balancer_enabled && healthy(*token, Venue::Balancer));
+            if let Some(&pool_id) = balancer_pool {
+                // A pool ID starts with the pool's address
+                let pool = Address::from_slice(&pool_id[..20]);
+                let key = PoolKey { base: self.addresses.weth, token: *token, venue: Venue::Balancer, fee_tier: None, pool };
+
+                for (is_buy, asset_in, asset_out) in [
+                    (true, self.addresses.weth, *token),
+                    (false, *token, self.addresses.weth),
+                ] {
+                    let call_data = self.encode_balancer_query(pool_id, asset_in, asset_out, amount);
+                    calls.push(Call3 {
+                        target: self.addresses.balancer_vault,
+                        allow_failure: true,
+                        call_data: call_data.clone(),
+                    });
+                    call_mapping.push((calls.len() - 1, key, is_buy));
+                    balancer_calls.push((calls.len() - 1, key, is_buy, call_data));
+                }
+            }
+
+            // ===== UNISWAP V3 QUOTES =====
+            // Each fee tier is a separate pool, so quote and track them
+            // separately to expose cross-tier arbs
+            let uniswap_healthy = healthy(*token, Venue::UniswapV3);
+            let (uniswap_fee_tiers, uniswap_routes) = if uniswap_healthy {
+                (uniswap_fee_tiers, uniswap_routes)
+            } else {
+                (&[][..], &[][..])
+            };
+            for &fee in uniswap_fee_tiers {
+                let key = PoolKey {
+                    base: self.addresses.weth,
+                    token: *token,
+                    venue: Venue::UniswapV3,
+                    fee_tier: Some(fee),
+                    pool: uniswap_v3_pool_address(self.addresses.weth, *token, fee),
+                };
+
+                // Buy LST (WETH -> LST)
+                let uni_buy_data = self.encode_uniswap_quote(
+                    self.addresses.weth,
+                    *token,
+                    amount,
+                    fee,
+                );
                 calls.push(Call3 {
-                    target: curve_pool,
+                    target: self.addresses.uniswap_quoter,
                     allow_failure: true,
-                    call_data: buy_data,
+                    call_data: uni_buy_data,
                 });
-                call_mapping.push((calls.len() - 1, *token, Venue::Curve, true));
-                
-                // Sell LST (LST -> ETH): get_dy(1, 0, amount)
-                let sell_data = self.encode_curve_get_dy(1, 0, amount);
+                call_mapping.push((calls.len() - 1, key, true));
+
+                // Sell LST (LST -> WETH)
+                let uni_sell_data = self.encode_uniswap_quote(
+                    *token,
+                    self.addresses.weth,
+                    amount,
+                    fee,
+                );
                 calls.push(Call3 {
-                    target: curve_pool,
+                    target: self.addresses.uniswap_quoter,
                     allow_failure: true,
-                    call_data: sell_data,
+                    call_data: uni_sell_data,
                 });
-                call_mapping.push((calls.len() - 1, *token, Venue::Curve, false));
+                call_mapping.push((calls.len() - 1, key, false));
+
+                // Price and in-range liquidity give the pool's virtual reserves
+                for (selector, call) in [
+                    ("slot0()", ReserveCall::UniswapSlot0),
+                    ("liquidity()", ReserveCall::UniswapLiquidity),
+                ] {
+                    calls.push(Call3 {
+                        target: key.pool,
+                        allow_failure: true,
+                        call_data: Bytes::from(ethers::utils::id(selector).to_vec()),
+                    });
+                    reserve_mapping.push((calls.len() - 1, key, call));
+                }
+            }
+
+            // ===== UNISWAP V3 MULTI-HOP QUOTES =====
+            // Each route is tracked as its own pool, identified by its path
+            for (_, path) in uniswap_routes.iter().filter(|(lst, _)| lst == token) {
+                let key = PoolKey {
+                    base: self.addresses.weth,
+                    token: *token,
+                    venue: Venue::UniswapV3,
+                    fee_tier: None,
+                    pool: route_id(path),
+                };
+
+                calls.push(Call3 {
+                    target: self.addresses.uniswap_quoter,
+                    allow_failure: true,
+                    call_data: self.encode_uniswap_path_quote(path.clone(), amount),
+                });
+                call_mapping.push((calls.len() - 1, key, true));
+
+                calls.push(Call3 {
+                    target: self.addresses.uniswap_quoter,
+                    allow_failure: true,
+                    call_data: self.encode_uniswap_path_quote(reverse_path(path), amount),
+                });
+                call_mapping.push((calls.len() - 1, key, false));
+            }
+
+            // ===== MAVERICK QUOTES =====
+            let maverick_pool = self.addresses.maverick_pools.get(token)
+                .filter(|_| maverick_enabled && healthy(*token, Venue::Maverick));
+            if let Some(&pool) = maverick_pool {
+                if !self.addresses.maverick_quoter.is_zero() {
+                    let key = PoolKey { base: self.addresses.weth, token: *token, venue: Venue::Maverick, fee_tier: None, pool };
+
+                    // Maverick pools sort their tokens, so tokenA is the lower address
+                    let weth_is_a = self.addresses.weth < *token;
+                    for (is_buy, token_a_in) in [(true, weth_is_a), (false, !weth_is_a)] {
+                        calls.push(Call3 {
+                            target: self.addresses.maverick_quoter,
+                            allow_failure: true,
+                            call_data: self.encode_maverick_quote(pool, amount, token_a_in),
+                        });
+                        call_mapping.push((calls.len() - 1, key, is_buy));
+                    }
+                }
+            }
+
+            // ===== SOLIDLY QUOTES =====
+            // Stable and volatile pools alike quote through the pool's own getAmountOut
+            let solidly_pools = self.addresses.solidly_pools.get(token)
+                .filter(|_| solidly_enabled && healthy(*token, Venue::Solidly));
+            for &pool in solidly_pools.into_iter().flatten() {
+                let key = PoolKey { base: self.addresses.weth, token: *token, venue: Venue::Solidly, fee_tier: None, pool };
+
+                for (is_buy, token_in) in [(true, self.addresses.weth), (false, *token)] {
+                    calls.push(Call3 {
+                        target: pool,
+                        allow_failure: true,
+                        call_data: self.encode_solidly_quote(amount, token_in),
+                    });
+                    call_mapping.push((calls.len() - 1, key, is_buy));
+                }
+
+                calls.push(Call3 {
+                    target: pool,
+                    allow_failure: true,
+                    call_data: Bytes::from(ethers::utils::id("getReserves()").to_vec()),
+                });
+                reserve_mapping.push((calls.len() - 1, key, ReserveCall::SolidlyReserves));
+            }
+        }
+
+        // ===== LST/LST PAIR QUOTES =====
+        // Buy is base -> token, sell token -> base, as WETH is for the rest
+        for pair in &self.lst_pairs {
+            for &fee in uniswap_fee_tiers {
+                let key = PoolKey {
+                    base: pair.base,
+                    token: pair.token,
+                    venue: Venue::UniswapV3,
+                    fee_tier: Some(fee),
+                    pool: uniswap_v3_pool_address(pair.base, pair.token, fee),
+                };
+                for (is_buy, token_in, token_out) in [(true, pair.base, pair.token), (false, pair.token, pair.base)] {
+                    calls.push(Call3 {
+                        target: self.addresses.uniswap_quoter,
+                        allow_failure: true,
+                        call_data: self.encode_uniswap_quote(token_in, token_out, amount, fee),
+                    });
+                    call_mapping.push((calls.len() - 1, key, is_buy));
+                }
+                for (selector, call) in [
+                    ("slot0()", ReserveCall::UniswapSlot0),
+                    ("liquidity()", ReserveCall::UniswapLiquidity),
+                ] {
+                    calls.push(Call3 {
+                        target: key.pool,
+                        allow_failure: true,
+                        call_data: Bytes::from(ethers::utils::id(selector).to_vec()),
+                    });
+                    reserve_mapping.push((calls.len() - 1, key, call));
+                }
+            }
+
+            if let Some(pool_id) = pair.balancer_pool.filter(|_| balancer_enabled) {
+                let key = PoolKey {
+                    base: pair.base,
+                    token: pair.token,
+                    venue: Venue::Balancer,
+                    fee_tier: None,
+                    pool: Address::from_slice(&pool_id[..20]),
+                };
+                for (is_buy, asset_in, asset_out) in [(true, pair.base, pair.token), (false, pair.token, pair.base)] {
+                    let call_data = self.encode_balancer_query(pool_id, asset_in, asset_out, amount);
+                    calls.push(Call3 {
+                        target: self.addresses.balancer_vault,
+                        allow_failure: true,
+                        call_data: call_data.clone(),
+                    });
+                    call_mapping.push((calls.len() - 1, key, is_buy));
+                    balancer_calls.push((calls.len() - 1, key, is_buy, call_data));
+                }
             }
-            
-            // ===== UNISWAP V3 QUOTES =====
-            // Buy LST (WETH -> LST)
-            let uni_buy_data = self.encode_uniswap_quote(
-                self.addresses.weth,
-                *token,
-                amount,
-                500, // 0.05% fee tier (common for LSTs)
-            );
-            calls.push(Call3 {
-                target: self.addresses.uniswap_quoter,
-                allow_failure: true,
-                call_data: uni_buy_data,
-            });
-            call_mapping.push((calls.len() - 1, *token, Venue::UniswapV3, true));
-            
-            // Sell LST (LST -> WETH)
-            let uni_sell_data = self.encode_uniswap_quote(
-                *token,
-                self.addresses.weth,
-                amount,
-                500,
-            );
-            calls.push(Call3 {
-                target: self.addresses.uniswap_quoter,
-                allow_failure: true,
-                call_data: uni_sell_data,
-            });
-            call_mapping.push((calls.len() - 1, *token, Venue::UniswapV3, false));
-            
-            // Also try 0.3% fee tier
-            let uni_buy_data_30 = self.encode_uniswap_quote(
-                self.addresses.weth,
-                *token,
-                amount,
-                3000,
-            );
-            calls.push(Call3 {
-                target: self.addresses.uniswap_quoter,
-                allow_failure: true,
-                call_data: uni_buy_data_30,
-            });
-            call_mapping.push((calls.len() - 1, *token, Venue::UniswapV3, true));
         }
         
+        // ===== SIZED QUOTES =====
+        // Pools without a reserve read are also quoted at the extra sizes
+        let with_reserves: std::collections::HashSet<PoolKey> =
+            reserve_mapping.iter().map(|(_, key, _)| *key).collect();
+        let mut sized_mapping: Vec<(usize, PoolKey, U256)> = Vec::new();
+        for (_, key, is_buy) in &call_mapping {
+            if !*is_buy || with_reserves.contains(key) || key.base != self.addresses.weth {
+                continue;
+            }
+            for &sized_amount in self.impact_quote_amounts_for(key.token) {
+                if let Some(call) = self.quote_call(key, sized_amount, true) {
+                    calls.push(call);
+                    sized_mapping.push((calls.len() - 1, *key, sized_amount));
+                }
+            }
+        }
+
         // Execute single multicall
         debug!("Executing multicall with {} calls", calls.len());
-        let results = multicall.aggregate_3(calls).call().await?;
+        let results = multicall.aggregate_3(calls).call().await.map_err(|e| match e {
+            // The request never got a usable response; another endpoint may do better
+            ContractError::MiddlewareError { .. } | ContractError::ProviderError { .. } => {
+                eyre::Report::new(TransportError(e.to_string()))
+            }
+            other => other.into(),
+        })?;
         
-        // Parse results
-        let mut token_quotes: std::collections::HashMap<Address, TokenQuotes> = 
+        let block_number = results.first().and_then(decode_block_number);
+
+        // Parse results, per (base, token)
+        let weth = self.addresses.weth;
+        let mut token_quotes: std::collections::HashMap<(Address, Address), TokenQuotes> = 
             std::collections::HashMap::new();
         
-        for (token, name) in tokens {
-            token_quotes.insert(*token, TokenQuotes {
-                token: *token,
+        let pairs = self.lst_pairs.iter().map(|pair| (pair.base, pair.token, &pair.name));
+        for (base, token, name) in tokens.iter().map(|(token, name)| (weth, *token, name)).chain(pairs) {
+            token_quotes.insert((base, token), TokenQuotes {
+                token,
                 token_name: name.clone(),
                 quotes: Vec::new(),
+                curve_amp: None,
+                sized_quotes: Vec::new(),
+                fetched_at_ms: timestamp_ms,
+                block_number,
+                base_token: (base != weth).then_some(base),
             });
         }
         
-        // Aggregate quotes by venue (take best quote per venue)
-        let mut venue_quotes: std::collections::HashMap<(Address, Venue), (U256, U256)> = 
+        // Aggregate quotes by venue and fee tier (take best quote per pool)
+        let mut venue_quotes: std::collections::HashMap<PoolKey, (U256, U256)> = 
             std::collections::HashMap::new();
         
-        for (idx, token, venue, is_buy) in &call_mapping {
+        // Whether any quote call per (token, venue) came back usable
+        let mut call_outcomes: HashMap<(Address, Venue), bool> = HashMap::new();
+
+        for (idx, key, is_buy) in &call_mapping {
+            // Pair calls aren't subject to the adaptive drop
+            let mut pair_outcome = false;
+            let outcome = if key.base == weth {
+                call_outcomes.entry((key.token, key.venue)).or_insert(false)
+            } else {
+                &mut pair_outcome
+            };
             if let Some(result) = results.get(*idx) {
                 // result is a tuple (success: bool, returnData: Bytes)
                 if result.0 && !result.1.is_empty() {
-                    if let Ok(amount_out) = self.decode_quote_result(&result.1, *venue) {
-                        let key = (*token, *venue);
-                        let entry = venue_quotes.entry(key).or_insert((U256::zero(), U256::zero()));
+                    // Decode failures are skipped so other venues still quote,
+                    // but counted so a systematic mismatch gets noticed
+                    let decoded = self.decode_quote_result(&result.1, key.venue);
+                    self.decode_stats.record(key.venue, decoded.is_ok());
+                    *outcome |= decoded.is_ok();
+
+                    if let Ok(amount_out) = decoded {
+                        let entry = venue_quotes.entry(*key).or_insert((U256::zero(), U256::zero()));
                         
                         if *is_buy {
                             // Take best (highest) buy amount
@@ -173,33 +789,151 @@ impl MulticallQuoter {
             }
         }
         
+        // ===== BALANCER FALLBACK =====
+        // queryBatchSwap isn't `view`, and some RPCs revert it inside
+        // aggregate3's static context while serving it fine on its own
+        if self.balancer_fallback {
+            let failed: Vec<_> = balancer_calls.into_iter()
+                .filter(|(idx, ..)| {
+                    !results.get(*idx).is_some_and(|result| result.0 && decode_balancer_query(&result.1).is_ok())
+                })
+                .map(|(_, key, is_buy, call_data)| (key, is_buy, call_data))
+                .collect();
+
+            if !failed.is_empty() {
+                debug!("Retrying {} Balancer quotes outside the multicall", failed.len());
+                let vault = self.addresses.balancer_vault;
+                let recovered = recover_balancer_quotes(failed, |call_data| {
+                    let client = client.clone();
+                    async move {
+                        let tx = TransactionRequest::new().to(vault).data(call_data);
+                        Ok(client.call(&tx.into(), None).await?)
+                    }
+                }).await;
+
+                for (key, is_buy, amount_out) in recovered {
+                    self.decode_stats.record(Venue::Balancer, true);
+                    if key.base == weth {
+                        call_outcomes.insert((key.token, key.venue), true);
+                    }
+                    let entry = venue_quotes.entry(key).or_insert((U256::zero(), U256::zero()));
+                    if is_buy {
+                        entry.0 = entry.0.max(amount_out);
+                    } else {
+                        entry.1 = entry.1.max(amount_out);
+                    }
+                }
+            }
+        }
+
+        if let Some(health) = &self.call_health {
+            for ((token, venue), success) in call_outcomes {
+                health.record(token, venue, success, now);
+            }
+        }
+
+        // Collect reserve reads per pool
+        let mut reserve_parts: std::collections::HashMap<PoolKey, ReserveParts> =
+            std::collections::HashMap::new();
+
+        for (idx, key, call) in &reserve_mapping {
+            let Some(result) = results.get(*idx) else { continue };
+            if !result.0 || result.1.len() < 32 {
+                continue;
+            }
+
+            if let ReserveCall::SolidlyReserves = call {
+                if result.1.len() >= 64 {
+                    reserve_parts.entry(*key).or_default().solidly_reserves = Some((
+                        U256::from_big_endian(&result.1[..32]),
+                        U256::from_big_endian(&result.1[32..64]),
+                    ));
+                }
+                continue;
+            }
+
+            if let ReserveCall::CurveAmp = call {
+                match decode_curve_amp(&result.1) {
+                    Some(amp) => {
+                        self.curve_amps.lock().insert(key.pool, (amp, Instant::now()));
+                    }
+                    None => debug!("Ignoring implausible A() from Curve pool {:?}", key.pool),
+                }
+                continue;
+            }
+
+            let value = U256::from_big_endian(&result.1[..32]);
+            let parts = reserve_parts.entry(*key).or_default();
+            match call {
+                ReserveCall::CurveBalance(i) => parts.curve_balances[*i] = Some(value),
+                ReserveCall::UniswapSlot0 => parts.sqrt_price_x96 = Some(value),
+                ReserveCall::UniswapLiquidity => parts.liquidity = Some(value),
+                ReserveCall::CurveAmp | ReserveCall::SolidlyReserves => {}
+            }
+        }
+
+        // Derive Curve quotes from the single balance read
+        if let Some((amp, fee_bps)) = self.curve_reserve_quotes {
+            for (key, parts) in reserve_parts.iter().filter(|(k, _)| k.venue == Venue::Curve) {
+                let amp = self.cached_curve_amp(key.pool).unwrap_or(amp);
+                let derived = self.pool_reserves(key, parts)
+                    .and_then(|balances| super::stableswap::quotes_from_balances(balances, amount, amp, fee_bps));
+                self.decode_stats.record(Venue::Curve, derived.is_some());
+
+                if let Some(quotes) = derived {
+                    venue_quotes.insert(*key, quotes);
+                }
+            }
+        }
+
+        // Each pool's sized quotes, alongside its main buy quote
+        let mut sized: HashMap<PoolKey, Vec<(U256, U256)>> = HashMap::new();
+        for (idx, key, sized_amount) in &sized_mapping {
+            let Some(result) = results.get(*idx).filter(|result| result.0) else { continue };
+            if let Ok(amount_out) = self.decode_quote_result(&result.1, key.venue) {
+                sized.entry(*key).or_default().push((*sized_amount, amount_out));
+            }
+        }
+        for (key, mut buy) in sized {
+            let Some(tq) = token_quotes.get_mut(&(key.base, key.token)) else { continue };
+            if let Some((main_buy, _)) = venue_quotes.get(&key).filter(|(buy, _)| !buy.is_zero()) {
+                buy.push((amount, *main_buy));
+            }
+            buy.sort();
+            buy.dedup_by_key(|(amount_in, _)| *amount_in);
+            tq.sized_quotes.push(SizedQuotes { venue: key.venue, fee_tier: key.fee_tier, pool: key.pool, buy });
+        }
+
         // Convert to final format
-        for ((token, venue), (buy_amount, sell_amount)) in venue_quotes {
+        for (key, (buy_amount, sell_amount)) in venue_quotes {
             if buy_amount > U256::zero() || sell_amount > U256::zero() {
-                if let Some(tq) = token_quotes.get_mut(&token) {
-                    tq.quotes.push((venue, Quote {
+                if let Some(tq) = token_quotes.get_mut(&(key.base, key.token)) {
+                    let quote = Quote {
                         buy_amount,
                         sell_amount,
                         liquidity: U256::zero(), // Could add liquidity queries
                         timestamp_ms,
-                    }));
+                        fee_tier: key.fee_tier,
+                        pool: key.pool,
+                        reserves: reserve_parts.get(&key)
+                            .and_then(|parts| self.pool_reserves(&key, parts)),
+                    };
+                    if self.check_impact(&tq.token_name, key.venue, &quote, amount) {
+                        tq.quotes.push((key.venue, quote));
+                    }
                 }
             }
         }
         
+        for tq in token_quotes.values_mut().filter(|tq| tq.base_token.is_none()) {
+            tq.curve_amp = self.get_curve_pool(tq.token).and_then(|pool| self.cached_curve_amp(pool));
+        }
+
         Ok(token_quotes.into_values().collect())
     }
     
     fn get_curve_pool(&self, token: Address) -> Option<Address> {
-        // stETH pool
-        if token == "0xae7ab96520DE3A18E5e111B5EaAb095312D7fE84".parse().unwrap() {
-            return Some(self.addresses.curve_steth);
-        }
-        // rETH pool
-        if token == "0xae78736Cd615f374D3085123A210448E74Fc6393".parse().unwrap() {
-            return Some(self.addresses.curve_reth);
-        }
-        None
+        self.addresses.curve_pools.get(&token).copied()
     }
     
     fn encode_curve_get_dy(&self, i: i128, j: i128, dx: U256) -> Bytes {
@@ -234,6 +968,41 @@ impl MulticallQuoter {
         Bytes::from(data)
     }
     
+    fn encode_curve_balances(&self, i: usize) -> Bytes {
+        // balances(uint256)
+        let selector = ethers::utils::id("balances(uint256)");
+        let mut data = selector[..4].to_vec();
+        data.extend(ethers::abi::encode(&[ethers::abi::Token::Uint(U256::from(i))]));
+        Bytes::from(data)
+    }
+
+    /// (base, LST) reserves from the raw reads, if all of them succeeded
+    fn pool_reserves(&self, key: &PoolKey, parts: &ReserveParts) -> Option<(U256, U256)> {
+        match key.venue {
+            Venue::Curve => Some((parts.curve_balances[0]?, parts.curve_balances[1]?)),
+            Venue::UniswapV3 => {
+                let (reserve0, reserve1) =
+                    uniswap_virtual_reserves(parts.sqrt_price_x96?, parts.liquidity?)?;
+                // token0 is the lower address
+                if key.base < key.token {
+                    Some((reserve0, reserve1))
+                } else {
+                    Some((reserve1, reserve0))
+                }
+            }
+            Venue::Solidly => {
+                let (reserve0, reserve1) = parts.solidly_reserves?;
+                // token0 is the lower address
+                if key.base < key.token {
+                    Some((reserve0, reserve1))
+                } else {
+                    Some((reserve1, reserve0))
+                }
+            }
+            _ => None,
+        }
+    }
+    
     fn encode_uniswap_quote(
         &self,
         token_in: Address,
@@ -276,6 +1045,127 @@ impl MulticallQuoter {
         Bytes::from(data)
     }
     
+    /// Encode Maverick V2 calculateSwap(pool, amount, tokenAIn, exactOutput, tickLimit)
+    /// for an exact-input swap with no tick limit
+    pub fn encode_maverick_quote(&self, pool: Address, amount_in: U256, token_a_in: bool) -> Bytes {
+        let selector = ethers::utils::id("calculateSwap(address,uint128,bool,bool,int32)");
+        let mut data = selector[..4].to_vec();
+
+        // No limit is int32 max when tokenA goes in (tick rises), int32 min otherwise
+        let tick_limit = if token_a_in {
+            U256::from(i32::MAX as u64)
+        } else {
+            U256::MAX - U256::from(i32::MAX as u64) // two's complement of i32::MIN
+        };
+
+        data.extend(ethers::abi::encode(&[
+            ethers::abi::Token::Address(pool),
+            ethers::abi::Token::Uint(U256::from(amount_in.low_u128())),
+            ethers::abi::Token::Bool(token_a_in),
+            ethers::abi::Token::Bool(false),
+            ethers::abi::Token::Int(tick_limit),
+        ]));
+
+        Bytes::from(data)
+    }
+
+    /// A quote call of `amount` against the pool behind `key`: base -> LST
+    /// when `is_buy`, LST -> base otherwise
+    fn quote_call(&self, key: &PoolKey, amount: U256, is_buy: bool) -> Option<Call3> {
+        let (token_in, token_out) = if is_buy { (key.base, key.token) } else { (key.token, key.base) };
+        let via_weth = key.base == self.addresses.weth;
+        let (target, call_data) = match (key.venue, key.fee_tier) {
+            (Venue::Curve, _) if via_weth => {
+                let (i, j) = if is_buy { (0, 1) } else { (1, 0) };
+                (key.pool, self.encode_curve_get_dy(i, j, amount))
+            }
+            (Venue::Balancer, _) => {
+                let pool_id = if via_weth {
+                    *self.addresses.balancer_pools.get(&key.token)?
+                } else {
+                    self.lst_pairs.iter()
+                        .find(|pair| pair.base == key.base && pair.token == key.token)?
+                        .balancer_pool?
+                };
+                (self.addresses.balancer_vault, self.encode_balancer_query(pool_id, token_in, token_out, amount))
+            }
+            (Venue::UniswapV3, Some(fee)) => {
+                (self.addresses.uniswap_quoter, self.encode_uniswap_quote(token_in, token_out, amount, fee))
+            }
+            (Venue::UniswapV3, None) => {
+                let (_, path) = self.uniswap_routes.iter().find(|(_, path)| route_id(path) == key.pool)?;
+                let path = if is_buy { path.clone() } else { reverse_path(path) };
+                (self.addresses.uniswap_quoter, self.encode_uniswap_path_quote(path, amount))
+            }
+            (Venue::Maverick, _) if via_weth => {
+                (self.addresses.maverick_quoter, self.encode_maverick_quote(key.pool, amount, token_in < token_out))
+            }
+            (Venue::Solidly, _) if via_weth => (key.pool, self.encode_solidly_quote(amount, token_in)),
+            // Only Uniswap and Balancer pools are quoted for LST/LST pairs
+            _ => return None,
+        };
+        Some(Call3 { target, allow_failure: true, call_data })
+    }
+
+    /// Encode a Solidly pool's getAmountOut(amountIn, tokenIn), which
+    /// applies the pool's fee and its stable or volatile curve
+    pub fn encode_solidly_quote(&self, amount_in: U256, token_in: Address) -> Bytes {
+        let selector = ethers::utils::id("getAmountOut(uint256,address)");
+        let mut data = selector[..4].to_vec();
+        data.extend(ethers::abi::encode(&[
+            ethers::abi::Token::Uint(amount_in),
+            ethers::abi::Token::Address(token_in),
+        ]));
+        Bytes::from(data)
+    }
+
+    /// Encode a single-step GIVEN_IN queryBatchSwap(kind, swaps, assets, funds)
+    /// of `amount_in` of `asset_in` through the pool
+    pub fn encode_balancer_query(&self, pool_id: H256, asset_in: Address, asset_out: Address, amount_in: U256) -> Bytes {
+        let selector = ethers::utils::id(
+            "queryBatchSwap(uint8,(bytes32,uint256,uint256,uint256,bytes)[],address[],(address,bool,address,bool))",
+        );
+        let mut data = selector[..4].to_vec();
+
+        // Funds aren't moved by a query, but the vault still reads them
+        let holder = self.addresses.multicall3;
+        data.extend(ethers::abi::encode(&[
+            ethers::abi::Token::Uint(U256::zero()), // GIVEN_IN
+            ethers::abi::Token::Array(vec![ethers::abi::Token::Tuple(vec![
+                ethers::abi::Token::FixedBytes(pool_id.as_bytes().to_vec()),
+                ethers::abi::Token::Uint(U256::zero()), // assets[0] in
+                ethers::abi::Token::Uint(U256::one()),  // assets[1] out
+                ethers::abi::Token::Uint(amount_in),
+                ethers::abi::Token::Bytes(Vec::new()),
+            ])]),
+            ethers::abi::Token::Array(vec![
+                ethers::abi::Token::Address(asset_in),
+                ethers::abi::Token::Address(asset_out),
+            ]),
+            ethers::abi::Token::Tuple(vec![
+                ethers::abi::Token::Address(holder),
+                ethers::abi::Token::Bool(false),
+                ethers::abi::Token::Address(holder),
+                ethers::abi::Token::Bool(false),
+            ]),
+        ]));
+
+        Bytes::from(data)
+    }
+
+    /// Encode quoteExactInput(bytes path, uint256 amountIn) for a multi-hop route
+    pub fn encode_uniswap_path_quote(&self, path: UniswapPath, amount_in: U256) -> Bytes {
+        let selector = ethers::utils::id("quoteExactInput(bytes,uint256)");
+        let mut data = selector[..4].to_vec();
+
+        data.extend(ethers::abi::encode(&[
+            ethers::abi::Token::Bytes(encode_uniswap_path(&path)),
+            ethers::abi::Token::Uint(amount_in),
+        ]));
+
+        Bytes::from(data)
+    }
+
     fn decode_quote_result(&self, data: &[u8], venue: Venue) -> eyre::Result<U256> {
         match venue {
             Venue::Curve => {
@@ -286,6 +1176,22 @@ impl MulticallQuoter {
                     Err(eyre::eyre!("Invalid Curve response"))
                 }
             }
+            Venue::UniswapV3 if data.len() > 128 => {
+                // quoteExactInput returns (uint256 amountOut, uint160[], uint32[], uint256),
+                // so the response carries per-hop arrays after the static head
+                let tokens = ethers::abi::decode(
+                    &[
+                        ethers::abi::ParamType::Uint(256),
+                        ethers::abi::ParamType::Array(Box::new(ethers::abi::ParamType::Uint(160))),
+                        ethers::abi::ParamType::Array(Box::new(ethers::abi::ParamType::Uint(32))),
+                        ethers::abi::ParamType::Uint(256),
+                    ],
+                    data,
+                )?;
+                tokens.into_iter().next()
+                    .and_then(|t| t.into_uint())
+                    .ok_or_else(|| eyre::eyre!("Invalid UniswapV3 multi-hop response"))
+            }
             Venue::UniswapV3 => {
                 // Returns (uint256 amountOut, uint160, uint32, uint256)
                 if data.len() >= 32 {
@@ -294,18 +1200,438 @@ impl MulticallQuoter {
                     Err(eyre::eyre!("Invalid UniswapV3 response"))
                 }
             }
-            Venue::Balancer => {
-                // Balancer queryBatchSwap returns int256[]
-                // First value is the delta (negative = received)
+            Venue::Balancer => decode_balancer_query(data),
+            Venue::Maverick => {
+                // calculateSwap returns (uint256 amountIn, uint256 amountOut, uint256 gasEstimate)
                 if data.len() >= 64 {
-                    // Skip array offset and length, get first int256
-                    let value = U256::from_big_endian(&data[32..64]);
-                    Ok(value)
+                    Ok(U256::from_big_endian(&data[32..64]))
+                } else {
+                    Err(eyre::eyre!("Invalid Maverick response"))
+                }
+            }
+            Venue::Solidly => {
+                // getAmountOut returns uint256
+                if data.len() >= 32 {
+                    Ok(U256::from_big_endian(&data[..32]))
                 } else {
-                    Err(eyre::eyre!("Invalid Balancer response"))
+                    Err(eyre::eyre!("Invalid Solidly response"))
                 }
             }
-            _ => Err(eyre::eyre!("Unsupported venue")),
         }
     }
 }
+
+/// Amount out of a queryBatchSwap response: int256[] vault deltas per
+/// asset. Deltas are from the vault's side, so the input asset's (first) is
+/// positive and the output asset's (last) is negative.
+fn decode_balancer_query(data: &[u8]) -> eyre::Result<U256> {
+    let tokens = ethers::abi::decode(
+        &[ethers::abi::ParamType::Array(Box::new(ethers::abi::ParamType::Int(256)))],
+        data,
+    )?;
+    let deltas: Vec<I256> = tokens.into_iter().next()
+        .and_then(|t| t.into_array())
+        .map(|deltas| deltas.into_iter().filter_map(|t| t.into_int()).map(I256::from_raw).collect())
+        .unwrap_or_default();
+    let (Some(delta_in), Some(delta_out)) = (deltas.first(), deltas.last()) else {
+        return Err(eyre::eyre!("Invalid Balancer response"));
+    };
+
+    if deltas.len() < 2 || !delta_in.is_positive() {
+        Err(eyre::eyre!("Invalid Balancer response"))
+    } else if delta_out.is_negative() {
+        Ok(delta_out.unsigned_abs())
+    } else {
+        Err(eyre::eyre!("Balancer query returned no output"))
+    }
+}
+
+/// Retry Balancer quotes that failed in the multicall through `call`, a
+/// standalone eth_call to the vault, returning those that now decode
+async fn recover_balancer_quotes<F, Fut>(
+    failed: Vec<(PoolKey, bool, Bytes)>,
+    call: F,
+) -> Vec<(PoolKey, bool, U256)>
+where
+    F: Fn(Bytes) -> Fut,
+    Fut: Future<Output = eyre::Result<Bytes>>,
+{
+    let responses = futures::future::join_all(
+        failed.iter().map(|(_, _, call_data)| call(call_data.clone())),
+    ).await;
+
+    failed.into_iter()
+        .zip(responses)
+        .filter_map(|((key, is_buy, _), response)| match response.and_then(|data| decode_balancer_query(&data)) {
+            Ok(amount_out) => Some((key, is_buy, amount_out)),
+            Err(e) => {
+                debug!("Standalone Balancer quote failed for {:?}: {:?}", key.pool, e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Virtual (token0, token1) reserves of a Uniswap V3 pool at its current price
+///
+/// Within the active tick the pool trades like a constant-product pool with
+/// x = L / sqrtP and y = L * sqrtP.
+fn uniswap_virtual_reserves(sqrt_price_x96: U256, liquidity: U256) -> Option<(U256, U256)> {
+    if sqrt_price_x96.is_zero() {
+        return None;
+    }
+
+    let q96 = U256::one() << 96;
+    let reserve0 = liquidity.full_mul(q96) / ethers::types::U512::from(sqrt_price_x96);
+    let reserve1 = liquidity.full_mul(sqrt_price_x96) / ethers::types::U512::from(q96);
+
+    Some((reserve0.try_into().ok()?, reserve1.try_into().ok()?))
+}
+
+/// Pack a path as Uniswap expects: token (20 bytes), fee (3 bytes), token, ...
+fn encode_uniswap_path(path: &[(Address, u32)]) -> Vec<u8> {
+    let mut packed = Vec::with_capacity(path.len() * 23);
+
+    for (i, (token, fee)) in path.iter().enumerate() {
+        packed.extend_from_slice(token.as_bytes());
+        if i + 1 < path.len() {
+            packed.extend_from_slice(&fee.to_be_bytes()[1..]);
+        }
+    }
+
+    packed
+}
+
+/// Reverse a path, keeping each fee on the hop it belongs to
+fn reverse_path(path: &[(Address, u32)]) -> UniswapPath {
+    let tokens = path.iter().rev().map(|(token, _)| *token);
+    let fees = path.iter().rev().skip(1).map(|(_, fee)| *fee).chain(std::iter::once(0));
+    tokens.zip(fees).collect()
+}
+
+/// Stable pseudo-address identifying a multi-hop route
+fn route_id(path: &[(Address, u32)]) -> Address {
+    Address::from_slice(&ethers::utils::keccak256(encode_uniswap_path(path))[12..])
+}
+
+/// Decode Curve's `A()` (uint256). Real pools run A in the single digits to
+/// low thousands, so zero or anything absurd is treated as a bad read.
+fn decode_curve_amp(data: &[u8]) -> Option<u64> {
+    if data.len() < 32 {
+        return None;
+    }
+    let amp = U256::from_big_endian(&data[..32]);
+    (!amp.is_zero() && amp <= U256::from(1_000_000u64)).then(|| amp.as_u64())
+}
+
+/// Reads the L2 block a batch executes against, to stamp its quotes with
+fn block_number_call() -> Call3 {
+    Call3 {
+        target: ARB_SYS,
+        allow_failure: true,
+        call_data: Bytes::from(ethers::utils::id("arbBlockNumber()").to_vec()),
+    }
+}
+
+/// The block `block_number_call` returned, if it succeeded
+fn decode_block_number((success, data): &(bool, Bytes)) -> Option<u64> {
+    (*success && data.len() >= 32).then(|| U256::from_big_endian(&data[..32]).low_u64())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quoter() -> MulticallQuoter {
+        MulticallQuoter::new(VenueAddresses {
+            multicall3: Address::zero(),
+            curve_pools: Default::default(),
+            balancer_vault: Address::zero(),
+            balancer_pools: Default::default(),
+            uniswap_quoter: Address::zero(),
+            maverick_quoter: Address::zero(),
+            maverick_pools: Default::default(),
+            solidly_pools: Default::default(),
+            weth: Address::zero(),
+        })
+    }
+
+    #[test]
+    fn test_token_without_venue_coverage_flagged() {
+        let token = |n: u64| (Address::from_low_u64_be(n), format!("lst{}", n));
+        let tokens = vec![token(1), token(2), token(3)];
+        let quoted = |n: u64, buy: u64| TokenQuotes {
+            token: Address::from_low_u64_be(n),
+            token_name: format!("lst{}", n),
+            quotes: vec![(Venue::UniswapV3, Quote { buy_amount: U256::from(buy), ..Default::default() })],
+            curve_amp: None,
+            sized_quotes: Vec::new(),
+            fetched_at_ms: 0,
+            block_number: None,
+            base_token: None,
+        };
+
+        // lst2's only venue returned nothing, lst3 got no quotes at all
+        let quotes = vec![quoted(1, 995), quoted(2, 0)];
+        assert_eq!(unreachable_tokens(&tokens, &quotes), vec![token(2), token(3)]);
+    }
+
+    #[test]
+    fn test_encode_uniswap_path() {
+        let weth = Address::from_low_u64_be(1);
+        let usdc = Address::from_low_u64_be(2);
+        let lst = Address::from_low_u64_be(3);
+        let path = vec![(weth, 500), (usdc, 100), (lst, 0)];
+
+        let packed = encode_uniswap_path(&path);
+        assert_eq!(packed.len(), 20 + 3 + 20 + 3 + 20);
+        assert_eq!(&packed[20..23], &[0x00, 0x01, 0xf4]); // 500
+        assert_eq!(&packed[43..46], &[0x00, 0x00, 0x64]); // 100
+        assert_eq!(&packed[46..], lst.as_bytes());
+
+        // Sell side runs LST -> USDC (100) -> WETH (500)
+        assert_eq!(reverse_path(&path), vec![(lst, 100), (usdc, 500), (weth, 0)]);
+        assert_ne!(route_id(&path), route_id(&reverse_path(&path)));
+    }
+
+    #[test]
+    fn test_uniswap_virtual_reserves() {
+        let q96 = U256::one() << 96;
+        let liquidity = U256::exp10(21);
+
+        // Price 1: both reserves equal L
+        assert_eq!(uniswap_virtual_reserves(q96, liquidity), Some((liquidity, liquidity)));
+
+        // Price 4 (sqrtP = 2): token1 reserve is 4x token0
+        let (r0, r1) = uniswap_virtual_reserves(q96 * 2, liquidity).unwrap();
+        assert_eq!(r0, liquidity / 2);
+        assert_eq!(r1, liquidity * 2);
+
+        assert_eq!(uniswap_virtual_reserves(U256::zero(), liquidity), None);
+    }
+
+    #[test]
+    fn test_pair_reserves_oriented_base_first() {
+        // WETH (zero) sorts below every LST; rETH (2) sorts below wstETH (3)
+        let quoter = quoter();
+        let (reth, wsteth) = (Address::from_low_u64_be(2), Address::from_low_u64_be(3));
+        let parts = ReserveParts {
+            solidly_reserves: Some((U256::from(100u64), U256::from(80u64))),
+            ..Default::default()
+        };
+        let key = |base, token| PoolKey { base, token, venue: Venue::Solidly, fee_tier: None, pool: Address::zero() };
+
+        // WETH/rETH: WETH is token0
+        assert_eq!(quoter.pool_reserves(&key(Address::zero(), reth), &parts), Some((100u64.into(), 80u64.into())));
+        // rETH quoted in wstETH: the base is token1
+        assert_eq!(quoter.pool_reserves(&key(wsteth, reth), &parts), Some((80u64.into(), 100u64.into())));
+    }
+
+    #[test]
+    fn test_decode_multi_hop_quote() {
+        let quoter = quoter();
+        let amount_out = U256::from(987_654_321u64);
+
+        let data = ethers::abi::encode(&[
+            ethers::abi::Token::Uint(amount_out),
+            ethers::abi::Token::Array(vec![ethers::abi::Token::Uint(1.into()), ethers::abi::Token::Uint(2.into())]),
+            ethers::abi::Token::Array(vec![ethers::abi::Token::Uint(3.into()), ethers::abi::Token::Uint(4.into())]),
+            ethers::abi::Token::Uint(150_000.into()),
+        ]);
+        assert_eq!(quoter.decode_quote_result(&data, Venue::UniswapV3).unwrap(), amount_out);
+
+        // Single-hop responses still decode from the first word
+        let single = ethers::abi::encode(&[
+            ethers::abi::Token::Uint(amount_out),
+            ethers::abi::Token::Uint(1.into()),
+            ethers::abi::Token::Uint(2.into()),
+            ethers::abi::Token::Uint(3.into()),
+        ]);
+        assert_eq!(quoter.decode_quote_result(&single, Venue::UniswapV3).unwrap(), amount_out);
+
+        // Truncated multi-hop data is a decode failure, not a bogus quote
+        assert!(quoter.decode_quote_result(&data[..160], Venue::UniswapV3).is_err());
+    }
+
+    #[test]
+    fn test_maverick_quote_round_trip() {
+        let quoter = quoter();
+        let pool = Address::from_low_u64_be(7);
+        let amount = ethers::utils::parse_ether("1").unwrap();
+
+        let data = quoter.encode_maverick_quote(pool, amount, false);
+        assert_eq!(&data[..4], &ethers::utils::id("calculateSwap(address,uint128,bool,bool,int32)")[..4]);
+
+        let args = ethers::abi::decode(
+            &[
+                ethers::abi::ParamType::Address,
+                ethers::abi::ParamType::Uint(128),
+                ethers::abi::ParamType::Bool,
+                ethers::abi::ParamType::Bool,
+                ethers::abi::ParamType::Int(32),
+            ],
+            &data[4..],
+        ).unwrap();
+        assert_eq!(args[0], ethers::abi::Token::Address(pool));
+        assert_eq!(args[1], ethers::abi::Token::Uint(amount));
+        assert_eq!(args[2], ethers::abi::Token::Bool(false));
+        // tokenB in: no limit is int32 min
+        let tick_limit = args[4].clone().into_int().unwrap();
+        assert_eq!(I256::from_raw(tick_limit), I256::from(i32::MIN));
+
+        // Amount out is the second word of (amountIn, amountOut, gasEstimate)
+        let amount_out = U256::from(998_000_000_000_000_000u64);
+        let response = ethers::abi::encode(&[
+            ethers::abi::Token::Uint(amount),
+            ethers::abi::Token::Uint(amount_out),
+            ethers::abi::Token::Uint(90_000.into()),
+        ]);
+        assert_eq!(quoter.decode_quote_result(&response, Venue::Maverick).unwrap(), amount_out);
+        assert!(quoter.decode_quote_result(&response[..32], Venue::Maverick).is_err());
+    }
+
+    #[test]
+    fn test_quotes_stamped_with_l2_block() {
+        let call = block_number_call();
+        assert_eq!(call.target, "0x0000000000000000000000000000000000000064".parse::<Address>().unwrap());
+        assert_eq!(call.call_data.to_vec(), ethers::utils::id("arbBlockNumber()").to_vec());
+
+        // An Arbitrum One L2 block, not the ~21M L1 block `block.number` gives
+        let l2_block = ethers::abi::encode(&[ethers::abi::Token::Uint(U256::from(265_000_000u64))]);
+        assert_eq!(decode_block_number(&(true, l2_block.clone().into())), Some(265_000_000));
+        assert_eq!(decode_block_number(&(false, l2_block.into())), None);
+        assert_eq!(decode_block_number(&(true, Bytes::new())), None);
+    }
+
+    #[test]
+    fn test_curve_amp_decoded_and_cached() {
+        let amp = ethers::abi::encode(&[ethers::abi::Token::Uint(U256::from(1_500u64))]);
+        assert_eq!(decode_curve_amp(&amp), Some(1_500));
+        assert_eq!(decode_curve_amp(&[0u8; 32]), None);
+        assert_eq!(decode_curve_amp(&amp[..16]), None);
+
+        let quoter = quoter();
+        let pool = Address::from_low_u64_be(9);
+        assert_eq!(quoter.cached_curve_amp(pool), None);
+        quoter.curve_amps.lock().insert(pool, (1_500, Instant::now()));
+        assert_eq!(quoter.cached_curve_amp(pool), Some(1_500));
+
+        // Stale after the TTL, so the next fetch reads A() again
+        let fetched = Instant::now().checked_sub(CURVE_AMP_TTL + Duration::from_secs(1));
+        if let Some(fetched) = fetched {
+            quoter.curve_amps.lock().insert(pool, (1_500, fetched));
+            assert_eq!(quoter.cached_curve_amp(pool), None);
+        }
+    }
+
+    #[test]
+    fn test_negative_impact_quote_dropped() {
+        let amount = U256::exp10(18);
+        // Spot is 1.1 LST per WETH, but the quote claims 1.2 at size
+        let quote = Quote {
+            buy_amount: U256::from(1_200_000_000_000_000_000u64),
+            sell_amount: U256::from(900_000_000_000_000_000u64),
+            reserves: Some((U256::exp10(21), U256::exp10(20) * 11)),
+            ..Default::default()
+        };
+        assert!(!quoter().check_impact("wstETH", Venue::UniswapV3, &quote, amount));
+
+        let warn_only = quoter().with_negative_impact_check(ImpactCheck::Warn, DEFAULT_IMPACT_TOLERANCE_BPS);
+        assert!(warn_only.check_impact("wstETH", Venue::UniswapV3, &quote, amount));
+
+        // ~909 bps over spot passes a looser tolerance
+        let loose = quoter().with_negative_impact_check(ImpactCheck::Drop, 1_000);
+        assert!(loose.check_impact("wstETH", Venue::UniswapV3, &quote, amount));
+    }
+
+    #[test]
+    fn test_balancer_query_round_trip() {
+        let quoter = quoter();
+        let (weth, lst) = (Address::from_low_u64_be(1), Address::from_low_u64_be(2));
+        let pool_id = H256::from_low_u64_be(7);
+        let amount = ethers::utils::parse_ether("1").unwrap();
+
+        let data = quoter.encode_balancer_query(pool_id, weth, lst, amount);
+        let args = ethers::abi::decode(
+            &[
+                ethers::abi::ParamType::Uint(8),
+                ethers::abi::ParamType::Array(Box::new(ethers::abi::ParamType::Tuple(vec![
+                    ethers::abi::ParamType::FixedBytes(32),
+                    ethers::abi::ParamType::Uint(256),
+                    ethers::abi::ParamType::Uint(256),
+                    ethers::abi::ParamType::Uint(256),
+                    ethers::abi::ParamType::Bytes,
+                ]))),
+                ethers::abi::ParamType::Array(Box::new(ethers::abi::ParamType::Address)),
+                ethers::abi::ParamType::Tuple(vec![
+                    ethers::abi::ParamType::Address,
+                    ethers::abi::ParamType::Bool,
+                    ethers::abi::ParamType::Address,
+                    ethers::abi::ParamType::Bool,
+                ]),
+            ],
+            &data[4..],
+        ).unwrap();
+        // GIVEN_IN, one step from assets[0] to assets[1]
+        assert_eq!(args[0], ethers::abi::Token::Uint(U256::zero()));
+        let step = args[1].clone().into_array().unwrap().remove(0).into_tuple().unwrap();
+        assert_eq!(step[0], ethers::abi::Token::FixedBytes(pool_id.as_bytes().to_vec()));
+        assert_eq!(step[1..4], [
+            ethers::abi::Token::Uint(U256::zero()),
+            ethers::abi::Token::Uint(U256::one()),
+            ethers::abi::Token::Uint(amount),
+        ]);
+        assert_eq!(args[2], ethers::abi::Token::Array(vec![
+            ethers::abi::Token::Address(weth),
+            ethers::abi::Token::Address(lst),
+        ]));
+
+        let deltas = |delta_in: I256, delta_out: I256| ethers::abi::encode(&[ethers::abi::Token::Array(vec![
+            ethers::abi::Token::Int(delta_in.into_raw()),
+            ethers::abi::Token::Int(delta_out.into_raw()),
+        ])]);
+        let amount_in = I256::from_raw(amount);
+        let amount_out = I256::from_raw(U256::from(998_000_000_000_000_000u64));
+
+        // The vault receives the input and pays out the (negative) output
+        let response = deltas(amount_in, -amount_out);
+        assert_eq!(quoter.decode_quote_result(&response, Venue::Balancer).unwrap(), amount_out.into_raw());
+        // Signs the other way round aren't a quote
+        assert!(quoter.decode_quote_result(&deltas(-amount_in, amount_out), Venue::Balancer).is_err());
+        assert!(quoter.decode_quote_result(&deltas(amount_in, I256::zero()), Venue::Balancer).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_balancer_quote_recovered_outside_multicall() {
+        let quoter = quoter();
+        let (weth, lst) = (Address::from_low_u64_be(1), Address::from_low_u64_be(2));
+        let pool_id = H256::from_low_u64_be(7);
+        let amount = U256::exp10(18);
+        let amount_out = U256::from(998_000_000_000_000_000u64);
+        let call_data = quoter.encode_balancer_query(pool_id, weth, lst, amount);
+
+        // queryBatchSwap reverted inside aggregate3, so its batch result is unusable
+        let key = PoolKey { base: weth, token: lst, venue: Venue::Balancer, fee_tier: None, pool: Address::zero() };
+        let batch_result = (false, Bytes::new());
+        assert!(!batch_result.0 || decode_balancer_query(&batch_result.1).is_err());
+
+        // On its own the vault answers: +amount in, -amount_out out
+        let recovered = recover_balancer_quotes(vec![(key, true, call_data.clone())], |data| {
+            let expected = call_data.clone();
+            async move {
+                assert_eq!(data, expected);
+                Ok(Bytes::from(ethers::abi::encode(&[ethers::abi::Token::Array(vec![
+                    ethers::abi::Token::Int(amount),
+                    ethers::abi::Token::Int(I256::from_raw(amount_out).wrapping_neg().into_raw()),
+                ])])))
+            }
+        }).await;
+        assert_eq!(recovered, vec![(key, true, amount_out)]);
+
+        // A standalone call that fails too leaves the quote missing
+        let failed = recover_balancer_quotes(vec![(key, false, call_data.clone())], |_| async {
+            Err(eyre::eyre!("execution reverted"))
+        }).await;
+        assert!(failed.is_empty());
+    }
+}
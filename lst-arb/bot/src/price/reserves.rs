@@ -0,0 +1,325 @@
+//! Pool Reserve Sources
+//!
+//! The solver sizes trades from pool reserves. Reserves are read on-chain
+//! first; when that fails (RPC errors, no archive access) a subgraph such as
+//! The Graph or Goldsky can stand in. Subgraph data lags the chain, so the
+//! fallback warns when the indexed block is older than the configured limit.
+
+use async_trait::async_trait;
+use ethers::prelude::*;
+use ethers::types::{Address, U256};
+use ethers::utils::keccak256;
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+use super::cache::Venue;
+use crate::rpc::{ChainClock, RpcLoadBalancer};
+
+/// Uniswap V3 factory (same address on Arbitrum and mainnet)
+pub const UNISWAP_V3_FACTORY: &str = "0x1F98431c8aD98523631AE4a59f267346ea31F984";
+
+/// Uniswap V3 pool init code hash
+pub const UNISWAP_V3_POOL_INIT_CODE_HASH: &str =
+    "0xe34f199b19b2b4f47f68442619d555527d244f78a3297ea89325f843f87b8b54";
+
+abigen!(
+    IERC20Balance,
+    r#"[
+        function balanceOf(address account) external view returns (uint256)
+    ]"#
+);
+
+/// Reserves of a WETH/LST pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolReserves {
+    pub pool: Address,
+    pub reserve_x: U256, // WETH
+    pub reserve_y: U256, // LST
+}
+
+/// Somewhere pool reserves can be read from
+#[async_trait]
+pub trait ReserveSource: Send + Sync {
+    async fn fetch_reserves(
+        &self,
+        token: Address,
+        venue: Venue,
+        fee_tier: Option<u32>,
+    ) -> eyre::Result<PoolReserves>;
+}
+
+/// Compute a Uniswap V3 pool address from its tokens and fee tier
+pub fn uniswap_v3_pool_address(token_a: Address, token_b: Address, fee: u32) -> Address {
+    let (token0, token1) = if token_a < token_b {
+        (token_a, token_b)
+    } else {
+        (token_b, token_a)
+    };
+
+    let salt = keccak256(ethers::abi::encode(&[
+        ethers::abi::Token::Address(token0),
+        ethers::abi::Token::Address(token1),
+        ethers::abi::Token::Uint(U256::from(fee)),
+    ]));
+
+    let factory: Address = UNISWAP_V3_FACTORY.parse().unwrap();
+    let init_code_hash: H256 = UNISWAP_V3_POOL_INIT_CODE_HASH.parse().unwrap();
+
+    ethers::utils::get_create2_address_from_hash(factory, salt, init_code_hash)
+}
+
+/// Reads reserves from pool token balances on-chain
+pub struct OnChainReserves {
+    rpc: Arc<RpcLoadBalancer>,
+    weth: Address,
+}
+
+impl OnChainReserves {
+    pub fn new(rpc: Arc<RpcLoadBalancer>, weth: Address) -> Self {
+        Self { rpc, weth }
+    }
+}
+
+#[async_trait]
+impl ReserveSource for OnChainReserves {
+    async fn fetch_reserves(
+        &self,
+        token: Address,
+        venue: Venue,
+        fee_tier: Option<u32>,
+    ) -> eyre::Result<PoolReserves> {
+        let pool = match (venue, fee_tier) {
+            (Venue::UniswapV3, Some(fee)) => uniswap_v3_pool_address(self.weth, token, fee),
+            _ => eyre::bail!("No on-chain reserve lookup for {:?}", venue),
+        };
+
+        let client = self.rpc.get_client().await
+            .ok_or_else(|| eyre::eyre!("No healthy RPC available"))?;
+
+        let weth = IERC20Balance::new(self.weth, client.clone());
+        let lst = IERC20Balance::new(token, client);
+
+        let reserve_x = weth.balance_of(pool).call().await?;
+        let reserve_y = lst.balance_of(pool).call().await?;
+
+        Ok(PoolReserves { pool, reserve_x, reserve_y })
+    }
+}
+
+/// Reads Uniswap V3 pool reserves from a subgraph
+pub struct SubgraphReserves {
+    http_client: reqwest::Client,
+    url: String,
+    weth: Address,
+    max_lag_secs: u64,
+    clock: Option<Arc<ChainClock>>,
+}
+
+impl SubgraphReserves {
+    pub fn new(url: String, weth: Address, max_lag_secs: u64) -> Self {
+        Self {
+            http_client: reqwest::Client::new(),
+            url,
+            weth,
+            max_lag_secs,
+            clock: None,
+        }
+    }
+
+    /// Measure subgraph lag against this clock instead of the raw local clock
+    pub fn with_clock(mut self, clock: Arc<ChainClock>) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+}
+
+#[async_trait]
+impl ReserveSource for SubgraphReserves {
+    async fn fetch_reserves(
+        &self,
+        token: Address,
+        venue: Venue,
+        fee_tier: Option<u32>,
+    ) -> eyre::Result<PoolReserves> {
+        let pool = match (venue, fee_tier) {
+            (Venue::UniswapV3, Some(fee)) => uniswap_v3_pool_address(self.weth, token, fee),
+            _ => eyre::bail!("No subgraph reserve lookup for {:?}", venue),
+        };
+
+        let query = format!(
+            r#"{{ pool(id: "{:?}") {{ token0 {{ id }} totalValueLockedToken0 totalValueLockedToken1 }} _meta {{ block {{ timestamp }} }} }}"#,
+            pool
+        );
+
+        let response: serde_json::Value = self.http_client
+            .post(&self.url)
+            .json(&serde_json::json!({ "query": query }))
+            .send()
+            .await?
+            .json()
+            .await?;
+
+        let data = response.get("data")
+            .ok_or_else(|| eyre::eyre!("Subgraph error: {}", response))?;
+        let pool_data = data.get("pool")
+            .filter(|p| !p.is_null())
+            .ok_or_else(|| eyre::eyre!("Pool {:?} not indexed by subgraph", pool))?;
+
+        let token0: Address = pool_data["token0"]["id"].as_str()
+            .ok_or_else(|| eyre::eyre!("Missing token0"))?
+            .parse()?;
+        let tvl0 = pool_data["totalValueLockedToken0"].as_str()
+            .and_then(decimal_to_wei)
+            .ok_or_else(|| eyre::eyre!("Invalid totalValueLockedToken0"))?;
+        let tvl1 = pool_data["totalValueLockedToken1"].as_str()
+            .and_then(decimal_to_wei)
+            .ok_or_else(|| eyre::eyre!("Invalid totalValueLockedToken1"))?;
+
+        // Subgraph data trails the chain - warn when it's too far behind
+        if let Some(block_ts) = data["_meta"]["block"]["timestamp"].as_u64() {
+            let now = match &self.clock {
+                Some(clock) => clock.now_secs(),
+                None => std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)?
+                    .as_secs(),
+            };
+            let lag = now.saturating_sub(block_ts);
+            if lag > self.max_lag_secs {
+                warn!("⚠️ Subgraph reserves for {:?} are {}s stale", pool, lag);
+            }
+        }
+
+        let (reserve_x, reserve_y) = if token0 == self.weth {
+            (tvl0, tvl1)
+        } else {
+            (tvl1, tvl0)
+        };
+
+        Ok(PoolReserves { pool, reserve_x, reserve_y })
+    }
+}
+
+/// Tries the primary source, falling back to the secondary on error
+pub struct FallbackReserves {
+    primary: Arc<dyn ReserveSource>,
+    fallback: Arc<dyn ReserveSource>,
+}
+
+impl FallbackReserves {
+    pub fn new(primary: Arc<dyn ReserveSource>, fallback: Arc<dyn ReserveSource>) -> Self {
+        Self { primary, fallback }
+    }
+}
+
+#[async_trait]
+impl ReserveSource for FallbackReserves {
+    async fn fetch_reserves(
+        &self,
+        token: Address,
+        venue: Venue,
+        fee_tier: Option<u32>,
+    ) -> eyre::Result<PoolReserves> {
+        match self.primary.fetch_reserves(token, venue, fee_tier).await {
+            Ok(reserves) => Ok(reserves),
+            Err(e) => {
+                debug!("On-chain reserves failed for {:?} {:?}: {:?}, using fallback", token, venue, e);
+                self.fallback.fetch_reserves(token, venue, fee_tier).await
+            }
+        }
+    }
+}
+
+/// Convert a decimal token amount ("123.456") to 18-decimal wei
+fn decimal_to_wei(value: &str) -> Option<U256> {
+    let (whole, frac) = value.split_once('.').unwrap_or((value, ""));
+    let frac: String = frac.chars().chain(std::iter::repeat('0')).take(18).collect();
+    let whole = if whole.is_empty() { "0" } else { whole };
+
+    let whole = U256::from_dec_str(whole).ok()?;
+    let frac = U256::from_dec_str(&frac).ok()?;
+
+    whole.checked_mul(U256::exp10(18))?.checked_add(frac)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    struct FailingSource;
+
+    #[async_trait]
+    impl ReserveSource for FailingSource {
+        async fn fetch_reserves(
+            &self,
+            _token: Address,
+            _venue: Venue,
+            _fee_tier: Option<u32>,
+        ) -> eyre::Result<PoolReserves> {
+            eyre::bail!("archive access required")
+        }
+    }
+
+    /// Serve a single canned JSON response over HTTP
+    async fn mock_http(body: String) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0u8; 8192];
+            let _ = socket.read(&mut buf).await;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+        });
+
+        format!("http://{}", addr)
+    }
+
+    #[test]
+    fn test_decimal_to_wei() {
+        assert_eq!(decimal_to_wei("1.5"), Some(U256::exp10(18) * 3 / 2));
+        assert_eq!(decimal_to_wei("42"), Some(U256::exp10(18) * 42));
+        // Extra precision beyond 18 decimals is truncated
+        assert_eq!(decimal_to_wei("0.0000000000000000019"), Some(U256::one()));
+        assert_eq!(decimal_to_wei("abc"), None);
+    }
+
+    #[tokio::test]
+    async fn test_failed_onchain_fetch_falls_back_to_subgraph() {
+        let weth: Address = "0x82aF49447D8a07e3bd95BD0d56f35241523fBab1".parse().unwrap();
+        let token: Address = "0x5979D7b546E38E414F7E9822514be443A4800529".parse().unwrap();
+
+        let body = serde_json::json!({
+            "data": {
+                "pool": {
+                    "token0": { "id": format!("{:?}", token) },
+                    "totalValueLockedToken0": "850.25",
+                    "totalValueLockedToken1": "1000.5"
+                },
+                "_meta": { "block": { "timestamp": 0 } }
+            }
+        });
+        let url = mock_http(body.to_string()).await;
+
+        let source = FallbackReserves::new(
+            Arc::new(FailingSource),
+            Arc::new(SubgraphReserves::new(url, weth, 300)),
+        );
+
+        let reserves = source
+            .fetch_reserves(token, Venue::UniswapV3, Some(100))
+            .await
+            .unwrap();
+
+        assert_eq!(reserves.pool, uniswap_v3_pool_address(weth, token, 100));
+        // token0 is the LST, so the WETH reserve comes from token1
+        assert_eq!(reserves.reserve_x, decimal_to_wei("1000.5").unwrap());
+        assert_eq!(reserves.reserve_y, decimal_to_wei("850.25").unwrap());
+    }
+}
@@ -0,0 +1,130 @@
+//! Curve StableSwap Math
+//!
+//! Integer port of the 2-coin StableSwap invariant (`get_D`, `get_y`,
+//! `get_dy`) so quotes can be derived from pool balances without calling
+//! `get_dy` on-chain for each direction.
+
+use ethers::types::U256;
+
+const N_COINS: u64 = 2;
+const MAX_ITERATIONS: usize = 255;
+
+/// StableSwap invariant D for balances `xp`
+fn get_d(xp: [U256; 2], amp: U256) -> Option<U256> {
+    let n = U256::from(N_COINS);
+    let s = xp[0].checked_add(xp[1])?;
+    if s.is_zero() {
+        return Some(U256::zero());
+    }
+
+    let ann = amp.checked_mul(n)?;
+    let mut d = s;
+
+    for _ in 0..MAX_ITERATIONS {
+        let mut d_p = d;
+        for x in xp {
+            d_p = d_p.checked_mul(d)? / x.checked_mul(n)?.max(U256::one());
+        }
+
+        let d_prev = d;
+        let numerator = ann.checked_mul(s)?.checked_add(d_p.checked_mul(n)?)?.checked_mul(d)?;
+        let denominator = ann.checked_sub(U256::one())?.checked_mul(d)?
+            .checked_add((n + 1).checked_mul(d_p)?)?;
+        d = numerator / denominator;
+
+        if d.abs_diff(d_prev) <= U256::one() {
+            return Some(d);
+        }
+    }
+
+    None
+}
+
+/// Balance of coin j after coin i's balance becomes `x`
+fn get_y(i: usize, j: usize, x: U256, xp: [U256; 2], amp: U256) -> Option<U256> {
+    if i == j || i > 1 || j > 1 {
+        return None;
+    }
+
+    let n = U256::from(N_COINS);
+    let d = get_d(xp, amp)?;
+    let ann = amp.checked_mul(n)?;
+
+    // With two coins, the only other balance is the new x
+    let c = d.checked_mul(d)? / x.checked_mul(n)?.max(U256::one());
+    let c = c.checked_mul(d)? / ann.checked_mul(n)?;
+    let b = x.checked_add(d / ann)?;
+
+    let mut y = d;
+    for _ in 0..MAX_ITERATIONS {
+        let y_prev = y;
+        y = y.checked_mul(y)?.checked_add(c)? / y.checked_mul(2.into())?.checked_add(b)?.checked_sub(d)?;
+
+        if y.abs_diff(y_prev) <= U256::one() {
+            return Some(y);
+        }
+    }
+
+    None
+}
+
+/// Output of swapping `dx` of coin i for coin j, after the pool fee
+pub fn get_dy(i: usize, j: usize, dx: U256, xp: [U256; 2], amp: u64, fee_bps: u64) -> Option<U256> {
+    let x = xp[i].checked_add(dx)?;
+    let y = get_y(i, j, x, xp, U256::from(amp))?;
+    let dy = xp[j].checked_sub(y)?.checked_sub(U256::one())?;
+    let fee = dy * U256::from(fee_bps) / U256::from(10_000u64);
+    Some(dy - fee)
+}
+
+/// Buy (ETH -> LST) and sell (LST -> ETH) quotes for `amount`, from one
+/// read of the pool's (ETH, LST) balances
+pub fn quotes_from_balances(
+    balances: (U256, U256),
+    amount: U256,
+    amp: u64,
+    fee_bps: u64,
+) -> Option<(U256, U256)> {
+    let xp = [balances.0, balances.1];
+    let buy = get_dy(0, 1, amount, xp, amp, fee_bps)?;
+    let sell = get_dy(1, 0, amount, xp, amp, fee_bps)?;
+    Some((buy, sell))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eth(value: u64) -> U256 {
+        U256::from(value) * U256::exp10(18)
+    }
+
+    #[test]
+    fn test_both_directions_from_one_balance_read() {
+        let amount = eth(1);
+
+        // Balanced pool: both directions close to 1:1 minus the 4bp fee
+        let (buy, sell) = quotes_from_balances((eth(10_000), eth(10_000)), amount, 100, 4).unwrap();
+        let expected = U256::from(9996u64) * U256::exp10(14);
+        assert!(buy.abs_diff(expected) < U256::exp10(13), "buy {}", buy);
+        assert_eq!(buy, sell);
+
+        // ETH-heavy pool: LST is scarce, so buying it gives less and selling
+        // it gives more than 1:1
+        let (buy, sell) = quotes_from_balances((eth(15_000), eth(5_000)), amount, 100, 4).unwrap();
+        assert!(buy < expected);
+        assert!(sell > expected);
+    }
+
+    #[test]
+    fn test_invariant_is_preserved() {
+        let xp = [eth(12_000), eth(8_000)];
+        let amp = U256::from(100u64);
+        let d = get_d(xp, amp).unwrap();
+
+        // Fee-less swap keeps D (within rounding)
+        let dy = get_dy(0, 1, eth(50), xp, 100, 0).unwrap();
+        let after = get_d([xp[0] + eth(50), xp[1] - dy], amp).unwrap();
+        assert!(after >= d && after - d < U256::exp10(6));
+    }
+}
@@ -0,0 +1,167 @@
+//! RPC Capability Probe
+//!
+//! Providers differ in the methods they serve: some have no pending tx
+//! feed, can't call against pending state, or disable tracing, access lists
+//! or archive reads. Each endpoint is probed once at startup with a cheap
+//! call per optional method; features that need one the endpoint lacks are
+//! switched off up front instead of failing on every attempt at runtime.
+
+use ethers::prelude::*;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{Address, BlockNumber, TransactionRequest};
+use std::collections::BTreeSet;
+use tracing::{debug, warn};
+
+use super::WsClient;
+
+/// How far below the head the archive probe reads: about three days of
+/// Arbitrum blocks, past what a pruned full node keeps
+pub const ARCHIVE_PROBE_DEPTH: u64 = 1_000_000;
+
+/// Methods (or method variants) not every provider offers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RpcMethod {
+    /// eth_subscribe("newPendingTransactions")
+    PendingTxFeed,
+    /// eth_call against the "pending" block
+    PendingState,
+    CreateAccessList,
+    TraceCall,
+    /// State reads at old blocks
+    ArchiveState,
+}
+
+impl RpcMethod {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::PendingTxFeed => "eth_subscribe(newPendingTransactions)",
+            Self::PendingState => "eth_call(pending)",
+            Self::CreateAccessList => "eth_createAccessList",
+            Self::TraceCall => "debug_traceCall",
+            Self::ArchiveState => "archive state",
+        }
+    }
+}
+
+/// What an endpoint turned out to support. Until probed, everything is
+/// assumed supported, as before the probe existed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RpcCapabilities {
+    unsupported: BTreeSet<RpcMethod>,
+}
+
+impl RpcCapabilities {
+    pub fn supports(&self, method: RpcMethod) -> bool {
+        !self.unsupported.contains(&method)
+    }
+
+    pub fn mark_unsupported(&mut self, method: RpcMethod) {
+        self.unsupported.insert(method);
+    }
+
+    pub fn unsupported(&self) -> impl Iterator<Item = RpcMethod> + '_ {
+        self.unsupported.iter().copied()
+    }
+
+    /// Whether `feature` (which needs `method`) can stay on: `wanted`,
+    /// unless the endpoint lacks the method, in which case it's switched off
+    pub fn allows(&self, feature: &str, method: RpcMethod, wanted: bool) -> bool {
+        if wanted && !self.supports(method) {
+            warn!("⚠️ {} needs {}, which the RPC doesn't serve: disabled", feature, method.name());
+            return false;
+        }
+        wanted
+    }
+}
+
+/// Probe the request-based methods. Each probe is a call a full node
+/// answers successfully, so any error marks the method unsupported.
+pub async fn probe<M: Middleware>(client: &M) -> RpcCapabilities {
+    let mut capabilities = RpcCapabilities::default();
+    let call: TypedTransaction = TransactionRequest::new().to(Address::zero()).into();
+    let archive_read = async {
+        let head = client.get_block_number().await.map_err(|e| e.to_string())?;
+        let block = BlockNumber::Number(archive_probe_block(head.as_u64()).into());
+        client.get_balance(Address::zero(), Some(block.into())).await.map(drop).map_err(|e| e.to_string())
+    };
+
+    let results = [
+        (RpcMethod::PendingState, client.call(&call, Some(BlockNumber::Pending.into())).await.map(drop).map_err(|e| e.to_string())),
+        (RpcMethod::CreateAccessList, client.create_access_list(&call, None).await.map(drop).map_err(|e| e.to_string())),
+        (
+            RpcMethod::TraceCall,
+            client.debug_trace_call(call.clone(), None, GethDebugTracingCallOptions::default()).await
+                .map(drop).map_err(|e| e.to_string()),
+        ),
+        (RpcMethod::ArchiveState, archive_read.await),
+    ];
+    for (method, result) in results {
+        if let Err(e) = result {
+            debug!("{} unavailable: {}", method.name(), e);
+            capabilities.mark_unsupported(method);
+        }
+    }
+    capabilities
+}
+
+/// Block the archive probe reads at. Recent enough to exist on any chain a
+/// node serves (block 1 of Arbitrum One predates Nitro, so even archive
+/// nodes can fail it), old enough that only archive nodes still have it.
+pub fn archive_probe_block(head: u64) -> u64 {
+    head.saturating_sub(ARCHIVE_PROBE_DEPTH).max(1)
+}
+
+/// Whether the endpoint serves a pending tx subscription
+pub async fn probe_pending_tx_feed(client: &WsClient) -> bool {
+    match client.subscribe_pending_txs().await {
+        Ok(stream) => {
+            let _ = stream.unsubscribe().await;
+            true
+        }
+        Err(e) => {
+            debug!("{} unavailable: {}", RpcMethod::PendingTxFeed.name(), e);
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::providers::{JsonRpcError, MockResponse};
+
+    #[tokio::test]
+    async fn test_missing_method_marked_unsupported() {
+        let (provider, mock) = Provider::mocked();
+
+        // Answered last to first: archive read, head block, trace, access list, pending call
+        mock.push::<U256, _>(U256::zero()).unwrap();
+        mock.push::<U64, _>(U64::from(250_000_000u64)).unwrap();
+        mock.push_response(MockResponse::Error(JsonRpcError {
+            code: -32601,
+            message: "the method debug_traceCall does not exist/is not available".into(),
+            data: None,
+        }));
+        mock.push(serde_json::json!({ "accessList": [], "gasUsed": "0x5208" })).unwrap();
+        mock.push::<Bytes, _>(Bytes::new()).unwrap();
+
+        let capabilities = probe(&provider).await;
+        assert!(!capabilities.supports(RpcMethod::TraceCall));
+        assert_eq!(capabilities.unsupported().collect::<Vec<_>>(), vec![RpcMethod::TraceCall]);
+        assert!(capabilities.supports(RpcMethod::PendingState));
+        assert!(capabilities.supports(RpcMethod::ArchiveState));
+        // Features needing the missing method degrade; the rest are untouched
+        assert!(!capabilities.allows("Tracing", RpcMethod::TraceCall, true));
+        assert!(capabilities.allows("Pending simulation", RpcMethod::PendingState, true));
+        assert!(!capabilities.allows("Pending simulation", RpcMethod::PendingState, false));
+        // Unprobed endpoints are assumed capable
+        assert!(RpcCapabilities::default().supports(RpcMethod::PendingTxFeed));
+    }
+
+    #[test]
+    fn test_archive_probe_reads_below_recent_head() {
+        assert_eq!(archive_probe_block(250_000_000), 250_000_000 - ARCHIVE_PROBE_DEPTH);
+        // A young chain (or devnet) still reads a block that exists
+        assert_eq!(archive_probe_block(5_000), 1);
+    }
+}
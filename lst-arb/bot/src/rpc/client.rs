@@ -1,10 +1,12 @@
 use ethers::prelude::*;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
 use dashmap::DashMap;
 use tracing::{info, warn, error};
 
+use super::capabilities::{self, RpcCapabilities, RpcMethod};
+
 pub type WsClient = Provider<Ws>;
 pub type SignedClient = SignerMiddleware<Provider<Ws>, LocalWallet>;
 
@@ -16,6 +18,23 @@ pub struct RpcHealth {
     pub last_check: Instant,
     pub is_healthy: bool,
     pub consecutive_failures: u32,
+    /// Times a dropped or failed connection was re-established
+    pub reconnect_count: u32,
+    /// Optional methods the endpoint serves, once probed
+    pub capabilities: RpcCapabilities,
+}
+
+/// Error messages from a WebSocket whose connection has gone away, as
+/// opposed to a slow or erroring endpoint
+const CLOSED_CONNECTION_ERRORS: [&str; 3] = [
+    "Websocket closed unexpectedly",
+    "Unexpected internal channel closure",
+    "Reconnect limit reached",
+];
+
+/// Whether an RPC error means the WebSocket is closed and must be reconnected
+pub fn is_connection_closed(error: &str) -> bool {
+    CLOSED_CONNECTION_ERRORS.iter().any(|message| error.contains(message))
 }
 
 pub struct RpcLoadBalancer {
@@ -24,6 +43,8 @@ pub struct RpcLoadBalancer {
     primary: RwLock<Option<Arc<WsClient>>>,
     clients: DashMap<String, Arc<WsClient>>,
     max_latency_ms: u64,
+    /// URL of each endpoint whose client was replaced, for resubscribing
+    reconnects: broadcast::Sender<String>,
 }
 
 impl RpcLoadBalancer {
@@ -41,6 +62,7 @@ impl RpcLoadBalancer {
             primary: RwLock::new(None),
             clients: DashMap::new(),
             max_latency_ms,
+            reconnects: broadcast::channel(16).0,
         };
         
         // Initialize connections
@@ -61,6 +83,8 @@ impl RpcLoadBalancer {
                         last_check: Instant::now(),
                         is_healthy: true,
                         consecutive_failures: 0,
+                        reconnect_count: 0,
+                        capabilities: RpcCapabilities::default(),
                     });
                     info!("Connected to RPC: {}", url);
                 }
@@ -73,6 +97,8 @@ impl RpcLoadBalancer {
                         last_check: Instant::now(),
                         is_healthy: false,
                         consecutive_failures: 1,
+                        reconnect_count: 0,
+                        capabilities: RpcCapabilities::default(),
                     });
                 }
             }
@@ -130,49 +156,120 @@ impl RpcLoadBalancer {
         
         None
     }
+
+    /// Pick a healthy client at random, favouring fast and reliable endpoints
+    ///
+    /// Spreads read-heavy work (quote fetching) across backups instead of
+    /// sending everything to the primary. Weights are recomputed from the
+    /// current health data on every call.
+    pub async fn get_client_weighted(&self) -> Option<Arc<WsClient>> {
+        let health: Vec<RpcHealth> = self.health.iter()
+            .filter(|entry| self.clients.contains_key(&entry.url))
+            .map(|entry| entry.value().clone())
+            .collect();
+
+        match weighted_choice(&health, &mut rand::thread_rng()) {
+            Some(url) => self.clients.get(url).map(|c| c.clone()),
+            None => self.get_client().await,
+        }
+    }
+
+    /// A healthy client other than `current`, for retrying a failed call
+    pub fn get_alternate_client(&self, current: &Arc<WsClient>) -> Option<Arc<WsClient>> {
+        self.health.iter()
+            .filter(|entry| entry.is_healthy)
+            .filter_map(|entry| self.clients.get(&entry.url).map(|c| c.clone()))
+            .find(|client| !Arc::ptr_eq(client, current))
+    }
+    
+    /// Notified with an endpoint's URL each time its client is replaced, so
+    /// subscriptions on the old connection can be rebuilt
+    pub fn subscribe_reconnects(&self) -> broadcast::Receiver<String> {
+        self.reconnects.subscribe()
+    }
+
+    /// Replace the client for `url` with a fresh connection. On failure the
+    /// dead handle is dropped so it is never handed out again.
+    async fn reconnect(&self, url: &str) -> Option<Arc<WsClient>> {
+        let stale = self.clients.remove(url).map(|(_, client)| client);
+
+        let fresh = match self.connect(url).await {
+            Ok(client) => Some(Arc::new(client)),
+            Err(e) => {
+                if stale.is_some() {
+                    warn!("Reconnect to {} failed: {:?}", url, e);
+                }
+                None
+            }
+        };
+
+        if let Some(stale) = &stale {
+            let mut primary = self.primary.write().await;
+            if primary.as_ref().is_some_and(|p| Arc::ptr_eq(p, stale)) {
+                *primary = fresh.clone();
+            }
+        }
+
+        let Some(fresh) = fresh else {
+            if let Some(mut health) = self.health.get_mut(url) {
+                health.is_healthy = false;
+            }
+            return None;
+        };
+
+        self.clients.insert(url.to_string(), fresh.clone());
+        if let Some(mut health) = self.health.get_mut(url) {
+            health.reconnect_count += 1;
+        }
+
+        info!("🔌 Reconnected to RPC: {}", url);
+        let _ = self.reconnects.send(url.to_string());
+        Some(fresh)
+    }
     
     pub async fn health_check(&self) {
         for url in &self.endpoints {
-            let client = match self.clients.get(url) {
-                Some(c) => c.clone(),
-                None => {
-                    // Try to reconnect
-                    match self.connect(url).await {
-                        Ok(c) => {
-                            self.clients.insert(url.clone(), Arc::new(c));
-                            self.clients.get(url).unwrap().clone()
-                        }
-                        Err(_) => continue,
-                    }
-                }
+            let client = match self.clients.get(url).map(|c| c.clone()) {
+                Some(c) => c,
+                None => match self.reconnect(url).await {
+                    Some(c) => c,
+                    None => continue,
+                },
             };
             
             let start = Instant::now();
-            match tokio::time::timeout(
+            let result = tokio::time::timeout(
                 Duration::from_millis(self.max_latency_ms * 2),
                 client.get_block_number()
-            ).await {
-                Ok(Ok(_block)) => {
-                    let latency = start.elapsed().as_millis() as u64;
-                    
-                    if let Some(mut health) = self.health.get_mut(url) {
-                        health.latency_ms = latency;
-                        health.success_rate = health.success_rate * 0.9 + 0.1;
-                        health.is_healthy = latency < self.max_latency_ms;
-                        health.last_check = Instant::now();
-                        health.consecutive_failures = 0;
-                    }
+            ).await;
+
+            if let Ok(Ok(_block)) = result {
+                let latency = start.elapsed().as_millis() as u64;
+                
+                if let Some(mut health) = self.health.get_mut(url) {
+                    health.latency_ms = latency;
+                    health.success_rate = health.success_rate * 0.9 + 0.1;
+                    health.is_healthy = latency < self.max_latency_ms;
+                    health.last_check = Instant::now();
+                    health.consecutive_failures = 0;
                 }
-                _ => {
-                    if let Some(mut health) = self.health.get_mut(url) {
-                        health.success_rate = health.success_rate * 0.9;
-                        health.consecutive_failures += 1;
-                        health.is_healthy = health.consecutive_failures < 3;
-                        health.last_check = Instant::now();
-                    }
-                    
-                    warn!("Health check failed for: {}", url);
+                continue;
+            }
+
+            if let Some(mut health) = self.health.get_mut(url) {
+                health.success_rate = health.success_rate * 0.9;
+                health.consecutive_failures += 1;
+                health.is_healthy = health.consecutive_failures < 3;
+                health.last_check = Instant::now();
+            }
+            
+            match result {
+                // A closed socket won't recover by waiting for more failed checks
+                Ok(Err(e)) if is_connection_closed(&e.to_string()) => {
+                    warn!("WebSocket to {} closed: {}", url, e);
+                    self.reconnect(url).await;
                 }
+                _ => warn!("Health check failed for: {}", url),
             }
         }
         
@@ -180,11 +277,70 @@ impl RpcLoadBalancer {
         self.select_primary().await;
     }
     
+    /// Probe each connected endpoint for the optional methods it serves
+    pub async fn probe_capabilities(&self) {
+        for url in &self.endpoints {
+            let Some(client) = self.clients.get(url).map(|c| c.clone()) else { continue };
+            let mut probed = capabilities::probe(&*client).await;
+            if !capabilities::probe_pending_tx_feed(&client).await {
+                probed.mark_unsupported(RpcMethod::PendingTxFeed);
+            }
+
+            let missing: Vec<&str> = probed.unsupported().map(|method| method.name()).collect();
+            if missing.is_empty() {
+                info!("🔎 {} serves every optional method", url);
+            } else {
+                info!("🔎 {} lacks: {}", url, missing.join(", "));
+            }
+            if let Some(mut health) = self.health.get_mut(url) {
+                health.capabilities = probed;
+            }
+        }
+    }
+
+    /// Capabilities of the endpoint behind `client` (unknown clients are
+    /// assumed fully capable)
+    pub fn capabilities_of(&self, client: &Arc<WsClient>) -> RpcCapabilities {
+        self.clients.iter()
+            .find(|entry| Arc::ptr_eq(entry.value(), client))
+            .and_then(|entry| self.health.get(entry.key()).map(|health| health.capabilities.clone()))
+            .unwrap_or_default()
+    }
+
     pub fn get_health_stats(&self) -> Vec<RpcHealth> {
         self.health.iter().map(|e| e.value().clone()).collect()
     }
 }
 
+/// Relative share of traffic for an endpoint: proportional to its success
+/// rate and inversely proportional to its latency
+fn endpoint_weight(health: &RpcHealth) -> f64 {
+    if !health.is_healthy {
+        return 0.0;
+    }
+    health.success_rate.max(0.0) / health.latency_ms.max(1) as f64
+}
+
+/// Choose an endpoint URL with probability proportional to its weight
+fn weighted_choice<'a>(health: &'a [RpcHealth], rng: &mut impl rand::Rng) -> Option<&'a str> {
+    let total: f64 = health.iter().map(endpoint_weight).sum();
+    if total <= 0.0 || !total.is_finite() {
+        return None;
+    }
+
+    let mut roll = rng.gen::<f64>() * total;
+    for entry in health {
+        let weight = endpoint_weight(entry);
+        if weight > 0.0 && roll < weight {
+            return Some(&entry.url);
+        }
+        roll -= weight;
+    }
+
+    // Float rounding left the roll just past the last weight
+    health.iter().rev().find(|h| endpoint_weight(h) > 0.0).map(|h| h.url.as_str())
+}
+
 // Signed client for transactions
 pub struct SignedClientManager {
     wallet: LocalWallet,
@@ -203,3 +359,64 @@ impl SignedClientManager {
         Some(SignerMiddleware::new(provider, wallet))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    fn health(url: &str, latency_ms: u64, success_rate: f64, is_healthy: bool) -> RpcHealth {
+        RpcHealth {
+            url: url.into(),
+            latency_ms,
+            success_rate,
+            last_check: Instant::now(),
+            is_healthy,
+            consecutive_failures: 0,
+            reconnect_count: 0,
+            capabilities: RpcCapabilities::default(),
+        }
+    }
+
+    #[test]
+    fn test_weighted_choice_favours_low_latency() {
+        let endpoints = vec![
+            health("fast", 10, 1.0, true),
+            health("slow", 100, 1.0, true),
+            health("flaky", 10, 0.5, true),
+            health("down", 1, 1.0, false),
+        ];
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+
+        let mut counts = std::collections::HashMap::new();
+        for _ in 0..20_000 {
+            let url = weighted_choice(&endpoints, &mut rng).unwrap();
+            *counts.entry(url).or_insert(0u32) += 1;
+        }
+
+        // Weights 0.1 : 0.01 : 0.05 -> 62.5% : 6.25% : 31.25%
+        let share = |url| counts.get(url).copied().unwrap_or(0) as f64 / 20_000.0;
+        assert!((share("fast") - 0.625).abs() < 0.02, "fast {}", share("fast"));
+        assert!((share("slow") - 0.0625).abs() < 0.02, "slow {}", share("slow"));
+        assert!((share("flaky") - 0.3125).abs() < 0.02, "flaky {}", share("flaky"));
+        assert_eq!(share("down"), 0.0);
+    }
+
+    #[test]
+    fn test_weighted_choice_without_healthy_endpoints() {
+        let endpoints = vec![health("down", 10, 1.0, false)];
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        assert!(weighted_choice(&endpoints, &mut rng).is_none());
+        assert!(weighted_choice(&[], &mut rng).is_none());
+    }
+
+    #[test]
+    fn test_closed_socket_errors_detected() {
+        assert!(is_connection_closed("(code: -1) Websocket closed unexpectedly"));
+        assert!(is_connection_closed(
+            "Unexpected internal channel closure. This is likely a bug. Please report via github"
+        ));
+        assert!(!is_connection_closed("request timed out"));
+        assert!(!is_connection_closed("(code: -32005, message: rate limit exceeded)"));
+    }
+}
@@ -0,0 +1,120 @@
+//! Local vs Chain Clock
+//!
+//! Staleness checks compare chain timestamps against the local clock, so a
+//! misconfigured server clock makes data look fresher or staler than it is.
+//! `ChainClock` measures the offset between the two from the latest block
+//! and warns when it is significant. When enabled, a skewed local clock is
+//! corrected by the measured offset so chain time becomes authoritative.
+
+use ethers::prelude::*;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+pub struct ChainClock {
+    /// Chain time minus local time, from the last observation
+    offset_ms: AtomicI64,
+    max_skew_ms: i64,
+    use_chain_time: bool,
+}
+
+impl ChainClock {
+    pub fn new(max_skew: Duration, use_chain_time: bool) -> Self {
+        Self {
+            offset_ms: AtomicI64::new(0),
+            max_skew_ms: max_skew.as_millis() as i64,
+            use_chain_time,
+        }
+    }
+
+    /// Record a block timestamp seen at `local_ms`; returns true when the skew is significant
+    pub fn observe(&self, block_timestamp_secs: u64, local_ms: u64) -> bool {
+        let offset = (block_timestamp_secs * 1000) as i64 - local_ms as i64;
+        self.offset_ms.store(offset, Ordering::Relaxed);
+
+        if self.is_skewed() {
+            warn!(
+                "⚠️ Local clock is {}ms {} chain time{}",
+                offset.abs(),
+                if offset > 0 { "behind" } else { "ahead of" },
+                if self.use_chain_time { " - using chain time" } else { "" }
+            );
+            true
+        } else {
+            debug!("Clock offset to chain: {}ms", offset);
+            false
+        }
+    }
+
+    /// Fetch the latest block and record its timestamp
+    pub async fn sync<M: Middleware>(&self, client: &M) -> eyre::Result<bool> {
+        let block = client.get_block(BlockNumber::Latest).await
+            .map_err(|e| eyre::eyre!("Failed to fetch latest block: {}", e))?
+            .ok_or_else(|| eyre::eyre!("Latest block not found"))?;
+
+        Ok(self.observe(block.timestamp.as_u64(), local_now_ms()))
+    }
+
+    pub fn is_skewed(&self) -> bool {
+        self.max_skew_ms > 0 && self.offset_ms.load(Ordering::Relaxed).abs() > self.max_skew_ms
+    }
+
+    /// Current time in ms, corrected to chain time when the local clock is skewed
+    pub fn now_ms(&self) -> u64 {
+        let local = local_now_ms();
+        if self.use_chain_time && self.is_skewed() {
+            (local as i64 + self.offset_ms.load(Ordering::Relaxed)).max(0) as u64
+        } else {
+            local
+        }
+    }
+
+    pub fn now_secs(&self) -> u64 {
+        self.now_ms() / 1000
+    }
+}
+
+fn local_now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_skew_falls_back_to_chain_time() {
+        let clock = ChainClock::new(Duration::from_secs(5), true);
+        let local = local_now_ms();
+
+        // Block a second behind local time is normal latency
+        assert!(!clock.observe(local / 1000 - 1, local));
+        assert!(clock.now_ms().abs_diff(local_now_ms()) < 1_000);
+
+        // Local clock running two minutes fast
+        assert!(clock.observe(local / 1000 - 120, local));
+        assert!(clock.now_ms().abs_diff(local_now_ms() - 120_000) < 2_000);
+
+        // Without the fallback only the warning fires
+        let warn_only = ChainClock::new(Duration::from_secs(5), false);
+        assert!(warn_only.observe(local / 1000 - 120, local));
+        assert!(warn_only.now_ms().abs_diff(local_now_ms()) < 1_000);
+    }
+
+    #[tokio::test]
+    async fn test_sync_from_latest_block() {
+        let (provider, mock) = Provider::mocked();
+        let block = Block::<TxHash> {
+            timestamp: U256::from(local_now_ms() / 1000 + 3600),
+            ..Default::default()
+        };
+        mock.push(block).unwrap();
+
+        let clock = ChainClock::new(Duration::from_secs(5), true);
+        assert!(clock.sync(&provider).await.unwrap());
+        assert!(clock.now_secs() >= local_now_ms() / 1000 + 3590);
+    }
+}
@@ -1,3 +1,9 @@
+mod capabilities;
 mod client;
+mod clock;
+mod retry;
 
+pub use capabilities::*;
 pub use client::*;
+pub use clock::*;
+pub use retry::*;
@@ -0,0 +1,152 @@
+//! Retry with Backoff
+//!
+//! Quote fetch failures are usually endpoint-specific (a timeout or rate
+//! limit on one RPC), so a failed call is retried on a different healthy
+//! client rather than abandoning the whole scan. Only transport failures are
+//! retried: a response that fails to decode will fail the same way again.
+//! Backoff is capped so retries never eat more than a fraction of a block.
+
+use std::future::Future;
+use std::time::Duration;
+use tracing::warn;
+
+/// First retry delay; doubled on each further attempt
+const BACKOFF_BASE: Duration = Duration::from_millis(25);
+
+/// Total time spent sleeping between attempts
+const BACKOFF_BUDGET: Duration = Duration::from_millis(200);
+
+/// An RPC call that failed before a response came back (timeout, dropped
+/// connection, rate limit), as opposed to a response that failed to decode
+#[derive(Debug, thiserror::Error)]
+#[error("transport error: {0}")]
+pub struct TransportError(pub String);
+
+/// Whether `err` is worth retrying on another endpoint
+pub fn is_transport_error(err: &eyre::Report) -> bool {
+    err.downcast_ref::<TransportError>().is_some()
+}
+
+/// Run `call` on `client`, retrying transport errors up to `max_retries`
+/// times with exponential backoff. Each retry runs on the client returned by
+/// `alternate` for the one that just failed, or the same client if there is
+/// no other.
+pub async fn with_backoff<C, T, F, Fut>(
+    client: C,
+    mut alternate: impl FnMut(&C) -> Option<C>,
+    max_retries: u32,
+    mut call: F,
+) -> eyre::Result<T>
+where
+    F: FnMut(C) -> Fut,
+    C: Clone,
+    Fut: Future<Output = eyre::Result<T>>,
+{
+    let mut current = client;
+    let mut delay = BACKOFF_BASE;
+    let mut slept = Duration::ZERO;
+    let mut attempt = 0;
+
+    loop {
+        let err = match call(current.clone()).await {
+            Ok(value) => return Ok(value),
+            Err(e) => e,
+        };
+
+        let remaining = BACKOFF_BUDGET.saturating_sub(slept);
+        if attempt >= max_retries || !is_transport_error(&err) || remaining.is_zero() {
+            return Err(err);
+        }
+
+        let wait = delay.min(remaining);
+        warn!(
+            "🔁 RPC call failed ({:?}), retrying in {}ms (attempt {}/{})",
+            err, wait.as_millis(), attempt + 1, max_retries
+        );
+        tokio::time::sleep(wait).await;
+        slept += wait;
+        delay *= 2;
+        attempt += 1;
+
+        if let Some(other) = alternate(&current) {
+            current = other;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::price::{Quote, TokenQuotes, Venue};
+    use ethers::types::{Address, U256};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Instant;
+
+    async fn fetch_quotes(client: u32) -> eyre::Result<Vec<TokenQuotes>> {
+        match client {
+            1 => return Err(TransportError("request timed out".into()).into()),
+            3 => eyre::bail!("invalid data returned for Curve quote"),
+            _ => {}
+        }
+        Ok(vec![TokenQuotes {
+            token: Address::from_low_u64_be(1),
+            token_name: "wstETH".into(),
+            quotes: vec![(Venue::Curve, Quote { buy_amount: U256::from(995u64), ..Default::default() })],
+            curve_amp: None,
+            sized_quotes: Vec::new(),
+            fetched_at_ms: 0,
+            block_number: None,
+            base_token: None,
+        }])
+    }
+
+    #[tokio::test]
+    async fn test_failed_fetch_retried_on_alternate_client() {
+        let calls = AtomicUsize::new(0);
+        let quotes = with_backoff(1u32, |_| Some(2), 2, |client| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            fetch_quotes(client)
+        }).await.unwrap();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(quotes.len(), 1);
+        assert_eq!(quotes[0].quotes[0].1.buy_amount, U256::from(995u64));
+    }
+
+    #[tokio::test]
+    async fn test_no_alternate_returns_original_error() {
+        let err = with_backoff(1u32, |_| None, 2, fetch_quotes).await.unwrap_err();
+        assert!(err.to_string().contains("timed out"));
+
+        // A successful first call never asks for an alternate
+        let quotes = with_backoff(2u32, |_| panic!("no retry expected"), 2, fetch_quotes).await;
+        assert!(quotes.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_decode_error_not_retried() {
+        let calls = AtomicUsize::new(0);
+        let err = with_backoff(3u32, |_| Some(2), 3, |client| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            fetch_quotes(client)
+        }).await.unwrap_err();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert!(!is_transport_error(&err));
+    }
+
+    #[tokio::test]
+    async fn test_backoff_bounded_by_budget() {
+        let calls = AtomicUsize::new(0);
+        let start = Instant::now();
+        let err = with_backoff(1u32, |_| Some(1), 10, |client| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            fetch_quotes(client)
+        }).await.unwrap_err();
+
+        // 25 + 50 + 100 + 25 (the rest of the budget), then give up
+        assert!(is_transport_error(&err));
+        assert_eq!(calls.load(Ordering::SeqCst), 5);
+        assert!(start.elapsed() < BACKOFF_BUDGET + Duration::from_millis(100));
+    }
+}
@@ -4,16 +4,21 @@
 //! - Tiered pool monitoring (Stream, Poll, Lazy)
 //! - Automatic downgrade of unproductive pools
 //! - WebSocket connection management
+//! - Persistence of promoted pools across restarts
 
 use ethers::types::Address;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
 use tracing::{debug, info, warn};
 
+use crate::watcher::shutdown_signal;
+
 /// Pool monitoring tiers
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PoolTier {
     /// Tier 1: Active WebSocket stream subscription
     /// Used for high-activity pools with recent arbitrage opportunities
@@ -78,14 +83,33 @@ impl TargetPool {
     /// Check if pool should be downgraded based on inactivity
     /// Returns true if no profitable arbs found in the specified duration
     pub fn should_downgrade(&self, inactivity_threshold: Duration) -> bool {
-        match self.last_opportunity_ts {
-            Some(ts) => ts.elapsed() > inactivity_threshold,
-            // If never had an opportunity, check against added time
-            None => self.added_ts.elapsed() > inactivity_threshold,
-        }
+        self.inactive_for() > inactivity_threshold
+    }
+
+    /// Time left before the pool is downgraded for inactivity
+    pub fn promotion_remaining(&self, inactivity_threshold: Duration) -> Duration {
+        inactivity_threshold.saturating_sub(self.inactive_for())
+    }
+
+    fn inactive_for(&self) -> Duration {
+        // If never had an opportunity, count from when it was added
+        self.last_opportunity_ts.unwrap_or(self.added_ts).elapsed()
     }
 }
 
+/// On-disk form of a pool's scheduling state
+///
+/// `Instant`s don't survive a restart, so the inactivity timer is stored as
+/// the time remaining before the pool would be downgraded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PersistedPool {
+    pub address: Address,
+    pub name: String,
+    pub tier: PoolTier,
+    pub opportunity_count: u64,
+    pub promotion_remaining_secs: u64,
+}
+
 /// Pool Scheduler for managing monitored pools
 pub struct PoolScheduler {
     /// All tracked pools by address
@@ -94,6 +118,10 @@ pub struct PoolScheduler {
     inactivity_threshold: Duration,
     /// Cleanup cycle interval (default: 5 minutes)
     cleanup_interval: Duration,
+    /// Where tier state is saved after each cleanup cycle
+    state_file: Option<PathBuf>,
+    /// Stops the cleanup task when fired
+    shutdown: Option<broadcast::Sender<()>>,
 }
 
 impl PoolScheduler {
@@ -102,6 +130,8 @@ impl PoolScheduler {
             pools: Arc::new(RwLock::new(HashMap::new())),
             inactivity_threshold: Duration::from_secs(60 * 60), // 60 minutes
             cleanup_interval: Duration::from_secs(5 * 60),       // 5 minutes
+            state_file: None,
+            shutdown: None,
         }
     }
 
@@ -110,9 +140,23 @@ impl PoolScheduler {
             pools: Arc::new(RwLock::new(HashMap::new())),
             inactivity_threshold: Duration::from_secs(inactivity_minutes * 60),
             cleanup_interval: Duration::from_secs(cleanup_minutes * 60),
+            state_file: None,
+            shutdown: None,
         }
     }
 
+    /// Persist tier state to this file after each cleanup cycle
+    pub fn with_state_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.state_file = Some(path.into());
+        self
+    }
+
+    /// Stop the cleanup task when the shutdown channel fires
+    pub fn with_shutdown(mut self, shutdown: broadcast::Sender<()>) -> Self {
+        self.shutdown = Some(shutdown);
+        self
+    }
+
     /// Add a pool to monitoring
     pub async fn add_pool(&self, address: Address, name: String, tier: PoolTier) {
         let mut pools = self.pools.write().await;
@@ -132,6 +176,18 @@ impl PoolScheduler {
         }
     }
 
+    /// Record an opportunity on a pool, adding it or promoting it back to
+    /// the Stream tier if it isn't there already
+    pub async fn promote(&self, address: Address, name: &str) {
+        let tier = self.pools.read().await.get(&address).map(|p| p.tier);
+        match tier {
+            None => self.add_pool(address, name.to_string(), PoolTier::Stream).await,
+            Some(PoolTier::Stream) => {}
+            Some(_) => self.upgrade_pool(address, PoolTier::Stream).await,
+        }
+        self.record_opportunity(address).await;
+    }
+
     /// Upgrade a pool to a higher tier
     pub async fn upgrade_pool(&self, address: Address, new_tier: PoolTier) {
         let mut pools = self.pools.write().await;
@@ -226,17 +282,92 @@ impl PoolScheduler {
         result
     }
 
+    /// Snapshot every pool's tier and remaining promotion time
+    pub async fn snapshot(&self) -> Vec<PersistedPool> {
+        let pools = self.pools.read().await;
+        pools
+            .values()
+            .map(|p| PersistedPool {
+                address: p.address,
+                name: p.name.clone(),
+                tier: p.tier,
+                opportunity_count: p.opportunity_count,
+                promotion_remaining_secs: p.promotion_remaining(self.inactivity_threshold).as_secs(),
+            })
+            .collect()
+    }
+
+    /// Restore pools from a snapshot, resuming each promotion where it left off
+    pub async fn restore(&self, snapshot: Vec<PersistedPool>) {
+        let now = Instant::now();
+        let mut pools = self.pools.write().await;
+
+        for saved in snapshot {
+            let mut pool = TargetPool::new(saved.address, saved.name, saved.tier);
+            pool.opportunity_count = saved.opportunity_count;
+
+            // Backdate the last opportunity so the same time remains before downgrade
+            let elapsed = self
+                .inactivity_threshold
+                .saturating_sub(Duration::from_secs(saved.promotion_remaining_secs));
+            pool.last_opportunity_ts = Some(now.checked_sub(elapsed).unwrap_or(now));
+
+            pools.insert(pool.address, pool);
+        }
+
+        info!("Restored {} pools from saved scheduler state", pools.len());
+    }
+
+    /// Save tier state to the configured state file
+    pub async fn save_state(&self) -> eyre::Result<()> {
+        let Some(path) = &self.state_file else {
+            return Ok(());
+        };
+
+        let json = serde_json::to_string_pretty(&self.snapshot().await)?;
+        // Write then rename so a crash mid-write can't corrupt the saved state
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Load tier state from the configured state file, if it exists
+    pub async fn load_state(&self) -> eyre::Result<()> {
+        let Some(path) = &self.state_file else {
+            return Ok(());
+        };
+        if !path.exists() {
+            return Ok(());
+        }
+
+        let snapshot: Vec<PersistedPool> = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        self.restore(snapshot).await;
+        Ok(())
+    }
+
     /// Start the automatic cleanup task
     /// Runs every 5 minutes (configurable)
     pub fn start_cleanup_task(self: Arc<Self>) -> tokio::task::JoinHandle<()> {
         let interval = self.cleanup_interval;
+        let mut shutdown_rx = self.shutdown.as_ref().map(|s| s.subscribe());
         tokio::spawn(async move {
             let mut ticker = tokio::time::interval(interval);
             // Skip immediate first tick
             ticker.tick().await;
 
             loop {
-                ticker.tick().await;
+                tokio::select! {
+                    _ = ticker.tick() => {}
+                    _ = shutdown_signal(&mut shutdown_rx) => {
+                        // Keep the latest tiers for the next start
+                        if let Err(e) = self.save_state().await {
+                            warn!("Failed to save scheduler state: {:?}", e);
+                        }
+                        info!("Scheduler cleanup task stopped");
+                        return;
+                    }
+                }
                 let result = self.run_cleanup_cycle().await;
                 if result.pools_downgraded > 0 {
                     info!(
@@ -244,6 +375,9 @@ impl PoolScheduler {
                         result.pools_downgraded
                     );
                 }
+                if let Err(e) = self.save_state().await {
+                    warn!("Failed to save scheduler state: {:?}", e);
+                }
             }
         })
     }
@@ -307,6 +441,20 @@ pub struct SchedulerStats {
 mod tests {
     use super::*;
 
+    #[tokio::test]
+    async fn test_cleanup_task_stops_on_shutdown() {
+        let (shutdown_tx, _) = broadcast::channel(1);
+        let scheduler = Arc::new(PoolScheduler::new().with_shutdown(shutdown_tx.clone()));
+
+        let handle = scheduler.start_cleanup_task();
+        shutdown_tx.send(()).unwrap();
+
+        tokio::time::timeout(Duration::from_secs(1), handle)
+            .await
+            .expect("cleanup task should stop on shutdown")
+            .unwrap();
+    }
+
     #[tokio::test]
     async fn test_pool_lifecycle() {
         let scheduler = PoolScheduler::new();
@@ -412,4 +560,60 @@ mod tests {
         assert_eq!(stats.tier3_count, 1);
         assert_eq!(stats.active_websockets, 1);
     }
+
+    #[tokio::test]
+    async fn test_promotion_survives_restart() {
+        let path = std::env::temp_dir().join(format!("scheduler-state-{}.json", std::process::id()));
+        let addr: Address = "0x1234567890123456789012345678901234567890"
+            .parse()
+            .unwrap();
+
+        // 60 minute threshold, pool promoted with 40 minutes of it left
+        let scheduler = PoolScheduler::with_thresholds(60, 5).with_state_file(&path);
+        scheduler
+            .restore(vec![PersistedPool {
+                address: addr,
+                name: "Hot Pool".to_string(),
+                tier: PoolTier::Stream,
+                opportunity_count: 7,
+                promotion_remaining_secs: 40 * 60,
+            }])
+            .await;
+        scheduler.save_state().await.unwrap();
+
+        let restarted = PoolScheduler::with_thresholds(60, 5).with_state_file(&path);
+        restarted.load_state().await.unwrap();
+        std::fs::remove_file(&path).ok();
+
+        let pools = restarted.get_pools_by_tier(PoolTier::Stream).await;
+        assert_eq!(pools.len(), 1);
+        assert_eq!(pools[0].opportunity_count, 7);
+
+        let remaining = pools[0].promotion_remaining(Duration::from_secs(3600));
+        assert!(remaining <= Duration::from_secs(40 * 60));
+        assert!(remaining > Duration::from_secs(40 * 60 - 5));
+
+        // Still promoted: not due for downgrade yet
+        assert_eq!(restarted.run_cleanup_cycle().await.pools_downgraded, 0);
+    }
+
+    #[tokio::test]
+    async fn test_promote_streams_new_and_downgraded_pools() {
+        let scheduler = PoolScheduler::new();
+        let addr: Address = "0x1234567890123456789012345678901234567890"
+            .parse()
+            .unwrap();
+
+        scheduler.promote(addr, "New Pool").await;
+        let pools = scheduler.get_pools_by_tier(PoolTier::Stream).await;
+        assert_eq!(pools.len(), 1);
+        assert_eq!(pools[0].opportunity_count, 1);
+
+        scheduler.downgrade_pool(addr, PoolTier::Lazy).await;
+        scheduler.promote(addr, "New Pool").await;
+        let pools = scheduler.get_pools_by_tier(PoolTier::Stream).await;
+        assert_eq!(pools.len(), 1);
+        assert!(pools[0].ws_active);
+        assert_eq!(pools[0].opportunity_count, 2);
+    }
 }
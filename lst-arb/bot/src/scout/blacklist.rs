@@ -0,0 +1,319 @@
+//! Persistent Pool Blacklist
+//!
+//! A pool that keeps reverting (paused, drained, a fee-on-transfer token)
+//! would otherwise be re-learned the hard way after every restart. Pools
+//! whose token fails a `SafetyChecker` check, and (opt-in) pools that
+//! revert too many times in a row, are blacklisted with a reason and
+//! timestamp, skipped by discovery and execution, and saved to a file that
+//! is reloaded on startup. Entries can expire, so a temporarily broken pool
+//! is re-evaluated after a while.
+//!
+//! Revert counting is off by default: on Arbitrum most reverts are lost
+//! races or a missed minProfit, not a broken pool.
+
+use dashmap::DashMap;
+use ethers::types::Address;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{info, warn};
+
+use super::TokenSafetyResult;
+use crate::detector::Opportunity;
+use crate::executor::ExecutionResult;
+
+/// Consecutive reverts through a pool that blacklist it (0 = never)
+pub const DEFAULT_BLACKLIST_AFTER_REVERTS: u32 = 0;
+
+/// How long a blacklisting lasts before the pool is re-evaluated
+pub const DEFAULT_BLACKLIST_TTL: Duration = Duration::from_secs(86_400);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlacklistEntry {
+    pub reason: String,
+    /// Unix ms when the pool was blacklisted
+    pub added_at_ms: u64,
+    /// Unix ms after which the pool is tried again (None = permanent)
+    pub expires_at_ms: Option<u64>,
+}
+
+impl BlacklistEntry {
+    fn is_active(&self, now_ms: u64) -> bool {
+        self.expires_at_ms.is_none_or(|expires| now_ms < expires)
+    }
+}
+
+pub struct PoolBlacklist {
+    /// Saved to on every change (None = this session only)
+    path: Option<PathBuf>,
+    /// Lifetime of new entries (None = permanent)
+    ttl: Option<Duration>,
+    /// 0 = never blacklist on reverts
+    revert_threshold: u32,
+    entries: Arc<DashMap<Address, BlacklistEntry>>,
+    consecutive_reverts: DashMap<Address, u32>,
+    /// Serializes background saves so an older snapshot can't land last
+    save_lock: Arc<Mutex<()>>,
+}
+
+impl Default for PoolBlacklist {
+    fn default() -> Self {
+        Self {
+            path: None,
+            ttl: Some(DEFAULT_BLACKLIST_TTL),
+            revert_threshold: DEFAULT_BLACKLIST_AFTER_REVERTS,
+            entries: Arc::new(DashMap::new()),
+            consecutive_reverts: DashMap::new(),
+            save_lock: Arc::new(Mutex::new(())),
+        }
+    }
+}
+
+impl PoolBlacklist {
+    /// Entries saved at `path` (none if the file doesn't exist yet), saving
+    /// back to it on every change
+    pub fn load(path: &Path) -> eyre::Result<Self> {
+        let blacklist = Self { path: Some(path.to_path_buf()), ..Self::default() };
+        if path.exists() {
+            let saved: BTreeMap<Address, BlacklistEntry> = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+            for (pool, entry) in saved {
+                blacklist.entries.insert(pool, entry);
+            }
+        }
+        Ok(blacklist)
+    }
+
+    /// Write the active entries to `path`
+    pub fn save(&self, path: &Path, now_ms: u64) -> eyre::Result<()> {
+        let _guard = self.save_lock.lock().unwrap_or_else(|e| e.into_inner());
+        save_entries(&self.entries, path, now_ms)
+    }
+
+    /// Entry lifetime (None = permanent)
+    pub fn with_ttl(mut self, ttl: Option<Duration>) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Blacklist a pool after this many consecutive reverts (0 = never)
+    pub fn with_revert_threshold(mut self, reverts: u32) -> Self {
+        self.revert_threshold = reverts;
+        self
+    }
+
+    pub fn blacklist_pool(&self, pool: Address, reason: String, now_ms: u64) {
+        warn!("🚫 Blacklisting pool {:?}: {}", pool, reason);
+        self.entries.insert(pool, BlacklistEntry {
+            reason,
+            added_at_ms: now_ms,
+            expires_at_ms: self.ttl.map(|ttl| now_ms + ttl.as_millis() as u64),
+        });
+        self.persist(now_ms);
+    }
+
+    /// Why `pool` is blacklisted, if it still is
+    pub fn reason(&self, pool: Address, now_ms: u64) -> Option<BlacklistEntry> {
+        self.entries.get(&pool)
+            .filter(|entry| entry.is_active(now_ms))
+            .map(|entry| entry.clone())
+    }
+
+    /// Pools currently blacklisted
+    pub fn active(&self, now_ms: u64) -> Vec<Address> {
+        self.entries.iter()
+            .filter(|entry| entry.is_active(now_ms))
+            .map(|entry| *entry.key())
+            .collect()
+    }
+
+    pub fn is_blacklisted(&self, pool: Address, now_ms: u64) -> bool {
+        self.reason(pool, now_ms).is_some()
+    }
+
+    /// Whether `opportunity` trades through a blacklisted pool
+    pub fn blocks(&self, opportunity: &Opportunity, now_ms: u64) -> bool {
+        [opportunity.buy_pool, opportunity.sell_pool].into_iter()
+            .chain(opportunity.route.iter().map(|leg| leg.pool))
+            .any(|pool| self.is_blacklisted(pool, now_ms))
+    }
+
+    /// Blacklist `pools` if their `token` failed a safety check. Errors and
+    /// unexplained reverts (e.g. the bot holds none of the token) are not
+    /// findings and are ignored.
+    pub fn record_safety(&self, token: Address, pools: &[Address], result: &TokenSafetyResult, now_ms: u64) {
+        let reason = match result {
+            TokenSafetyResult::TaxToken { gas_used } => format!("token {:?} transfer uses {} gas (tax token)", token, gas_used),
+            TokenSafetyResult::Paused => format!("token {:?} is paused", token),
+            TokenSafetyResult::Blacklisted => format!("token {:?} blacklists the bot", token),
+            TokenSafetyResult::Safe { .. }
+            | TokenSafetyResult::Reverted { .. }
+            | TokenSafetyResult::Error { .. } => return,
+        };
+        for pool in pools {
+            if !self.is_blacklisted(*pool, now_ms) {
+                self.blacklist_pool(*pool, reason.clone(), now_ms);
+            }
+        }
+    }
+
+    /// Count a resolved trade against its pools, route legs included: a
+    /// confirmation clears their revert streaks, and the streak reaching
+    /// the threshold blacklists the pool
+    pub fn record_outcome(&self, opportunity: &Opportunity, result: &ExecutionResult, now_ms: u64) {
+        let mut seen = HashSet::new();
+        let pools: Vec<Address> = [opportunity.buy_pool, opportunity.sell_pool].into_iter()
+            .chain(opportunity.route.iter().map(|leg| leg.pool))
+            .filter(|pool| !pool.is_zero() && seen.insert(*pool))
+            .collect();
+        let pools = pools.iter();
+        match result {
+            ExecutionResult::Confirmed { .. } => {
+                for pool in pools {
+                    self.consecutive_reverts.remove(pool);
+                }
+            }
+            ExecutionResult::Reverted { reason, .. } if self.revert_threshold > 0 => {
+                for pool in pools {
+                    let reverts = {
+                        let mut count = self.consecutive_reverts.entry(*pool).or_default();
+                        *count += 1;
+                        *count
+                    };
+                    if reverts >= self.revert_threshold && !self.is_blacklisted(*pool, now_ms) {
+                        self.consecutive_reverts.remove(pool);
+                        self.blacklist_pool(*pool, format!("{} consecutive reverts, last: {}", reverts, reason), now_ms);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Save in the background when called from the runtime, so execution
+    /// never waits on the disk
+    fn persist(&self, now_ms: u64) {
+        let Some(path) = self.path.clone() else { return };
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                let entries = self.entries.clone();
+                let save_lock = self.save_lock.clone();
+                drop(handle.spawn_blocking(move || {
+                    let _guard = save_lock.lock().unwrap_or_else(|e| e.into_inner());
+                    log_save(&path, save_entries(&entries, &path, now_ms));
+                }));
+            }
+            Err(_) => log_save(&path, self.save(&path, now_ms)),
+        }
+    }
+}
+
+fn log_save(path: &Path, result: eyre::Result<()>) {
+    match result {
+        Ok(()) => info!("Saved pool blacklist to {}", path.display()),
+        Err(e) => warn!("Failed to save pool blacklist to {}: {:?}", path.display(), e),
+    }
+}
+
+fn save_entries(entries: &DashMap<Address, BlacklistEntry>, path: &Path, now_ms: u64) -> eyre::Result<()> {
+    let active: BTreeMap<Address, BlacklistEntry> = entries.iter()
+        .filter(|entry| entry.is_active(now_ms))
+        .map(|entry| (*entry.key(), entry.value().clone()))
+        .collect();
+    // Write then rename so a crash mid-write can't corrupt the file
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, serde_json::to_string_pretty(&active)?)?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::{H256, U256};
+
+    fn opportunity(buy_pool: u64, sell_pool: u64) -> Opportunity {
+        Opportunity {
+            buy_pool: Address::from_low_u64_be(buy_pool),
+            sell_pool: Address::from_low_u64_be(sell_pool),
+            spread_bps: 25,
+            ..Opportunity::fixture()
+        }
+    }
+
+    fn reverted() -> ExecutionResult {
+        ExecutionResult::Reverted {
+            hash: H256::zero(),
+            reason: "Transaction reverted".into(),
+            gas_cost: U256::zero(),
+            opportunity_id: H256::zero(),
+        }
+    }
+
+    #[test]
+    fn test_reverting_pool_blacklisted_across_restart_until_expiry() {
+        let path = std::env::temp_dir().join(format!("pool_blacklist_{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let day = DEFAULT_BLACKLIST_TTL.as_millis() as u64;
+
+        let blacklist = PoolBlacklist::load(&path).unwrap().with_revert_threshold(2);
+        // Pool 10 reverts twice with different partners; 11 and 12 only once each
+        blacklist.record_outcome(&opportunity(10, 11), &reverted(), 1_000);
+        assert!(!blacklist.is_blacklisted(Address::from_low_u64_be(10), 1_000));
+        blacklist.record_outcome(&opportunity(10, 12), &reverted(), 2_000);
+        assert!(blacklist.is_blacklisted(Address::from_low_u64_be(10), 2_000));
+        assert!(!blacklist.is_blacklisted(Address::from_low_u64_be(11), 2_000));
+        assert!(blacklist.blocks(&opportunity(13, 10), 2_000));
+
+        // A confirmation resets the streak
+        blacklist.record_outcome(&opportunity(11, 14), &ExecutionResult::Confirmed {
+            hash: H256::zero(),
+            profit: U256::zero(),
+            gas_cost: U256::zero(),
+            expected_profit: U256::zero(),
+            opportunity_id: H256::zero(),
+        }, 3_000);
+        blacklist.record_outcome(&opportunity(11, 15), &reverted(), 3_000);
+        assert!(!blacklist.is_blacklisted(Address::from_low_u64_be(11), 3_000));
+
+        // Survives a restart, then expires
+        let reloaded = PoolBlacklist::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let entry = reloaded.reason(Address::from_low_u64_be(10), 3_000).unwrap();
+        assert!(entry.reason.starts_with("2 consecutive reverts"));
+        assert_eq!(entry.added_at_ms, 2_000);
+        assert!(reloaded.is_blacklisted(Address::from_low_u64_be(10), 2_000 + day - 1));
+        assert!(!reloaded.is_blacklisted(Address::from_low_u64_be(10), 2_000 + day));
+    }
+
+    #[test]
+    fn test_route_legs_and_unsafe_tokens_blacklisted() {
+        let blacklist = PoolBlacklist::default();
+        let leg = Address::from_low_u64_be(20);
+
+        // Off by default: reverts alone never blacklist
+        let mut routed = opportunity(10, 11);
+        routed.route = crate::detector::route::pair_route(
+            Address::zero(), routed.token,
+            (routed.buy_venue, leg), (routed.sell_venue, Address::from_low_u64_be(11)),
+            routed.trade_amount,
+        );
+        for _ in 0..5 {
+            blacklist.record_outcome(&routed, &reverted(), 1_000);
+        }
+        assert!(blacklist.active(1_000).is_empty());
+
+        let blacklist = blacklist.with_revert_threshold(1);
+        blacklist.record_outcome(&routed, &reverted(), 1_000);
+        assert!(blacklist.is_blacklisted(leg, 1_000));
+
+        // A paused token blacklists its pools; a failed check doesn't
+        let token = Address::from_low_u64_be(1);
+        let pools = [Address::from_low_u64_be(30)];
+        blacklist.record_safety(token, &pools, &TokenSafetyResult::Error { reason: "timeout".into() }, 1_000);
+        assert!(!blacklist.is_blacklisted(pools[0], 1_000));
+        blacklist.record_safety(token, &pools, &TokenSafetyResult::Paused, 1_000);
+        assert!(blacklist.reason(pools[0], 1_000).unwrap().reason.contains("paused"));
+    }
+}
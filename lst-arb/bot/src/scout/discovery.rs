@@ -0,0 +1,365 @@
+//! Pool Discovery Sources
+//!
+//! Finds pools trading the configured LSTs. No single index has full
+//! coverage or uptime, so discovery is pluggable: each `PoolSource` is
+//! queried in priority order and the results are merged, with the first
+//! source to report a pool winning on duplicates. A failing or empty
+//! source just means the next one fills the gap. Results are cached for a
+//! few minutes so frequent rediscovery doesn't get rate-limited by the APIs;
+//! an empty result isn't cached, so a transient outage is retried next time.
+
+use async_trait::async_trait;
+use ethers::types::Address;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+use super::PoolBlacklist;
+
+/// How long discovered pools are reused before the sources are queried again
+pub const DEFAULT_DISCOVERY_CACHE_TTL: Duration = Duration::from_secs(300);
+
+/// How often discovery runs after startup
+pub const DEFAULT_REDISCOVER_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A pool found by a discovery source
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiscoveredPool {
+    pub address: Address,
+    /// The monitored token this pool trades
+    pub token: Address,
+    /// DEX the pool belongs to (e.g. "uniswap", "camelot")
+    pub dex: String,
+}
+
+/// Somewhere pools can be discovered from
+#[async_trait]
+pub trait PoolSource: Send + Sync {
+    fn name(&self) -> &str;
+
+    async fn fetch_pools(&self, tokens: &[Address]) -> eyre::Result<Vec<DiscoveredPool>>;
+}
+
+/// Pools listed in a local JSON file (an array of `DiscoveredPool`)
+pub struct StaticPoolSource {
+    path: PathBuf,
+}
+
+impl StaticPoolSource {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[async_trait]
+impl PoolSource for StaticPoolSource {
+    fn name(&self) -> &str {
+        "static"
+    }
+
+    async fn fetch_pools(&self, tokens: &[Address]) -> eyre::Result<Vec<DiscoveredPool>> {
+        let pools: Vec<DiscoveredPool> = serde_json::from_str(&std::fs::read_to_string(&self.path)?)?;
+        Ok(pools.into_iter().filter(|p| tokens.contains(&p.token)).collect())
+    }
+}
+
+/// Pairs from the DexScreener token API
+pub struct DexScreenerSource {
+    http_client: reqwest::Client,
+    base_url: String,
+    chain_id: String,
+}
+
+impl DexScreenerSource {
+    pub fn new(chain_id: impl Into<String>) -> Self {
+        Self {
+            http_client: reqwest::Client::new(),
+            base_url: "https://api.dexscreener.com".into(),
+            chain_id: chain_id.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl PoolSource for DexScreenerSource {
+    fn name(&self) -> &str {
+        "dexscreener"
+    }
+
+    async fn fetch_pools(&self, tokens: &[Address]) -> eyre::Result<Vec<DiscoveredPool>> {
+        let mut pools = Vec::new();
+
+        for token in tokens {
+            let url = format!("{}/latest/dex/tokens/{:?}", self.base_url, token);
+            let response: serde_json::Value = self.http_client.get(&url).send().await?.json().await?;
+
+            let pairs = response["pairs"].as_array().cloned().unwrap_or_default();
+            for pair in pairs.iter().filter(|p| p["chainId"].as_str() == Some(self.chain_id.as_str())) {
+                let Some(address) = pair["pairAddress"].as_str().and_then(|a| a.parse().ok()) else {
+                    continue;
+                };
+                pools.push(DiscoveredPool {
+                    address,
+                    token: *token,
+                    dex: pair["dexId"].as_str().unwrap_or("unknown").to_string(),
+                });
+            }
+        }
+
+        Ok(pools)
+    }
+}
+
+/// Pools from a Uniswap V3-schema subgraph (Uniswap, Camelot, Goldsky mirrors)
+pub struct SubgraphPoolSource {
+    http_client: reqwest::Client,
+    url: String,
+    dex: String,
+}
+
+impl SubgraphPoolSource {
+    pub fn new(url: String, dex: impl Into<String>) -> Self {
+        Self {
+            http_client: reqwest::Client::new(),
+            url,
+            dex: dex.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl PoolSource for SubgraphPoolSource {
+    fn name(&self) -> &str {
+        "subgraph"
+    }
+
+    async fn fetch_pools(&self, tokens: &[Address]) -> eyre::Result<Vec<DiscoveredPool>> {
+        let ids: Vec<String> = tokens.iter().map(|t| format!("\"{:?}\"", t)).collect();
+        let ids = ids.join(",");
+        let query = format!(
+            r#"{{ pools(first: 200, where: {{ or: [{{ token0_in: [{ids}] }}, {{ token1_in: [{ids}] }}] }}) {{ id token0 {{ id }} token1 {{ id }} }} }}"#,
+        );
+
+        let response: serde_json::Value = self.http_client
+            .post(&self.url)
+            .json(&serde_json::json!({ "query": query }))
+            .send()
+            .await?
+            .json()
+            .await?;
+
+        let pools = response["data"]["pools"].as_array()
+            .ok_or_else(|| eyre::eyre!("Subgraph error: {}", response))?;
+
+        let mut discovered = Vec::new();
+        for pool in pools {
+            let Some(address) = pool["id"].as_str().and_then(|a| a.parse().ok()) else {
+                continue;
+            };
+            let token = [&pool["token0"]["id"], &pool["token1"]["id"]].into_iter()
+                .filter_map(|id| id.as_str().and_then(|a| a.parse::<Address>().ok()))
+                .find(|t| tokens.contains(t));
+
+            if let Some(token) = token {
+                discovered.push(DiscoveredPool { address, token, dex: self.dex.clone() });
+            }
+        }
+
+        Ok(discovered)
+    }
+}
+
+/// Pools last discovered, for which tokens, and when
+struct CachedPools {
+    fetched_at: Instant,
+    tokens: Vec<Address>,
+    pools: Vec<DiscoveredPool>,
+}
+
+/// Queries pool sources in priority order and merges their results
+pub struct PoolDiscovery {
+    sources: Vec<Arc<dyn PoolSource>>,
+    cache_ttl: Duration,
+    cache: tokio::sync::Mutex<Option<CachedPools>>,
+    blacklist: Option<Arc<PoolBlacklist>>,
+}
+
+impl PoolDiscovery {
+    pub fn new(sources: Vec<Arc<dyn PoolSource>>) -> Self {
+        Self {
+            sources,
+            cache_ttl: DEFAULT_DISCOVERY_CACHE_TTL,
+            cache: tokio::sync::Mutex::new(None),
+            blacklist: None,
+        }
+    }
+
+    /// Leave out pools on `blacklist`
+    pub fn with_blacklist(mut self, blacklist: Arc<PoolBlacklist>) -> Self {
+        self.blacklist = Some(blacklist);
+        self
+    }
+
+    /// Reuse discovered pools for this long (zero disables the cache)
+    pub fn with_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.cache_ttl = ttl;
+        self
+    }
+
+    /// Pools for `tokens`, from the cache if they were discovered within the TTL
+    pub async fn discover_cached(&self, tokens: &[Address]) -> Vec<DiscoveredPool> {
+        {
+            let cache = self.cache.lock().await;
+            if let Some(cached) = cache.as_ref() {
+                if cached.tokens == tokens && cached.fetched_at.elapsed() < self.cache_ttl {
+                    debug!("Using {} cached pools ({:?} old)", cached.pools.len(), cached.fetched_at.elapsed());
+                    return cached.pools.clone();
+                }
+            }
+        }
+        self.force_refresh(tokens).await
+    }
+
+    /// Query the sources regardless of the cache, and cache the result
+    /// unless it's empty
+    pub async fn force_refresh(&self, tokens: &[Address]) -> Vec<DiscoveredPool> {
+        let pools = self.discover(tokens).await;
+        if pools.is_empty() {
+            return pools;
+        }
+        *self.cache.lock().await = Some(CachedPools {
+            fetched_at: Instant::now(),
+            tokens: tokens.to_vec(),
+            pools: pools.clone(),
+        });
+        pools
+    }
+
+    /// Pools for `tokens` from every source, deduplicated by address and
+    /// without blacklisted ones
+    pub async fn discover(&self, tokens: &[Address]) -> Vec<DiscoveredPool> {
+        let now_ms = chrono::Utc::now().timestamp_millis() as u64;
+        let mut seen = HashSet::new();
+        if let Some(blacklist) = &self.blacklist {
+            seen.extend(blacklist.active(now_ms));
+        }
+        let mut pools = Vec::new();
+
+        for source in &self.sources {
+            match source.fetch_pools(tokens).await {
+                Ok(found) => {
+                    let before = pools.len();
+                    pools.extend(found.into_iter().filter(|p| seen.insert(p.address)));
+                    debug!("Pool source {} added {} new pools", source.name(), pools.len() - before);
+                }
+                Err(e) => warn!("Pool source {} failed: {:?}", source.name(), e),
+            }
+        }
+
+        info!("🔍 Discovered {} pools from {} sources", pools.len(), self.sources.len());
+        pools
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct EmptySource;
+
+    #[async_trait]
+    impl PoolSource for EmptySource {
+        fn name(&self) -> &str {
+            "empty"
+        }
+
+        async fn fetch_pools(&self, _tokens: &[Address]) -> eyre::Result<Vec<DiscoveredPool>> {
+            Ok(Vec::new())
+        }
+    }
+
+    fn pool(address: u64, token: u64, dex: &str) -> DiscoveredPool {
+        DiscoveredPool {
+            address: Address::from_low_u64_be(address),
+            token: Address::from_low_u64_be(token),
+            dex: dex.into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_static_source_fills_in_for_empty_primary() {
+        let path = std::env::temp_dir().join(format!("static_pools_{}.json", std::process::id()));
+        let listed = vec![pool(10, 1, "camelot"), pool(11, 1, "camelot"), pool(12, 2, "uniswap")];
+        std::fs::write(&path, serde_json::to_string(&listed).unwrap()).unwrap();
+
+        let discovery = PoolDiscovery::new(vec![
+            Arc::new(EmptySource),
+            Arc::new(StaticPoolSource::new(&path)),
+            // Duplicates from later sources are dropped
+            Arc::new(StaticPoolSource::new(&path)),
+        ]);
+        let pools = discovery.discover(&[Address::from_low_u64_be(1)]).await;
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(pools, vec![pool(10, 1, "camelot"), pool(11, 1, "camelot")]);
+    }
+
+    /// Counts fetches, returning one pool per token
+    #[derive(Default)]
+    struct CountingSource {
+        fetches: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait]
+    impl PoolSource for CountingSource {
+        fn name(&self) -> &str {
+            "counting"
+        }
+
+        async fn fetch_pools(&self, tokens: &[Address]) -> eyre::Result<Vec<DiscoveredPool>> {
+            self.fetches.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(tokens.iter().map(|t| DiscoveredPool { address: *t, token: *t, dex: "uniswap".into() }).collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_discovery_cached_within_ttl() {
+        let source = Arc::new(CountingSource::default());
+        let fetches = || source.fetches.load(std::sync::atomic::Ordering::SeqCst);
+        let discovery = PoolDiscovery::new(vec![source.clone()]);
+        let tokens = [Address::from_low_u64_be(1)];
+
+        let first = discovery.discover_cached(&tokens).await;
+        assert_eq!(discovery.discover_cached(&tokens).await, first);
+        assert_eq!(fetches(), 1);
+
+        // A different token set or an explicit refresh goes to the sources
+        discovery.discover_cached(&[Address::from_low_u64_be(2)]).await;
+        assert_eq!(fetches(), 2);
+        discovery.force_refresh(&tokens).await;
+        discovery.discover_cached(&tokens).await;
+        assert_eq!(fetches(), 3);
+
+        // Nothing found isn't cached, so the next call asks again
+        assert!(discovery.discover_cached(&[]).await.is_empty());
+        discovery.discover_cached(&[]).await;
+        assert_eq!(fetches(), 5);
+
+        let uncached = PoolDiscovery::new(vec![source.clone()]).with_cache_ttl(Duration::ZERO);
+        uncached.discover_cached(&tokens).await;
+        uncached.discover_cached(&tokens).await;
+        assert_eq!(fetches(), 7);
+    }
+
+    #[tokio::test]
+    async fn test_failing_source_is_skipped() {
+        let discovery = PoolDiscovery::new(vec![
+            Arc::new(StaticPoolSource::new("/nonexistent/pools.json")),
+            Arc::new(EmptySource),
+        ]);
+        assert!(discovery.discover(&[Address::from_low_u64_be(1)]).await.is_empty());
+    }
+}
@@ -3,7 +3,13 @@
 //! Provides safety checks and defensive mechanisms including:
 //! - Honey pot detection for scam tokens
 //! - Token safety verification before trading
+//! - Pool discovery from pluggable, priority-ordered sources
+//! - A persistent blacklist of pools that keep reverting
 
+mod blacklist;
+mod discovery;
 mod safety;
 
+pub use blacklist::*;
+pub use discovery::*;
 pub use safety::{SafetyChecker, TokenSafetyResult};
@@ -0,0 +1,158 @@
+//! Per-Block Simulation Cache
+//!
+//! Bursts of swap events fire several triggers within one block, and each
+//! re-detects the same opportunities against reserves that haven't moved.
+//! Until the block advances, an opportunity of the same tokens, route and
+//! (roughly) size simulates exactly as before, so the eth_call's outcome is
+//! reused instead of paying for another round trip. Outcomes also expire
+//! after a short wall-clock TTL, so a missed block (a stalled head read)
+//! can't keep serving them. Profit and gas cost are still worked out per
+//! call, from the opportunity and gas price at hand.
+
+use ethers::types::{Address, U256};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::detector::Opportunity;
+use crate::price::Venue;
+use super::l1_fee::L1Component;
+
+/// Trade sizes within 1% of each other share a bucket
+const SIZE_BUCKET_RATIO: f64 = 1.01;
+
+/// Cached outcomes are reused for at most this long (two Arbitrum blocks)
+pub const DEFAULT_SIMULATION_CACHE_TTL: Duration = Duration::from_millis(500);
+
+/// What the contract call did at a given block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CallOutcome {
+    Passed { gas_estimate: U256, l1: L1Component },
+    Reverted { reason: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    token: Address,
+    base_token: Address,
+    buy_venue: (Venue, Option<u32>, Address),
+    sell_venue: (Venue, Option<u32>, Address),
+    /// (venue, pool, token out) of every leg, so routes differing only in
+    /// their middle hops don't share an outcome
+    route: Vec<(Venue, Address, Address)>,
+    size_bucket: i64,
+    block: u64,
+}
+
+impl CacheKey {
+    fn of(opportunity: &Opportunity, block: u64) -> Self {
+        Self {
+            token: opportunity.token,
+            base_token: opportunity.base_token,
+            buy_venue: (opportunity.buy_venue, opportunity.buy_fee_tier, opportunity.buy_pool),
+            sell_venue: (opportunity.sell_venue, opportunity.sell_fee_tier, opportunity.sell_pool),
+            route: opportunity.route.iter().map(|leg| (leg.venue, leg.pool, leg.token_out)).collect(),
+            size_bucket: size_bucket(opportunity.trade_amount),
+            block,
+        }
+    }
+}
+
+/// Logarithmic bucket of a trade size
+fn size_bucket(amount: U256) -> i64 {
+    let amount = crate::detector::solver::u256_to_f64(amount).unwrap_or(f64::MAX).max(1.0);
+    (amount.ln() / SIZE_BUCKET_RATIO.ln()).floor() as i64
+}
+
+#[derive(Debug)]
+pub struct SimulationCache {
+    /// Latest block seen; 0 = none yet, nothing is cached
+    block: u64,
+    ttl: Duration,
+    /// Outcomes and when their simulation ran
+    outcomes: HashMap<CacheKey, (CallOutcome, Instant)>,
+}
+
+impl Default for SimulationCache {
+    fn default() -> Self {
+        Self { block: 0, ttl: DEFAULT_SIMULATION_CACHE_TTL, outcomes: HashMap::new() }
+    }
+}
+
+impl SimulationCache {
+    /// Note the latest block; a new one invalidates everything cached
+    pub fn observe_block(&mut self, block: u64) {
+        if block > self.block {
+            self.block = block;
+            self.outcomes.clear();
+        }
+    }
+
+    /// The outcome of simulating a matching opportunity in this block,
+    /// unless it's older than the TTL
+    pub fn get(&self, opportunity: &Opportunity, now: Instant) -> Option<CallOutcome> {
+        self.outcomes.get(&CacheKey::of(opportunity, self.block))
+            .filter(|(_, simulated_at)| now.saturating_duration_since(*simulated_at) < self.ttl)
+            .map(|(outcome, _)| outcome.clone())
+    }
+
+    /// Cache the outcome of a simulation that ran at `simulated_at`
+    pub fn insert(&mut self, opportunity: &Opportunity, outcome: CallOutcome, simulated_at: Instant) {
+        if self.block > 0 {
+            self.outcomes.insert(CacheKey::of(opportunity, self.block), (outcome, simulated_at));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detector::route::Leg;
+
+    fn opportunity(trade_amount: &str) -> Opportunity {
+        Opportunity {
+            buy_pool: Address::from_low_u64_be(10),
+            sell_pool: Address::from_low_u64_be(11),
+            trade_amount: ethers::utils::parse_ether(trade_amount).unwrap(),
+            ..Opportunity::fixture()
+        }
+    }
+
+    #[test]
+    fn test_outcome_reused_within_block_only() {
+        let mut cache = SimulationCache::default();
+        let passed = CallOutcome::Passed { gas_estimate: U256::from(300_000u64), l1: L1Component::default() };
+        let now = Instant::now();
+
+        // Nothing is cached before the first block is known
+        cache.insert(&opportunity("5"), passed.clone(), now);
+        assert_eq!(cache.get(&opportunity("5"), now), None);
+
+        cache.observe_block(100);
+        cache.insert(&opportunity("5"), passed.clone(), now);
+        // A re-detection at nearly the same size hits; a different size, pool,
+        // base token or middle hop misses
+        assert_eq!(cache.get(&opportunity("5.001"), now), Some(passed.clone()));
+        assert_eq!(cache.get(&opportunity("6"), now), None);
+        let other_pool = Opportunity { sell_pool: Address::from_low_u64_be(12), ..opportunity("5") };
+        assert_eq!(cache.get(&other_pool, now), None);
+        let other_base = Opportunity { base_token: Address::from_low_u64_be(2), ..opportunity("5") };
+        assert_eq!(cache.get(&other_base, now), None);
+        let hop = |pool| Leg {
+            pool: Address::from_low_u64_be(pool),
+            venue: Venue::Curve,
+            token_in: Address::zero(),
+            token_out: Address::from_low_u64_be(3),
+            amount_in: U256::zero(),
+        };
+        cache.insert(&Opportunity { route: vec![hop(20)], ..opportunity("5") }, passed.clone(), now);
+        assert!(cache.get(&Opportunity { route: vec![hop(20)], ..opportunity("5") }, now).is_some());
+        assert_eq!(cache.get(&Opportunity { route: vec![hop(21)], ..opportunity("5") }, now), None);
+
+        // Without a new block, the TTL still expires it
+        assert_eq!(cache.get(&opportunity("5"), now + DEFAULT_SIMULATION_CACHE_TTL), None);
+
+        // The next block invalidates it
+        cache.observe_block(101);
+        assert_eq!(cache.get(&opportunity("5"), now), None);
+    }
+}
@@ -0,0 +1,88 @@
+//! Arbitrum L1 Data Fee
+//!
+//! Every Arbitrum transaction also pays to post its calldata to L1, charged
+//! as extra L2 gas units. The node's `eth_estimateGas` already includes
+//! them, but the fallback estimate used when that call fails doesn't, and
+//! for calldata-heavy routes they're a real share of the cost. The
+//! `NodeInterface` precompile prices the L1 component for a given
+//! transaction, so it's added explicitly rather than assumed.
+
+use ethers::prelude::*;
+use ethers::types::{Address, Bytes, U256};
+use std::sync::Arc;
+
+/// Arbitrum's NodeInterface (virtual; served by eth_call/estimateGas only)
+pub const ARBITRUM_NODE_INTERFACE: &str = "0x00000000000000000000000000000000000000C8";
+
+abigen!(
+    NodeInterface,
+    r#"[
+        function gasEstimateL1Component(address to, bool contractCreation, bytes data) external payable returns (uint64 gasEstimateForL1, uint256 baseFee, uint256 l1BaseFeeEstimate)
+    ]"#
+);
+
+/// L1 posting cost of one transaction, in L2 gas units at the L2 base fee
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct L1Component {
+    pub gas: U256,
+    pub base_fee: U256,
+}
+
+impl L1Component {
+    pub fn fee_wei(&self) -> U256 {
+        self.gas * self.base_fee
+    }
+}
+
+/// The L1 component of calling `to` with `data`
+pub async fn estimate_l1_component<M: Middleware + 'static>(
+    client: Arc<M>,
+    to: Address,
+    data: Bytes,
+) -> eyre::Result<L1Component> {
+    let node_interface = NodeInterface::new(ARBITRUM_NODE_INTERFACE.parse::<Address>()?, client);
+    let (gas, base_fee, _) = node_interface
+        .gas_estimate_l1_component(to, false, data)
+        .call()
+        .await
+        .map_err(|e| eyre::eyre!("gasEstimateL1Component failed: {}", e))?;
+    Ok(L1Component { gas: U256::from(gas), base_fee })
+}
+
+/// Total cost of a transaction estimated at `gas_estimate` units (L1 units
+/// included): its L2 execution at `gas_price`, plus the L1 component at the
+/// base fee it was priced at
+pub fn gas_cost_wei(gas_estimate: U256, gas_price: U256, l1: L1Component) -> U256 {
+    gas_estimate.saturating_sub(l1.gas) * gas_price + l1.fee_wei()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::abi::Token;
+
+    #[tokio::test]
+    async fn test_l1_fee_priced_for_calldata() {
+        let (provider, mock) = Provider::mocked();
+        let client = Arc::new(provider);
+
+        // 2,000 L1 gas units at a 0.01 gwei base fee
+        let returned = ethers::abi::encode(&[
+            Token::Uint(U256::from(2_000u64)),
+            Token::Uint(U256::from(10_000_000u64)),
+            Token::Uint(U256::from(30_000_000_000u64)),
+        ]);
+        mock.push::<Bytes, _>(Bytes::from(returned)).unwrap();
+
+        let calldata = Bytes::from(vec![0xab; 600]);
+        let l1 = estimate_l1_component(client, Address::from_low_u64_be(1), calldata).await.unwrap();
+        assert_eq!(l1, L1Component { gas: U256::from(2_000u64), base_fee: U256::from(10_000_000u64) });
+        assert_eq!(l1.fee_wei(), U256::from(20_000_000_000u64));
+
+        // Counted once: the node's estimate already holds the L1 units
+        let gas_price = U256::from(10_000_000u64);
+        assert_eq!(gas_cost_wei(U256::from(302_000u64), gas_price, l1), U256::from(302_000u64) * gas_price);
+        // Without an L1 component the cost is unchanged
+        assert_eq!(gas_cost_wei(U256::from(300_000u64), gas_price, L1Component::default()), U256::from(300_000u64) * gas_price);
+    }
+}
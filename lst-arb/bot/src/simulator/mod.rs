@@ -1,18 +1,38 @@
 use ethers::prelude::*;
 use ethers::types::{Address, U256};
 use ethers::types::transaction::eip2718::TypedTransaction;
+use parking_lot::Mutex;
 use std::sync::Arc;
-use tracing::warn;
+use tracing::{debug, warn};
 
 use crate::rpc::WsClient;
 use crate::detector::Opportunity;
+use crate::detector::spread::{pool_amplification, pool_fee_bps, solidly_stable_output};
+use crate::price::stableswap;
 use crate::price::Venue;
 
+pub mod cache;
+pub mod l1_fee;
+pub mod revert;
+pub mod sweep;
+pub use cache::{CallOutcome, SimulationCache};
+pub use l1_fee::L1Component;
+pub use revert::RevertReason;
+pub use sweep::SizeSweep;
+
+/// Default adverse move assumed in each pool between quoting and inclusion
+pub const DEFAULT_MAX_SLIPPAGE_BPS: u64 = 10;
+
+// executeRoute/simulateRoute aren't in LstArbitrage.sol yet, and are only
+// called once enabled with `Simulator::with_routes`
 abigen!(
     LstArbitrage,
     r#"[
         function executeArb(address lst, uint256 amount, uint8 buyVenue, uint8 sellVenue, uint256 minProfit) external
         function simulateArb(address lst, uint256 amount, uint8 buyVenue, uint8 sellVenue) external returns (uint256 expectedProfit)
+        struct RouteLeg { address pool; uint8 venue; address tokenIn; address tokenOut; uint256 amountIn; }
+        function executeRoute(RouteLeg[] legs, uint256 minProfit) external
+        function simulateRoute(RouteLeg[] legs) external returns (uint256 expectedProfit)
     ]"#
 );
 
@@ -21,18 +41,150 @@ pub struct SimulationResult {
     pub success: bool,
     pub expected_profit: U256,
     pub gas_estimate: U256,
+    /// Includes `l1_fee_wei`
     pub gas_cost_wei: U256,
+    /// Arbitrum L1 data posting fee (zero when not estimated)
+    pub l1_fee_wei: U256,
     pub net_profit: U256,
     pub revert_reason: Option<String>,
 }
 
+/// Simulates and builds calls against whichever arb contract the executor
+/// selected for an opportunity's venue pair
 pub struct Simulator {
-    arb_contract: Address,
+    /// Multicall3 used to batch size sweeps
+    multicall3: Option<Address>,
+    /// Simulate against the pending block (mempool txs applied) instead of the latest
+    pending_state: bool,
+    /// The by-venue `executeArb` only ever flash-loans WETH
+    weth: Address,
+    /// Call outcomes reused until the next block
+    cache: Option<Mutex<SimulationCache>>,
+    /// Price the Arbitrum L1 data fee through NodeInterface
+    l1_fee: bool,
+    /// The contract implements `executeRoute`/`simulateRoute`
+    routes: bool,
+}
+
+impl Default for Simulator {
+    fn default() -> Self {
+        Self {
+            multicall3: None,
+            pending_state: false,
+            weth: crate::detector::ARBITRUM_WETH.parse().unwrap(),
+            cache: None,
+            l1_fee: false,
+            routes: false,
+        }
+    }
 }
 
 impl Simulator {
-    pub fn new(arb_contract: Address) -> Self {
-        Self { arb_contract }
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_multicall(mut self, multicall3: Address) -> Self {
+        self.multicall3 = Some(multicall3);
+        self
+    }
+
+    /// Simulate against the node's pending state, so a triggering swap that
+    /// hasn't been mined yet is already reflected
+    pub fn with_pending_state(mut self, pending_state: bool) -> Self {
+        self.pending_state = pending_state;
+        self
+    }
+
+    /// Use another chain's WETH
+    pub fn with_weth(mut self, weth: Address) -> Self {
+        self.weth = weth;
+        self
+    }
+
+    /// Reuse a simulation's outcome for the same token, pools and size
+    /// bucket until the block advances
+    pub fn with_cache(mut self) -> Self {
+        self.cache = Some(Mutex::new(SimulationCache::default()));
+        self
+    }
+
+    /// Add the L1 data fee, priced by Arbitrum's NodeInterface, to each
+    /// simulation's gas cost
+    pub fn with_l1_fee(mut self, l1_fee: bool) -> Self {
+        self.l1_fee = l1_fee;
+        self
+    }
+
+    /// Allow opportunities that need `executeRoute`. LstArbitrage.sol only
+    /// implements `executeArb`, so this is off unless the deployed contract
+    /// adds the route functions.
+    pub fn with_routes(mut self, routes: bool) -> Self {
+        self.routes = routes;
+        self
+    }
+
+    /// Whether the contract can execute `opportunity` at all
+    pub fn can_execute(&self, opportunity: &Opportunity) -> bool {
+        self.routes || self.by_venue(opportunity)
+    }
+
+    /// Note the latest block; cached outcomes from older blocks are dropped
+    pub fn observe_block(&self, block: u64) {
+        if let Some(cache) = &self.cache {
+            cache.lock().observe_block(block);
+        }
+    }
+
+    /// The cache, when it applies: pending state moves without a new
+    /// block, so nothing simulated against it is reused
+    fn cache(&self) -> Option<&Mutex<SimulationCache>> {
+        self.cache.as_ref().filter(|_| !self.pending_state)
+    }
+
+    /// Whether `opportunity` goes through `executeArb` by venue: a plain
+    /// arb starting from WETH. Anything else, including LST/LST pairs,
+    /// goes leg by leg through `executeRoute`.
+    fn by_venue(&self, opportunity: &Opportunity) -> bool {
+        opportunity.is_pair() && opportunity.base_token == self.weth
+    }
+
+    /// Block the simulation calls run against (None = latest)
+    fn block(&self) -> Option<BlockId> {
+        self.pending_state.then(|| BlockNumber::Pending.into())
+    }
+    
+    /// Call `simulateArb` at each of `sizes` in one Multicall3 batch, so
+    /// the whole sweep costs a single round trip
+    pub async fn sweep_sizes(
+        &self,
+        client: Arc<WsClient>,
+        arb_contract: Address,
+        opportunity: &Opportunity,
+        sizes: &[U256],
+    ) -> eyre::Result<SizeSweep> {
+        let multicall3 = self.multicall3
+            .ok_or_else(|| eyre::eyre!("Size sweep needs a Multicall3 address"))?;
+        let contract = LstArbitrage::new(arb_contract, client.clone());
+        let multicall = crate::price::Multicall3::new(multicall3, client);
+
+        let calls = sizes.iter()
+            .map(|size| {
+                let call_data = simulate_call(&contract, opportunity, self.by_venue(opportunity), *size).calldata().unwrap_or_default();
+                crate::price::Call3 { target: arb_contract, allow_failure: true, call_data }
+            })
+            .collect();
+
+        let returned = multicall.aggregate_3(calls).call().await?;
+        let results = sizes.iter()
+            .zip(returned)
+            .map(|(size, (success, data))| {
+                let profit = (success && data.len() >= 32).then(|| U256::from_big_endian(&data[..32]));
+                (*size, profit)
+            })
+            .collect();
+
+        Ok(SizeSweep { results })
     }
     
     /// Simulate the arbitrage transaction using eth_call
@@ -40,34 +192,29 @@ impl Simulator {
     pub async fn simulate(
         &self,
         client: Arc<WsClient>,
+        arb_contract: Address,
         opportunity: &Opportunity,
         gas_price: U256,
     ) -> eyre::Result<SimulationResult> {
-        let contract = LstArbitrage::new(self.arb_contract, client.clone());
-        
-        // Build the simulation call
-        let buy_venue = opportunity.buy_venue.to_u8();
-        let sell_venue = opportunity.sell_venue.to_u8();
-        
-        // First, try to estimate gas
-        let call = contract.execute_arb(
-            opportunity.token,
-            opportunity.trade_amount,
-            buy_venue,
-            sell_venue,
-            U256::zero(), // Set minProfit to 0 for simulation
-        );
-        
-        // Use eth_call to simulate
-        match call.call().await {
-            Ok(_) => {
-                // Estimate gas
-                let gas_estimate = match call.estimate_gas().await {
-                    Ok(gas) => gas,
-                    Err(_) => U256::from(500_000u64), // Default estimate
-                };
-                
-                let gas_cost = gas_estimate * gas_price;
+        let started = std::time::Instant::now();
+        let cached = self.cache().and_then(|cache| cache.lock().get(opportunity, started));
+        let outcome = match cached {
+            Some(outcome) => {
+                debug!("Reusing this block's simulation for {}", opportunity.token_name);
+                outcome
+            }
+            None => {
+                let outcome = self.call_outcome(client, arb_contract, opportunity).await;
+                if let Some(cache) = self.cache() {
+                    cache.lock().insert(opportunity, outcome.clone(), started);
+                }
+                outcome
+            }
+        };
+
+        match outcome {
+            CallOutcome::Passed { gas_estimate, l1 } => {
+                let gas_cost = l1_fee::gas_cost_wei(gas_estimate, gas_price, l1);
                 
                 // Calculate expected profit from opportunity data
                 let expected_profit = opportunity.expected_profit;
@@ -83,25 +230,18 @@ impl Simulator {
                     expected_profit,
                     gas_estimate,
                     gas_cost_wei: gas_cost,
+                    l1_fee_wei: l1.fee_wei(),
                     net_profit,
                     revert_reason: None,
                 })
             }
-            Err(e) => {
-                // Extract revert reason if available
-                let revert_reason = extract_revert_reason(&e);
-                
-                warn!(
-                    "Simulation failed for {}: {:?}",
-                    opportunity.token_name,
-                    revert_reason
-                );
-                
+            CallOutcome::Reverted { reason: revert_reason } => {
                 Ok(SimulationResult {
                     success: false,
                     expected_profit: U256::zero(),
                     gas_estimate: U256::zero(),
                     gas_cost_wei: U256::zero(),
+                    l1_fee_wei: U256::zero(),
                     net_profit: U256::zero(),
                     revert_reason: Some(revert_reason),
                 })
@@ -109,29 +249,80 @@ impl Simulator {
         }
     }
     
+    /// Run the contract call and estimate its gas (minProfit 0 for simulation)
+    async fn call_outcome(
+        &self,
+        client: Arc<WsClient>,
+        arb_contract: Address,
+        opportunity: &Opportunity,
+    ) -> CallOutcome {
+        let contract = LstArbitrage::new(arb_contract, client.clone());
+
+        let mut call = execute_call(&contract, opportunity, self.by_venue(opportunity), U256::zero());
+        if let Some(block) = self.block() {
+            call = call.block(block);
+        }
+
+        // Use eth_call to simulate
+        match call.call().await {
+            Ok(_) => {
+                let l1 = if self.l1_fee {
+                    let calldata = call.calldata().unwrap_or_default();
+                    match l1_fee::estimate_l1_component(client, arb_contract, calldata).await {
+                        Ok(l1) => l1,
+                        Err(e) => {
+                            warn!("L1 fee estimate failed for {}, leaving it out: {}", opportunity.token_name, e);
+                            L1Component::default()
+                        }
+                    }
+                } else {
+                    L1Component::default()
+                };
+                // The node's estimate includes the L1 units; the default doesn't
+                let gas_estimate = match call.estimate_gas().await {
+                    Ok(gas) => gas,
+                    Err(_) => U256::from(500_000u64) + l1.gas, // Default estimate
+                };
+                CallOutcome::Passed { gas_estimate, l1 }
+            }
+            Err(e) => {
+                // Extract revert reason if available
+                let reason = extract_revert_reason(&e);
+
+                warn!(
+                    "Simulation failed for {}: {:?}",
+                    opportunity.token_name,
+                    reason
+                );
+
+                CallOutcome::Reverted { reason }
+            }
+        }
+    }
+
     /// Quick simulation without full gas estimation
     /// Used for rapid filtering
     pub async fn quick_simulate(
         &self,
         client: Arc<WsClient>,
+        arb_contract: Address,
         opportunity: &Opportunity,
     ) -> bool {
-        let contract = LstArbitrage::new(self.arb_contract, client.clone());
+        let contract = LstArbitrage::new(arb_contract, client.clone());
         
-        let call = contract.execute_arb(
-            opportunity.token,
-            opportunity.trade_amount,
-            opportunity.buy_venue.to_u8(),
-            opportunity.sell_venue.to_u8(),
-            U256::zero(),
-        );
+        let mut call = execute_call(&contract, opportunity, self.by_venue(opportunity), U256::zero());
+        if let Some(block) = self.block() {
+            call = call.block(block);
+        }
         
         call.call().await.is_ok()
     }
     
     /// Build the actual transaction for execution
+    #[allow(clippy::too_many_arguments)]
     pub fn build_transaction(
         &self,
+        arb_contract: Address,
         opportunity: &Opportunity,
         min_profit: U256,
         gas_limit: U256,
@@ -140,17 +331,11 @@ impl Simulator {
         nonce: U256,
     ) -> TypedTransaction {
         let contract = LstArbitrage::new(
-            self.arb_contract,
+            arb_contract,
             Arc::new(Provider::<Http>::try_from("http://localhost:8545").unwrap())
         );
         
-        let call = contract.execute_arb(
-            opportunity.token,
-            opportunity.trade_amount,
-            opportunity.buy_venue.to_u8(),
-            opportunity.sell_venue.to_u8(),
-            min_profit,
-        );
+        let call = execute_call(&contract, opportunity, self.by_venue(opportunity), min_profit);
         
         let mut tx: TypedTransaction = call.tx.clone();
         
@@ -168,19 +353,250 @@ impl Simulator {
     }
 }
 
-fn extract_revert_reason(error: &ContractError<Provider<Ws>>) -> String {
-    match error {
-        ContractError::Revert(bytes) => {
-            // Try to decode as string
-            if bytes.len() > 68 {
-                // Skip selector (4 bytes) and offset (32 bytes) and length (32 bytes)
-                let string_data = &bytes[68..];
-                if let Ok(s) = String::from_utf8(string_data.to_vec()) {
-                    return s.trim_matches('\0').to_string();
-                }
+/// `executeArb` by venue for a plain arb, `executeRoute` leg by leg otherwise
+fn execute_call<M: Middleware>(
+    contract: &LstArbitrage<M>,
+    opportunity: &Opportunity,
+    by_venue: bool,
+    min_profit: U256,
+) -> FunctionCall<Arc<M>, M, ()> {
+    if by_venue {
+        contract.execute_arb(
+            opportunity.token,
+            opportunity.trade_amount,
+            opportunity.buy_venue.to_u8(),
+            opportunity.sell_venue.to_u8(),
+            min_profit,
+        )
+    } else {
+        contract.execute_route(route_legs(opportunity, opportunity.trade_amount), min_profit)
+    }
+}
+
+/// `simulateArb` or `simulateRoute` for `opportunity` resized to `amount`
+fn simulate_call<M: Middleware>(
+    contract: &LstArbitrage<M>,
+    opportunity: &Opportunity,
+    by_venue: bool,
+    amount: U256,
+) -> FunctionCall<Arc<M>, M, U256> {
+    if by_venue {
+        contract.simulate_arb(
+            opportunity.token,
+            amount,
+            opportunity.buy_venue.to_u8(),
+            opportunity.sell_venue.to_u8(),
+        )
+    } else {
+        contract.simulate_route(route_legs(opportunity, amount))
+    }
+}
+
+/// The contract's leg structs for `opportunity`'s route at input `amount`
+fn route_legs(opportunity: &Opportunity, amount: U256) -> Vec<RouteLeg> {
+    crate::detector::route::with_amount(&opportunity.route, amount).into_iter()
+        .map(|leg| RouteLeg {
+            pool: leg.pool,
+            venue: leg.venue.to_u8(),
+            token_in: leg.token_in,
+            token_out: leg.token_out,
+            amount_in: leg.amount_in,
+        })
+        .collect()
+}
+
+/// Worst-case profit for `opportunity` if both pools move `max_slippage_bps`
+/// against us after quoting, from the pools' (base, LST) `reserves` as
+/// [buy, sell]. In the base token, like minProfit itself, and capped at the
+/// base profit so the guard is never tighter than the estimate the trade
+/// was sized on.
+pub fn compute_min_profit(
+    opportunity: &Opportunity,
+    reserves: [(U256, U256); 2],
+    max_slippage_bps: u64,
+) -> Option<U256> {
+    let slipped = |reserve: U256| reserve * U256::from(10_000u64.saturating_sub(max_slippage_bps)) / U256::from(10_000u64);
+    let [(buy_weth, buy_lst), (sell_weth, sell_lst)] = reserves;
+
+    // The LST side of the buy pool and the WETH side of the sell pool shrink
+    let lst_out = swap_output(
+        opportunity.buy_venue,
+        opportunity.buy_fee_tier,
+        opportunity.buy_stable,
+        pool_amplification(opportunity.buy_venue, opportunity.curve_amp),
+        opportunity.trade_amount,
+        [buy_weth, slipped(buy_lst)],
+        0,
+    )?;
+    let eth_out = swap_output(
+        opportunity.sell_venue,
+        opportunity.sell_fee_tier,
+        opportunity.sell_stable,
+        pool_amplification(opportunity.sell_venue, opportunity.curve_amp),
+        lst_out,
+        [slipped(sell_weth), sell_lst],
+        1,
+    )?;
+
+    Some(eth_out.saturating_sub(opportunity.trade_amount).min(opportunity.base_profit))
+}
+
+/// Output of swapping `amount_in` of coin `i` for the other coin in a pool
+/// with balances `xp`, on the pool's curve (`stable` for Solidly stable
+/// pools, `amp` for StableSwap ones)
+fn swap_output(venue: Venue, fee_tier: Option<u32>, stable: bool, amp: Option<u64>, amount_in: U256, xp: [U256; 2], i: usize) -> Option<U256> {
+    let fee_bps = pool_fee_bps(venue, fee_tier, stable);
+    let j = 1 - i;
+    match amp {
+        Some(amp) => stableswap::get_dy(i, j, amount_in, xp, amp, fee_bps),
+        None => {
+            let in_after_fee = amount_in * U256::from(10_000 - fee_bps) / U256::from(10_000u64);
+            if stable && venue == Venue::Solidly {
+                return solidly_stable_output(xp[i], xp[j], in_after_fee);
             }
-            format!("Revert: 0x{}", hex::encode(bytes))
+            xp[j].checked_mul(in_after_fee)?.checked_div(xp[i].checked_add(in_after_fee)?)
         }
+    }
+}
+
+fn extract_revert_reason(error: &ContractError<Provider<Ws>>) -> String {
+    match error {
+        ContractError::Revert(bytes) => RevertReason::classify(bytes).to_string(),
         _ => format!("{:?}", error),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eth(value: &str) -> U256 {
+        ethers::utils::parse_ether(value).unwrap()
+    }
+
+    fn opportunity(trade_amount: &str) -> Opportunity {
+        Opportunity {
+            token: Address::zero(),
+            buy_venue: Venue::UniswapV3,
+            buy_fee_tier: Some(100),
+            spread_bps: 0,
+            expected_profit: eth("1"),
+            base_profit: eth("1"),
+            trade_amount: eth(trade_amount),
+            ..Opportunity::fixture()
+        }
+    }
+
+    #[test]
+    fn test_min_profit_tracks_price_impact_and_slippage() {
+        // LST 2% cheaper on the buy pool than the sell pool
+        let reserves = [(eth("1000"), eth("1020")), (eth("1000"), eth("1000"))];
+
+        let small = compute_min_profit(&opportunity("1"), reserves, 0).unwrap();
+        let slipped = compute_min_profit(&opportunity("1"), reserves, 50).unwrap();
+        // Fees and the trade's own impact take a little of the 2% edge
+        assert!(small > eth("0.017") && small < eth("0.02"));
+        // 50bps on each pool eats about half of it
+        assert!(slipped < small && slipped > eth("0.006"));
+
+        // A trade moving the pools eats most of its own edge
+        let fat = compute_min_profit(&opportunity("20"), reserves, 0).unwrap();
+        assert!(fat < eth("0.02") * 20u64 / 2u64);
+
+        // Slippage beyond the edge leaves nothing
+        assert_eq!(compute_min_profit(&opportunity("1"), reserves, 300).unwrap(), U256::zero());
+    }
+
+    #[test]
+    fn test_route_calldata() {
+        use crate::detector::route::{pair_route, Leg};
+        use ethers::abi::{AbiDecode, AbiEncode};
+        let (weth, wsteth, reth) = (Address::from_low_u64_be(1), Address::from_low_u64_be(2), Address::from_low_u64_be(3));
+        let pool = Address::from_low_u64_be;
+        let calldata = |opportunity: &Opportunity| {
+            let tx = Simulator::new().with_weth(weth).build_transaction(
+                Address::zero(), opportunity, eth("0.01"), U256::from(500_000u64), U256::one(), U256::zero(), U256::zero(),
+            );
+            tx.data().cloned().unwrap_or_default()
+        };
+
+        // A two-leg route still calls executeArb by venue
+        let mut pair = opportunity("5");
+        pair.token = wsteth;
+        pair.base_token = weth;
+        pair.sell_venue = Venue::Curve;
+        pair.route = pair_route(weth, wsteth, (Venue::UniswapV3, pool(10)), (Venue::Curve, pool(11)), eth("5"));
+        let legacy = ExecuteArbCall {
+            lst: wsteth,
+            amount: eth("5"),
+            buy_venue: Venue::UniswapV3.to_u8(),
+            sell_venue: Venue::Curve.to_u8(),
+            min_profit: eth("0.01"),
+        };
+        assert_eq!(calldata(&pair), Bytes::from(legacy.encode()));
+        let mut unrouted = pair.clone();
+        unrouted.route.clear();
+        assert_eq!(calldata(&unrouted), calldata(&pair));
+
+        // executeArb only flash-loans WETH: an rETH -> wstETH -> rETH pair goes leg by leg
+        let mut cross = pair.clone();
+        cross.base_token = reth;
+        cross.route = pair_route(reth, wsteth, (Venue::UniswapV3, pool(13)), (Venue::Balancer, pool(14)), eth("5"));
+        let decoded = ExecuteRouteCall::decode(calldata(&cross)).unwrap();
+        assert_eq!(decoded.legs[0].token_in, reth);
+        assert_eq!(decoded.legs[1].token_out, reth);
+
+        // WETH -> wstETH -> rETH -> WETH goes leg by leg
+        let mut triangular = pair;
+        triangular.route = vec![
+            Leg { pool: pool(10), venue: Venue::UniswapV3, token_in: weth, token_out: wsteth, amount_in: eth("5") },
+            Leg { pool: pool(12), venue: Venue::Balancer, token_in: wsteth, token_out: reth, amount_in: U256::zero() },
+            Leg { pool: pool(11), venue: Venue::Curve, token_in: reth, token_out: weth, amount_in: U256::zero() },
+        ];
+        let decoded = ExecuteRouteCall::decode(calldata(&triangular)).unwrap();
+        assert_eq!(decoded.min_profit, eth("0.01"));
+        let legs: Vec<_> = decoded.legs.iter()
+            .map(|leg| (leg.pool, leg.venue, leg.token_in, leg.token_out, leg.amount_in))
+            .collect();
+        assert_eq!(legs, vec![
+            (pool(10), Venue::UniswapV3.to_u8(), weth, wsteth, eth("5")),
+            (pool(12), Venue::Balancer.to_u8(), wsteth, reth, U256::zero()),
+            (pool(11), Venue::Curve.to_u8(), reth, weth, U256::zero()),
+        ]);
+    }
+
+    /// Against a fork that doesn't mine on its own, an unmined WETH deposit
+    /// (a 1:1 ETH -> WETH swap) shows up in pending-state calls only
+    ///
+    /// `anvil --fork-url <arbitrum rpc> --no-mining`, then
+    /// `FORK_WS_URL=ws://127.0.0.1:8545 cargo test pending_state -- --ignored`
+    #[tokio::test]
+    #[ignore]
+    async fn test_pending_state_reflects_unmined_swap() {
+        let url = std::env::var("FORK_WS_URL").expect("FORK_WS_URL required");
+        let client = Arc::new(Provider::<Ws>::connect(url).await.unwrap());
+
+        let weth: Address = "0x82aF49447D8a07e3bd95BD0d56f35241523fBab1".parse().unwrap();
+        // anvil's first unlocked account
+        let trader: Address = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266".parse().unwrap();
+        let balance_of = |block: Option<BlockId>| {
+            let client = client.clone();
+            let mut data = ethers::utils::id("balanceOf(address)").to_vec();
+            data.extend_from_slice(&ethers::abi::encode(&[ethers::abi::Token::Address(trader)]));
+            let tx: TypedTransaction = TransactionRequest::new().to(weth).data(data).into();
+            async move { U256::from_big_endian(&client.call(&tx, block).await.unwrap()) }
+        };
+
+        let before = balance_of(None).await;
+        let deposit = TransactionRequest::new()
+            .from(trader)
+            .to(weth)
+            .value(eth("1"))
+            .data(ethers::utils::id("deposit()").to_vec());
+        let _pending = client.send_transaction(deposit, None).await.unwrap();
+
+        let pending = Simulator::new().with_pending_state(true);
+        assert_eq!(balance_of(Simulator::new().block()).await, before);
+        assert_eq!(balance_of(pending.block()).await, before + eth("1"));
+    }
+}
@@ -0,0 +1,184 @@
+//! Revert Reason Decoding
+//!
+//! Revert data comes in three standard shapes: `Error(string)` from
+//! `require`/`revert("...")`, `Panic(uint256)` from compiler checks, and
+//! custom errors (a 4-byte selector plus ABI-encoded arguments). Errors
+//! declared by the arb contract are named and their arguments decoded, so
+//! logs read "InsufficientProfit(actual: .., required: ..)" instead of hex.
+
+use ethers::abi::{self, ParamType, Token};
+use ethers::types::Bytes;
+use std::fmt;
+
+/// Selector of `Error(string)`
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+/// Selector of `Panic(uint256)`
+const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// Custom errors declared by LstArbitrage.sol
+const ARB_ERRORS: &[&str] = &[
+    "NotOwner()",
+    "NotBalancer()",
+    "StepFailed(uint256,bytes)",
+    "InsufficientProfit(uint256,uint256)",
+    "TargetNotApproved(address)",
+    "InvalidParams()",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RevertReason {
+    /// `Error(string)`
+    StringMessage(String),
+    /// A custom error: its selector and ABI-encoded arguments
+    CustomError { selector: [u8; 4], data: Bytes },
+    /// `Panic(uint256)` with its code
+    Panic(u64),
+    /// Reverted with no data
+    Empty,
+}
+
+impl RevertReason {
+    /// Classify raw revert data
+    pub fn classify(data: &[u8]) -> Self {
+        let Some((selector, args)) = data.split_first_chunk::<4>() else {
+            return Self::Empty;
+        };
+
+        match *selector {
+            ERROR_SELECTOR => {
+                if let Ok(tokens) = abi::decode(&[ParamType::String], args) {
+                    if let Some(Token::String(message)) = tokens.into_iter().next() {
+                        return Self::StringMessage(message);
+                    }
+                }
+            }
+            PANIC_SELECTOR => {
+                if let Ok(tokens) = abi::decode(&[ParamType::Uint(256)], args) {
+                    if let Some(Token::Uint(code)) = tokens.into_iter().next() {
+                        return Self::Panic(code.low_u64());
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        Self::CustomError { selector: *selector, data: Bytes::from(args.to_vec()) }
+    }
+}
+
+/// What a Solidity panic code means
+fn panic_description(code: u64) -> Option<&'static str> {
+    Some(match code {
+        0x01 => "assertion failed",
+        0x11 => "arithmetic overflow",
+        0x12 => "division by zero",
+        0x21 => "invalid enum value",
+        0x22 => "invalid storage byte array",
+        0x31 => "pop on empty array",
+        0x32 => "array index out of bounds",
+        0x41 => "out of memory",
+        0x51 => "call to uninitialized function",
+        _ => return None,
+    })
+}
+
+/// Name and argument types of an arb contract error with this selector
+fn arb_error(selector: [u8; 4]) -> Option<(&'static str, Vec<ParamType>)> {
+    ARB_ERRORS.iter()
+        .find(|signature| ethers::utils::id(signature) == selector)
+        .map(|signature| {
+            let (name, _) = signature.split_once('(').unwrap_or((signature, ""));
+            let params = match name {
+                "StepFailed" => vec![ParamType::Uint(256), ParamType::Bytes],
+                "InsufficientProfit" => vec![ParamType::Uint(256), ParamType::Uint(256)],
+                "TargetNotApproved" => vec![ParamType::Address],
+                _ => Vec::new(),
+            };
+            (name, params)
+        })
+}
+
+impl fmt::Display for RevertReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::StringMessage(message) => write!(f, "{}", message),
+            Self::Panic(code) => match panic_description(*code) {
+                Some(description) => write!(f, "Panic: {}", description),
+                None => write!(f, "Panic: code 0x{:x}", code),
+            },
+            Self::Empty => write!(f, "Reverted without reason"),
+            Self::CustomError { selector, data } => {
+                let Some((name, params)) = arb_error(*selector) else {
+                    return write!(f, "Custom error 0x{} (data 0x{})", hex::encode(selector), hex::encode(data));
+                };
+                let Ok(args) = abi::decode(&params, data) else {
+                    return write!(f, "{} (undecodable data 0x{})", name, hex::encode(data));
+                };
+
+                match args.as_slice() {
+                    [Token::Uint(index), Token::Bytes(inner)] => {
+                        write!(f, "{}(step {}): {}", name, index, RevertReason::classify(inner))
+                    }
+                    [Token::Uint(actual), Token::Uint(required)] => write!(
+                        f,
+                        "{}(actual: {} ETH, required: {} ETH)",
+                        name,
+                        ethers::utils::format_ether(*actual),
+                        ethers::utils::format_ether(*required)
+                    ),
+                    [Token::Address(target)] => write!(f, "{}({:?})", name, target),
+                    _ => write!(f, "{}", name),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::U256;
+
+    fn encode(signature: &str, args: &[Token]) -> Vec<u8> {
+        let mut data = ethers::utils::id(signature).to_vec();
+        data.extend(abi::encode(args));
+        data
+    }
+
+    #[test]
+    fn test_standard_reverts_classified() {
+        let message = encode("Error(string)", &[Token::String("STF".into())]);
+        assert_eq!(RevertReason::classify(&message), RevertReason::StringMessage("STF".into()));
+
+        let panic = encode("Panic(uint256)", &[Token::Uint(U256::from(0x11))]);
+        assert_eq!(RevertReason::classify(&panic), RevertReason::Panic(0x11));
+        assert_eq!(RevertReason::classify(&panic).to_string(), "Panic: arithmetic overflow");
+
+        assert_eq!(RevertReason::classify(&[]), RevertReason::Empty);
+    }
+
+    #[test]
+    fn test_arb_contract_errors_named() {
+        let profit = encode("InsufficientProfit(uint256,uint256)", &[
+            Token::Uint(U256::exp10(15)),
+            Token::Uint(U256::exp10(16)),
+        ]);
+        assert!(matches!(RevertReason::classify(&profit), RevertReason::CustomError { .. }));
+        assert_eq!(
+            RevertReason::classify(&profit).to_string(),
+            "InsufficientProfit(actual: 0.001000000000000000 ETH, required: 0.010000000000000000 ETH)"
+        );
+
+        // A failed step carries the venue's own revert data
+        let inner = encode("Panic(uint256)", &[Token::Uint(U256::from(0x12))]);
+        let step = encode("StepFailed(uint256,bytes)", &[Token::Uint(U256::one()), Token::Bytes(inner)]);
+        assert_eq!(RevertReason::classify(&step).to_string(), "StepFailed(step 1): Panic: division by zero");
+
+        assert_eq!(RevertReason::classify(&encode("NotOwner()", &[])).to_string(), "NotOwner");
+
+        // Unknown selectors still show the raw data
+        let unknown = RevertReason::classify(&[0xde, 0xad, 0xbe, 0xef, 0x01]);
+        assert_eq!(unknown.to_string(), "Custom error 0xdeadbeef (data 0x01)");
+    }
+}
@@ -0,0 +1,108 @@
+//! Trade Size Sweep
+//!
+//! The solver sizes trades from a model of each pool, which drifts from the
+//! real curve on thin or oddly shaped liquidity. Before executing, the
+//! contract's `simulateArb` is called at several fractions of the solver's
+//! size in one Multicall3 batch, and when the on-chain profit at the
+//! solver's size is too far from its estimate the best simulated size is
+//! traded instead.
+
+use ethers::types::U256;
+
+/// Fractions (percent) of the solver's size simulated in a sweep
+pub const SWEEP_FRACTIONS_PCT: [u64; 5] = [25, 50, 75, 100, 125];
+
+/// Solver/on-chain profit mismatch (bps) that triggers a size correction
+pub const DEFAULT_SIZE_SWEEP_THRESHOLD_BPS: u64 = 500;
+
+/// Sizes to simulate around the solver's optimal size
+pub fn sweep_sizes_for(optimal: U256) -> Vec<U256> {
+    SWEEP_FRACTIONS_PCT.iter()
+        .map(|pct| optimal * U256::from(*pct) / U256::from(100u64))
+        .filter(|size| !size.is_zero())
+        .collect()
+}
+
+/// On-chain profit at each simulated size; None where the call reverted
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SizeSweep {
+    pub results: Vec<(U256, Option<U256>)>,
+}
+
+impl SizeSweep {
+    /// The size with the highest simulated profit, and that profit. Gas
+    /// barely depends on size, so this is also the best size net of gas.
+    pub fn best(&self) -> Option<(U256, U256)> {
+        self.results.iter()
+            .filter_map(|(size, profit)| profit.map(|profit| (*size, profit)))
+            .filter(|(_, profit)| !profit.is_zero())
+            .max_by_key(|(_, profit)| *profit)
+    }
+
+    /// Simulated profit at `size`, if it was swept and didn't revert
+    pub fn profit_at(&self, size: U256) -> Option<U256> {
+        self.results.iter()
+            .find(|(swept, _)| *swept == size)
+            .and_then(|(_, profit)| *profit)
+    }
+
+    /// The (size, profit) to trade instead of the solver's when its
+    /// estimate at `optimal` is off by more than `threshold_bps`
+    pub fn correction(&self, optimal: U256, estimated_profit: U256, threshold_bps: u64) -> Option<(U256, U256)> {
+        let off_by = match self.profit_at(optimal) {
+            Some(actual) => discrepancy_bps(estimated_profit, actual),
+            // Reverted at the solver's size: whatever else worked is better
+            None => u64::MAX,
+        };
+        if off_by <= threshold_bps {
+            return None;
+        }
+        self.best()
+    }
+}
+
+/// How far `actual` is from `estimated`, in bps of the estimate
+pub fn discrepancy_bps(estimated: U256, actual: U256) -> u64 {
+    if estimated.is_zero() {
+        return if actual.is_zero() { 0 } else { u64::MAX };
+    }
+    let diff = if estimated > actual { estimated - actual } else { actual - estimated };
+    let bps = diff.saturating_mul(U256::from(10_000u64)) / estimated;
+    if bps > U256::from(u64::MAX) { u64::MAX } else { bps.as_u64() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eth(milli: u64) -> U256 {
+        U256::from(milli) * U256::exp10(15)
+    }
+
+    #[test]
+    fn test_sweep_corrects_overestimated_size() {
+        let optimal = eth(10_000);
+        let sizes = sweep_sizes_for(optimal);
+        assert_eq!(sizes, vec![eth(2_500), eth(5_000), eth(7_500), eth(10_000), eth(12_500)]);
+
+        // The pool is thinner than modelled: profit peaks at half the size
+        let sweep = SizeSweep {
+            results: vec![
+                (sizes[0], Some(eth(6))),
+                (sizes[1], Some(eth(9))),
+                (sizes[2], Some(eth(7))),
+                (sizes[3], Some(eth(3))),
+                (sizes[4], None),
+            ],
+        };
+        assert_eq!(sweep.best(), Some((eth(5_000), eth(9))));
+        assert_eq!(sweep.correction(optimal, eth(12), 500), Some((eth(5_000), eth(9))));
+
+        // Within the threshold the solver's size stands
+        assert_eq!(sweep.correction(optimal, eth(3), 500), None);
+
+        // Reverting at the solver's size always corrects
+        let reverted = SizeSweep { results: vec![(sizes[1], Some(eth(2))), (sizes[3], None)] };
+        assert_eq!(reverted.correction(optimal, eth(3), 500), Some((eth(5_000), eth(2))));
+    }
+}
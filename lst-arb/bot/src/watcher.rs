@@ -6,9 +6,10 @@
 use ethers::prelude::*;
 use ethers::types::{Address, Filter, Log, H256};
 use std::sync::Arc;
-use tokio::sync::mpsc;
+use tokio::sync::{broadcast, mpsc};
 use tracing::{debug, info, warn, error};
 
+use crate::chain::ChainProfile;
 use crate::rpc::WsClient;
 
 // Event signatures (keccak256 of event signature)
@@ -34,6 +35,31 @@ pub enum SwapEvent {
     UniswapV2 { pool: Address, block: u64 },
     Curve { pool: Address, block: u64 },
     Balancer { pool_id: H256, block: u64 },
+    /// Unconfirmed transaction touching a watched pool
+    Pending { target: Address, tx_hash: H256 },
+}
+
+/// Where swap events are sourced from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WatcherMode {
+    /// Swap logs from mined blocks
+    #[default]
+    ConfirmedLogs,
+    /// Also watch the pending transaction feed for swaps a block earlier
+    /// (falls back to confirmed logs when the provider doesn't offer it)
+    PendingTxs,
+}
+
+impl std::str::FromStr for WatcherMode {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> eyre::Result<Self> {
+        match s {
+            "confirmed_logs" => Ok(Self::ConfirmedLogs),
+            "pending_txs" => Ok(Self::PendingTxs),
+            other => Err(eyre::eyre!("Unknown watcher mode: {}", other)),
+        }
+    }
 }
 
 /// Watcher configuration
@@ -45,33 +71,38 @@ pub struct WatcherConfig {
     pub uniswap_v2_pools: Vec<Address>,
     /// Curve pool addresses to watch
     pub curve_pools: Vec<Address>,
-    /// Balancer vault address
+    /// Balancer vault address (zero where Balancer isn't deployed)
     pub balancer_vault: Address,
+    /// Event source
+    pub mode: WatcherMode,
 }
 
 impl WatcherConfig {
-    /// Create config for Arbitrum LST/LRT pools
-    pub fn arbitrum_lst_pools() -> Self {
+    /// Create config for the chain profile's LST/LRT pools
+    pub fn for_chain(chain: &ChainProfile) -> Self {
         Self {
-            uniswap_v3_pools: vec![
-                // wstETH/ETH 0.05%
-                "0x35218a1cbaC5Bbc3E57fd9Bd38219D37571b3537".parse().unwrap(),
-                // wstETH/ETH 0.01%
-                "0x7A20B2F07d5B2A9aE5F1F24b8C3c0c9F7b9e4C3A".parse().unwrap(),
-                // rETH/ETH 0.05%
-                "0x09BA4E5F0D0f0C3A0a7AC7D7A05c1C0A0B0C0D0E".parse().unwrap(),
-            ],
-            uniswap_v2_pools: vec![
-                // Camelot wstETH/ETH (Uniswap V2 fork)
-                "0x0E3eF0c8D2D4A1d4F8c9c0F9c8E9c0D2f0A1b2C3".parse().unwrap(),
-            ],
-            curve_pools: vec![
-                // Curve wstETH/ETH NG Pool on Arbitrum
-                "0x6eB2dc694eB516B16Dc9d7671f465248B71E9091".parse().unwrap(),
-            ],
-            // Arbitrum Balancer V2 Vault
-            balancer_vault: "0xBA12222222228d8Ba445958a75a0704d566BF2C8".parse().unwrap(),
+            uniswap_v3_pools: chain.watched_uniswap_v3_pools.clone(),
+            uniswap_v2_pools: chain.watched_uniswap_v2_pools.clone(),
+            curve_pools: chain.watched_curve_pools.clone(),
+            balancer_vault: chain.balancer_vault,
+            mode: WatcherMode::ConfirmedLogs,
+        }
+    }
+
+    pub fn with_mode(mut self, mode: WatcherMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    fn watched_addresses(&self) -> Vec<Address> {
+        let mut addresses: Vec<Address> = Vec::new();
+        addresses.extend(&self.uniswap_v3_pools);
+        addresses.extend(&self.uniswap_v2_pools);
+        addresses.extend(&self.curve_pools);
+        if !self.balancer_vault.is_zero() {
+            addresses.push(self.balancer_vault);
         }
+        addresses
     }
 }
 
@@ -101,6 +132,16 @@ impl EventWatcher {
         let config = self.config.clone();
         let client_clone = client.clone();
 
+        // An empty address filter would match every swap on the chain
+        if config.watched_addresses().is_empty() {
+            warn!("No pools to watch on this chain; relying on the backup poll");
+            return Ok(rx);
+        }
+
+        if config.mode == WatcherMode::PendingTxs {
+            tokio::spawn(Self::watch_pending(config.clone(), client.clone(), tx.clone()));
+        }
+
         tokio::spawn(async move {
             // Subscribe to logs inside the spawned task
             let mut stream = match client_clone.subscribe_logs(&filter).await {
@@ -129,14 +170,43 @@ impl EventWatcher {
         Ok(rx)
     }
 
+    /// Emit pending transactions that touch watched pools, alongside the log stream
+    async fn watch_pending(
+        config: WatcherConfig,
+        client: Arc<WsClient>,
+        tx: mpsc::UnboundedSender<SwapEvent>,
+    ) {
+        let mut stream = match client.subscribe_pending_txs().await {
+            Ok(s) => s,
+            Err(e) => {
+                warn!("Pending tx feed unavailable, using confirmed logs only: {:?}", e);
+                return;
+            }
+        };
+
+        info!("Pending tx watcher started");
+        let watched = config.watched_addresses();
+
+        while let Some(tx_hash) = stream.next().await {
+            // The feed only carries hashes - fetch the body to see what it calls
+            let Ok(Some(transaction)) = client.get_transaction(tx_hash).await else {
+                continue;
+            };
+            if let Some(target) = pending_swap_target(&watched, &transaction) {
+                debug!("Pending swap {:?} touches {:?}", tx_hash, target);
+                if tx.send(SwapEvent::Pending { target, tx_hash }).is_err() {
+                    break;
+                }
+            }
+        }
+
+        warn!("Pending tx stream ended");
+    }
+
     /// Build the log filter for all watched events
     fn build_filter(&self) -> Filter {
         // Collect all pool addresses we want to watch
-        let mut addresses: Vec<Address> = Vec::new();
-        addresses.extend(&self.config.uniswap_v3_pools);
-        addresses.extend(&self.config.uniswap_v2_pools);
-        addresses.extend(&self.config.curve_pools);
-        addresses.push(self.config.balancer_vault);
+        let addresses = self.config.watched_addresses();
 
         // Build topic filter (OR of all swap event signatures)
         let topics: Vec<H256> = vec![
@@ -187,6 +257,8 @@ impl EventWatcher {
 pub enum DetectionTrigger {
     /// Triggered by a swap event
     SwapEvent(SwapEvent),
+    /// Triggered by an unconfirmed swap on a watched pool
+    PendingSwap { target: Address, tx_hash: H256 },
     /// Triggered by backup polling interval
     BackupPoll,
     /// Triggered by new block
@@ -197,6 +269,7 @@ pub enum DetectionTrigger {
 pub struct CombinedWatcher {
     event_watcher: EventWatcher,
     backup_interval_ms: u64,
+    shutdown: Option<broadcast::Sender<()>>,
 }
 
 impl CombinedWatcher {
@@ -204,9 +277,16 @@ impl CombinedWatcher {
         Self {
             event_watcher: EventWatcher::new(config),
             backup_interval_ms,
+            shutdown: None,
         }
     }
 
+    /// Stop emitting triggers when the shutdown channel fires
+    pub fn with_shutdown(mut self, shutdown: broadcast::Sender<()>) -> Self {
+        self.shutdown = Some(shutdown);
+        self
+    }
+
     /// Start the combined watcher
     /// Returns a receiver that emits DetectionTriggers
     pub async fn start(
@@ -220,6 +300,7 @@ impl CombinedWatcher {
 
         let backup_ms = self.backup_interval_ms;
         let client_clone = client.clone();
+        let mut shutdown_rx = self.shutdown.as_ref().map(|s| s.subscribe());
 
         tokio::spawn(async move {
             // Subscribe to new blocks inside the spawned task
@@ -241,7 +322,13 @@ impl CombinedWatcher {
                 tokio::select! {
                     // Swap event received - highest priority
                     Some(event) = event_rx.recv() => {
-                        if tx.send(DetectionTrigger::SwapEvent(event)).is_err() {
+                        let trigger = match event {
+                            SwapEvent::Pending { target, tx_hash } => {
+                                DetectionTrigger::PendingSwap { target, tx_hash }
+                            }
+                            event => DetectionTrigger::SwapEvent(event),
+                        };
+                        if tx.send(trigger).is_err() {
                             break;
                         }
                     }
@@ -262,6 +349,11 @@ impl CombinedWatcher {
                             break;
                         }
                     }
+
+                    _ = shutdown_signal(&mut shutdown_rx) => {
+                        info!("Combined watcher shutting down");
+                        return;
+                    }
                 }
             }
 
@@ -272,6 +364,27 @@ impl CombinedWatcher {
     }
 }
 
+/// The watched pool a pending transaction swaps through, if any: either it
+/// calls the pool directly or a router passes the pool address in calldata
+pub fn pending_swap_target(watched: &[Address], transaction: &Transaction) -> Option<Address> {
+    if let Some(to) = transaction.to.filter(|to| watched.contains(to)) {
+        return Some(to);
+    }
+    watched.iter().copied().find(|pool| {
+        transaction.input.windows(20).any(|w| w == pool.as_bytes())
+    })
+}
+
+/// Resolves when shutdown is signalled; never resolves without a channel
+pub async fn shutdown_signal(shutdown: &mut Option<broadcast::Receiver<()>>) {
+    match shutdown {
+        Some(rx) => {
+            let _ = rx.recv().await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -282,10 +395,38 @@ mod tests {
         assert!(!topic.is_zero());
     }
 
+    #[test]
+    fn test_pending_swap_target() {
+        let config = WatcherConfig::for_chain(&ChainProfile::arbitrum_one());
+        let watched = config.watched_addresses();
+        let curve = config.curve_pools[0];
+
+        let direct = Transaction { to: Some(curve), ..Default::default() };
+        assert_eq!(pending_swap_target(&watched, &direct), Some(curve));
+
+        // Router call with the pool address in its calldata
+        let mut input = vec![0u8; 16];
+        input.extend_from_slice(&[0u8; 12]);
+        input.extend_from_slice(curve.as_bytes());
+        let routed = Transaction {
+            to: Some(Address::from_low_u64_be(0xdead)),
+            input: input.into(),
+            ..Default::default()
+        };
+        assert_eq!(pending_swap_target(&watched, &routed), Some(curve));
+
+        let unrelated = Transaction { to: Some(Address::from_low_u64_be(0xdead)), ..Default::default() };
+        assert_eq!(pending_swap_target(&watched, &unrelated), None);
+        assert_eq!("pending_txs".parse::<WatcherMode>().unwrap(), WatcherMode::PendingTxs);
+    }
+
     #[test]
     fn test_config_creation() {
-        let config = WatcherConfig::arbitrum_lst_pools();
+        let config = WatcherConfig::for_chain(&ChainProfile::arbitrum_one());
         assert!(!config.uniswap_v3_pools.is_empty());
         assert!(!config.curve_pools.is_empty());
+
+        let nova = WatcherConfig::for_chain(&ChainProfile::arbitrum_nova());
+        assert!(nova.watched_addresses().is_empty());
     }
 }
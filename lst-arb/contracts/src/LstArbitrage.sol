@@ -64,6 +64,7 @@ contract LstArbitrage is IFlashLoanRecipient {
 
     event BatchExecuted(uint256 stepsExecuted, uint256 gasUsed);
     event FlashLoanExecuted(address[] tokens, uint256[] amounts, uint256 profit);
+    event ProfitRealized(uint256 profit);
     event TargetApproved(address indexed target, bool approved);
     event TokenApproved(address indexed token, address indexed spender);
 
@@ -271,6 +272,7 @@ contract LstArbitrage is IFlashLoanRecipient {
         if (profit < minProfit) revert InsufficientProfit(profit, minProfit);
 
         emit FlashLoanExecuted(_toAddresses(tokens), amounts, profit);
+        emit ProfitRealized(profit);
     }
 
     // ============================================